@@ -16,6 +16,12 @@
 //! | 2.10       | Simple-Reflex-Agent               | `SimpleReflexAgent`                                      |
 //! | 2.12       | Model-Based-Reflex-Agent          | `ReflexAgentWithState`                                   |
 //!
+use self::envs::vacuum;
+use crate::util::Rng;
+use rand::seq::SliceRandom;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
 /// # Table Driven Agent
 ///
 /// The table driven agent program is fairly simple to design. We need a function that
@@ -240,10 +246,16 @@ pub mod table {
     ///
     /// The `derive` macro annotations give us an automatic `Lookup` implementation for the
     /// `Weather` percept.
+    ///
+    /// Raw sensor readings such as `f64` temperatures can't be percepts here. Floating point
+    /// numbers are neither `Eq` nor `Hash`: `NaN` isn't equal to itself, and readings that print
+    /// the same can differ in their last bits, so a table keyed on them would almost never find
+    /// a match. Discretize the readings first, for example with
+    /// [`reflex::thresholded`](../reflex/fn.thresholded.html).
     pub trait Lookup = Eq + std::hash::Hash;
 
     /// The table in figure 2.8 that holds the action for a given percept sequence
-    pub type Table<Percept: Lookup, Action> = HashMap<Vec<Percept>, Action>;
+    pub type Table<Percept, Action> = HashMap<Vec<Percept>, Action>;
 
     /// The table driven agent. See the module documentation for a derivation of this
     /// struct and example usage.
@@ -277,13 +289,14 @@ pub mod table {
 /// In fact, it is perfectly suited to the same reflex pattern shown in figure 2.8 for the
 /// vacuum cleaner. It can look at a single percept, Sunny or Rainy; and Open or Close respectively.
 pub mod reflex {
+    use super::Agent;
     use std::marker::PhantomData;
 
     pub fn identity<T>(t: T) -> T {
         t
     }
 
-    /// Figure 2.10
+    // Figure 2.10
     //pub trait SimpleReflexAgent<Percept, State, Action> {
     //    fn interpret_input(percept: Percept) -> State;
     //}
@@ -311,13 +324,14 @@ pub mod reflex {
     ///     }
     /// };
     ///
-    /// let agent = SimpleReflexAgent::new(interpet_input, rule_match);
+    /// let mut agent = SimpleReflexAgent::new(interpet_input, rule_match);
     ///
+    /// assert_eq!(agent.run(Weather::Rainy), Window::Close);
     /// ```
     pub struct SimpleReflexAgent<Percept, State, Action, InterpretInput, RuleMatch>
     where
-        InterpretInput: FnOnce(Percept) -> State,
-        RuleMatch: FnOnce(State) -> Action,
+        InterpretInput: FnMut(Percept) -> State,
+        RuleMatch: FnMut(State) -> Action,
     {
         interpret_input: InterpretInput,
         rule_match: RuleMatch,
@@ -327,15 +341,90 @@ pub mod reflex {
     impl<Percept, State, Action, InterpretInput, RuleMatch>
         SimpleReflexAgent<Percept, State, Action, InterpretInput, RuleMatch>
     where
-        InterpretInput: FnOnce(Percept) -> State,
-        RuleMatch: FnOnce(State) -> Action,
+        InterpretInput: FnMut(Percept) -> State,
+        RuleMatch: FnMut(State) -> Action,
     {
         pub fn new(interpret_input: InterpretInput, rule_match: RuleMatch) -> Self {
             SimpleReflexAgent {
                 interpret_input, rule_match,
-                _phantom: PhantomData::default()
+                _phantom: PhantomData
             }
         }
+
+        /// Interprets the percept as a state and returns the action of the rule that matches it.
+        pub fn run(&mut self, percept: Percept) -> Action {
+            let state = (self.interpret_input)(percept);
+            (self.rule_match)(state)
+        }
+    }
+
+    impl<Percept, State, Action, InterpretInput, RuleMatch> Agent
+        for SimpleReflexAgent<Percept, State, Action, InterpretInput, RuleMatch>
+    where
+        InterpretInput: FnMut(Percept) -> State,
+        RuleMatch: FnMut(State) -> Action,
+    {
+        type Percept = Percept;
+        type Action = Action;
+
+        fn program(&mut self, percept: Percept) -> Action {
+            self.run(percept)
+        }
+    }
+
+    /// An `interpret_input` for continuous percepts, such as sensor readings, that sorts each
+    /// reading into one of a few bins so that the rules can match on the bin. A reading below
+    /// `thresholds[0]` is interpreted as `states[0]`, one from `thresholds[i - 1]` up to
+    /// `thresholds[i]` as `states[i]`, and one at or above the last threshold as the last state.
+    ///
+    /// # Panics
+    ///
+    /// Panics if there isn't exactly one more state than thresholds, or if the thresholds aren't
+    /// in increasing order.
+    ///
+    /// # Examples
+    ///
+    /// A window agent that only has a thermometer treats a cold day as a rainy one:
+    ///
+    /// ```
+    /// # use aima_rust::agents::reflex::{self, SimpleReflexAgent};
+    /// # use aima_rust::agents::envs::weather::{Window, Weather};
+    /// let interpret_input = reflex::thresholded(&[20.0], vec![Weather::Rainy, Weather::Sunny]);
+    /// let rule_match = |weather| match weather {
+    ///     Weather::Sunny => Window::Open,
+    ///     Weather::Rainy => Window::Close,
+    /// };
+    /// let mut agent = SimpleReflexAgent::new(interpret_input, rule_match);
+    ///
+    /// let temperatures = [12.0, 19.9, 20.0, 31.5, 8.25];
+    /// let actions: Vec<Window> = temperatures.iter().map(|&t| agent.run(t)).collect();
+    ///
+    /// assert_eq!(
+    ///     actions,
+    ///     vec![Window::Close, Window::Close, Window::Open, Window::Open, Window::Close]
+    /// );
+    /// ```
+    pub fn thresholded<State: Clone>(
+        thresholds: &[f64],
+        states: Vec<State>,
+    ) -> impl FnMut(f64) -> State {
+        assert_eq!(
+            states.len(),
+            thresholds.len() + 1,
+            "need one more state than thresholds"
+        );
+        assert!(
+            thresholds.windows(2).all(|pair| pair[0] < pair[1]),
+            "thresholds must be increasing"
+        );
+        let thresholds = thresholds.to_vec();
+        move |reading| {
+            let bin = thresholds
+                .iter()
+                .take_while(|&&threshold| reading >= threshold)
+                .count();
+            states[bin].clone()
+        }
     }
 
 
@@ -344,7 +433,7 @@ pub mod reflex {
 
 pub mod envs {
     pub mod weather {
-        #[derive(PartialEq, Eq, Hash)]
+        #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
         pub enum Weather {
             Sunny, Rainy
         }
@@ -354,4 +443,939 @@ pub mod envs {
             Open, Close
         }
     }
-}
\ No newline at end of file
+
+    /// # The Vacuum-Cleaner World
+    ///
+    /// The two-square world of figure 2.2. Each square, A on the left and B on the right, is
+    /// either clean or dirty. In the trivial world the agent perceives only its own square and
+    /// actions are deterministic; in the erratic world of figure 4.9 sucking is unpredictable and
+    /// the agent perceives the full state. Sucking up dirt earns ten points and each move costs
+    /// one.
+    pub mod vacuum {
+        use crate::agents::Environment;
+        use crate::search::{NondeterministicProblem, Problem};
+        use crate::util::Rng;
+        use rand::seq::SliceRandom;
+        #[cfg(feature = "serde")]
+        use serde::{Deserialize, Serialize};
+
+        #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+        #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+        pub enum Location {
+            A,
+            B,
+        }
+
+        #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+        #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+        pub enum Status {
+            Clean,
+            Dirty,
+        }
+
+        #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+        #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+        pub enum Action {
+            Left,
+            Right,
+            Suck,
+            NoOp,
+        }
+
+        /// The agent's location and whether it is dirty.
+        pub type Percept = (Location, Status);
+
+        /// The vacuum world with a single agent in it.
+        #[derive(Clone, Debug, PartialEq)]
+        #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+        pub struct TrivialVacuumEnvironment {
+            a: Status,
+            b: Status,
+            location: Location,
+            performance: f64,
+        }
+
+        impl TrivialVacuumEnvironment {
+            /// Creates a new world given the status of each square and where the agent starts.
+            pub fn new(a: Status, b: Status, location: Location) -> Self {
+                TrivialVacuumEnvironment {
+                    a,
+                    b,
+                    location,
+                    performance: 0.0,
+                }
+            }
+
+            /// Whether `location` is clean or dirty.
+            pub fn status(&self, location: Location) -> Status {
+                match location {
+                    Location::A => self.a,
+                    Location::B => self.b,
+                }
+            }
+
+            /// Where the agent is.
+            pub fn location(&self) -> Location {
+                self.location
+            }
+        }
+
+        impl Environment for TrivialVacuumEnvironment {
+            type Percept = Percept;
+            type Action = Action;
+
+            fn num_agents(&self) -> usize {
+                1
+            }
+
+            fn percept(&self, _agent: usize) -> Percept {
+                (self.location, self.status(self.location))
+            }
+
+            fn execute(&mut self, actions: Vec<Action>) {
+                match actions[0] {
+                    Action::Left => {
+                        self.location = Location::A;
+                        self.performance -= 1.0;
+                    }
+                    Action::Right => {
+                        self.location = Location::B;
+                        self.performance -= 1.0;
+                    }
+                    Action::Suck => {
+                        let square = match self.location {
+                            Location::A => &mut self.a,
+                            Location::B => &mut self.b,
+                        };
+                        if *square == Status::Dirty {
+                            *square = Status::Clean;
+                            self.performance += 10.0;
+                        }
+                    }
+                    Action::NoOp => {}
+                }
+            }
+
+            fn performance(&self, _agent: usize) -> f64 {
+                self.performance
+            }
+
+            fn is_done(&self) -> bool {
+                self.a == Status::Clean && self.b == Status::Clean
+            }
+        }
+        /// The full state of the world: where the agent is, and the status of squares A and B.
+        pub type WorldState = (Location, Status, Status);
+
+        /// The vacuum world as a deterministic search problem, with the goal of cleaning both
+        /// squares.
+        pub struct VacuumWorld {
+            initial: WorldState,
+        }
+
+        impl VacuumWorld {
+            /// Creates a new problem given the state the world starts in.
+            pub fn new(initial: WorldState) -> Self {
+                VacuumWorld { initial }
+            }
+        }
+
+        impl Problem for VacuumWorld {
+            type State = WorldState;
+            type Action = Action;
+
+            fn initial_state(&self) -> WorldState {
+                self.initial
+            }
+
+            fn actions(&self, _state: &WorldState) -> Vec<Action> {
+                vec![Action::Left, Action::Right, Action::Suck]
+            }
+
+            fn result(&self, &(location, a, b): &WorldState, action: &Action) -> WorldState {
+                match (action, location) {
+                    (Action::Left, _) => (Location::A, a, b),
+                    (Action::Right, _) => (Location::B, a, b),
+                    (Action::Suck, Location::A) => (location, Status::Clean, b),
+                    (Action::Suck, Location::B) => (location, a, Status::Clean),
+                    (Action::NoOp, _) => (location, a, b),
+                }
+            }
+
+            fn goal_test(&self, state: &WorldState) -> bool {
+                world_is_clean(state)
+            }
+        }
+
+        /// Figure 4.9
+        ///
+        /// The erratic vacuum world as a nondeterministic search problem. Sucking a dirty square
+        /// cleans it and sometimes cleans the other square too; sucking a clean square sometimes
+        /// deposits dirt on it. The goal is to have both squares clean.
+        pub struct ErraticVacuumWorld {
+            initial: WorldState,
+        }
+
+        impl ErraticVacuumWorld {
+            /// Creates a new problem given the state the world starts in.
+            pub fn new(initial: WorldState) -> Self {
+                ErraticVacuumWorld { initial }
+            }
+        }
+
+        impl NondeterministicProblem for ErraticVacuumWorld {
+            type State = WorldState;
+            type Action = Action;
+
+            fn initial_state(&self) -> WorldState {
+                self.initial
+            }
+
+            fn actions(&self, _state: &WorldState) -> Vec<Action> {
+                vec![Action::Suck, Action::Left, Action::Right]
+            }
+
+            fn results(&self, &(location, a, b): &WorldState, action: &Action) -> Vec<WorldState> {
+                let with = |here: Status, other: Status| match location {
+                    Location::A => (location, here, other),
+                    Location::B => (location, other, here),
+                };
+                let (here, other) = match location {
+                    Location::A => (a, b),
+                    Location::B => (b, a),
+                };
+                let mut outcomes = match action {
+                    Action::Left => vec![(Location::A, a, b)],
+                    Action::Right => vec![(Location::B, a, b)],
+                    Action::NoOp => vec![(location, a, b)],
+                    Action::Suck if here == Status::Dirty => vec![
+                        with(Status::Clean, other),
+                        with(Status::Clean, Status::Clean),
+                    ],
+                    Action::Suck => vec![with(here, other), with(Status::Dirty, other)],
+                };
+                outcomes.dedup();
+                outcomes
+            }
+
+            fn goal_test(&self, state: &WorldState) -> bool {
+                world_is_clean(state)
+            }
+        }
+
+        /// The erratic vacuum world as a live environment for a single agent, which perceives the
+        /// full state. Each action's outcome is drawn at random from those `ErraticVacuumWorld`
+        /// allows, using a generator seeded with `seed`. Sucking up dirt earns ten points and
+        /// each move costs one.
+        #[derive(Clone, Debug)]
+        pub struct ErraticVacuumEnvironment {
+            state: WorldState,
+            rng: Rng,
+            performance: f64,
+        }
+
+        impl ErraticVacuumEnvironment {
+            /// Creates a new world given the state it starts in and a seed for its outcomes.
+            pub fn new(state: WorldState, seed: u64) -> Self {
+                ErraticVacuumEnvironment {
+                    state,
+                    rng: Rng::seeded(seed),
+                    performance: 0.0,
+                }
+            }
+        }
+
+        impl Environment for ErraticVacuumEnvironment {
+            type Percept = WorldState;
+            type Action = Action;
+
+            fn num_agents(&self) -> usize {
+                1
+            }
+
+            fn percept(&self, _agent: usize) -> WorldState {
+                self.state
+            }
+
+            fn execute(&mut self, actions: Vec<Action>) {
+                let world = ErraticVacuumWorld::new(self.state);
+                let outcomes = world.results(&self.state, &actions[0]);
+                let next = *outcomes.choose(&mut self.rng).unwrap();
+                let dirty = |(_, a, b): WorldState| {
+                    [a, b].iter().filter(|&&s| s == Status::Dirty).count() as f64
+                };
+                self.performance += 10.0 * (dirty(self.state) - dirty(next)).max(0.0);
+                if next.0 != self.state.0 {
+                    self.performance -= 1.0;
+                }
+                self.state = next;
+            }
+
+            fn performance(&self, _agent: usize) -> f64 {
+                self.performance
+            }
+
+            fn is_done(&self) -> bool {
+                world_is_clean(&self.state)
+            }
+        }
+
+        fn world_is_clean(&(_, a, b): &WorldState) -> bool {
+            a == Status::Clean && b == Status::Clean
+        }
+    }
+
+    /// # Grid World
+    ///
+    /// A rectangular world of free and wall cells shared by several agents. Each agent sees the
+    /// three-by-three neighborhood around it and moves one cell at a time. All agents move at
+    /// once. A move is blocked if it would leave the grid, enter a wall, land on the same cell as
+    /// another agent, or swap places with one. A blocked agent stays put and loses a point. An
+    /// agent may follow another into the cell it is leaving.
+    ///
+    /// Cells are `(x, y)` pairs with `(0, 0)` at the bottom left, as in `mdp::GridMDP`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use aima_rust::agents::{Agent, Environment};
+    /// # use aima_rust::agents::envs::grid::{GridWorld, Move, Percept};
+    /// struct Always(Move);
+    ///
+    /// impl Agent for Always {
+    ///     type Percept = Percept;
+    ///     type Action = Move;
+    ///
+    ///     fn program(&mut self, _percept: Percept) -> Move {
+    ///         self.0
+    ///     }
+    /// }
+    ///
+    /// let mut world = GridWorld::new(5, 1);
+    /// let left = world.add_agent((1, 0));
+    /// let right = world.add_agent((3, 0));
+    /// assert_eq!(world.to_string(), ".A.B.");
+    ///
+    /// // Both agents head for (2, 0); neither gets it.
+    /// world.step(&mut [&mut Always(Move::Right), &mut Always(Move::Left)]);
+    /// assert_eq!(world.position(left), (1, 0));
+    /// assert_eq!(world.position(right), (3, 0));
+    /// assert_eq!(world.performance(left), -1.0);
+    ///
+    /// // Moving in step, every agent advances.
+    /// world.step(&mut [&mut Always(Move::Right), &mut Always(Move::Right)]);
+    /// assert_eq!(world.position(left), (2, 0));
+    /// assert_eq!(world.position(right), (4, 0));
+    /// assert_eq!(world.to_string(), "..A.B");
+    /// ```
+    pub mod grid {
+        use crate::agents::Environment;
+        use std::collections::HashSet;
+        use std::fmt;
+
+        pub type Cell = (i32, i32);
+
+        #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+        pub enum Move {
+            Up,
+            Down,
+            Left,
+            Right,
+            Stay,
+        }
+
+        impl Move {
+            /// The cell reached from `cell` by this move, ignoring anything in the way.
+            pub fn from(self, (x, y): Cell) -> Cell {
+                match self {
+                    Move::Up => (x, y + 1),
+                    Move::Down => (x, y - 1),
+                    Move::Left => (x - 1, y),
+                    Move::Right => (x + 1, y),
+                    Move::Stay => (x, y),
+                }
+            }
+        }
+
+        /// What an agent sees in a cell of its neighborhood.
+        #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+        pub enum Sight {
+            Free,
+            /// A wall, or the edge of the grid.
+            Wall,
+            Agent,
+        }
+
+        /// The three-by-three neighborhood of an agent, as rows from the top, with the agent itself
+        /// in the middle.
+        pub type Percept = [[Sight; 3]; 3];
+
+        #[derive(Clone, Debug, PartialEq)]
+        pub struct GridWorld {
+            width: i32,
+            height: i32,
+            walls: HashSet<Cell>,
+            positions: Vec<Cell>,
+            bumps: Vec<usize>,
+        }
+
+        impl GridWorld {
+            /// Creates a new world with no walls or agents given its width and height.
+            pub fn new(width: i32, height: i32) -> Self {
+                GridWorld {
+                    width,
+                    height,
+                    walls: HashSet::new(),
+                    positions: Vec::new(),
+                    bumps: Vec::new(),
+                }
+            }
+
+            /// Adds a wall at `cell`.
+            pub fn wall(mut self, cell: Cell) -> Self {
+                self.walls.insert(cell);
+                self
+            }
+
+            /// Places a new agent at `cell` and returns its number.
+            ///
+            /// # Panics
+            ///
+            /// Panics if `cell` is not free.
+            pub fn add_agent(&mut self, cell: Cell) -> usize {
+                assert!(self.is_free(cell), "{:?} is not free", cell);
+                self.positions.push(cell);
+                self.bumps.push(0);
+                self.positions.len() - 1
+            }
+
+            /// Where the agent numbered `agent` is.
+            pub fn position(&self, agent: usize) -> Cell {
+                self.positions[agent]
+            }
+
+            /// Whether `cell` is on the grid and not a wall.
+            fn is_open(&self, (x, y): Cell) -> bool {
+                (0..self.width).contains(&x)
+                    && (0..self.height).contains(&y)
+                    && !self.walls.contains(&(x, y))
+            }
+
+            /// Whether `cell` is open and no agent is in it.
+            fn is_free(&self, cell: Cell) -> bool {
+                self.is_open(cell) && !self.positions.contains(&cell)
+            }
+        }
+
+        impl Environment for GridWorld {
+            type Percept = Percept;
+            type Action = Move;
+
+            fn num_agents(&self) -> usize {
+                self.positions.len()
+            }
+
+            fn percept(&self, agent: usize) -> Percept {
+                let (x, y) = self.positions[agent];
+                let mut view = [[Sight::Free; 3]; 3];
+                for (row, dy) in [1, 0, -1].iter().enumerate() {
+                    for (column, dx) in [-1, 0, 1].iter().enumerate() {
+                        let cell = (x + dx, y + dy);
+                        view[row][column] = if !self.is_open(cell) {
+                            Sight::Wall
+                        } else if self.positions.contains(&cell) {
+                            Sight::Agent
+                        } else {
+                            Sight::Free
+                        };
+                    }
+                }
+                view
+            }
+
+            fn execute(&mut self, actions: Vec<Move>) {
+                let mut targets: Vec<Cell> = self
+                    .positions
+                    .iter()
+                    .zip(&actions)
+                    .map(|(&cell, action)| {
+                        let target = action.from(cell);
+                        if self.is_open(target) {
+                            target
+                        } else {
+                            cell
+                        }
+                    })
+                    .collect();
+
+                // Blocking one agent can block the agents behind it, so repeat until settled.
+                loop {
+                    let blocked: Vec<usize> = (0..targets.len())
+                        .filter(|&i| {
+                            targets[i] != self.positions[i]
+                                && (0..targets.len()).any(|j| {
+                                    j != i
+                                        && (targets[j] == targets[i]
+                                            || (targets[i] == self.positions[j]
+                                                && targets[j] == self.positions[i]))
+                                })
+                        })
+                        .collect();
+                    if blocked.is_empty() {
+                        break;
+                    }
+                    for i in blocked {
+                        targets[i] = self.positions[i];
+                    }
+                }
+
+                for (agent, action) in actions.iter().enumerate() {
+                    if *action != Move::Stay && targets[agent] == self.positions[agent] {
+                        self.bumps[agent] += 1;
+                    }
+                }
+                self.positions = targets;
+            }
+
+            fn performance(&self, agent: usize) -> f64 {
+                -(self.bumps[agent] as f64)
+            }
+        }
+
+        /// Draws the grid from the top row down, with `#` for walls, `.` for free cells and a
+        /// letter for each agent: `A` for agent zero, `B` for agent one and so on.
+        impl fmt::Display for GridWorld {
+            fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                for y in (0..self.height).rev() {
+                    let row: String = (0..self.width)
+                        .map(|x| {
+                            if let Some(agent) = self.positions.iter().position(|&p| p == (x, y)) {
+                                (b'A' + (agent % 26) as u8) as char
+                            } else if self.walls.contains(&(x, y)) {
+                                '#'
+                            } else {
+                                '.'
+                            }
+                        })
+                        .collect();
+                    if y + 1 < self.height {
+                        writeln!(f)?;
+                    }
+                    write!(f, "{}", row)?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+/// Figure 2.1
+///
+/// An agent perceives its environment through sensors and acts upon it through actuators. Its
+/// behavior is described by the agent program, which maps each percept to an action.
+pub trait Agent {
+    type Percept;
+    type Action;
+
+    /// Chooses an action given the latest percept.
+    fn program(&mut self, percept: Self::Percept) -> Self::Action;
+}
+
+/// Figure 2.1
+///
+/// An environment that one or more agents, numbered from zero, perceive and act in. Every agent
+/// acts at each time step, and the actions take effect simultaneously.
+pub trait Environment {
+    type Percept;
+    type Action;
+
+    /// The number of agents in the environment.
+    fn num_agents(&self) -> usize;
+
+    /// What the agent numbered `agent` currently perceives.
+    fn percept(&self, agent: usize) -> Self::Percept;
+
+    /// Carries out one action for each agent, in agent order, and advances the environment one
+    /// time step.
+    fn execute(&mut self, actions: Vec<Self::Action>);
+
+    /// The performance measure of the agent numbered `agent` so far.
+    fn performance(&self, agent: usize) -> f64;
+
+    /// Whether there is nothing left for the agents to do.
+    fn is_done(&self) -> bool {
+        false
+    }
+
+    /// Runs one time step: gives each agent its percept, and executes the actions they choose.
+    fn step(
+        &mut self,
+        agents: &mut [&mut dyn Agent<Percept = Self::Percept, Action = Self::Action>],
+    ) {
+        let actions = agents
+            .iter_mut()
+            .enumerate()
+            .map(|(i, agent)| agent.program(self.percept(i)))
+            .collect();
+        self.execute(actions);
+    }
+
+    /// Runs `steps` time steps, or until the environment is done, and returns the performance
+    /// measure of the first agent.
+    fn run(
+        &mut self,
+        agents: &mut [&mut dyn Agent<Percept = Self::Percept, Action = Self::Action>],
+        steps: usize,
+    ) -> f64
+    where
+        Self: Sized,
+    {
+        self.run_with(agents, steps, |_, _| {})
+    }
+
+    /// Runs like `run`, calling `on_step` after every step with the index of the step, counting
+    /// from zero, and the performance measure of the first agent at that point, so that a long
+    /// simulation can be monitored or logged as it goes.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use aima_rust::agents::{Environment, ReflexVacuumAgent};
+    /// # use aima_rust::agents::envs::vacuum::{Location, Status, TrivialVacuumEnvironment};
+    /// let mut env = TrivialVacuumEnvironment::new(Status::Dirty, Status::Dirty, Location::A);
+    /// let mut scores = Vec::new();
+    ///
+    /// let performance = env.run_with(&mut [&mut ReflexVacuumAgent], 2, |step, score| {
+    ///     scores.push((step, score));
+    /// });
+    ///
+    /// // Suck up the dirt in A, then move to B.
+    /// assert_eq!(scores, vec![(0, 10.0), (1, 9.0)]);
+    /// assert_eq!(performance, 9.0);
+    ///
+    /// // Suck up the dirt in B, after which the world is clean and the run stops early.
+    /// let mut steps = 0;
+    /// let performance = env.run_with(&mut [&mut ReflexVacuumAgent], 100, |_, _| steps += 1);
+    /// assert_eq!((steps, performance), (1, 19.0));
+    /// ```
+    fn run_with<F>(
+        &mut self,
+        agents: &mut [&mut dyn Agent<Percept = Self::Percept, Action = Self::Action>],
+        steps: usize,
+        mut on_step: F,
+    ) -> f64
+    where
+        Self: Sized,
+        F: FnMut(usize, f64),
+    {
+        for step in 0..steps {
+            if self.is_done() {
+                break;
+            }
+            self.step(agents);
+            on_step(step, self.performance(0));
+        }
+        self.performance(0)
+    }
+}
+
+/// A vacuum agent that ignores its percepts and chooses among the four actions at random. The
+/// choices come from a generator seeded with `seed`, so a run can be reproduced.
+///
+/// # Examples
+///
+/// ```
+/// # use aima_rust::agents::{Environment, RandomVacuumAgent};
+/// # use aima_rust::agents::envs::vacuum::{Location, Status, TrivialVacuumEnvironment};
+/// let mut env = TrivialVacuumEnvironment::new(Status::Dirty, Status::Dirty, Location::A);
+/// let mut agent = RandomVacuumAgent::new(3);
+///
+/// env.run(&mut [&mut agent], 100);
+///
+/// assert!(env.is_done());
+/// assert_eq!(env.status(Location::A), Status::Clean);
+/// ```
+pub struct RandomVacuumAgent {
+    rng: Rng,
+}
+
+impl RandomVacuumAgent {
+    /// Creates a new agent given a seed for its choices.
+    pub fn new(seed: u64) -> Self {
+        RandomVacuumAgent {
+            rng: Rng::seeded(seed),
+        }
+    }
+}
+
+impl Agent for RandomVacuumAgent {
+    type Percept = vacuum::Percept;
+    type Action = vacuum::Action;
+
+    fn program(&mut self, _percept: vacuum::Percept) -> vacuum::Action {
+        *[
+            vacuum::Action::Left,
+            vacuum::Action::Right,
+            vacuum::Action::Suck,
+            vacuum::Action::NoOp,
+        ]
+        .choose(&mut self.rng)
+        .unwrap()
+    }
+}
+
+/// Figure 2.8
+///
+/// The reflex vacuum agent: it sucks if its square is dirty, and otherwise moves to the other
+/// square.
+///
+/// # Examples
+///
+/// ```
+/// # use aima_rust::agents::{Agent, ReflexVacuumAgent};
+/// # use aima_rust::agents::envs::vacuum::{Action, Location, Status};
+/// let mut agent = ReflexVacuumAgent;
+///
+/// assert_eq!(agent.program((Location::A, Status::Dirty)), Action::Suck);
+/// assert_eq!(agent.program((Location::A, Status::Clean)), Action::Right);
+/// assert_eq!(agent.program((Location::B, Status::Clean)), Action::Left);
+/// ```
+pub struct ReflexVacuumAgent;
+
+impl Agent for ReflexVacuumAgent {
+    type Percept = vacuum::Percept;
+    type Action = vacuum::Action;
+
+    fn program(&mut self, (location, status): vacuum::Percept) -> vacuum::Action {
+        match (location, status) {
+            (_, vacuum::Status::Dirty) => vacuum::Action::Suck,
+            (vacuum::Location::A, _) => vacuum::Action::Right,
+            (vacuum::Location::B, _) => vacuum::Action::Left,
+        }
+    }
+}
+
+/// One time step of a recorded run.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct RecordedStep<E, P, A> {
+    /// What each agent perceived before acting.
+    pub percepts: Vec<P>,
+    /// The action each agent took.
+    pub actions: Vec<A>,
+    /// The environment after the actions took effect.
+    pub state: E,
+    /// Each agent's performance measure after the actions took effect.
+    pub performance: Vec<f64>,
+}
+
+/// Everything needed to reproduce a run: the seed the agents were created with, if any, the
+/// starting environment, and each step taken from it. With the `serde` feature enabled the record
+/// can be serialized, for example to save a failing run for later debugging.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Record<E, P, A> {
+    pub seed: Option<u64>,
+    pub initial: E,
+    pub steps: Vec<RecordedStep<E, P, A>>,
+}
+
+/// # Recorder
+///
+/// An environment wrapper that records every percept, action and resulting state, so that a run of
+/// nondeterministic agents can be inspected and replayed exactly.
+///
+/// # Examples
+///
+/// ```
+/// # use aima_rust::agents::{Environment, RandomVacuumAgent, Recorder};
+/// # use aima_rust::agents::envs::vacuum::{Location, Status, TrivialVacuumEnvironment};
+/// let seed = 11;
+/// let env = TrivialVacuumEnvironment::new(Status::Dirty, Status::Dirty, Location::B);
+/// let mut recorder = Recorder::new(env).with_seed(seed);
+///
+/// recorder.run(&mut [&mut RandomVacuumAgent::new(seed)], 20);
+///
+/// let record = recorder.record();
+/// let replayed = recorder.replay();
+/// let recorded: Vec<_> = record.steps.iter().map(|step| step.state.clone()).collect();
+///
+/// assert_eq!(record.seed, Some(11));
+/// assert_eq!(replayed, recorded);
+/// assert_eq!(replayed.last().unwrap().performance(0), recorder.performance(0));
+/// ```
+pub struct Recorder<E: Environment> {
+    env: E,
+    record: Record<E, E::Percept, E::Action>,
+}
+
+impl<E> Recorder<E>
+where
+    E: Environment + Clone,
+    E::Percept: Clone,
+    E::Action: Clone,
+{
+    /// Creates a new recorder given the environment to record.
+    pub fn new(env: E) -> Self {
+        Recorder {
+            record: Record {
+                seed: None,
+                initial: env.clone(),
+                steps: Vec::new(),
+            },
+            env,
+        }
+    }
+
+    /// Notes the seed the agents of the run were created with.
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.record.seed = Some(seed);
+        self
+    }
+
+    /// The environment being recorded.
+    pub fn env(&self) -> &E {
+        &self.env
+    }
+
+    /// The record of the run so far.
+    pub fn record(&self) -> &Record<E, E::Percept, E::Action> {
+        &self.record
+    }
+
+    /// Reconstructs the run by executing the recorded actions from the starting environment, and
+    /// returns the environment after each step.
+    pub fn replay(&self) -> Vec<E> {
+        let mut env = self.record.initial.clone();
+        self.record
+            .steps
+            .iter()
+            .map(|step| {
+                env.execute(step.actions.clone());
+                env.clone()
+            })
+            .collect()
+    }
+}
+
+impl<E> Environment for Recorder<E>
+where
+    E: Environment + Clone,
+    E::Percept: Clone,
+    E::Action: Clone,
+{
+    type Percept = E::Percept;
+    type Action = E::Action;
+
+    fn num_agents(&self) -> usize {
+        self.env.num_agents()
+    }
+
+    fn percept(&self, agent: usize) -> E::Percept {
+        self.env.percept(agent)
+    }
+
+    fn execute(&mut self, actions: Vec<E::Action>) {
+        let percepts = (0..self.env.num_agents())
+            .map(|agent| self.env.percept(agent))
+            .collect();
+        self.env.execute(actions.clone());
+        let performance = (0..self.env.num_agents())
+            .map(|agent| self.env.performance(agent))
+            .collect();
+        self.record.steps.push(RecordedStep {
+            percepts,
+            actions,
+            state: self.env.clone(),
+            performance,
+        });
+    }
+
+    fn performance(&self, agent: usize) -> f64 {
+        self.env.performance(agent)
+    }
+
+    fn is_done(&self) -> bool {
+        self.env.is_done()
+    }
+}
+
+/// One decision made by a `Traced` agent.
+#[derive(Clone, Debug, PartialEq)]
+pub struct TracedStep<P, A> {
+    /// The index of the decision, counting from zero.
+    pub step: usize,
+    pub percept: P,
+    pub action: A,
+}
+
+/// An agent wrapper that logs every percept the agent receives and the action it chooses, so
+/// that its decisions can be inspected while debugging or checked in a test. It acts exactly as
+/// the agent it wraps.
+///
+/// Where a `Recorder` logs a whole environment, `Traced` follows a single agent.
+///
+/// # Examples
+///
+/// ```
+/// # use aima_rust::agents::{Environment, ReflexVacuumAgent, Traced};
+/// # use aima_rust::agents::envs::vacuum::{Action, Location, Status, TrivialVacuumEnvironment};
+/// let mut env = TrivialVacuumEnvironment::new(Status::Dirty, Status::Dirty, Location::A);
+/// let mut agent = Traced::new(ReflexVacuumAgent);
+///
+/// env.run(&mut [&mut agent], 3);
+///
+/// let actions: Vec<_> = agent.history().iter().map(|step| step.action).collect();
+/// assert_eq!(actions, vec![Action::Suck, Action::Right, Action::Suck]);
+/// assert_eq!(agent.history()[1].percept, (Location::A, Status::Clean));
+/// assert_eq!(agent.history()[2].step, 2);
+/// ```
+pub struct Traced<A: Agent> {
+    agent: A,
+    history: Vec<TracedStep<A::Percept, A::Action>>,
+}
+
+impl<A: Agent> Traced<A> {
+    /// Creates a new wrapper given the agent to trace.
+    pub fn new(agent: A) -> Self {
+        Traced {
+            agent,
+            history: Vec::new(),
+        }
+    }
+
+    /// Every decision so far, oldest first.
+    pub fn history(&self) -> &[TracedStep<A::Percept, A::Action>] {
+        &self.history
+    }
+
+    /// The agent being traced.
+    pub fn agent(&self) -> &A {
+        &self.agent
+    }
+
+    /// Stops tracing and returns the agent.
+    pub fn into_inner(self) -> A {
+        self.agent
+    }
+}
+
+impl<A> Agent for Traced<A>
+where
+    A: Agent,
+    A::Percept: Clone,
+    A::Action: Clone,
+{
+    type Percept = A::Percept;
+    type Action = A::Action;
+
+    fn program(&mut self, percept: A::Percept) -> A::Action {
+        let action = self.agent.program(percept.clone());
+        self.history.push(TracedStep {
+            step: self.history.len(),
+            percept,
+            action: action.clone(),
+        });
+        action
+    }
+}
@@ -0,0 +1,988 @@
+//! # Constraint Satisfaction Problems
+//!
+//! This module covers material in Chapter 6, constraint satisfaction problems.
+//!
+//! ## Index of Algorithms
+//!
+//! | **Figure** | **Name**                          | **Module Link**
+//! |:-----------|:----------------------------------|:-------------------------------
+//! | 6          | CSP                               | `CSP`                                                    |
+//! | 6.1        | Map-Coloring Australia            | `australia`                                              |
+//! | 6.3        | AC-3                              | `ac3`                                                    |
+//! | 6          | AC-3 with Trace                   | `ac3_with_trace`                                         |
+//! | 6          | All-Different                     | `all_different`                                          |
+//! | 6          | Constraint Propagation            | `propagate`                                              |
+//! | 6.5        | Backtracking-Search               | `backtracking_search`                                    |
+//! | 6          | N-Queens                          | `n_queens`                                               |
+//! | 6.4        | Sudoku                            | `sudoku`                                                 |
+//! | 6.8        | Min-Conflicts                     | `min_conflicts`                                          |
+//! | 6          | Min-Conflicts with Restarts       | `min_conflicts_with_restarts`                            |
+//!
+use crate::util::Rng;
+use rand::seq::SliceRandom;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::fmt;
+use std::hash::Hash;
+use std::rc::Rc;
+
+/// A binary constraint, as seen from one end: whether a value of this variable is allowed
+/// together with a value of the other.
+type Check<D> = Rc<dyn Fn(&D, &D) -> bool>;
+
+/// The constraints on each arc: all of them must allow a pair of values.
+type Checks<V, D> = HashMap<(V, V), Vec<Check<D>>>;
+
+/// # Constraint Satisfaction Problem
+///
+/// A set of variables, each with a domain of values it may take, and binary constraints saying
+/// which pairs of values two variables may take together. Two variables are _neighbors_ if a
+/// constraint connects them. A solution assigns every variable a value from its domain such that
+/// every constraint holds.
+///
+/// The domains are the current ones: inference such as `ac3` narrows them in place, so a search
+/// can pass the narrowed problem on.
+///
+/// Problems are put together by chaining `with_constraint` onto `new`:
+///
+/// ```
+/// # use aima_rust::csp::CSP;
+/// let csp = CSP::new(vec![("x", vec![1, 2, 3]), ("y", vec![1, 2, 3])])
+///     .with_constraint("x", "y", |x, y| x < y);
+///
+/// assert_eq!(csp.neighbors(&"x"), &["y"]);
+/// assert!(csp.allows(&"x", &1, &"y", &2));
+/// assert!(!csp.allows(&"y", &1, &"x", &2));
+/// ```
+#[derive(Clone)]
+pub struct CSP<V, D> {
+    variables: Vec<V>,
+    domains: HashMap<V, Vec<D>>,
+    // Searches clone the problem at every step to narrow its domains, so the parts that never
+    // change are shared.
+    neighbors: Rc<HashMap<V, Vec<V>>>,
+    constraints: Rc<Checks<V, D>>,
+    hall_groups: Rc<Vec<Vec<V>>>,
+}
+
+impl<V: Clone + Eq + Hash, D: Clone + PartialEq> CSP<V, D> {
+    /// Creates a new CSP given each variable along with its domain. The variables keep the order
+    /// they are given in. There are no constraints until `with_constraint` adds them.
+    pub fn new(domains: Vec<(V, Vec<D>)>) -> Self {
+        CSP {
+            variables: domains.iter().map(|(var, _)| var.clone()).collect(),
+            neighbors: Rc::new(
+                domains
+                    .iter()
+                    .map(|(var, _)| (var.clone(), Vec::new()))
+                    .collect(),
+            ),
+            domains: domains.into_iter().collect(),
+            constraints: Rc::new(HashMap::new()),
+            hall_groups: Rc::new(Vec::new()),
+        }
+    }
+
+    /// Adds a constraint between `a` and `b`: `allowed(x, y)` says whether `a` may take `x`
+    /// while `b` takes `y`. If the two are already constrained, both constraints must hold.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `a` and `b` are the same variable, or if either isn't a variable of the CSP.
+    pub fn with_constraint<F>(mut self, a: V, b: V, allowed: F) -> Self
+    where
+        F: Fn(&D, &D) -> bool + 'static,
+    {
+        assert!(a != b, "a binary constraint needs two different variables");
+        assert!(
+            self.domains.contains_key(&a) && self.domains.contains_key(&b),
+            "the constraint is on a variable the CSP doesn't have"
+        );
+        let allowed = Rc::new(allowed);
+        let reversed = Rc::clone(&allowed);
+        self.add_check(a.clone(), b.clone(), Rc::new(move |x, y| allowed(x, y)));
+        self.add_check(b, a, Rc::new(move |y, x| reversed(x, y)));
+        self
+    }
+
+    fn add_check(&mut self, a: V, b: V, check: Check<D>) {
+        let neighbors = Rc::make_mut(&mut self.neighbors).get_mut(&a).unwrap();
+        if !neighbors.contains(&b) {
+            neighbors.push(b.clone());
+        }
+        Rc::make_mut(&mut self.constraints)
+            .entry((a, b))
+            .or_default()
+            .push(check);
+    }
+
+    /// Adds an all-different constraint: a `!=` constraint between every two of its variables,
+    /// and, if it was made `with_hall_sets`, its Hall-set pruning to `propagate`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a variable of the constraint isn't a variable of the CSP.
+    pub fn with_all_different(mut self, constraint: AllDifferent<V>) -> Self
+    where
+        D: 'static,
+    {
+        let variables = &constraint.variables;
+        for (i, a) in variables.iter().enumerate() {
+            for b in &variables[i + 1..] {
+                self = self.with_constraint(a.clone(), b.clone(), |x: &D, y: &D| x != y);
+            }
+        }
+        if constraint.hall_sets {
+            Rc::make_mut(&mut self.hall_groups).push(constraint.variables);
+        }
+        self
+    }
+
+    /// The variables, in the order they were given.
+    pub fn variables(&self) -> &[V] {
+        &self.variables
+    }
+
+    /// The values `var` may still take.
+    pub fn domain(&self, var: &V) -> &[D] {
+        &self.domains[var]
+    }
+
+    /// The variables `var` shares a constraint with, in the order the constraints were added.
+    pub fn neighbors(&self, var: &V) -> &[V] {
+        &self.neighbors[var]
+    }
+
+    /// Whether `a` taking `x` and `b` taking `y` satisfies the constraint between them. Two
+    /// variables with no constraint between them allow anything.
+    pub fn allows(&self, a: &V, x: &D, b: &V, y: &D) -> bool {
+        self.constraints
+            .get(&(a.clone(), b.clone()))
+            .is_none_or(|checks| checks.iter().all(|allowed| allowed(x, y)))
+    }
+
+    /// Whether `assignment` is a solution: it gives every variable a value, and every constraint
+    /// holds.
+    pub fn is_solution(&self, assignment: &HashMap<V, D>) -> bool {
+        self.variables
+            .iter()
+            .all(|var| assignment.contains_key(var))
+            && self
+                .arcs()
+                .iter()
+                .all(|(a, b)| self.allows(a, &assignment[a], b, &assignment[b]))
+    }
+
+    /// The number of neighbors of `var` that `assignment` gives a value which doesn't go with
+    /// `var` taking `value`. Neighbors without a value don't count.
+    pub fn conflicts(&self, var: &V, value: &D, assignment: &HashMap<V, D>) -> usize {
+        self.neighbors[var]
+            .iter()
+            .filter(|other| {
+                assignment
+                    .get(*other)
+                    .is_some_and(|y| !self.allows(var, value, other, y))
+            })
+            .count()
+    }
+
+    /// Narrows the domain of `var` to `value` alone, as when the variable is assigned.
+    pub fn assume(&mut self, var: &V, value: D) {
+        self.domains.insert(var.clone(), vec![value]);
+    }
+
+    /// Removes `value` from the domain of `var`, and returns whether it was there.
+    pub fn prune(&mut self, var: &V, value: &D) -> bool {
+        let domain = self.domains.get_mut(var).unwrap();
+        let before = domain.len();
+        domain.retain(|x| x != value);
+        domain.len() < before
+    }
+
+    /// Every arc of the CSP: each variable paired with each of its neighbors in turn.
+    fn arcs(&self) -> Vec<(V, V)> {
+        self.variables
+            .iter()
+            .flat_map(|xi| {
+                self.neighbors[xi]
+                    .iter()
+                    .map(move |xj| (xi.clone(), xj.clone()))
+            })
+            .collect()
+    }
+}
+
+impl<V: fmt::Debug, D: fmt::Debug> fmt::Debug for CSP<V, D> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("CSP")
+            .field("variables", &self.variables)
+            .field("domains", &self.domains)
+            .field("neighbors", &self.neighbors)
+            .finish()
+    }
+}
+
+/// Figure 6.1
+///
+/// The map-coloring problem of Australia: color each of its seven states and territories red,
+/// green or blue so that no two that share a border have the same color. Tasmania, `"T"`,
+/// borders none of the others.
+pub fn australia() -> CSP<&'static str, &'static str> {
+    let states = ["WA", "NT", "SA", "Q", "NSW", "V", "T"];
+    let borders = [
+        ("WA", "NT"),
+        ("WA", "SA"),
+        ("NT", "SA"),
+        ("NT", "Q"),
+        ("SA", "Q"),
+        ("SA", "NSW"),
+        ("SA", "V"),
+        ("Q", "NSW"),
+        ("NSW", "V"),
+    ];
+    let colors = vec!["red", "green", "blue"];
+    borders.iter().fold(
+        CSP::new(states.iter().map(|&s| (s, colors.clone())).collect()),
+        |csp, &(a, b)| csp.with_constraint(a, b, |x, y| x != y),
+    )
+}
+
+/// Places $n$ queens on an $n \times n$ chessboard so that none attacks another. Variable $i$
+/// is the queen in column $i$, and its value is the row she stands in, so no two queens share a
+/// column by construction. An all-different constraint keeps them out of each other's rows, and
+/// a constraint between each two columns out of each other's diagonals.
+pub fn n_queens(n: usize) -> CSP<usize, usize> {
+    let columns: Vec<usize> = (0..n).collect();
+    let mut csp = CSP::new(columns.iter().map(|&c| (c, columns.clone())).collect())
+        .with_all_different(all_different(&columns));
+    for a in 0..n {
+        for b in a + 1..n {
+            let distance = b - a;
+            csp = csp.with_constraint(a, b, move |x: &usize, y: &usize| x.abs_diff(*y) != distance);
+        }
+    }
+    csp
+}
+
+/// A cell of a Sudoku grid, as its row and column.
+pub type Cell = (usize, usize);
+
+/// Figure 6.4
+///
+/// A Sudoku puzzle as a CSP. Each cell of the grid is a variable, `(row, column)`, taking a
+/// digit from 1 to 9, and each row, column and 3x3 box is all-different. In `grid`, a given
+/// cell holds its digit and an empty one holds 0; a given cell's domain is its digit alone.
+///
+/// # Panics
+///
+/// Panics if `grid` holds a number greater than 9.
+///
+/// # Examples
+///
+/// Backtracking with the minimum-remaining-values ordering and maintained arc consistency
+/// solves a puzzle from the book's companion code:
+///
+/// ```
+/// # use aima_rust::csp::{backtracking_search, sudoku, to_grid, Inference, VariableOrdering};
+/// let puzzle = [
+///     [0, 0, 3, 0, 2, 0, 6, 0, 0],
+///     [9, 0, 0, 3, 0, 5, 0, 0, 1],
+///     [0, 0, 1, 8, 0, 6, 4, 0, 0],
+///     [0, 0, 8, 1, 0, 2, 9, 0, 0],
+///     [7, 0, 0, 0, 0, 0, 0, 0, 8],
+///     [0, 0, 6, 7, 0, 8, 2, 0, 0],
+///     [0, 0, 2, 6, 0, 9, 5, 0, 0],
+///     [8, 0, 0, 2, 0, 3, 0, 0, 9],
+///     [0, 0, 5, 0, 1, 0, 3, 0, 0],
+/// ];
+/// let csp = sudoku(puzzle);
+/// let ordering = VariableOrdering::MinimumRemainingValues;
+/// let solution = backtracking_search(&csp, ordering, Inference::MaintainArcConsistency);
+///
+/// assert_eq!(
+///     to_grid(&solution.unwrap()),
+///     [
+///         [4, 8, 3, 9, 2, 1, 6, 5, 7],
+///         [9, 6, 7, 3, 4, 5, 8, 2, 1],
+///         [2, 5, 1, 8, 7, 6, 4, 9, 3],
+///         [5, 4, 8, 1, 3, 2, 9, 7, 6],
+///         [7, 2, 9, 5, 6, 4, 1, 3, 8],
+///         [1, 3, 6, 7, 9, 8, 2, 4, 5],
+///         [3, 7, 2, 6, 8, 9, 5, 1, 4],
+///         [8, 1, 4, 2, 5, 3, 7, 6, 9],
+///         [6, 9, 5, 4, 1, 7, 3, 8, 2],
+///     ]
+/// );
+/// ```
+///
+/// No two givens clash here, but the top right cell needs the 9 that its column already has:
+///
+/// ```
+/// # use aima_rust::csp::{backtracking_search, sudoku, Inference, VariableOrdering};
+/// let mut grid = [[0; 9]; 9];
+/// grid[0] = [1, 2, 3, 4, 5, 6, 7, 8, 0];
+/// grid[5][8] = 9;
+///
+/// let csp = sudoku(grid);
+/// let ordering = VariableOrdering::MinimumRemainingValues;
+/// assert_eq!(backtracking_search(&csp, ordering, Inference::MaintainArcConsistency), None);
+/// ```
+pub fn sudoku(grid: [[u8; 9]; 9]) -> CSP<Cell, u8> {
+    let mut cells = Vec::new();
+    for (row, digits) in grid.iter().enumerate() {
+        for (column, &digit) in digits.iter().enumerate() {
+            assert!(digit <= 9, "a Sudoku cell holds a digit from 1 to 9, or 0");
+            let domain = if digit == 0 {
+                (1..=9).collect()
+            } else {
+                vec![digit]
+            };
+            cells.push(((row, column), domain));
+        }
+    }
+    let mut groups: Vec<Vec<Cell>> = Vec::new();
+    for i in 0..9 {
+        groups.push((0..9).map(|j| (i, j)).collect());
+        groups.push((0..9).map(|j| (j, i)).collect());
+        groups.push(
+            (0..9)
+                .map(|j| (i / 3 * 3 + j / 3, i % 3 * 3 + j % 3))
+                .collect(),
+        );
+    }
+    groups.iter().fold(CSP::new(cells), |csp, group| {
+        csp.with_all_different(all_different(group))
+    })
+}
+
+/// The grid of digits a solution of `sudoku` fills in, with 0 for any cell it leaves out.
+pub fn to_grid(assignment: &HashMap<Cell, u8>) -> [[u8; 9]; 9] {
+    let mut grid = [[0; 9]; 9];
+    for (&(row, column), &digit) in assignment {
+        grid[row][column] = digit;
+    }
+    grid
+}
+
+/// An all-different constraint over a group of variables, made by `all_different` and added to
+/// a CSP by `CSP::with_all_different`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct AllDifferent<V> {
+    variables: Vec<V>,
+    hall_sets: bool,
+}
+
+impl<V> AllDifferent<V> {
+    /// Has `propagate` prune the group by Hall sets, on top of the `!=` constraints between its
+    /// variables.
+    ///
+    /// A Hall set is a set of $k$ variables of the group whose domains hold only $k$ values
+    /// between them. Those variables must use up all $k$ values, so no other variable in the
+    /// group can take any of them; and if $k$ variables have fewer than $k$ values, the group
+    /// can't be satisfied at all. The `!=` constraints only see two variables at a time, so they
+    /// find neither unless the domains are down to single values. The pruning looks for Hall
+    /// sets whose values are the domain of one variable of the group or the union of the domains
+    /// of two, which catches the naked pairs and triples of Sudoku.
+    ///
+    /// Each round of pruning takes time cubic in the size of the group, on top of AC-3. That is
+    /// worth it when a group has about as many values as variables, as in Sudoku, n-queens or
+    /// scheduling a set of tasks into as many slots, where domains soon get small and overlap.
+    /// When there are many more values than variables, as in coloring a map with plenty of
+    /// colors, Hall sets hardly ever form and the pruning is wasted effort.
+    ///
+    /// # Examples
+    ///
+    /// Four cells of a Sudoku row, where the first two can only be 1 or 2. The `!=` constraints
+    /// see nothing to prune, but 1 and 2 are used up by the first two cells:
+    ///
+    /// ```
+    /// # use aima_rust::csp::{all_different, propagate, CSP};
+    /// let row = |constraint| {
+    ///     CSP::new(vec![
+    ///         ("a", vec![1, 2]),
+    ///         ("b", vec![1, 2]),
+    ///         ("c", vec![1, 2, 3, 4]),
+    ///         ("d", vec![1, 2, 3, 4]),
+    ///     ])
+    ///     .with_all_different(constraint)
+    /// };
+    /// let cells = ["a", "b", "c", "d"];
+    ///
+    /// let mut binary = row(all_different(&cells));
+    /// assert!(propagate(&mut binary));
+    /// assert_eq!(binary.domain(&"c"), &[1, 2, 3, 4]);
+    ///
+    /// let mut hall = row(all_different(&cells).with_hall_sets());
+    /// assert!(propagate(&mut hall));
+    /// assert_eq!(hall.domain(&"c"), &[3, 4]);
+    /// assert_eq!(hall.domain(&"d"), &[3, 4]);
+    ///
+    /// // Three cells can't share two values.
+    /// let mut crowded = row(all_different(&cells).with_hall_sets());
+    /// crowded.assume(&"c", 2);
+    /// crowded.assume(&"d", 2);
+    /// assert!(!propagate(&mut crowded));
+    /// ```
+    ///
+    /// On 8-queens with a few queens placed, the Hall sets narrow the remaining rows further, and
+    /// the search still finds a valid placement:
+    ///
+    /// ```
+    /// # use aima_rust::csp::{all_different, backtracking_search, n_queens, propagate, CSP};
+    /// # use aima_rust::csp::{Inference, VariableOrdering};
+    /// let columns: Vec<usize> = (0..8).collect();
+    /// let place = |hall: bool| {
+    ///     let mut csp = n_queens(8);
+    ///     if hall {
+    ///         csp = csp.with_all_different(all_different(&columns).with_hall_sets());
+    ///     }
+    ///     csp.assume(&0, 6);
+    ///     csp.assume(&1, 0);
+    ///     csp.assume(&2, 2);
+    ///     csp
+    /// };
+    /// let remaining = |csp: &CSP<usize, usize>| {
+    ///     columns.iter().map(|c| csp.domain(c).len()).sum::<usize>()
+    /// };
+    ///
+    /// let (mut binary, mut hall) = (place(false), place(true));
+    /// assert!(propagate(&mut binary));
+    /// assert!(propagate(&mut hall));
+    /// assert_eq!((remaining(&binary), remaining(&hall)), (14, 8));
+    ///
+    /// let ordering = VariableOrdering::MinimumRemainingValues;
+    /// let solution = backtracking_search(&hall, ordering, Inference::MaintainArcConsistency);
+    /// assert!(hall.is_solution(&solution.unwrap()));
+    /// ```
+    pub fn with_hall_sets(mut self) -> Self {
+        self.hall_sets = true;
+        self
+    }
+
+    /// The variables that must all take different values.
+    pub fn variables(&self) -> &[V] {
+        &self.variables
+    }
+}
+
+/// An all-different constraint on `vars`: no two of them may take the same value. On its own it
+/// stands for the `!=` constraints between every two of the variables; `with_hall_sets` adds
+/// the pruning that looks at the group as a whole.
+pub fn all_different<V: Clone>(vars: &[V]) -> AllDifferent<V> {
+    AllDifferent {
+        variables: vars.to_vec(),
+        hall_sets: false,
+    }
+}
+
+/// A value AC-3 removed: the arc $(X_i, X_j)$ it was revising, and the value of $X_i$ that no
+/// value left for $X_j$ allowed.
+pub type ArcRemoval<V, D> = ((V, V), D);
+
+/// Figure 6.3
+///
+/// AC-3 makes every arc of `csp` consistent, narrowing its domains in place. An arc
+/// $(X_i, X_j)$ is consistent when every value left for $X_i$ is allowed by some value left for
+/// $X_j$. Starting with every arc in a queue, AC-3 revises each in turn, removing the values of
+/// $X_i$ with no support; whenever that removes something, the arcs $(X_k, X_i)$ from the other
+/// neighbors of $X_i$ go back on the queue, as their support may be gone. It returns `false` as
+/// soon as a domain is empty, which means the CSP has no solution, and `true` otherwise.
+///
+/// # Examples
+///
+/// With Western Australia red and Queensland green, South Australia and the Northern Territory
+/// must both be blue, which their shared border rules out:
+///
+/// ```
+/// # use aima_rust::csp::{ac3, australia};
+/// let mut csp = australia();
+/// assert!(ac3(&mut csp));
+/// assert_eq!(csp.domain(&"SA"), &["red", "green", "blue"]);
+///
+/// csp.assume(&"WA", "red");
+/// csp.assume(&"Q", "green");
+/// assert!(!ac3(&mut csp));
+/// ```
+pub fn ac3<V, D>(csp: &mut CSP<V, D>) -> bool
+where
+    V: Clone + Eq + Hash,
+    D: Clone + PartialEq,
+{
+    let arcs = csp.arcs();
+    revise_arcs(csp, arcs, &mut Vec::new())
+}
+
+/// Runs `ac3`, and also returns every value it removed, in the order it removed them, along with
+/// the arc being revised at the time. Removing those values from the domains `csp` had before,
+/// in order, gives the domains it has after.
+///
+/// # Examples
+///
+/// With Western Australia and Victoria both red, red is ruled out for their neighbors, and
+/// nothing else:
+///
+/// ```
+/// # use aima_rust::csp::{ac3_with_trace, australia};
+/// let mut csp = australia();
+/// csp.assume(&"WA", "red");
+/// csp.assume(&"V", "red");
+/// let before = csp.clone();
+///
+/// let (consistent, trace) = ac3_with_trace(&mut csp);
+/// assert!(consistent);
+/// assert_eq!(
+///     trace,
+///     vec![
+///         (("NT", "WA"), "red"),
+///         (("SA", "WA"), "red"),
+///         (("NSW", "V"), "red"),
+///     ]
+/// );
+///
+/// let mut replayed = before;
+/// for ((var, _), value) in &trace {
+///     assert!(replayed.prune(var, value));
+/// }
+/// for var in csp.variables() {
+///     assert_eq!(replayed.domain(var), csp.domain(var));
+/// }
+/// ```
+pub fn ac3_with_trace<V, D>(csp: &mut CSP<V, D>) -> (bool, Vec<ArcRemoval<V, D>>)
+where
+    V: Clone + Eq + Hash,
+    D: Clone + PartialEq,
+{
+    let mut trace = Vec::new();
+    let arcs = csp.arcs();
+    let consistent = revise_arcs(csp, arcs, &mut trace);
+    (consistent, trace)
+}
+
+/// The loop of AC-3, starting from the queue `arcs` and logging each removal to `trace`.
+fn revise_arcs<V, D>(
+    csp: &mut CSP<V, D>,
+    arcs: Vec<(V, V)>,
+    trace: &mut Vec<ArcRemoval<V, D>>,
+) -> bool
+where
+    V: Clone + Eq + Hash,
+    D: Clone + PartialEq,
+{
+    let mut queued: HashSet<(V, V)> = arcs.iter().cloned().collect();
+    let mut queue: VecDeque<(V, V)> = arcs.into_iter().collect();
+    while let Some((xi, xj)) = queue.pop_front() {
+        queued.remove(&(xi.clone(), xj.clone()));
+        if revise(csp, &xi, &xj, trace) {
+            if csp.domains[&xi].is_empty() {
+                return false;
+            }
+            for xk in &csp.neighbors[&xi] {
+                if *xk != xj && queued.insert((xk.clone(), xi.clone())) {
+                    queue.push_back((xk.clone(), xi.clone()));
+                }
+            }
+        }
+    }
+    true
+}
+
+/// Removes the values of `xi` that no value of `xj` allows, and returns whether there were any.
+fn revise<V, D>(csp: &mut CSP<V, D>, xi: &V, xj: &V, trace: &mut Vec<ArcRemoval<V, D>>) -> bool
+where
+    V: Clone + Eq + Hash,
+    D: Clone + PartialEq,
+{
+    let supports = &csp.domains[xj];
+    let (kept, removed): (Vec<D>, Vec<D>) = csp.domains[xi]
+        .iter()
+        .cloned()
+        .partition(|x| supports.iter().any(|y| csp.allows(xi, x, xj, y)));
+    if removed.is_empty() {
+        return false;
+    }
+    csp.domains.insert(xi.clone(), kept);
+    trace.extend(removed.into_iter().map(|x| ((xi.clone(), xj.clone()), x)));
+    true
+}
+
+/// Constraint propagation: AC-3, together with the Hall-set pruning of every all-different
+/// constraint made `with_hall_sets`, repeated until neither removes anything more. Like `ac3`,
+/// it narrows the domains of `csp` in place, and returns `false` if one of them is emptied.
+pub fn propagate<V, D>(csp: &mut CSP<V, D>) -> bool
+where
+    V: Clone + Eq + Hash,
+    D: Clone + PartialEq,
+{
+    let arcs = csp.arcs();
+    propagate_from(csp, arcs)
+}
+
+/// Constraint propagation starting from the queue `arcs`.
+fn propagate_from<V, D>(csp: &mut CSP<V, D>, mut arcs: Vec<(V, V)>) -> bool
+where
+    V: Clone + Eq + Hash,
+    D: Clone + PartialEq,
+{
+    loop {
+        if !revise_arcs(csp, arcs, &mut Vec::new()) {
+            return false;
+        }
+        let mut changed = Vec::new();
+        for group in Rc::clone(&csp.hall_groups).iter() {
+            match prune_hall_sets(csp, group) {
+                Some(vars) => changed.extend(vars),
+                None => return false,
+            }
+        }
+        if changed.is_empty() {
+            return true;
+        }
+        arcs = changed
+            .iter()
+            .flat_map(|xi| {
+                csp.neighbors[xi]
+                    .iter()
+                    .map(move |xk| (xk.clone(), xi.clone()))
+            })
+            .collect();
+    }
+}
+
+/// Prunes `group` by its Hall sets, trying the domain of each variable and the union of the
+/// domains of each two as the values of a set. Returns the variables whose domains shrank, or
+/// `None` if some set of variables has fewer values than it needs.
+fn prune_hall_sets<V, D>(csp: &mut CSP<V, D>, group: &[V]) -> Option<Vec<V>>
+where
+    V: Clone + Eq + Hash,
+    D: Clone + PartialEq,
+{
+    let mut candidates: Vec<Vec<D>> = Vec::new();
+    for (i, a) in group.iter().enumerate() {
+        for b in &group[i..] {
+            let mut values = csp.domains[a].clone();
+            for y in &csp.domains[b] {
+                if !values.contains(y) {
+                    values.push(y.clone());
+                }
+            }
+            if !candidates.iter().any(|c| same_values(c, &values)) {
+                candidates.push(values);
+            }
+        }
+    }
+
+    let mut changed = Vec::new();
+    for values in candidates {
+        let (inside, outside): (Vec<&V>, Vec<&V>) = group
+            .iter()
+            .partition(|var| csp.domains[*var].iter().all(|x| values.contains(x)));
+        if inside.len() > values.len() {
+            return None;
+        }
+        if inside.len() == values.len() {
+            for var in outside {
+                let domain = csp.domains.get_mut(var).unwrap();
+                let before = domain.len();
+                domain.retain(|x| !values.contains(x));
+                if domain.len() < before && !changed.contains(var) {
+                    changed.push(var.clone());
+                }
+            }
+        }
+    }
+    Some(changed)
+}
+
+fn same_values<D: PartialEq>(a: &[D], b: &[D]) -> bool {
+    a.len() == b.len() && a.iter().all(|x| b.contains(x))
+}
+
+/// How `backtracking_search` picks the variable to assign next.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum VariableOrdering {
+    /// The first unassigned variable, in the order the CSP lists them.
+    Static,
+    /// The unassigned variable with the fewest values left in its domain, the first of them on
+    /// ties. Failing early on the most constrained variable prunes the search the most.
+    MinimumRemainingValues,
+}
+
+/// The inference `backtracking_search` makes after each assignment.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Inference {
+    /// None: values are only checked against the variables already assigned.
+    None,
+    /// Forward checking: removes the values of each neighbor of the assigned variable that the
+    /// assignment rules out.
+    ForwardChecking,
+    /// Maintaining arc consistency: runs `propagate` from the arcs into the assigned variable,
+    /// so that removals carry on past its neighbors.
+    MaintainArcConsistency,
+}
+
+/// Figure 6.5
+///
+/// Backtracking search assigns one variable at a time, trying each value still in its domain
+/// that is consistent with the variables already assigned, and backs up as soon as a variable has
+/// no such value left. `ordering` picks which variable to assign next, and `inference` how much
+/// each assignment narrows the domains of the others. Values are tried in the order of their
+/// domain. Returns a solution, or `None` if there is none.
+///
+/// # Examples
+///
+/// ```
+/// # use aima_rust::csp::{australia, backtracking_search, Inference, VariableOrdering};
+/// use Inference::{ForwardChecking, MaintainArcConsistency};
+/// let csp = australia();
+/// for inference in [Inference::None, ForwardChecking, MaintainArcConsistency] {
+///     let solution = backtracking_search(&csp, VariableOrdering::Static, inference).unwrap();
+///     assert!(csp.is_solution(&solution));
+///     assert_eq!(solution[&"WA"], "red");
+/// }
+///
+/// // Two colors aren't enough, as WA, NT and SA all border each other.
+/// let mut csp = australia();
+/// for state in csp.variables().to_vec() {
+///     csp.prune(&state, &"blue");
+/// }
+/// let ordering = VariableOrdering::MinimumRemainingValues;
+/// assert_eq!(backtracking_search(&csp, ordering, Inference::None), None);
+/// ```
+pub fn backtracking_search<V, D>(
+    csp: &CSP<V, D>,
+    ordering: VariableOrdering,
+    inference: Inference,
+) -> Option<HashMap<V, D>>
+where
+    V: Clone + Eq + Hash,
+    D: Clone + PartialEq,
+{
+    backtrack(csp, &mut HashMap::new(), ordering, inference)
+}
+
+fn backtrack<V, D>(
+    csp: &CSP<V, D>,
+    assignment: &mut HashMap<V, D>,
+    ordering: VariableOrdering,
+    inference: Inference,
+) -> Option<HashMap<V, D>>
+where
+    V: Clone + Eq + Hash,
+    D: Clone + PartialEq,
+{
+    let unassigned = csp
+        .variables
+        .iter()
+        .filter(|var| !assignment.contains_key(*var));
+    let var = match ordering {
+        VariableOrdering::Static => unassigned.into_iter().next(),
+        VariableOrdering::MinimumRemainingValues => {
+            unassigned.fold(None, |best: Option<&V>, var| match best {
+                Some(b) if csp.domains[b].len() <= csp.domains[var].len() => Some(b),
+                _ => Some(var),
+            })
+        }
+    };
+    let var = match var {
+        Some(var) => var.clone(),
+        None => return Some(assignment.clone()),
+    };
+
+    for value in &csp.domains[&var] {
+        let consistent = csp.neighbors[&var].iter().all(|other| {
+            assignment
+                .get(other)
+                .is_none_or(|y| csp.allows(&var, value, other, y))
+        });
+        if !consistent {
+            continue;
+        }
+        let mut narrowed = csp.clone();
+        narrowed.assume(&var, value.clone());
+        let feasible = match inference {
+            Inference::None => true,
+            Inference::ForwardChecking => csp.neighbors[&var].iter().all(|other| {
+                assignment.contains_key(other) || {
+                    let domain = narrowed.domains.get_mut(other).unwrap();
+                    domain.retain(|y| csp.allows(&var, value, other, y));
+                    !domain.is_empty()
+                }
+            }),
+            Inference::MaintainArcConsistency => {
+                let arcs = csp.neighbors[&var]
+                    .iter()
+                    .map(|other| (other.clone(), var.clone()))
+                    .collect();
+                propagate_from(&mut narrowed, arcs)
+            }
+        };
+        if feasible {
+            assignment.insert(var.clone(), value.clone());
+            if let Some(solution) = backtrack(&narrowed, assignment, ordering, inference) {
+                return Some(solution);
+            }
+            assignment.remove(&var);
+        }
+    }
+    None
+}
+
+/// Figure 6.8
+///
+/// Min-conflicts local search. It starts from a complete assignment, giving each variable in
+/// turn the value with the fewest conflicts with those before it. Then, for up to `max_steps`
+/// steps, it picks a variable in conflict at random and gives it the value with the fewest
+/// conflicts, breaking ties at random. Returns the first solution it reaches, or `None` if it
+/// runs out of steps. Randomness comes from a generator seeded with `seed`.
+///
+/// # Examples
+///
+/// Min-conflicts is remarkably fast on n-queens, whatever the size of the board:
+///
+/// ```
+/// # use aima_rust::csp::{min_conflicts, n_queens};
+/// let csp = n_queens(50);
+/// let solution = min_conflicts(&csp, 1_000, 7).unwrap();
+/// assert!(csp.is_solution(&solution));
+/// ```
+pub fn min_conflicts<V, D>(csp: &CSP<V, D>, max_steps: usize, seed: u64) -> Option<HashMap<V, D>>
+where
+    V: Clone + Eq + Hash,
+    D: Clone + PartialEq,
+{
+    min_conflicts_with_restarts(csp, max_steps, usize::MAX, seed).0
+}
+
+/// Min-conflicts that starts over when it gets stuck. Min-conflicts can wander for a long time
+/// on a plateau, where every move keeps the same number of conflicts, or sit in a local minimum
+/// it can't climb out of. Here, if `patience` steps in a row fail to bring the number of
+/// violated constraints below the fewest seen since the last start, the search restarts from a
+/// new assignment, built as in `min_conflicts` but with the variables taken in random order.
+/// The `max_steps` budget covers every start together.
+///
+/// Returns the solution, or `None` if the steps run out, along with the number of restarts.
+///
+/// # Examples
+///
+/// On 6-queens, an initial assignment can leave min-conflicts in a trap it never escapes, even
+/// with a budget of 1000 steps. Restarting after 10 steps without progress solves every one of
+/// twenty seeded runs that plain min-conflicts only partly solves:
+///
+/// ```
+/// # use aima_rust::csp::{min_conflicts, min_conflicts_with_restarts, n_queens};
+/// let csp = n_queens(6);
+/// let plain = (0..20)
+///     .filter(|&seed| min_conflicts(&csp, 1_000, seed).is_some())
+///     .count();
+/// let restarting = (0..20)
+///     .filter(|&seed| min_conflicts_with_restarts(&csp, 1_000, 10, seed).0.is_some())
+///     .count();
+/// assert!(plain < 20);
+/// assert_eq!(restarting, 20);
+///
+/// assert!(min_conflicts(&csp, 1_000, 2).is_none());
+/// let (solution, restarts) = min_conflicts_with_restarts(&csp, 1_000, 10, 2);
+/// assert!(csp.is_solution(&solution.unwrap()));
+/// assert!(restarts > 0);
+/// ```
+pub fn min_conflicts_with_restarts<V, D>(
+    csp: &CSP<V, D>,
+    max_steps: usize,
+    patience: usize,
+    seed: u64,
+) -> (Option<HashMap<V, D>>, usize)
+where
+    V: Clone + Eq + Hash,
+    D: Clone + PartialEq,
+{
+    if csp.variables.iter().any(|var| csp.domains[var].is_empty()) {
+        return (None, 0);
+    }
+    let mut rng = Rng::seeded(seed);
+    let mut order = csp.variables.clone();
+    let mut assignment = greedy_assignment(csp, &order, &mut rng);
+    let mut fewest = violations(csp, &assignment);
+    let (mut stalled, mut restarts) = (0, 0);
+    for _ in 0..max_steps {
+        let conflicted: Vec<&V> = csp
+            .variables
+            .iter()
+            .filter(|var| csp.conflicts(var, &assignment[*var], &assignment) > 0)
+            .collect();
+        let var = match conflicted.choose(&mut rng) {
+            Some(&var) => var.clone(),
+            None => return (Some(assignment), restarts),
+        };
+        let value = min_conflicts_value(csp, &var, &assignment, &mut rng);
+        assignment.insert(var, value);
+
+        let now = violations(csp, &assignment);
+        if now < fewest {
+            fewest = now;
+            stalled = 0;
+        } else {
+            stalled += 1;
+            if stalled >= patience {
+                order.shuffle(&mut rng);
+                assignment = greedy_assignment(csp, &order, &mut rng);
+                fewest = violations(csp, &assignment);
+                stalled = 0;
+                restarts += 1;
+            }
+        }
+    }
+    let solved = csp.is_solution(&assignment);
+    (if solved { Some(assignment) } else { None }, restarts)
+}
+
+/// Gives each variable of `order` in turn the value with the fewest conflicts with those before.
+fn greedy_assignment<V, D>(csp: &CSP<V, D>, order: &[V], rng: &mut Rng) -> HashMap<V, D>
+where
+    V: Clone + Eq + Hash,
+    D: Clone + PartialEq,
+{
+    let mut assignment = HashMap::new();
+    for var in order {
+        let value = min_conflicts_value(csp, var, &assignment, rng);
+        assignment.insert(var.clone(), value);
+    }
+    assignment
+}
+
+/// The value of `var` with the fewest conflicts with `assignment`, chosen at random among ties.
+fn min_conflicts_value<V, D>(
+    csp: &CSP<V, D>,
+    var: &V,
+    assignment: &HashMap<V, D>,
+    rng: &mut Rng,
+) -> D
+where
+    V: Clone + Eq + Hash,
+    D: Clone + PartialEq,
+{
+    let counts: Vec<usize> = csp.domains[var]
+        .iter()
+        .map(|value| csp.conflicts(var, value, assignment))
+        .collect();
+    let fewest = *counts.iter().min().unwrap();
+    let best: Vec<&D> = csp.domains[var]
+        .iter()
+        .zip(&counts)
+        .filter(|(_, &n)| n == fewest)
+        .map(|(value, _)| value)
+        .collect();
+    (*best.choose(rng).unwrap()).clone()
+}
+
+/// The number of constraints `assignment` violates, counting each pair of variables once.
+fn violations<V, D>(csp: &CSP<V, D>, assignment: &HashMap<V, D>) -> usize
+where
+    V: Clone + Eq + Hash,
+    D: Clone + PartialEq,
+{
+    let total: usize = csp
+        .variables
+        .iter()
+        .map(|var| csp.conflicts(var, &assignment[var], assignment))
+        .sum();
+    total / 2
+}
@@ -0,0 +1,480 @@
+//! # First-Order Logic
+//!
+//! This module covers material in Chapter 9.
+//!
+//! ## Index of Algorithms
+//!
+//! | **Figure** | **Name**                          | **Module Link**
+//! |:-----------|:----------------------------------|:-------------------------------
+//! | 9          | Subst                             | `subst`                                                  |
+//! | 9          | Substitution Composition          | `compose`                                                |
+//! | 9.1        | Unify                             | `unify`                                                  |
+//! | 9          | Definite Clause Knowledge Base    | `FolKB`                                                  |
+//! | 9.6        | FOL-BC-Ask                        | `fol_bc_ask`                                             |
+//!
+use std::collections::BTreeMap;
+use std::fmt;
+
+/// A term of first-order logic: a variable, a constant symbol, or a function symbol applied to
+/// argument terms.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Term {
+    Var(String),
+    Const(String),
+    Compound(String, Vec<Term>),
+}
+
+impl Term {
+    /// Creates a new variable given its name.
+    pub fn var(name: &str) -> Self {
+        Term::Var(name.to_string())
+    }
+
+    /// Creates a new constant given its name.
+    pub fn constant(name: &str) -> Self {
+        Term::Const(name.to_string())
+    }
+
+    /// Creates a new compound term given a function symbol and its arguments.
+    pub fn compound(functor: &str, args: Vec<Term>) -> Self {
+        Term::Compound(functor.to_string(), args)
+    }
+}
+
+impl fmt::Display for Term {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Term::Var(name) | Term::Const(name) => write!(f, "{}", name),
+            Term::Compound(functor, args) => {
+                write!(f, "{}(", functor)?;
+                for (i, arg) in args.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "{}", arg)?;
+                }
+                write!(f, ")")
+            }
+        }
+    }
+}
+
+/// A substitution, or binding list, mapping variable names to terms. Written $\\{x/A, y/B\\}$ in
+/// the text.
+pub type Substitution = BTreeMap<String, Term>;
+
+/// Applies the substitution `s` to `term`, replacing every bound variable by its binding.
+///
+/// The replacement is simultaneous: the bindings that are substituted in are not themselves
+/// rewritten. Use `compose` to chain substitutions.
+///
+/// # Examples
+///
+/// ```
+/// # use aima_rust::fol::{subst, Substitution, Term};
+/// let mut s = Substitution::new();
+/// s.insert("x".to_string(), Term::constant("John"));
+///
+/// let knows = Term::compound("Knows", vec![Term::var("x"), Term::var("y")]);
+///
+/// assert_eq!(subst(&s, &knows).to_string(), "Knows(John, y)");
+/// ```
+pub fn subst(s: &Substitution, term: &Term) -> Term {
+    match term {
+        Term::Var(name) => s.get(name).cloned().unwrap_or_else(|| term.clone()),
+        Term::Const(_) => term.clone(),
+        Term::Compound(functor, args) => Term::Compound(
+            functor.clone(),
+            args.iter().map(|arg| subst(s, arg)).collect(),
+        ),
+    }
+}
+
+/// Composes two substitutions into one whose effect is that of applying `s1` and then `s2`:
+///
+/// $$ \text{SUBST}(\text{COMPOSE}(\theta_1, \theta_2), p) = \text{SUBST}(\theta_2, \text{SUBST}(\theta_1, p)) $$
+///
+/// `s2` is applied to each binding of `s1`, and the bindings of `s2` for variables that `s1`
+/// doesn't bind are added. Bindings of a variable to itself are dropped.
+///
+/// Composition is associative but not commutative: the order in which substitutions are applied
+/// matters whenever one binds a variable that appears in the other's bindings.
+///
+/// # Examples
+///
+/// ```
+/// # use aima_rust::fol::{compose, Substitution, Term};
+/// let binding = |var: &str, term: Term| -> Substitution {
+///     vec![(var.to_string(), term)].into_iter().collect()
+/// };
+/// let x_y = binding("x", Term::var("y"));
+/// let y_a = binding("y", Term::constant("A"));
+///
+/// let composed = compose(&x_y, &y_a);
+/// assert_eq!(composed["x"], Term::constant("A"));
+/// assert_eq!(composed["y"], Term::constant("A"));
+///
+/// // Applying the substitutions the other way round leaves x bound to y.
+/// assert_eq!(compose(&y_a, &x_y)["x"], Term::var("y"));
+///
+/// // Associativity.
+/// let x_fy = binding("x", Term::compound("F", vec![Term::var("y")]));
+/// let y_z = binding("y", Term::var("z"));
+/// let z_b = binding("z", Term::constant("B"));
+/// assert_eq!(
+///     compose(&compose(&x_fy, &y_z), &z_b),
+///     compose(&x_fy, &compose(&y_z, &z_b))
+/// );
+/// ```
+pub fn compose(s1: &Substitution, s2: &Substitution) -> Substitution {
+    let mut composed: Substitution = s1
+        .iter()
+        .map(|(var, term)| (var.clone(), subst(s2, term)))
+        .collect();
+    for (var, term) in s2 {
+        composed.entry(var.clone()).or_insert_with(|| term.clone());
+    }
+    composed.retain(|var, term| *term != Term::Var(var.clone()));
+    composed
+}
+
+/// Figure 9.1
+///
+/// Finds the most general unifier of `x` and `y` that extends `theta`: a substitution that makes
+/// the two terms identical, or `None` if there is none. Predicates and their arguments are
+/// unified as compound terms.
+///
+/// Bindings are kept in triangular form, so a variable may be bound to a term that mentions
+/// another bound variable.
+///
+/// # Examples
+///
+/// ```
+/// # use aima_rust::fol::{subst, unify, Substitution, Term};
+/// let knows_john_x = Term::compound("Knows", vec![Term::constant("John"), Term::var("x")]);
+/// let knows_y_mother = Term::compound(
+///     "Knows",
+///     vec![Term::var("y"), Term::compound("Mother", vec![Term::var("y")])],
+/// );
+///
+/// let theta = unify(&knows_john_x, &knows_y_mother, &Substitution::new()).unwrap();
+/// assert_eq!(theta["y"], Term::constant("John"));
+/// assert_eq!(subst(&theta, &theta["x"]).to_string(), "Mother(John)");
+///
+/// let knows_x_elizabeth = Term::compound("Knows", vec![Term::var("x"), Term::constant("Elizabeth")]);
+/// let knows_x_bill = Term::compound("Knows", vec![Term::var("x"), Term::constant("Bill")]);
+/// assert_eq!(unify(&knows_x_elizabeth, &knows_x_bill, &Substitution::new()), None);
+/// ```
+pub fn unify(x: &Term, y: &Term, theta: &Substitution) -> Option<Substitution> {
+    unify_with_options(x, y, theta, true)
+}
+
+/// Unifies `x` and `y` like `unify`, with the occurs check made optional.
+///
+/// The occurs check refuses to bind a variable to a term that contains it, such as $x$ to
+/// $F(x)$. It costs time quadratic in the size of the terms, and Prolog omits it for speed.
+/// Without it, unification can succeed where no finite unifier exists: the resulting binding
+/// is cyclic and describes an infinite term, and inference built on it is unsound. Later
+/// operations that follow such a binding, including further unification, may fail to
+/// terminate. Only turn the check off when the terms are known not to produce such bindings.
+///
+/// # Examples
+///
+/// ```
+/// # use aima_rust::fol::{unify_with_options, Substitution, Term};
+/// let x = Term::var("x");
+/// let f_x = Term::compound("F", vec![Term::var("x")]);
+///
+/// assert_eq!(unify_with_options(&x, &f_x, &Substitution::new(), true), None);
+///
+/// let cyclic = unify_with_options(&x, &f_x, &Substitution::new(), false).unwrap();
+/// assert_eq!(cyclic["x"], f_x);
+/// ```
+pub fn unify_with_options(
+    x: &Term,
+    y: &Term,
+    theta: &Substitution,
+    occurs_check: bool,
+) -> Option<Substitution> {
+    match (x, y) {
+        _ if x == y => Some(theta.clone()),
+        (Term::Var(var), _) => unify_var(var, y, theta, occurs_check),
+        (_, Term::Var(var)) => unify_var(var, x, theta, occurs_check),
+        (Term::Compound(f, xs), Term::Compound(g, ys)) if f == g && xs.len() == ys.len() => {
+            xs.iter().zip(ys).try_fold(theta.clone(), |theta, (x, y)| {
+                unify_with_options(x, y, &theta, occurs_check)
+            })
+        }
+        _ => None,
+    }
+}
+
+fn unify_var(
+    var: &str,
+    x: &Term,
+    theta: &Substitution,
+    occurs_check: bool,
+) -> Option<Substitution> {
+    if let Some(binding) = theta.get(var) {
+        return unify_with_options(binding, x, theta, occurs_check);
+    }
+    if let Term::Var(name) = x {
+        if let Some(binding) = theta.get(name) {
+            return unify_with_options(&Term::Var(var.to_string()), binding, theta, occurs_check);
+        }
+    }
+    if occurs_check && occurs(var, x, theta) {
+        return None;
+    }
+    let mut extended = theta.clone();
+    extended.insert(var.to_string(), x.clone());
+    Some(extended)
+}
+
+/// Whether the variable `var` occurs in `x`, following the bindings in `theta`.
+fn occurs(var: &str, x: &Term, theta: &Substitution) -> bool {
+    match x {
+        Term::Var(name) if name == var => true,
+        Term::Var(name) => theta
+            .get(name)
+            .is_some_and(|binding| occurs(var, binding, theta)),
+        Term::Const(_) => false,
+        Term::Compound(_, args) => args.iter().any(|arg| occurs(var, arg, theta)),
+    }
+}
+
+/// A definite clause: a conjunction of atomic sentences, the body, implying a single atomic
+/// sentence, the head. A fact is a definite clause with an empty body. Atomic sentences are
+/// written as compound terms, such as `Parent(x, y)`, or constants for predicates without
+/// arguments.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct DefiniteClause {
+    pub body: Vec<Term>,
+    pub head: Term,
+}
+
+impl DefiniteClause {
+    /// Creates a new fact given the sentence it asserts.
+    pub fn fact(head: Term) -> Self {
+        DefiniteClause {
+            body: Vec::new(),
+            head,
+        }
+    }
+
+    /// Creates a new rule given the sentences of its body and its head.
+    pub fn rule(body: Vec<Term>, head: Term) -> Self {
+        DefiniteClause { body, head }
+    }
+
+    /// A copy of the clause with every variable renamed apart from those of any other clause,
+    /// by appending `#n`.
+    fn standardize_apart(&self, n: usize) -> DefiniteClause {
+        let rename = |term: &Term| rename_vars(term, n);
+        DefiniteClause {
+            body: self.body.iter().map(rename).collect(),
+            head: rename(&self.head),
+        }
+    }
+}
+
+fn rename_vars(term: &Term, n: usize) -> Term {
+    match term {
+        Term::Var(name) => Term::Var(format!("{}#{}", name, n)),
+        Term::Const(_) => term.clone(),
+        Term::Compound(functor, args) => Term::Compound(
+            functor.clone(),
+            args.iter().map(|arg| rename_vars(arg, n)).collect(),
+        ),
+    }
+}
+
+impl fmt::Display for DefiniteClause {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (i, conjunct) in self.body.iter().enumerate() {
+            write!(f, "{}{}", if i > 0 { " ∧ " } else { "" }, conjunct)?;
+        }
+        if !self.body.is_empty() {
+            write!(f, " ⇒ ")?;
+        }
+        write!(f, "{}", self.head)
+    }
+}
+
+/// A first-order knowledge base of definite clauses.
+#[derive(Clone, Debug, Default)]
+pub struct FolKB {
+    clauses: Vec<DefiniteClause>,
+}
+
+impl FolKB {
+    /// Creates a new, empty knowledge base.
+    pub fn new() -> Self {
+        FolKB::default()
+    }
+
+    /// Adds `clause` to the knowledge base.
+    pub fn tell(&mut self, clause: DefiniteClause) {
+        self.clauses.push(clause);
+    }
+
+    /// The clauses in the knowledge base, in the order they were told.
+    pub fn clauses(&self) -> &[DefiniteClause] {
+        &self.clauses
+    }
+}
+
+/// The answers found by `fol_bc_ask`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct BcAnswers {
+    /// One substitution for the variables of the query per distinct answer, in the order found.
+    pub answers: Vec<Substitution>,
+    /// Whether the depth limit cut off some line of proof, so that there may be more answers.
+    pub limit_reached: bool,
+}
+
+/// Figure 9.6
+///
+/// Backward chaining: proves `query` by finding clauses whose head unifies with it and then,
+/// recursively, proving their bodies. Every way of proving the query gives an answer, a binding
+/// for each of its variables.
+///
+/// Over recursive clauses, such as an ancestor relation defined in terms of itself, backward
+/// chaining can recurse forever. So proofs are limited to a depth of `max_depth` rules: at depth
+/// zero only facts are used, and each rule applied uses up one level for the proofs of its body.
+/// This trades completeness for termination: an answer whose every proof is deeper than the
+/// limit isn't found, and `limit_reached` tells whether the limit stopped any line of proof.
+///
+/// # Examples
+///
+/// ```
+/// # use aima_rust::fol::{fol_bc_ask, DefiniteClause, FolKB, Term};
+/// let atom = |predicate: &str, x: Term, y: Term| Term::compound(predicate, vec![x, y]);
+/// let (x, y, z) = (Term::var("x"), Term::var("y"), Term::var("z"));
+///
+/// let mut kb = FolKB::new();
+/// for (parent, child) in &[("Abe", "Homer"), ("Homer", "Bart"), ("Homer", "Lisa")] {
+///     let fact = atom("Parent", Term::constant(parent), Term::constant(child));
+///     kb.tell(DefiniteClause::fact(fact));
+/// }
+/// kb.tell(DefiniteClause::rule(
+///     vec![atom("Parent", x.clone(), y.clone())],
+///     atom("Ancestor", x.clone(), y.clone()),
+/// ));
+/// kb.tell(DefiniteClause::rule(
+///     vec![atom("Parent", x.clone(), y.clone()), atom("Ancestor", y.clone(), z.clone())],
+///     atom("Ancestor", x.clone(), z.clone()),
+/// ));
+///
+/// let query = atom("Ancestor", Term::constant("Abe"), Term::var("who"));
+/// let descendants = |max_depth| {
+///     let result = fol_bc_ask(&kb, &query, max_depth);
+///     let names: Vec<String> = result.answers.iter().map(|t| t["who"].to_string()).collect();
+///     (names, result.limit_reached)
+/// };
+///
+/// // With no rules allowed, only facts answer, and there are no Ancestor facts.
+/// assert_eq!(descendants(0), (vec![], true));
+/// assert_eq!(descendants(1).0, vec!["Homer"]);
+/// assert_eq!(descendants(2).0, vec!["Homer", "Bart", "Lisa"]);
+///
+/// // Parent is defined by facts alone, so the limit never matters.
+/// let children = fol_bc_ask(&kb, &atom("Parent", Term::constant("Homer"), Term::var("c")), 0);
+/// assert_eq!(children.answers.len(), 2);
+/// assert!(!children.limit_reached);
+/// ```
+pub fn fol_bc_ask(kb: &FolKB, query: &Term, max_depth: usize) -> BcAnswers {
+    let mut search = BackwardChaining {
+        kb,
+        renamed: 0,
+        limit_reached: false,
+    };
+    let mut variables = Vec::new();
+    collect_vars(query, &mut variables);
+
+    let mut answers: Vec<Substitution> = Vec::new();
+    for theta in search.or(query, &Substitution::new(), max_depth) {
+        let answer: Substitution = variables
+            .iter()
+            .map(|var| (var.clone(), resolve(&theta, &Term::Var(var.clone()))))
+            .collect();
+        if !answers.contains(&answer) {
+            answers.push(answer);
+        }
+    }
+    BcAnswers {
+        answers,
+        limit_reached: search.limit_reached,
+    }
+}
+
+/// The state of one backward-chaining query.
+struct BackwardChaining<'a> {
+    kb: &'a FolKB,
+    /// The number of clauses standardized apart so far, used to name their variables.
+    renamed: usize,
+    limit_reached: bool,
+}
+
+impl BackwardChaining<'_> {
+    /// FOL-BC-OR: every substitution extending `theta` under which `goal` can be proved.
+    fn or(&mut self, goal: &Term, theta: &Substitution, depth: usize) -> Vec<Substitution> {
+        let mut results = Vec::new();
+        for clause in self.kb.clauses() {
+            self.renamed += 1;
+            let clause = clause.standardize_apart(self.renamed);
+            if let Some(theta) = unify(&clause.head, goal, theta) {
+                if clause.body.is_empty() {
+                    results.push(theta);
+                } else if depth == 0 {
+                    self.limit_reached = true;
+                } else {
+                    results.extend(self.and(&clause.body, &theta, depth - 1));
+                }
+            }
+        }
+        results
+    }
+
+    /// FOL-BC-AND: every substitution extending `theta` under which all of `goals` can be proved.
+    fn and(&mut self, goals: &[Term], theta: &Substitution, depth: usize) -> Vec<Substitution> {
+        match goals.split_first() {
+            None => vec![theta.clone()],
+            Some((first, rest)) => {
+                let mut results = Vec::new();
+                for theta in self.or(&subst(theta, first), theta, depth) {
+                    results.extend(self.and(rest, &theta, depth));
+                }
+                results
+            }
+        }
+    }
+}
+
+/// The variables of `term`, in order of first appearance.
+fn collect_vars(term: &Term, vars: &mut Vec<String>) {
+    match term {
+        Term::Var(name) => {
+            if !vars.contains(name) {
+                vars.push(name.clone());
+            }
+        }
+        Term::Const(_) => {}
+        Term::Compound(_, args) => args.iter().for_each(|arg| collect_vars(arg, vars)),
+    }
+}
+
+/// Applies the triangular substitution `theta` to `term` until no bound variable is left.
+fn resolve(theta: &Substitution, term: &Term) -> Term {
+    match term {
+        Term::Var(name) => match theta.get(name) {
+            Some(binding) => resolve(theta, binding),
+            None => term.clone(),
+        },
+        Term::Const(_) => term.clone(),
+        Term::Compound(functor, args) => Term::Compound(
+            functor.clone(),
+            args.iter().map(|arg| resolve(theta, arg)).collect(),
+        ),
+    }
+}
@@ -0,0 +1,1100 @@
+//! # Adversarial Search
+//!
+//! This module covers material in Chapter 5.
+//!
+//! ## Index of Algorithms
+//!
+//! | **Figure** | **Name**                          | **Module Link**
+//! |:-----------|:----------------------------------|:-------------------------------
+//! | 5          | Game                              | `Game`                                                   |
+//! | 5.3        | Minimax-Decision                  | `minimax_decision`                                       |
+//! | 5          | Heuristic Minimax-Decision        | `minimax_cutoff_decision`                                |
+//! | 5.7        | Alpha-Beta-Search                 | `alphabeta_search`                                       |
+//! | 5          | Heuristic Alpha-Beta-Search       | `alphabeta_cutoff_search`                                |
+//! | 5          | Iterative Deepening Alpha-Beta    | `best_move_within`                                       |
+//! | 5          | Monte-Carlo-Tree-Search           | `mcts`                                                   |
+//! | 5          | Tic-Tac-Toe                       | [`TicTacToe`](tic_tac_toe/index.html)                    |
+//! | 5          | Connect Four                      | [`ConnectFour`](connect_four/index.html)                 |
+//!
+use crate::util::Rng;
+use rand::seq::SliceRandom;
+use rand::Rng as _;
+use std::cell::Cell;
+use std::time::{Duration, Instant};
+
+/// A two-player, turn-taking, zero-sum game with perfect information: an initial state, whose
+/// turn it is, the legal moves, a transition model, a terminal test and a utility function.
+pub trait Game {
+    type State: Clone;
+    type Action: Clone;
+    type Player: Clone + Eq;
+
+    /// The state the game starts in.
+    fn initial_state(&self) -> Self::State;
+
+    /// The player whose turn it is in `state`.
+    fn to_move(&self, state: &Self::State) -> Self::Player;
+
+    /// The legal moves in `state`.
+    fn actions(&self, state: &Self::State) -> Vec<Self::Action>;
+
+    /// The legal moves in `state` in the order the alpha-beta searches should try them: the same
+    /// moves as `actions`, which stays the authority on what is legal, but best first if the game
+    /// can guess which moves are best. A good ordering lets alpha-beta prune far more of the tree.
+    /// By default, the order of `actions`.
+    ///
+    /// # Examples
+    ///
+    /// Connect Four tries the center columns first. Searching four moves ahead with the columns
+    /// left to right instead finds the same move, but only after evaluating many more positions:
+    ///
+    /// ```
+    /// # use aima_rust::games::{alphabeta_cutoff_search, Game};
+    /// # use aima_rust::games::connect_four::{evaluate, Board, ConnectFour, Player};
+    /// # use std::cell::Cell;
+    /// struct Counted {
+    ///     center_first: bool,
+    ///     positions: Cell<usize>,
+    /// }
+    ///
+    /// impl Game for Counted {
+    ///     type State = Board;
+    ///     type Action = usize;
+    ///     type Player = Player;
+    ///
+    ///     fn initial_state(&self) -> Board { ConnectFour.initial_state() }
+    ///     fn to_move(&self, board: &Board) -> Player { ConnectFour.to_move(board) }
+    ///     fn actions(&self, board: &Board) -> Vec<usize> { ConnectFour.actions(board) }
+    ///     fn terminal_test(&self, board: &Board) -> bool { ConnectFour.terminal_test(board) }
+    ///     fn utility(&self, board: &Board, player: &Player) -> f64 {
+    ///         ConnectFour.utility(board, player)
+    ///     }
+    ///
+    ///     fn ordered_actions(&self, board: &Board) -> Vec<usize> {
+    ///         if self.center_first {
+    ///             ConnectFour.ordered_actions(board)
+    ///         } else {
+    ///             ConnectFour.actions(board)
+    ///         }
+    ///     }
+    ///
+    ///     fn result(&self, board: &Board, column: &usize) -> Board {
+    ///         self.positions.set(self.positions.get() + 1);
+    ///         ConnectFour.result(board, column)
+    ///     }
+    /// }
+    ///
+    /// let board = Board::from_moves(&[0, 6, 1, 6, 2]);
+    /// let search = |center_first: bool| {
+    ///     let game = Counted { center_first, positions: Cell::new(0) };
+    ///     let action = alphabeta_cutoff_search(&game, &board, |_, depth| depth >= 4, evaluate);
+    ///     (action, game.positions.get())
+    /// };
+    ///
+    /// let (ordered, ordered_positions) = search(true);
+    /// let (plain, plain_positions) = search(false);
+    ///
+    /// assert_eq!(ordered, Some(3));
+    /// assert_eq!(ordered, plain);
+    /// assert!(ordered_positions * 3 < plain_positions);
+    /// ```
+    fn ordered_actions(&self, state: &Self::State) -> Vec<Self::Action> {
+        self.actions(state)
+    }
+
+    /// The state reached by making `action` in `state`.
+    fn result(&self, state: &Self::State, action: &Self::Action) -> Self::State;
+
+    /// Whether the game is over in `state`.
+    fn terminal_test(&self, state: &Self::State) -> bool;
+
+    /// The final value of the terminal `state` to `player`.
+    fn utility(&self, state: &Self::State, player: &Self::Player) -> f64;
+}
+
+/// The pieces of a minimax search that stay fixed as it recurses.
+struct Minimax<'a, G: Game, C, E> {
+    game: &'a G,
+    player: G::Player,
+    cutoff_test: C,
+    eval: E,
+}
+
+impl<'a, G, C, E> Minimax<'a, G, C, E>
+where
+    G: Game,
+    C: Fn(&G::State, usize) -> bool,
+    E: Fn(&G::State, &G::Player) -> f64,
+{
+    /// The best move for `player` in `state`; ties go to the move listed first.
+    fn decision(&self, state: &G::State) -> Option<G::Action> {
+        let mut best = None;
+        let mut best_value = f64::NEG_INFINITY;
+        for action in self.game.actions(state) {
+            let v = self.value(&self.game.result(state, &action), 1);
+            if best.is_none() || v > best_value {
+                best_value = v;
+                best = Some(action);
+            }
+        }
+        best
+    }
+
+    /// The value of `state` to `player`, `depth` moves below the root.
+    fn value(&self, state: &G::State, depth: usize) -> f64 {
+        if self.game.terminal_test(state) {
+            return self.game.utility(state, &self.player);
+        }
+        if (self.cutoff_test)(state, depth) {
+            return (self.eval)(state, &self.player);
+        }
+        let values = self
+            .game
+            .actions(state)
+            .into_iter()
+            .map(|action| self.value(&self.game.result(state, &action), depth + 1));
+        if self.game.to_move(state) == self.player {
+            values.fold(f64::NEG_INFINITY, f64::max)
+        } else {
+            values.fold(f64::INFINITY, f64::min)
+        }
+    }
+}
+
+/// The pieces of an alpha-beta search that stay fixed as it recurses.
+struct AlphaBeta<'a, G: Game, C, E> {
+    game: &'a G,
+    player: G::Player,
+    cutoff_test: C,
+    eval: E,
+    /// When to give up; once it passes, the values the search returns are meaningless.
+    deadline: Option<Instant>,
+    expired: Cell<bool>,
+    /// Whether `cutoff_test` stopped the search anywhere.
+    cut_off: Cell<bool>,
+}
+
+impl<'a, G, C, E> AlphaBeta<'a, G, C, E>
+where
+    G: Game,
+    C: Fn(&G::State, usize) -> bool,
+    E: Fn(&G::State, &G::Player) -> f64,
+{
+    fn new(game: &'a G, state: &G::State, cutoff_test: C, eval: E) -> Self {
+        AlphaBeta {
+            game,
+            player: game.to_move(state),
+            cutoff_test,
+            eval,
+            deadline: None,
+            expired: Cell::new(false),
+            cut_off: Cell::new(false),
+        }
+    }
+
+    /// The best move for `player` in `state`; ties go to the move listed first.
+    fn decision(&self, state: &G::State) -> Option<G::Action> {
+        let mut best = None;
+        let mut alpha = f64::NEG_INFINITY;
+        for action in self.game.ordered_actions(state) {
+            let v = self.value(&self.game.result(state, &action), alpha, f64::INFINITY, 1);
+            if best.is_none() || v > alpha {
+                alpha = v;
+                best = Some(action);
+            }
+        }
+        best
+    }
+
+    /// The value of `state` to `player`, `depth` moves below the root.
+    fn value(&self, state: &G::State, mut alpha: f64, mut beta: f64, depth: usize) -> f64 {
+        if self.game.terminal_test(state) {
+            return self.game.utility(state, &self.player);
+        }
+        if self
+            .deadline
+            .is_some_and(|deadline| Instant::now() >= deadline)
+        {
+            self.expired.set(true);
+            return 0.0;
+        }
+        if (self.cutoff_test)(state, depth) {
+            self.cut_off.set(true);
+            return (self.eval)(state, &self.player);
+        }
+        let children = self
+            .game
+            .ordered_actions(state)
+            .into_iter()
+            .map(|action| self.game.result(state, &action));
+        if self.game.to_move(state) == self.player {
+            let mut v = f64::NEG_INFINITY;
+            for child in children {
+                v = v.max(self.value(&child, alpha, beta, depth + 1));
+                if v >= beta {
+                    return v;
+                }
+                alpha = alpha.max(v);
+            }
+            v
+        } else {
+            let mut v = f64::INFINITY;
+            for child in children {
+                v = v.min(self.value(&child, alpha, beta, depth + 1));
+                if v <= alpha {
+                    return v;
+                }
+                beta = beta.min(v);
+            }
+            v
+        }
+    }
+}
+
+/// Figure 5.3
+///
+/// Chooses the move for the player to move in `state` that leads to the state with the highest
+/// minimax value, searching the whole game tree down to the terminal states. Returns `None` if
+/// there are no legal moves.
+///
+/// The search visits every state in the tree, so it is only practical for very small games;
+/// `alphabeta_search` reaches the same decision while skipping branches that can't matter.
+pub fn minimax_decision<G: Game>(game: &G, state: &G::State) -> Option<G::Action> {
+    minimax_cutoff_decision(game, state, |_, _| false, |_, _| 0.0)
+}
+
+/// Minimax decision that stops early and estimates the value of the positions it doesn't search
+/// through to the end.
+///
+/// The signatures of the two hooks are:
+///
+/// - `cutoff_test(&G::State, depth: usize) -> bool`, asked at each non-terminal state reached
+///   `depth` moves below `state`. Returning `true` stops the search there.
+/// - `eval(&G::State, player: &G::Player) -> f64`, the estimated value of a cut-off state to the
+///   player making the decision. It should stay strictly between the utilities of a loss and a
+///   win, so that a certain outcome always outweighs an estimate.
+///
+/// # Examples
+///
+/// ```
+/// # use aima_rust::games::minimax_cutoff_decision;
+/// # use aima_rust::games::connect_four::{evaluate, Board, ConnectFour};
+/// // Red threatens to complete the bottom row, so Yellow must play in column 3.
+/// let board = Board::from_moves(&[0, 6, 1, 6, 2]);
+///
+/// let reply = minimax_cutoff_decision(&ConnectFour, &board, |_, depth| depth >= 2, evaluate);
+///
+/// assert_eq!(reply, Some(3));
+/// assert!(board.is_playable(3));
+/// ```
+pub fn minimax_cutoff_decision<G, C, E>(
+    game: &G,
+    state: &G::State,
+    cutoff_test: C,
+    eval: E,
+) -> Option<G::Action>
+where
+    G: Game,
+    C: Fn(&G::State, usize) -> bool,
+    E: Fn(&G::State, &G::Player) -> f64,
+{
+    Minimax {
+        game,
+        player: game.to_move(state),
+        cutoff_test,
+        eval,
+    }
+    .decision(state)
+}
+
+/// Figure 5.7
+///
+/// Chooses the move for the player to move in `state` by searching the game tree all the way to
+/// the terminal states, pruning branches that can't affect the decision. Returns `None` if there
+/// are no legal moves.
+///
+/// The search is exact, so it is only practical for small games; see `alphabeta_cutoff_search`
+/// for larger ones.
+pub fn alphabeta_search<G: Game>(game: &G, state: &G::State) -> Option<G::Action> {
+    alphabeta_cutoff_search(game, state, |_, _| false, |_, _| 0.0)
+}
+
+/// Alpha-beta search that stops early and estimates the value of the positions it doesn't search
+/// through to the end.
+///
+/// `cutoff_test` and `eval` have the same signatures as in `minimax_cutoff_decision`. When
+/// `cutoff_test(state, depth)` returns `true`, the search uses `eval(state, player)` in place of
+/// the state's true value, and the decision agrees with `minimax_cutoff_decision` given the same
+/// hooks.
+///
+/// # Examples
+///
+/// Looking two moves ahead in Connect Four is enough to block a row of three:
+///
+/// ```
+/// # use aima_rust::games::alphabeta_cutoff_search;
+/// # use aima_rust::games::connect_four::{evaluate, Board, ConnectFour};
+/// let board = Board::from_moves(&[0, 6, 1, 6, 2]);
+///
+/// let blocking = alphabeta_cutoff_search(&ConnectFour, &board, |_, depth| depth >= 2, evaluate);
+///
+/// assert_eq!(blocking, Some(3));
+/// ```
+pub fn alphabeta_cutoff_search<G, C, E>(
+    game: &G,
+    state: &G::State,
+    cutoff_test: C,
+    eval: E,
+) -> Option<G::Action>
+where
+    G: Game,
+    C: Fn(&G::State, usize) -> bool,
+    E: Fn(&G::State, &G::Player) -> f64,
+{
+    AlphaBeta::new(game, state, cutoff_test, eval).decision(state)
+}
+
+/// Chooses a move for the player to move in `state` within roughly `time_budget`, by iterative
+/// deepening: alpha-beta search to depth one, then two, and so on, using `eval` at the depth limit
+/// as in `alphabeta_cutoff_search`. Returns the move found by the deepest search that finished,
+/// together with that depth, or `None` if there are no legal moves.
+///
+/// A search that runs out of time is abandoned, so the function returns shortly after the budget
+/// expires. The depth-one search always runs to completion so that there is a move to return. The
+/// deepening stops early once a search reaches every terminal state, since its decision is then
+/// exact. Time is measured with a monotonic clock.
+///
+/// # Examples
+///
+/// ```
+/// # use aima_rust::games::best_move_within;
+/// # use aima_rust::games::connect_four::{evaluate, Board, ConnectFour};
+/// # use std::time::{Duration, Instant};
+/// let board = Board::from_moves(&[0, 6, 1, 6, 2]);
+///
+/// let start = Instant::now();
+/// let (quick, quick_depth) =
+///     best_move_within(&ConnectFour, &board, Duration::from_millis(1), evaluate).unwrap();
+/// // A generous bound: a search that ignored its deadline would run for far longer.
+/// assert!(start.elapsed() < Duration::from_secs(10));
+/// assert!(board.is_playable(quick));
+///
+/// let (considered, depth) =
+///     best_move_within(&ConnectFour, &board, Duration::from_millis(200), evaluate).unwrap();
+/// assert!(depth >= 2 && depth >= quick_depth);
+/// // Searching at least two moves ahead sees Red's threat to complete the bottom row.
+/// assert_eq!(considered, 3);
+/// ```
+pub fn best_move_within<G, E>(
+    game: &G,
+    state: &G::State,
+    time_budget: Duration,
+    eval: E,
+) -> Option<(G::Action, usize)>
+where
+    G: Game,
+    E: Fn(&G::State, &G::Player) -> f64,
+{
+    let deadline = Instant::now() + time_budget;
+    let mut best = None;
+    for limit in 1.. {
+        let mut search = AlphaBeta::new(game, state, |_: &G::State, depth| depth >= limit, &eval);
+        if limit > 1 {
+            search.deadline = Some(deadline);
+        }
+        let action = search.decision(state);
+        if search.expired.get() {
+            break;
+        }
+        best = action.map(|action| (action, limit));
+        if !search.cut_off.get() || Instant::now() >= deadline {
+            break;
+        }
+    }
+    best
+}
+
+/// What Monte Carlo tree search learned about one move from the root.
+#[derive(Clone, Debug, PartialEq)]
+pub struct MctsChild<A> {
+    pub action: A,
+    /// The number of playouts that went through this move.
+    pub visits: usize,
+    /// The total reward of those playouts to the player making the move: one for each win and
+    /// one half for each draw.
+    pub wins: f64,
+}
+
+impl<A> MctsChild<A> {
+    /// The average reward of the playouts through this move.
+    pub fn win_rate(&self) -> f64 {
+        if self.visits == 0 {
+            0.0
+        } else {
+            self.wins / self.visits as f64
+        }
+    }
+}
+
+/// A node of the Monte Carlo search tree, kept in an arena and linked by index.
+struct MctsNode<G: Game> {
+    state: G::State,
+    /// The player who made the move that led here from the parent; `None` at the root.
+    mover: Option<G::Player>,
+    /// The position of that move among the parent's actions.
+    index: usize,
+    parent: Option<usize>,
+    children: Vec<usize>,
+    untried: Vec<(usize, G::Action)>,
+    visits: usize,
+    wins: f64,
+}
+
+/// The exploration constant of UCB1.
+const EXPLORATION: f64 = std::f64::consts::SQRT_2;
+
+/// Runs `iterations` rounds of Monte Carlo tree search from `state` and reports the statistics
+/// gathered for each legal move, in the order the game lists them.
+///
+/// Each round follows the four steps of UCT: *selection* descends the tree, choosing the child
+/// that maximizes the UCB1 bound
+///
+/// $$ \frac{w_i}{n_i} + \sqrt{2} \sqrt{\frac{\ln N}{n_i}} $$
+///
+/// where $w_i$ and $n_i$ are the child's reward and visits and $N$ is the parent's visits;
+/// *expansion* adds one untried move; *simulation* plays uniformly random moves to the end of
+/// the game; and *back-propagation* credits the result to every node on the path.
+///
+/// Utilities are read as $+1$ for a win, $-1$ for a loss and $0$ for a draw. Random choices are
+/// drawn from a generator seeded with `seed`, so a search is reproducible.
+pub fn mcts_children<G: Game>(
+    game: &G,
+    state: &G::State,
+    iterations: usize,
+    seed: u64,
+) -> Vec<MctsChild<G::Action>> {
+    let mut rng = Rng::seeded(seed);
+    let mut tree: Vec<MctsNode<G>> = vec![MctsNode {
+        state: state.clone(),
+        mover: None,
+        index: 0,
+        parent: None,
+        children: Vec::new(),
+        untried: untried_actions(game, state),
+        visits: 0,
+        wins: 0.0,
+    }];
+
+    for _ in 0..iterations {
+        // Selection
+        let mut node = 0;
+        while tree[node].untried.is_empty() && !tree[node].children.is_empty() {
+            let ln_parent_visits = (tree[node].visits as f64).ln();
+            let ucb = |child: &MctsNode<G>| {
+                let visits = child.visits as f64;
+                child.wins / visits + EXPLORATION * (ln_parent_visits / visits).sqrt()
+            };
+            node = *tree[node]
+                .children
+                .iter()
+                .max_by(|&&a, &&b| ucb(&tree[a]).total_cmp(&ucb(&tree[b])))
+                .unwrap();
+        }
+
+        // Expansion
+        if !tree[node].untried.is_empty() {
+            let i = rng.gen_range(0..tree[node].untried.len());
+            let (index, action) = tree[node].untried.swap_remove(i);
+            let mover = game.to_move(&tree[node].state);
+            let state = game.result(&tree[node].state, &action);
+            let child = tree.len();
+            tree.push(MctsNode {
+                untried: untried_actions(game, &state),
+                state,
+                mover: Some(mover),
+                index,
+                parent: Some(node),
+                children: Vec::new(),
+                visits: 0,
+                wins: 0.0,
+            });
+            tree[node].children.push(child);
+            node = child;
+        }
+
+        // Simulation
+        let mut rollout = tree[node].state.clone();
+        while !game.terminal_test(&rollout) {
+            let actions = game.actions(&rollout);
+            let action = actions.choose(&mut rng).unwrap();
+            rollout = game.result(&rollout, action);
+        }
+
+        // Back-propagation
+        let mut current = Some(node);
+        while let Some(i) = current {
+            let node = &mut tree[i];
+            node.visits += 1;
+            if let Some(mover) = &node.mover {
+                node.wins += (game.utility(&rollout, mover) + 1.0) / 2.0;
+            }
+            current = node.parent;
+        }
+    }
+
+    let mut children: Vec<MctsChild<G::Action>> = untried_actions(game, state)
+        .into_iter()
+        .map(|(_, action)| MctsChild {
+            action,
+            visits: 0,
+            wins: 0.0,
+        })
+        .collect();
+    for &i in &tree[0].children {
+        let node = &tree[i];
+        children[node.index].visits = node.visits;
+        children[node.index].wins = node.wins;
+    }
+    children
+}
+
+/// The legal moves in `state`, numbered in the order the game lists them, or none if the game is
+/// over.
+fn untried_actions<G: Game>(game: &G, state: &G::State) -> Vec<(usize, G::Action)> {
+    if game.terminal_test(state) {
+        Vec::new()
+    } else {
+        game.actions(state).into_iter().enumerate().collect()
+    }
+}
+
+/// Monte Carlo tree search: chooses the move for the player to move in `state` after
+/// `iterations` rounds of UCT, as described in `mcts_children`. The move chosen is the one
+/// explored most often. Returns `None` if there are no legal moves.
+///
+/// Unlike alpha-beta search, MCTS needs no evaluation function, and its effort is spent on the
+/// moves that look most promising, which suits games with a large branching factor.
+///
+/// # Examples
+///
+/// Playing Tic-Tac-Toe against a perfect opponent, MCTS holds the draw:
+///
+/// ```
+/// # use aima_rust::games::{alphabeta_search, mcts, Game};
+/// # use aima_rust::games::tic_tac_toe::{Player, TicTacToe};
+/// for mcts_player in &[Player::X, Player::O] {
+///     let mut board = TicTacToe.initial_state();
+///     while !TicTacToe.terminal_test(&board) {
+///         let action = if TicTacToe.to_move(&board) == *mcts_player {
+///             mcts(&TicTacToe, &board, 2000, 7)
+///         } else {
+///             alphabeta_search(&TicTacToe, &board)
+///         };
+///         board = TicTacToe.result(&board, &action.unwrap());
+///     }
+///     assert_eq!(TicTacToe.utility(&board, mcts_player), 0.0);
+/// }
+/// ```
+///
+/// On Connect Four, a small budget still yields a legal move, and the statistics behind it can be
+/// inspected:
+///
+/// ```
+/// # use aima_rust::games::{mcts, mcts_children};
+/// # use aima_rust::games::connect_four::{Board, ConnectFour};
+/// let board = Board::from_moves(&[3, 3, 3, 3, 3, 3]);
+///
+/// let column = mcts(&ConnectFour, &board, 200, 1).unwrap();
+/// assert!(board.is_playable(column));
+///
+/// let children = mcts_children(&ConnectFour, &board, 200, 1);
+/// assert_eq!(children.len(), 6);
+/// assert_eq!(children.iter().map(|child| child.visits).sum::<usize>(), 200);
+/// assert!(children.iter().all(|child| (0.0..=1.0).contains(&child.win_rate())));
+/// ```
+pub fn mcts<G: Game>(
+    game: &G,
+    state: &G::State,
+    iterations: usize,
+    seed: u64,
+) -> Option<G::Action> {
+    mcts_children(game, state, iterations, seed)
+        .into_iter()
+        .fold(
+            None,
+            |best: Option<MctsChild<G::Action>>, child| match best {
+                Some(best) if best.visits >= child.visits => Some(best),
+                _ => Some(child),
+            },
+        )
+        .map(|child| child.action)
+}
+
+/// # Connect Four
+///
+/// Two players take turns dropping discs into the columns of an upright board seven columns wide
+/// and six rows high. A disc falls to the lowest empty cell of its column, and the first player
+/// to line up four discs horizontally, vertically or diagonally wins.
+///
+/// The game tree is far too large to search exhaustively, so players search to a fixed depth and
+/// fall back on `evaluate`:
+///
+/// ```
+/// # use aima_rust::games::alphabeta_cutoff_search;
+/// # use aima_rust::games::connect_four::{evaluate, Board, ConnectFour};
+/// # use aima_rust::games::Game;
+/// let opening = alphabeta_cutoff_search(
+///     &ConnectFour,
+///     &ConnectFour.initial_state(),
+///     |_, depth| depth >= 3,
+///     evaluate,
+/// );
+///
+/// // The center column takes part in the most lines of four.
+/// assert_eq!(opening, Some(3));
+/// ```
+pub mod connect_four {
+    use super::Game;
+    use std::fmt;
+
+    /// The number of columns on the board.
+    pub const COLUMNS: usize = 7;
+
+    /// The number of rows on the board.
+    pub const ROWS: usize = 6;
+
+    /// The columns in the order moves are tried: from the center outward, which helps alpha-beta
+    /// search find good moves early.
+    const MOVE_ORDER: [usize; COLUMNS] = [3, 2, 4, 1, 5, 0, 6];
+
+    /// The directions a line of four can run in: horizontal, vertical and both diagonals.
+    const DIRECTIONS: [(i32, i32); 4] = [(1, 0), (0, 1), (1, 1), (1, -1)];
+
+    #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+    pub enum Player {
+        Red,
+        Yellow,
+    }
+
+    impl Player {
+        /// The other player.
+        pub fn opponent(self) -> Player {
+            match self {
+                Player::Red => Player::Yellow,
+                Player::Yellow => Player::Red,
+            }
+        }
+    }
+
+    /// A Connect Four position. Red moves first.
+    #[derive(Clone, Debug, PartialEq, Eq, Hash)]
+    pub struct Board {
+        /// `cells[column][row]`, with row `0` at the bottom.
+        cells: [[Option<Player>; ROWS]; COLUMNS],
+        heights: [usize; COLUMNS],
+        to_move: Player,
+        winner: Option<Player>,
+        moves: usize,
+    }
+
+    impl Board {
+        /// Creates a new, empty board.
+        pub fn new() -> Self {
+            Board {
+                cells: [[None; ROWS]; COLUMNS],
+                heights: [0; COLUMNS],
+                to_move: Player::Red,
+                winner: None,
+                moves: 0,
+            }
+        }
+
+        /// Creates a new board given the columns played so far, starting with Red.
+        pub fn from_moves(columns: &[usize]) -> Self {
+            columns
+                .iter()
+                .fold(Board::new(), |board, &column| board.play(column))
+        }
+
+        /// The disc in `column` and `row`, counting rows from the bottom.
+        pub fn get(&self, column: usize, row: usize) -> Option<Player> {
+            self.cells[column][row]
+        }
+
+        /// The player whose turn it is.
+        pub fn to_move(&self) -> Player {
+            self.to_move
+        }
+
+        /// The player who has four in a row, if either does.
+        pub fn winner(&self) -> Option<Player> {
+            self.winner
+        }
+
+        /// Whether a disc can be dropped in `column`.
+        pub fn is_playable(&self, column: usize) -> bool {
+            self.winner.is_none() && column < COLUMNS && self.heights[column] < ROWS
+        }
+
+        /// The board after the player to move drops a disc in `column`.
+        ///
+        /// # Panics
+        ///
+        /// Panics if the game is over or `column` is full.
+        pub fn play(&self, column: usize) -> Board {
+            assert!(
+                self.is_playable(column),
+                "column {} can't be played",
+                column
+            );
+            let mut board = self.clone();
+            let row = board.heights[column];
+            board.cells[column][row] = Some(self.to_move);
+            board.heights[column] += 1;
+            board.moves += 1;
+            board.to_move = self.to_move.opponent();
+            if board.completes_line(column, row) {
+                board.winner = Some(self.to_move);
+            }
+            board
+        }
+
+        /// Whether the disc at `column` and `row` is part of four in a row. Only the lines through
+        /// the newest disc need checking after a move.
+        fn completes_line(&self, column: usize, row: usize) -> bool {
+            let player = self.cells[column][row];
+            DIRECTIONS.iter().any(|&(dc, dr)| {
+                let run = |sign: i32| {
+                    (1..4)
+                        .take_while(|&step| {
+                            self.cell(
+                                column as i32 + sign * step * dc,
+                                row as i32 + sign * step * dr,
+                            ) == Some(player)
+                        })
+                        .count()
+                };
+                1 + run(1) + run(-1) >= 4
+            })
+        }
+
+        /// The contents of a cell, or `None` if it lies off the board.
+        fn cell(&self, column: i32, row: i32) -> Option<Option<Player>> {
+            if (0..COLUMNS as i32).contains(&column) && (0..ROWS as i32).contains(&row) {
+                Some(self.cells[column as usize][row as usize])
+            } else {
+                None
+            }
+        }
+
+        /// Every line of four cells on the board.
+        fn windows(&self) -> impl Iterator<Item = [Option<Player>; 4]> + '_ {
+            (0..COLUMNS as i32).flat_map(move |column| {
+                (0..ROWS as i32).flat_map(move |row| {
+                    DIRECTIONS.iter().filter_map(move |&(dc, dr)| {
+                        let mut window = [None; 4];
+                        for (step, slot) in window.iter_mut().enumerate() {
+                            let step = step as i32;
+                            *slot = self.cell(column + step * dc, row + step * dr)?;
+                        }
+                        Some(window)
+                    })
+                })
+            })
+        }
+    }
+
+    impl Default for Board {
+        fn default() -> Self {
+            Board::new()
+        }
+    }
+
+    impl fmt::Display for Board {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            for row in (0..ROWS).rev() {
+                let line: Vec<&str> = (0..COLUMNS)
+                    .map(|column| match self.cells[column][row] {
+                        Some(Player::Red) => "R",
+                        Some(Player::Yellow) => "Y",
+                        None => ".",
+                    })
+                    .collect();
+                writeln!(f, "{}", line.join(" "))?;
+            }
+            let labels: Vec<String> = (0..COLUMNS).map(|column| column.to_string()).collect();
+            write!(f, "{}", labels.join(" "))
+        }
+    }
+
+    /// Connect Four as a `Game`. Actions are column indices, listed from left to right and tried
+    /// by the alpha-beta searches from the center outward.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use aima_rust::games::connect_four::{Board, ConnectFour, Player};
+    /// # use aima_rust::games::Game;
+    /// let board = Board::from_moves(&[3, 4, 3, 4, 3, 4, 3]);
+    ///
+    /// assert_eq!(board.winner(), Some(Player::Red));
+    /// assert!(ConnectFour.terminal_test(&board));
+    /// assert_eq!(ConnectFour.utility(&board, &Player::Red), 1.0);
+    /// assert_eq!(ConnectFour.utility(&board, &Player::Yellow), -1.0);
+    /// ```
+    pub struct ConnectFour;
+
+    impl Game for ConnectFour {
+        type State = Board;
+        type Action = usize;
+        type Player = Player;
+
+        fn initial_state(&self) -> Board {
+            Board::new()
+        }
+
+        fn to_move(&self, board: &Board) -> Player {
+            board.to_move
+        }
+
+        fn actions(&self, board: &Board) -> Vec<usize> {
+            (0..COLUMNS)
+                .filter(|&column| board.is_playable(column))
+                .collect()
+        }
+
+        fn ordered_actions(&self, board: &Board) -> Vec<usize> {
+            MOVE_ORDER
+                .iter()
+                .cloned()
+                .filter(|&column| board.is_playable(column))
+                .collect()
+        }
+
+        fn result(&self, board: &Board, column: &usize) -> Board {
+            board.play(*column)
+        }
+
+        fn terminal_test(&self, board: &Board) -> bool {
+            board.winner.is_some() || board.moves == ROWS * COLUMNS
+        }
+
+        fn utility(&self, board: &Board, player: &Player) -> f64 {
+            match board.winner {
+                Some(winner) if winner == *player => 1.0,
+                Some(_) => -1.0,
+                None => 0.0,
+            }
+        }
+    }
+
+    /// A heuristic value of `board` to `player`: the player's open three-in-a-rows, lines of four
+    /// holding three of their discs and one empty cell, minus the opponent's, with a smaller bonus
+    /// for each disc in the center column. The value is scaled to stay strictly between the
+    /// utilities of a loss and a win.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use aima_rust::games::connect_four::{evaluate, Board, Player};
+    /// let board = Board::from_moves(&[0, 6, 1, 6, 2]);
+    ///
+    /// assert!(evaluate(&board, &Player::Red) > 0.0);
+    /// assert_eq!(evaluate(&board, &Player::Yellow), -evaluate(&board, &Player::Red));
+    /// ```
+    pub fn evaluate(board: &Board, player: &Player) -> f64 {
+        let count = |window: &[Option<Player>; 4], who: Player| {
+            window.iter().filter(|&&cell| cell == Some(who)).count()
+        };
+        let open_threes: i32 = board
+            .windows()
+            .map(
+                |window| match (count(&window, *player), count(&window, player.opponent())) {
+                    (3, 0) => 1,
+                    (0, 3) => -1,
+                    _ => 0,
+                },
+            )
+            .sum();
+        let center: i32 = board.cells[COLUMNS / 2]
+            .iter()
+            .map(|&cell| match cell {
+                Some(who) if who == *player => 1,
+                Some(_) => -1,
+                None => 0,
+            })
+            .sum();
+        // There are fewer than 100 lines of four, and a center bonus never outweighs a three.
+        (f64::from(open_threes) + 0.1 * f64::from(center)) / 100.0
+    }
+}
+
+/// # Tic-Tac-Toe
+///
+/// Players X and O take turns marking the cells of a three-by-three grid, and the first to mark
+/// three cells in a row, column or diagonal wins. The game is small enough to search exhaustively,
+/// and with perfect play it ends in a draw:
+///
+/// ```
+/// # use aima_rust::games::{alphabeta_search, Game};
+/// # use aima_rust::games::tic_tac_toe::{Player, TicTacToe};
+/// let mut board = TicTacToe.initial_state();
+/// while let Some(cell) = alphabeta_search(&TicTacToe, &board) {
+///     board = TicTacToe.result(&board, &cell);
+/// }
+///
+/// assert_eq!(board.winner(), None);
+/// assert_eq!(TicTacToe.utility(&board, &Player::X), 0.0);
+/// ```
+pub mod tic_tac_toe {
+    use super::Game;
+    use std::fmt;
+
+    /// The cells of each line of three, numbered row by row from the top left.
+    const LINES: [[usize; 3]; 8] = [
+        [0, 1, 2],
+        [3, 4, 5],
+        [6, 7, 8],
+        [0, 3, 6],
+        [1, 4, 7],
+        [2, 5, 8],
+        [0, 4, 8],
+        [2, 4, 6],
+    ];
+
+    #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+    pub enum Player {
+        X,
+        O,
+    }
+
+    impl Player {
+        /// The other player.
+        pub fn opponent(self) -> Player {
+            match self {
+                Player::X => Player::O,
+                Player::O => Player::X,
+            }
+        }
+    }
+
+    /// A Tic-Tac-Toe position. X moves first.
+    #[derive(Clone, Debug, PartialEq, Eq, Hash)]
+    pub struct Board {
+        /// The cells, numbered row by row from the top left.
+        cells: [Option<Player>; 9],
+        to_move: Player,
+        winner: Option<Player>,
+    }
+
+    impl Board {
+        /// Creates a new, empty board.
+        pub fn new() -> Self {
+            Board {
+                cells: [None; 9],
+                to_move: Player::X,
+                winner: None,
+            }
+        }
+
+        /// The mark in `cell`, numbered row by row from the top left.
+        pub fn get(&self, cell: usize) -> Option<Player> {
+            self.cells[cell]
+        }
+
+        /// The player who has three in a row, if either does.
+        pub fn winner(&self) -> Option<Player> {
+            self.winner
+        }
+
+        /// The board after the player to move marks `cell`.
+        ///
+        /// # Panics
+        ///
+        /// Panics if the game is over or `cell` is already marked.
+        pub fn play(&self, cell: usize) -> Board {
+            assert!(
+                self.winner.is_none() && self.cells[cell].is_none(),
+                "cell {} can't be played",
+                cell
+            );
+            let mut board = self.clone();
+            board.cells[cell] = Some(self.to_move);
+            board.to_move = self.to_move.opponent();
+            if LINES
+                .iter()
+                .any(|line| line.iter().all(|&i| board.cells[i] == Some(self.to_move)))
+            {
+                board.winner = Some(self.to_move);
+            }
+            board
+        }
+    }
+
+    impl Default for Board {
+        fn default() -> Self {
+            Board::new()
+        }
+    }
+
+    impl fmt::Display for Board {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            let rows: Vec<String> = self
+                .cells
+                .chunks(3)
+                .map(|row| {
+                    let marks: Vec<&str> = row
+                        .iter()
+                        .map(|cell| match cell {
+                            Some(Player::X) => "X",
+                            Some(Player::O) => "O",
+                            None => ".",
+                        })
+                        .collect();
+                    marks.join(" ")
+                })
+                .collect();
+            write!(f, "{}", rows.join("\n"))
+        }
+    }
+
+    /// Tic-Tac-Toe as a `Game`. Actions are cell numbers.
+    pub struct TicTacToe;
+
+    impl Game for TicTacToe {
+        type State = Board;
+        type Action = usize;
+        type Player = Player;
+
+        fn initial_state(&self) -> Board {
+            Board::new()
+        }
+
+        fn to_move(&self, board: &Board) -> Player {
+            board.to_move
+        }
+
+        fn actions(&self, board: &Board) -> Vec<usize> {
+            if board.winner.is_some() {
+                return Vec::new();
+            }
+            (0..9).filter(|&cell| board.cells[cell].is_none()).collect()
+        }
+
+        fn result(&self, board: &Board, cell: &usize) -> Board {
+            board.play(*cell)
+        }
+
+        fn terminal_test(&self, board: &Board) -> bool {
+            board.winner.is_some() || board.cells.iter().all(Option::is_some)
+        }
+
+        fn utility(&self, board: &Board, player: &Player) -> f64 {
+            match board.winner {
+                Some(winner) if winner == *player => 1.0,
+                Some(_) => -1.0,
+                None => 0.0,
+            }
+        }
+    }
+}
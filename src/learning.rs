@@ -0,0 +1,3878 @@
+//! # Learning
+//!
+//! This module covers material in Chapter 18, learning from examples, Chapter 19, knowledge in
+//! learning, and Chapter 20, learning probabilistic models.
+//!
+//! ## Index of Algorithms
+//!
+//! | **Figure** | **Name**                          | **Module Link**
+//! |:-----------|:----------------------------------|:-------------------------------
+//! | 18         | Data-Set                          | `DataSet`                                                |
+//! | 18.3       | Restaurant Examples               | `restaurant`                                             |
+//! | 18.5       | Decision-Tree-Learning            | `DecisionTreeLearner`                                    |
+//! | 18         | Perceptron-Learning               | `PerceptronLearner`                                      |
+//! | 18         | Decision-Stump                    | `DecisionStump`                                          |
+//! | 18         | Hoeffding Tree                    | `HoeffdingTreeLearner`                                   |
+//! | 18         | Weighted-Majority                 | `WeightedMajority`                                       |
+//! | 19.3       | Version-Space-Learning            | `version_space_learning`                                 |
+//! | 18         | Neural Network                    | `NeuralNetwork`                                          |
+//! | 18.24      | Back-Prop-Learning                | `BackPropagationLearner`                                 |
+//! | 18         | Gradient Checking                 | `gradient_check`                                         |
+//! | 18.8       | Cross-Validation                  | `cross_validation`                                       |
+//! | 18         | Learning-Curve                    | `learning_curve`                                         |
+//! | 18         | Confusion-Matrix                  | `ClassificationReport`                                   |
+//! | 20         | K-Means                           | `kmeans`                                                 |
+//! | 20         | EM-Gaussian-Mixture               | `gaussian_mixture`                                       |
+//! | 18         | Preprocessing                     | `preprocess`                                             |
+//!
+use crate::logic::{Expr, Model};
+use crate::util::Rng;
+use rand::seq::SliceRandom;
+use rand::Rng as _;
+#[cfg(feature = "serde")]
+use serde::{de::Error as _, Deserialize, Deserializer, Serialize, Serializer};
+use std::collections::HashMap;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::rc::Rc;
+
+/// A single attribute value of an example. Attributes are either numbers, such as a temperature
+/// reading, or categories, such as the `"French"` type of a restaurant.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum Value {
+    Numeric(f64),
+    Categorical(String),
+}
+
+impl Value {
+    /// Returns the number held by a numeric value, or `None` for categories.
+    pub fn as_f64(&self) -> Option<f64> {
+        match self {
+            Value::Numeric(x) => Some(*x),
+            Value::Categorical(_) => None,
+        }
+    }
+}
+
+// Values are used as class labels and table keys, so numbers are compared by their bits. This
+// makes `NaN` equal to itself, which is what we want when counting values.
+impl PartialEq for Value {
+    fn eq(&self, other: &Value) -> bool {
+        match (self, other) {
+            (Value::Numeric(a), Value::Numeric(b)) => a.to_bits() == b.to_bits(),
+            (Value::Categorical(a), Value::Categorical(b)) => a == b,
+            _ => false,
+        }
+    }
+}
+
+impl Eq for Value {}
+
+impl Hash for Value {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        match self {
+            Value::Numeric(x) => x.to_bits().hash(state),
+            Value::Categorical(s) => s.hash(state),
+        }
+    }
+}
+
+impl From<f64> for Value {
+    fn from(x: f64) -> Self {
+        Value::Numeric(x)
+    }
+}
+
+impl From<&str> for Value {
+    fn from(s: &str) -> Self {
+        Value::Categorical(s.to_string())
+    }
+}
+
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Value::Numeric(x) => write!(f, "{}", x),
+            Value::Categorical(s) => write!(f, "{}", s),
+        }
+    }
+}
+
+/// A set of examples to learn from. Each example is a row of attribute values and one of the
+/// attributes, the `target`, is the value we would like to predict from the others.
+///
+/// # Examples
+///
+/// ```
+/// # use aima_rust::learning::{DataSet, Value};
+/// let dataset = DataSet::new(
+///     &["outlook", "windy", "play"],
+///     vec![
+///         vec!["sunny".into(), "no".into(), "yes".into()],
+///         vec!["rainy".into(), "yes".into(), "no".into()],
+///     ],
+///     2,
+/// );
+///
+/// assert_eq!(dataset.inputs(), vec![0, 1]);
+/// assert_eq!(dataset.classes(), vec![Value::from("yes"), Value::from("no")]);
+/// ```
+#[derive(Clone, Debug)]
+pub struct DataSet {
+    pub attr_names: Vec<String>,
+    pub examples: Vec<Vec<Value>>,
+    pub target: usize,
+}
+
+impl DataSet {
+    /// Creates a new DataSet given the attribute names, the examples, and the index of the
+    /// target attribute.
+    pub fn new(attr_names: &[&str], examples: Vec<Vec<Value>>, target: usize) -> Self {
+        DataSet {
+            attr_names: attr_names.iter().map(|name| name.to_string()).collect(),
+            examples,
+            target,
+        }
+    }
+
+    /// The indices of the input attributes, that is, every attribute except the target.
+    pub fn inputs(&self) -> Vec<usize> {
+        (0..self.attr_names.len())
+            .filter(|&i| i != self.target)
+            .collect()
+    }
+
+    /// The distinct values of the target attribute, in the order they first appear.
+    pub fn classes(&self) -> Vec<Value> {
+        let mut classes = Vec::new();
+        for example in &self.examples {
+            if !classes.contains(&example[self.target]) {
+                classes.push(example[self.target].clone());
+            }
+        }
+        classes
+    }
+
+    /// Creates a DataSet with the same attributes and target as this one, but holding
+    /// `examples` instead.
+    pub fn with_examples(&self, examples: Vec<Vec<Value>>) -> DataSet {
+        DataSet {
+            attr_names: self.attr_names.clone(),
+            examples,
+            target: self.target,
+        }
+    }
+
+    /// Splits the examples at random into a training set and a test set holding about
+    /// `test_fraction` of them. The examples are shuffled using `seed`, so the same seed gives the
+    /// same split.
+    ///
+    /// With `stratify`, each class is split separately, so that both sets keep the class
+    /// proportions of the whole. This matters for small or imbalanced datasets, where a plain
+    /// random split can leave a rare class out of the test set altogether.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `test_fraction` is not between `0.0` and `1.0`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use aima_rust::learning::{DataSet, Value};
+    /// let examples = (0..100)
+    ///     .map(|i| vec![(i as f64).into(), (if i < 80 { "common" } else { "rare" }).into()])
+    ///     .collect();
+    /// let dataset = DataSet::new(&["id", "kind"], examples, 1);
+    ///
+    /// let (train, test) = dataset.train_test_split(0.25, true, 42);
+    /// let rare = |d: &DataSet| d.examples.iter().filter(|e| e[1] == Value::from("rare")).count();
+    /// assert_eq!((train.examples.len(), test.examples.len()), (75, 25));
+    /// assert_eq!((rare(&train), rare(&test)), (15, 5));
+    ///
+    /// // Every example lands in exactly one of the two sets.
+    /// let mut ids: Vec<f64> = train.examples.iter().chain(&test.examples)
+    ///     .map(|example| example[0].as_f64().unwrap())
+    ///     .collect();
+    /// ids.sort_by(f64::total_cmp);
+    /// ids.dedup();
+    /// assert_eq!(ids.len(), 100);
+    /// ```
+    pub fn train_test_split(
+        &self,
+        test_fraction: f64,
+        stratify: bool,
+        seed: u64,
+    ) -> (DataSet, DataSet) {
+        assert!(
+            (0.0..=1.0).contains(&test_fraction),
+            "the test fraction must be between 0 and 1"
+        );
+        let groups: Vec<Vec<Vec<Value>>> = if stratify {
+            self.classes()
+                .iter()
+                .map(|class| {
+                    self.examples
+                        .iter()
+                        .filter(|example| example[self.target] == *class)
+                        .cloned()
+                        .collect()
+                })
+                .collect()
+        } else {
+            vec![self.examples.clone()]
+        };
+
+        let mut rng = Rng::seeded(seed);
+        let (mut train, mut test) = (Vec::new(), Vec::new());
+        for mut group in groups {
+            group.shuffle(&mut rng);
+            let n_test = (group.len() as f64 * test_fraction).round() as usize;
+            train.extend(group.split_off(n_test));
+            test.extend(group);
+        }
+        train.shuffle(&mut rng);
+        test.shuffle(&mut rng);
+        (self.with_examples(train), self.with_examples(test))
+    }
+
+    /// Splits the examples at random into `k` folds of nearly equal size, for cross-validation.
+    /// The examples are shuffled using `seed`, so the same seed gives the same folds.
+    ///
+    /// With `stratify`, the examples of each class are dealt out across the folds in turn, so
+    /// that every fold keeps the class proportions of the whole, and a class with at least `k`
+    /// examples appears in every fold.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `k` is less than two or greater than the number of examples.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use aima_rust::learning::{DataSet, Value};
+    /// let examples = (0..50)
+    ///     .map(|i| vec![(i as f64).into(), (if i < 45 { "common" } else { "rare" }).into()])
+    ///     .collect();
+    /// let dataset = DataSet::new(&["id", "kind"], examples, 1);
+    /// let has_rare = |fold: &DataSet| fold.examples.iter().any(|e| e[1] == Value::from("rare"));
+    ///
+    /// for seed in 0..10 {
+    ///     let folds = dataset.folds(5, true, seed);
+    ///     assert!(folds.iter().all(|fold| fold.examples.len() == 10 && has_rare(fold)));
+    /// }
+    /// // Random folds leave the rare class out of some fold for some seeds.
+    /// let missing = (0..10)
+    ///     .filter(|&seed| !dataset.folds(5, false, seed).iter().all(has_rare))
+    ///     .count();
+    /// assert!(missing > 0);
+    /// ```
+    pub fn folds(&self, k: usize, stratify: bool, seed: u64) -> Vec<DataSet> {
+        assert!(
+            k >= 2 && k <= self.examples.len(),
+            "k must be between 2 and the number of examples"
+        );
+        let mut rng = Rng::seeded(seed);
+        let folds = if stratify {
+            let mut folds = vec![Vec::new(); k];
+            let mut next = 0;
+            for class in self.classes() {
+                let mut group: Vec<Vec<Value>> = self
+                    .examples
+                    .iter()
+                    .filter(|example| example[self.target] == class)
+                    .cloned()
+                    .collect();
+                group.shuffle(&mut rng);
+                for example in group {
+                    folds[next % k].push(example);
+                    next += 1;
+                }
+            }
+            for fold in &mut folds {
+                fold.shuffle(&mut rng);
+            }
+            folds
+        } else {
+            let mut examples = self.examples.clone();
+            examples.shuffle(&mut rng);
+            split_into_folds(&examples, k)
+        };
+        folds
+            .into_iter()
+            .map(|fold| self.with_examples(fold))
+            .collect()
+    }
+
+    /// Describes the dataset: how many examples it has, the values or range of each input
+    /// attribute, and how the examples divide between the classes. Looking at it before
+    /// training can catch problems such as an attribute that never varies.
+    ///
+    /// An attribute is summarized as numeric if every one of its values is a number, and as
+    /// categorical otherwise.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use aima_rust::learning::{restaurant, AttributeStats, Value};
+    /// let summary = restaurant().summary();
+    ///
+    /// assert_eq!(summary.examples, 12);
+    /// assert_eq!(summary.attributes.len(), 10);
+    /// assert_eq!(summary.classes, vec![(Value::from("Yes"), 6), (Value::from("No"), 6)]);
+    ///
+    /// let patrons = &summary.attributes[4];
+    /// assert_eq!(patrons.name, "Pat");
+    /// let counts = vec![("Some".into(), 4), ("Full".into(), 6), ("None".into(), 2)];
+    /// assert_eq!(patrons.stats, AttributeStats::Categorical(counts));
+    ///
+    /// assert!(summary.to_string().contains("WillWait: Yes 6 (50.0%), No 6 (50.0%)"));
+    /// ```
+    ///
+    /// Numeric attributes get their range, mean and standard deviation:
+    ///
+    /// ```
+    /// # use aima_rust::learning::{AttributeStats, DataSet};
+    /// let examples = vec![
+    ///     vec![1.0.into(), 3.0.into(), "a".into()],
+    ///     vec![3.0.into(), 3.0.into(), "b".into()],
+    /// ];
+    /// let summary = DataSet::new(&["x", "y", "class"], examples, 2).summary();
+    ///
+    /// let stats = AttributeStats::Numeric { min: 1.0, mean: 2.0, max: 3.0, std: 1.0 };
+    /// assert_eq!(summary.attributes[0].stats, stats);
+    /// assert!(summary.attributes[1].is_constant());
+    /// ```
+    pub fn summary(&self) -> DataSetSummary {
+        let count = |values: Vec<&Value>| {
+            let mut counts = Counts::new();
+            for value in values {
+                add_count(&mut counts, value);
+            }
+            counts
+        };
+        let attributes = self
+            .inputs()
+            .into_iter()
+            .map(|i| {
+                let values: Vec<&Value> = self.examples.iter().map(|example| &example[i]).collect();
+                let numbers: Option<Vec<f64>> = values.iter().map(|v| v.as_f64()).collect();
+                let stats = match numbers {
+                    Some(xs) if !xs.is_empty() => {
+                        let n = xs.len() as f64;
+                        let mean = xs.iter().sum::<f64>() / n;
+                        let variance = xs.iter().map(|x| (x - mean).powi(2)).sum::<f64>() / n;
+                        AttributeStats::Numeric {
+                            min: xs.iter().cloned().fold(f64::INFINITY, f64::min),
+                            mean,
+                            max: xs.iter().cloned().fold(f64::NEG_INFINITY, f64::max),
+                            std: variance.sqrt(),
+                        }
+                    }
+                    _ => AttributeStats::Categorical(count(values)),
+                };
+                AttributeSummary {
+                    name: self.attr_names[i].clone(),
+                    stats,
+                }
+            })
+            .collect();
+        DataSetSummary {
+            examples: self.examples.len(),
+            attributes,
+            target: self.attr_names[self.target].clone(),
+            classes: count(self.examples.iter().map(|e| &e[self.target]).collect()),
+        }
+    }
+}
+
+/// What `DataSet::summary` found about a dataset.
+#[derive(Clone, Debug, PartialEq)]
+pub struct DataSetSummary {
+    /// The number of examples.
+    pub examples: usize,
+    /// A summary of each input attribute, in order.
+    pub attributes: Vec<AttributeSummary>,
+    /// The name of the target attribute.
+    pub target: String,
+    /// How many examples there are of each class, in the order the classes first appear.
+    pub classes: Vec<(Value, usize)>,
+}
+
+/// The values taken by one input attribute of a dataset.
+#[derive(Clone, Debug, PartialEq)]
+pub struct AttributeSummary {
+    pub name: String,
+    pub stats: AttributeStats,
+}
+
+impl AttributeSummary {
+    /// Whether the attribute has the same value in every example, and so can't help tell the
+    /// examples apart.
+    pub fn is_constant(&self) -> bool {
+        match &self.stats {
+            AttributeStats::Categorical(counts) => counts.len() <= 1,
+            AttributeStats::Numeric { min, max, .. } => min == max,
+        }
+    }
+}
+
+/// Statistics for one attribute: how often each value occurs for a categorical attribute, in the
+/// order the values first appear, or the spread of a numeric one. The standard deviation is that
+/// of the examples themselves, dividing by their number.
+#[derive(Clone, Debug, PartialEq)]
+pub enum AttributeStats {
+    Categorical(Vec<(Value, usize)>),
+    Numeric {
+        min: f64,
+        mean: f64,
+        max: f64,
+        std: f64,
+    },
+}
+
+impl fmt::Display for DataSetSummary {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let counts = |counts: &[(Value, usize)]| {
+            counts
+                .iter()
+                .map(|(value, n)| {
+                    let share = 100.0 * *n as f64 / self.examples as f64;
+                    format!("{} {} ({:.1}%)", value, n, share)
+                })
+                .collect::<Vec<_>>()
+                .join(", ")
+        };
+        writeln!(
+            f,
+            "{} examples, {} input attributes",
+            self.examples,
+            self.attributes.len()
+        )?;
+        for attribute in &self.attributes {
+            write!(f, "  {}: ", attribute.name)?;
+            match &attribute.stats {
+                AttributeStats::Categorical(values) => write!(f, "{}", counts(values))?,
+                AttributeStats::Numeric {
+                    min,
+                    mean,
+                    max,
+                    std,
+                } => write!(
+                    f,
+                    "min {:.3}, mean {:.3}, max {:.3}, std {:.3}",
+                    min, mean, max, std
+                )?,
+            }
+            if attribute.is_constant() {
+                write!(f, " [constant]")?;
+            }
+            writeln!(f)?;
+        }
+        writeln!(f, "target {}: {}", self.target, counts(&self.classes))
+    }
+}
+
+/// Figure 18.3
+///
+/// The restaurant examples: twelve occasions on which someone decided whether to wait for a
+/// table, described by ten attributes. The target, `WillWait`, is the last attribute.
+pub fn restaurant() -> DataSet {
+    let rows = [
+        "Yes No No Yes Some $$$ No Yes French 0-10 Yes",
+        "Yes No No Yes Full $ No No Thai 30-60 No",
+        "No Yes No No Some $ No No Burger 0-10 Yes",
+        "Yes No Yes Yes Full $ Yes No Thai 10-30 Yes",
+        "Yes No Yes No Full $$$ No Yes French >60 No",
+        "No Yes No Yes Some $$ Yes Yes Italian 0-10 Yes",
+        "No Yes No No None $ Yes No Burger 0-10 No",
+        "No No No Yes Some $$ Yes Yes Thai 0-10 Yes",
+        "No Yes Yes No Full $ Yes No Burger >60 No",
+        "Yes Yes Yes Yes Full $$$ No Yes Italian 10-30 No",
+        "No No No No None $ No No Thai 0-10 No",
+        "Yes Yes Yes Yes Full $ No No Burger 30-60 Yes",
+    ];
+    let examples = rows
+        .iter()
+        .map(|row| row.split(' ').map(Value::from).collect())
+        .collect();
+    DataSet::new(
+        &[
+            "Alt", "Bar", "Fri", "Hun", "Pat", "Price", "Rain", "Res", "Type", "Est", "WillWait",
+        ],
+        examples,
+        10,
+    )
+}
+
+/// Cuts `examples` into `k` consecutive runs of nearly equal length.
+fn split_into_folds(examples: &[Vec<Value>], k: usize) -> Vec<Vec<Vec<Value>>> {
+    let n = examples.len();
+    (0..k)
+        .map(|fold| examples[fold * n / k..(fold + 1) * n / k].to_vec())
+        .collect()
+}
+
+/// A learner builds a hypothesis from a DataSet, which it then uses to predict the target value
+/// of new examples. Calling `train` again replaces anything learned previously.
+pub trait Learner {
+    /// Fits the learner to the examples in `dataset`.
+    fn train(&mut self, dataset: &DataSet);
+
+    /// Predicts the target value of `example`. The example is a full row of the DataSet the
+    /// learner was trained on; the value in the target position is ignored.
+    fn predict(&self, example: &[Value]) -> Value;
+}
+
+/// # Perceptron
+///
+/// The perceptron is a linear classifier over numeric inputs. It predicts the positive class when
+/// $w \cdot x \geq 0$, where $x$ is the example's inputs with a constant $1$ prepended for the bias
+/// weight. Every misclassified example nudges the weights toward its correct side:
+///
+/// $$ w_i \leftarrow w_i + \alpha \, y \, x_i $$
+///
+/// with $y$ being $+1$ or $-1$. If the classes can be separated by a line (or hyperplane), the
+/// perceptron is guaranteed to find one. Otherwise it never settles and training stops once the
+/// pass limit is reached.
+///
+/// # Examples
+///
+/// A 2-D dataset labelled by whether $x + y > 1$ is linearly separable:
+///
+/// ```
+/// # use aima_rust::learning::{DataSet, Learner, PerceptronLearner, Value};
+/// let points = [(0.0, 0.0), (0.2, 0.5), (0.4, 0.1), (1.0, 1.0), (0.9, 0.6), (0.3, 1.2)];
+/// let examples = points
+///     .iter()
+///     .map(|&(x, y)| vec![x.into(), y.into(), (if x + y > 1.0 { "above" } else { "below" }).into()])
+///     .collect();
+/// let dataset = DataSet::new(&["x", "y", "side"], examples, 2);
+///
+/// let mut perceptron = PerceptronLearner::new(0.1, 100, 42);
+/// perceptron.train(&dataset);
+///
+/// assert!(perceptron.converged());
+/// assert_eq!(perceptron.predict(&[1.5.into(), 1.5.into(), "?".into()]), Value::from("above"));
+/// assert_eq!(perceptron.predict(&[0.1.into(), 0.1.into(), "?".into()]), Value::from("below"));
+/// assert_eq!(perceptron.weights().len(), 3);
+/// ```
+///
+/// XOR is not, so the perceptron runs out of passes without converging:
+///
+/// ```
+/// # use aima_rust::learning::{DataSet, Learner, PerceptronLearner};
+/// let examples = vec![
+///     vec![0.0.into(), 0.0.into(), 0.0.into()],
+///     vec![0.0.into(), 1.0.into(), 1.0.into()],
+///     vec![1.0.into(), 0.0.into(), 1.0.into()],
+///     vec![1.0.into(), 1.0.into(), 0.0.into()],
+/// ];
+/// let dataset = DataSet::new(&["a", "b", "xor"], examples, 2);
+///
+/// let mut perceptron = PerceptronLearner::new(0.1, 50, 42);
+/// perceptron.train(&dataset);
+///
+/// assert!(!perceptron.converged());
+/// assert_eq!(perceptron.passes(), 50);
+/// ```
+///
+/// # Panics
+///
+/// `train` panics if the dataset has more than two classes, and `predict` panics if the
+/// perceptron hasn't been trained.
+pub struct PerceptronLearner {
+    learning_rate: f64,
+    max_passes: usize,
+    seed: u64,
+    inputs: Vec<usize>,
+    classes: Vec<Value>,
+    weights: Vec<f64>,
+    passes: usize,
+    converged: bool,
+}
+
+impl PerceptronLearner {
+    /// Creates a new PerceptronLearner that makes at most `max_passes` over the examples. The
+    /// initial weights are drawn from `seed`, so training the same data twice gives the same
+    /// weights.
+    pub fn new(learning_rate: f64, max_passes: usize, seed: u64) -> Self {
+        PerceptronLearner {
+            learning_rate,
+            max_passes,
+            seed,
+            inputs: Vec::new(),
+            classes: Vec::new(),
+            weights: Vec::new(),
+            passes: 0,
+            converged: false,
+        }
+    }
+
+    /// The learned weights, starting with the bias weight followed by one weight per input.
+    pub fn weights(&self) -> &[f64] {
+        &self.weights
+    }
+
+    /// Whether the last call to `train` classified every example correctly.
+    pub fn converged(&self) -> bool {
+        self.converged
+    }
+
+    /// The number of passes over the examples made by the last call to `train`.
+    pub fn passes(&self) -> usize {
+        self.passes
+    }
+
+    fn features(&self, example: &[Value]) -> Vec<f64> {
+        let mut x = vec![1.0];
+        x.extend(self.inputs.iter().map(|&i| {
+            example[i]
+                .as_f64()
+                .expect("perceptron inputs must be numeric")
+        }));
+        x
+    }
+
+    fn activation(&self, x: &[f64]) -> f64 {
+        let sum: f64 = self.weights.iter().zip(x).map(|(w, x)| w * x).sum();
+        if sum >= 0.0 {
+            1.0
+        } else {
+            -1.0
+        }
+    }
+}
+
+impl Learner for PerceptronLearner {
+    fn train(&mut self, dataset: &DataSet) {
+        self.inputs = dataset.inputs();
+        self.classes = dataset.classes();
+        assert!(
+            self.classes.len() <= 2,
+            "the perceptron can only separate two classes"
+        );
+
+        let mut rng = Rng::seeded(self.seed);
+        self.weights = (0..=self.inputs.len())
+            .map(|_| rng.gen_range(-0.5..0.5))
+            .collect();
+
+        self.passes = 0;
+        self.converged = false;
+        while self.passes < self.max_passes && !self.converged {
+            self.passes += 1;
+            self.converged = true;
+            for example in &dataset.examples {
+                let x = self.features(example);
+                let y = if example[dataset.target] == self.classes[0] {
+                    -1.0
+                } else {
+                    1.0
+                };
+                if self.activation(&x) != y {
+                    self.converged = false;
+                    for (w, x) in self.weights.iter_mut().zip(&x) {
+                        *w += self.learning_rate * y * x;
+                    }
+                }
+            }
+        }
+    }
+
+    fn predict(&self, example: &[Value]) -> Value {
+        assert!(
+            !self.classes.is_empty(),
+            "the perceptron must be trained before it can predict"
+        );
+        let x = self.features(example);
+        if self.activation(&x) < 0.0 || self.classes.len() < 2 {
+            self.classes[0].clone()
+        } else {
+            self.classes[1].clone()
+        }
+    }
+}
+
+/// # Decision Stump
+///
+/// A decision stump is a decision tree with a single test: it splits the examples on one input
+/// attribute and predicts a fixed class on each side of the split. Numeric attributes are split
+/// by a threshold, $x \leq t$, halfway between two adjacent values seen in training, and
+/// categorical ones by equality with one of their values.
+///
+/// On its own a stump is a weak classifier, but that is what boosting wants. Trained with
+/// `train`, it takes a weight for every example and picks the split with the smallest total
+/// weight of misclassified examples, so that reweighting the examples a previous stump got wrong
+/// steers the next one toward them. As a `Learner`, it weights every example equally.
+///
+/// # Examples
+///
+/// ```
+/// # use aima_rust::learning::{error_rate, DataSet, DecisionStump, Learner, Value};
+/// let labels = ["small", "small", "small", "big", "big", "small"];
+/// let examples = labels
+///     .iter()
+///     .enumerate()
+///     .map(|(i, &label)| vec![(i as f64 + 1.0).into(), label.into()])
+///     .collect();
+/// let dataset = DataSet::new(&["x", "size"], examples, 1);
+///
+/// // Evenly weighted, the best split is x <= 3.5, which gets the last example wrong.
+/// let mut stump = DecisionStump::new();
+/// stump.train(&dataset, &[1.0; 6]);
+/// assert_eq!(stump.attribute(), 0);
+/// assert_eq!(stump.predict(&[6.0.into(), "?".into()]), Value::from("big"));
+/// assert_eq!(stump.weighted_error(), 1.0);
+///
+/// // Once that example weighs more than the others together, the stump gets it right instead.
+/// stump.train(&dataset, &[1.0, 1.0, 1.0, 1.0, 1.0, 10.0]);
+/// assert_eq!(stump.predict(&[6.0.into(), "?".into()]), Value::from("small"));
+///
+/// // A concept one attribute separates is learned without error.
+/// let examples = (0..10)
+///     .map(|i| vec![(i as f64).into(), "noise".into(), (if i < 4 { "no" } else { "yes" }).into()])
+///     .collect();
+/// let dataset = DataSet::new(&["x", "other", "label"], examples, 2);
+/// let mut stump = DecisionStump::new();
+/// Learner::train(&mut stump, &dataset);
+/// assert_eq!(error_rate(&stump, &dataset), 0.0);
+/// ```
+#[derive(Clone, Debug)]
+pub struct DecisionStump {
+    attribute: usize,
+    test: StumpTest,
+    if_true: Value,
+    if_false: Value,
+    weighted_error: f64,
+}
+
+/// The test a `DecisionStump` applies to its attribute.
+#[derive(Clone, Debug, PartialEq)]
+enum StumpTest {
+    AtMost(f64),
+    Equals(Value),
+}
+
+impl StumpTest {
+    fn passes(&self, value: &Value) -> bool {
+        match self {
+            StumpTest::AtMost(t) => value.as_f64().is_some_and(|x| x <= *t),
+            StumpTest::Equals(v) => value == v,
+        }
+    }
+}
+
+impl DecisionStump {
+    /// Creates a new, untrained DecisionStump.
+    pub fn new() -> Self {
+        DecisionStump {
+            attribute: 0,
+            test: StumpTest::Equals(Value::Numeric(f64::NAN)),
+            if_true: Value::Numeric(f64::NAN),
+            if_false: Value::Numeric(f64::NAN),
+            weighted_error: 0.0,
+        }
+    }
+
+    /// Fits the stump to the examples in `dataset`, where `weights[i]` is the weight of the
+    /// `i`th example. Weights need not sum to one.
+    ///
+    /// # Panics
+    ///
+    /// Panics if there is not exactly one weight per example, or if the dataset has no examples
+    /// or no inputs.
+    pub fn train(&mut self, dataset: &DataSet, weights: &[f64]) {
+        assert_eq!(
+            weights.len(),
+            dataset.examples.len(),
+            "there must be one weight per example"
+        );
+        assert!(
+            !dataset.examples.is_empty() && !dataset.inputs().is_empty(),
+            "a stump needs examples and inputs to split"
+        );
+
+        let mut best: Option<DecisionStump> = None;
+        for attribute in dataset.inputs() {
+            for test in candidate_tests(dataset, attribute) {
+                let stump = fit_sides(dataset, weights, attribute, test);
+                if best
+                    .as_ref()
+                    .is_none_or(|b| stump.weighted_error < b.weighted_error)
+                {
+                    best = Some(stump);
+                }
+            }
+        }
+        // With a single distinct value for every input there is nothing to split on, so predict
+        // the weighted majority everywhere.
+        *self = best.unwrap_or_else(|| {
+            let attribute = dataset.inputs()[0];
+            let value = dataset.examples[0][attribute].clone();
+            fit_sides(dataset, weights, attribute, StumpTest::Equals(value))
+        });
+    }
+
+    /// The attribute the stump splits on.
+    pub fn attribute(&self) -> usize {
+        self.attribute
+    }
+
+    /// The total weight of the training examples the stump misclassifies.
+    pub fn weighted_error(&self) -> f64 {
+        self.weighted_error
+    }
+}
+
+impl Default for DecisionStump {
+    fn default() -> Self {
+        DecisionStump::new()
+    }
+}
+
+/// Every test worth trying on `attribute`: thresholds halfway between adjacent distinct numbers,
+/// or equality with each distinct category.
+fn candidate_tests(dataset: &DataSet, attribute: usize) -> Vec<StumpTest> {
+    let mut numbers = Vec::new();
+    let mut categories = Vec::new();
+    for example in &dataset.examples {
+        match &example[attribute] {
+            Value::Numeric(x) => numbers.push(*x),
+            category => {
+                if !categories.contains(category) {
+                    categories.push(category.clone());
+                }
+            }
+        }
+    }
+    numbers.sort_by(f64::total_cmp);
+    numbers.dedup();
+
+    let mut tests: Vec<StumpTest> = numbers
+        .windows(2)
+        .map(|pair| StumpTest::AtMost((pair[0] + pair[1]) / 2.0))
+        .collect();
+    if categories.len() > 1 {
+        tests.extend(categories.into_iter().map(StumpTest::Equals));
+    }
+    tests
+}
+
+/// The stump splitting on `test`, predicting the class of greatest weight on each side.
+fn fit_sides(
+    dataset: &DataSet,
+    weights: &[f64],
+    attribute: usize,
+    test: StumpTest,
+) -> DecisionStump {
+    let mut sides: [Vec<(Value, f64)>; 2] = [Vec::new(), Vec::new()];
+    for (example, &weight) in dataset.examples.iter().zip(weights) {
+        let side = &mut sides[if test.passes(&example[attribute]) {
+            0
+        } else {
+            1
+        }];
+        let class = &example[dataset.target];
+        match side.iter_mut().find(|(c, _)| c == class) {
+            Some((_, total)) => *total += weight,
+            None => side.push((class.clone(), weight)),
+        }
+    }
+
+    let total: f64 = weights.iter().sum();
+    let majority = |side: &[(Value, f64)]| {
+        side.iter()
+            .fold(None, |best: Option<&(Value, f64)>, entry| match best {
+                Some(b) if b.1 >= entry.1 => Some(b),
+                _ => Some(entry),
+            })
+            .cloned()
+    };
+    // A side no example reaches predicts the same as the other side.
+    let (true_side, false_side) = (majority(&sides[0]), majority(&sides[1]));
+    let unseen = |other: &Option<(Value, f64)>| other.clone().map(|(class, _)| (class, 0.0));
+    let (if_true, right_true) = true_side.clone().or_else(|| unseen(&false_side)).unwrap();
+    let (if_false, right_false) = false_side.or_else(|| unseen(&true_side)).unwrap();
+    DecisionStump {
+        attribute,
+        test,
+        if_true,
+        if_false,
+        weighted_error: total - right_true - right_false,
+    }
+}
+
+impl Learner for DecisionStump {
+    fn train(&mut self, dataset: &DataSet) {
+        DecisionStump::train(self, dataset, &vec![1.0; dataset.examples.len()]);
+    }
+
+    fn predict(&self, example: &[Value]) -> Value {
+        if self.test.passes(&example[self.attribute]) {
+            self.if_true.clone()
+        } else {
+            self.if_false.clone()
+        }
+    }
+}
+
+/// How often each value occurs, in the order the values first appear.
+type Counts = Vec<(Value, usize)>;
+
+fn add_count(counts: &mut Counts, value: &Value) {
+    match counts.iter_mut().find(|(v, _)| v == value) {
+        Some((_, n)) => *n += 1,
+        None => counts.push((value.clone(), 1)),
+    }
+}
+
+/// The entropy, in bits, of the distribution given by `counts`.
+fn entropy(counts: &[usize]) -> f64 {
+    let total: usize = counts.iter().sum();
+    counts
+        .iter()
+        .filter(|&&n| n > 0)
+        .map(|&n| {
+            let p = n as f64 / total as f64;
+            -p * p.log2()
+        })
+        .sum()
+}
+
+/// The most common value in `counts`, the earliest on ties.
+fn most_common(counts: &Counts) -> Option<&Value> {
+    counts
+        .iter()
+        .fold(None, |best: Option<&(Value, usize)>, c| match best {
+            Some(b) if b.1 >= c.1 => Some(b),
+            _ => Some(c),
+        })
+        .map(|(value, _)| value)
+}
+
+/// The statistics a leaf of a `HoeffdingTreeLearner` keeps about the examples that reached it.
+#[derive(Clone, Debug)]
+struct HoeffdingLeaf {
+    classes: Counts,
+    /// For each attribute still available to split on, the class counts for each of its values.
+    attributes: Vec<(usize, Vec<(Value, Counts)>)>,
+    since_check: usize,
+}
+
+impl HoeffdingLeaf {
+    fn new(attributes: Vec<usize>) -> Self {
+        HoeffdingLeaf {
+            classes: Vec::new(),
+            attributes: attributes.into_iter().map(|a| (a, Vec::new())).collect(),
+            since_check: 0,
+        }
+    }
+
+    fn examples(&self) -> usize {
+        self.classes.iter().map(|(_, n)| n).sum()
+    }
+
+    /// The information gain of splitting on each available attribute.
+    fn gains(&self) -> Vec<(usize, f64)> {
+        let n = self.examples() as f64;
+        let before = entropy(&self.classes.iter().map(|(_, n)| *n).collect::<Vec<_>>());
+        self.attributes
+            .iter()
+            .map(|(attribute, values)| {
+                let remainder: f64 = values
+                    .iter()
+                    .map(|(_, counts)| {
+                        let counts: Vec<usize> = counts.iter().map(|(_, n)| *n).collect();
+                        counts.iter().sum::<usize>() as f64 / n * entropy(&counts)
+                    })
+                    .sum();
+                (*attribute, before - remainder)
+            })
+            .collect()
+    }
+
+    /// The split the Hoeffding bound allows, given the bound `epsilon` for the examples seen.
+    fn split(&self, epsilon: f64, tie_threshold: f64) -> Option<HoeffdingNode> {
+        if self.classes.len() < 2 {
+            return None;
+        }
+        let mut gains = self.gains();
+        gains.sort_by(|a, b| b.1.total_cmp(&a.1));
+        let (best, g1) = *gains.first()?;
+        let g2 = gains.get(1).map_or(0.0, |&(_, g)| g);
+        if g1 <= 0.0 || (g1 - g2 <= epsilon && epsilon >= tie_threshold) {
+            return None;
+        }
+        let remaining: Vec<usize> = self
+            .attributes
+            .iter()
+            .map(|(a, _)| *a)
+            .filter(|&a| a != best)
+            .collect();
+        let (_, values) = self.attributes.iter().find(|(a, _)| *a == best)?;
+        Some(HoeffdingNode::Split {
+            attribute: best,
+            classes: self.classes.clone(),
+            children: values
+                .iter()
+                .map(|(value, counts)| {
+                    let mut child = HoeffdingLeaf::new(remaining.clone());
+                    child.classes = counts.clone();
+                    (value.clone(), HoeffdingNode::Leaf(child))
+                })
+                .collect(),
+        })
+    }
+}
+
+#[derive(Clone, Debug)]
+enum HoeffdingNode {
+    Leaf(HoeffdingLeaf),
+    Split {
+        attribute: usize,
+        /// The class counts of every example that has reached the node, used to predict for
+        /// values no example has brought to it yet.
+        classes: Counts,
+        children: Vec<(Value, HoeffdingNode)>,
+    },
+}
+
+/// # Hoeffding Tree
+///
+/// A decision tree learned incrementally from a stream of examples, also called a Very Fast
+/// Decision Tree. Each example is passed down to a leaf, which keeps counts of the classes seen
+/// for every value of every attribute not yet tested on the path. Every `grace_period` examples
+/// the leaf checks whether the attribute with the highest information gain, $G_1$, is clearly
+/// ahead of the runner-up, $G_2$. The Hoeffding bound says that after $n$ examples the observed
+/// gain is within
+///
+/// $$ \epsilon = \sqrt{\frac{R^2 \ln(1 / \delta)}{2n}} $$
+///
+/// of its true value with probability $1 - \delta$, where $R = \log_2 c$ is the range of the
+/// gain for $c$ classes. The leaf splits when $G_1 - G_2 > \epsilon$, or when $\epsilon$ drops
+/// below a tie threshold $\tau$ and the two attributes are too close to matter. So the tree only
+/// grows once the data justifies it, and each example is looked at once.
+///
+/// Attributes are treated as categorical. The defaults are $\delta = 10^{-6}$, $\tau = 0.05$
+/// and a grace period of 20.
+///
+/// # Examples
+///
+/// The class follows the first of four coin-flip attributes, with a quarter of the labels
+/// flipped at random:
+///
+/// ```
+/// # use aima_rust::learning::{HoeffdingTreeLearner, Learner, Value};
+/// # use aima_rust::util::Rng;
+/// use rand::Rng as _;
+/// let mut rng = Rng::seeded(7);
+/// let flip = |rng: &mut Rng| Value::from(if rng.gen_bool(0.5) { "yes" } else { "no" });
+/// let mut example = |rng: &mut Rng| {
+///     let mut example: Vec<Value> = (0..4).map(|_| flip(rng)).collect();
+///     let noisy = rng.gen_bool(0.25);
+///     let class = if (example[0] == Value::from("yes")) != noisy { "+" } else { "-" };
+///     example.push(class.into());
+///     example
+/// };
+///
+/// let mut tree = HoeffdingTreeLearner::new(4);
+/// for _ in 0..60 {
+///     tree.update(&example(&mut rng));
+/// }
+/// // Sixty noisy examples aren't enough to be sure of anything.
+/// assert_eq!(tree.root_attribute(), None);
+/// assert_eq!(tree.leaf_counts(), vec![60]);
+///
+/// for _ in 0..2000 {
+///     tree.update(&example(&mut rng));
+/// }
+/// assert_eq!(tree.root_attribute(), Some(0));
+/// assert_eq!(tree.leaf_counts().iter().sum::<usize>(), 2060);
+///
+/// let yes = vec!["yes".into(), "no".into(), "yes".into(), "no".into(), "?".into()];
+/// let no = vec!["no".into(), "no".into(), "yes".into(), "yes".into(), "?".into()];
+/// assert_eq!(tree.predict(&yes), Value::from("+"));
+/// assert_eq!(tree.predict(&no), Value::from("-"));
+/// ```
+#[derive(Clone, Debug)]
+pub struct HoeffdingTreeLearner {
+    target: usize,
+    delta: f64,
+    tie_threshold: f64,
+    grace_period: usize,
+    classes: Vec<Value>,
+    root: Option<HoeffdingNode>,
+}
+
+impl HoeffdingTreeLearner {
+    /// Creates a new, empty tree given the index of the target attribute in each example.
+    pub fn new(target: usize) -> Self {
+        HoeffdingTreeLearner {
+            target,
+            delta: 1e-6,
+            tie_threshold: 0.05,
+            grace_period: 20,
+            classes: Vec::new(),
+            root: None,
+        }
+    }
+
+    /// Sets $\delta$, the probability of the bound being wrong that each split may risk.
+    pub fn with_delta(mut self, delta: f64) -> Self {
+        self.delta = delta;
+        self
+    }
+
+    /// Sets the tie threshold $\tau$.
+    pub fn with_tie_threshold(mut self, tie_threshold: f64) -> Self {
+        self.tie_threshold = tie_threshold;
+        self
+    }
+
+    /// Sets how many examples a leaf takes in between checks for a split.
+    pub fn with_grace_period(mut self, grace_period: usize) -> Self {
+        self.grace_period = grace_period.max(1);
+        self
+    }
+
+    /// Learns from one more example, splitting the leaf it reaches if the bound allows.
+    pub fn update(&mut self, example: &[Value]) {
+        let class = &example[self.target];
+        if !self.classes.contains(class) {
+            self.classes.push(class.clone());
+        }
+        let target = self.target;
+        let grace_period = self.grace_period;
+        let range = (self.classes.len().max(2) as f64).log2();
+        let scale = range * range * (1.0 / self.delta).ln();
+        let bound = |n: usize| (scale / (2.0 * n as f64)).sqrt();
+        let tie_threshold = self.tie_threshold;
+        let mut node = self.root.get_or_insert_with(|| {
+            HoeffdingNode::Leaf(HoeffdingLeaf::new(
+                (0..example.len()).filter(|&i| i != target).collect(),
+            ))
+        });
+        let mut tested = Vec::new();
+        while let HoeffdingNode::Split {
+            attribute,
+            children,
+            classes,
+        } = node
+        {
+            let value = &example[*attribute];
+            tested.push(*attribute);
+            let index = match children.iter().position(|(v, _)| v == value) {
+                Some(index) => index,
+                None => {
+                    let attributes = (0..example.len())
+                        .filter(|a| *a != target && !tested.contains(a))
+                        .collect();
+                    let leaf = HoeffdingLeaf::new(attributes);
+                    children.push((value.clone(), HoeffdingNode::Leaf(leaf)));
+                    children.len() - 1
+                }
+            };
+            add_count(classes, class);
+            node = &mut children[index].1;
+        }
+        if let HoeffdingNode::Leaf(leaf) = node {
+            add_count(&mut leaf.classes, class);
+            for (attribute, values) in &mut leaf.attributes {
+                let value = &example[*attribute];
+                match values.iter_mut().find(|(v, _)| v == value) {
+                    Some((_, counts)) => add_count(counts, class),
+                    None => values.push((value.clone(), vec![(class.clone(), 1)])),
+                }
+            }
+            leaf.since_check += 1;
+            if leaf.since_check >= grace_period {
+                leaf.since_check = 0;
+                let epsilon = bound(leaf.examples());
+                if let Some(split) = leaf.split(epsilon, tie_threshold) {
+                    *node = split;
+                }
+            }
+        }
+    }
+
+    /// The attribute tested at the root of the tree, or `None` while the root is still a leaf.
+    pub fn root_attribute(&self) -> Option<usize> {
+        match &self.root {
+            Some(HoeffdingNode::Split { attribute, .. }) => Some(*attribute),
+            _ => None,
+        }
+    }
+
+    /// The number of examples that have reached each leaf, from left to right. A leaf created
+    /// by a split starts with the examples its parent saw with its value.
+    pub fn leaf_counts(&self) -> Vec<usize> {
+        fn collect(node: &HoeffdingNode, counts: &mut Vec<usize>) {
+            match node {
+                HoeffdingNode::Leaf(leaf) => counts.push(leaf.examples()),
+                HoeffdingNode::Split { children, .. } => {
+                    for (_, child) in children {
+                        collect(child, counts);
+                    }
+                }
+            }
+        }
+        let mut counts = Vec::new();
+        if let Some(root) = &self.root {
+            collect(root, &mut counts);
+        }
+        counts
+    }
+}
+
+impl Learner for HoeffdingTreeLearner {
+    /// Starts a new tree and streams the examples of `dataset` into it, in order.
+    fn train(&mut self, dataset: &DataSet) {
+        self.target = dataset.target;
+        self.classes.clear();
+        self.root = None;
+        for example in &dataset.examples {
+            self.update(example);
+        }
+    }
+
+    /// Predicts the most common class at the leaf `example` reaches, or at the deepest node it
+    /// reaches if it has a value no example has brought there yet.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the tree hasn't seen any examples.
+    fn predict(&self, example: &[Value]) -> Value {
+        let mut node = self
+            .root
+            .as_ref()
+            .expect("the tree hasn't seen any examples");
+        loop {
+            match node {
+                HoeffdingNode::Leaf(leaf) => return most_common(&leaf.classes).unwrap().clone(),
+                HoeffdingNode::Split {
+                    attribute,
+                    children,
+                    classes,
+                } => match children.iter().find(|(v, _)| *v == example[*attribute]) {
+                    Some((_, child)) => node = child,
+                    None => return most_common(classes).unwrap().clone(),
+                },
+            }
+        }
+    }
+}
+
+/// A decision tree, as learned by `DecisionTreeLearner`. Each internal node tests an attribute
+/// of the example and passes it on to one of its subtrees, and each leaf holds the class it
+/// predicts.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum DecisionTree {
+    /// A leaf predicting its class.
+    Leaf(Value),
+    /// A test of a categorical attribute, with a subtree for each of its values. An example with
+    /// a value no branch is for is given `plurality`, the most common class of the training
+    /// examples that reached the node.
+    Split {
+        attribute: usize,
+        branches: Vec<(Value, DecisionTree)>,
+        plurality: Value,
+    },
+    /// A test of a numeric attribute: examples with a value of at most `threshold` go to
+    /// `at_most`, and the others to `above`. An example whose value isn't a number is given
+    /// `plurality`.
+    Threshold {
+        attribute: usize,
+        threshold: f64,
+        at_most: Box<DecisionTree>,
+        above: Box<DecisionTree>,
+        plurality: Value,
+    },
+}
+
+impl DecisionTree {
+    /// The class the tree predicts for `example`, a full row of the DataSet it was learned from.
+    pub fn predict(&self, example: &[Value]) -> Value {
+        let mut node = self;
+        loop {
+            match node {
+                DecisionTree::Leaf(class) => return class.clone(),
+                DecisionTree::Split {
+                    attribute,
+                    branches,
+                    plurality,
+                } => match branches.iter().find(|(v, _)| *v == example[*attribute]) {
+                    Some((_, child)) => node = child,
+                    None => return plurality.clone(),
+                },
+                DecisionTree::Threshold {
+                    attribute,
+                    threshold,
+                    at_most,
+                    above,
+                    plurality,
+                } => match example[*attribute].as_f64() {
+                    Some(x) if x <= *threshold => node = at_most,
+                    Some(_) => node = above,
+                    None => return plurality.clone(),
+                },
+            }
+        }
+    }
+
+    /// The number of nodes in the tree, leaves included.
+    pub fn size(&self) -> usize {
+        1 + self
+            .children()
+            .into_iter()
+            .map(DecisionTree::size)
+            .sum::<usize>()
+    }
+
+    /// Saves the tree with `serializer`, along with `DECISION_TREE_FORMAT_VERSION`. Every split,
+    /// threshold and leaf is kept exactly, so a tree read back with `load` makes the same
+    /// predictions, as long as the format can represent every `f64` exactly.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use aima_rust::learning::{DataSet, DecisionTree, DecisionTreeLearner, Learner, Value};
+    /// # use aima_rust::util::Rng;
+    /// use rand::Rng as _;
+    /// let mut rng = Rng::seeded(3);
+    /// let examples = (0..200)
+    ///     .map(|_| {
+    ///         let (x, y): (f64, f64) = (rng.gen(), rng.gen());
+    ///         let class = if x * x + y > 0.7 { "out" } else { "in" };
+    ///         vec![x.into(), y.into(), class.into()]
+    ///     })
+    ///     .collect();
+    /// let dataset = DataSet::new(&["x", "y", "class"], examples, 2);
+    /// let (train, test) = dataset.train_test_split(0.5, false, 1);
+    ///
+    /// let mut learner = DecisionTreeLearner::new(None);
+    /// learner.train(&train);
+    /// let tree = learner.tree().unwrap();
+    ///
+    /// let mut saved = Vec::new();
+    /// tree.save(&mut serde_json::Serializer::new(&mut saved)).unwrap();
+    /// let loaded = DecisionTree::load(&mut serde_json::Deserializer::from_slice(&saved)).unwrap();
+    ///
+    /// assert_eq!(&loaded, tree);
+    /// for example in &test.examples {
+    ///     assert_eq!(loaded.predict(example), learner.predict(example));
+    /// }
+    ///
+    /// // A tree saved in some other version of the format is refused.
+    /// let future = r#"{"version":2,"tree":{"Leaf":{"Categorical":"in"}}}"#;
+    /// assert!(DecisionTree::load(&mut serde_json::Deserializer::from_str(future)).is_err());
+    /// ```
+    #[cfg(feature = "serde")]
+    pub fn save<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        SavedTree {
+            version: DECISION_TREE_FORMAT_VERSION,
+            tree: self,
+        }
+        .serialize(serializer)
+    }
+
+    /// Loads a tree saved by `save`. Fails if the input isn't a saved tree, or if it was saved
+    /// in a version of the format other than `DECISION_TREE_FORMAT_VERSION`.
+    #[cfg(feature = "serde")]
+    pub fn load<'de, D: Deserializer<'de>>(deserializer: D) -> Result<DecisionTree, D::Error> {
+        let saved = SavedTree::<DecisionTree>::deserialize(deserializer)?;
+        if saved.version != DECISION_TREE_FORMAT_VERSION {
+            return Err(D::Error::custom(format!(
+                "decision tree format version {} is not supported, expected {}",
+                saved.version, DECISION_TREE_FORMAT_VERSION
+            )));
+        }
+        Ok(saved.tree)
+    }
+
+    fn children(&self) -> Vec<&DecisionTree> {
+        match self {
+            DecisionTree::Leaf(_) => Vec::new(),
+            DecisionTree::Split { branches, .. } => {
+                branches.iter().map(|(_, child)| child).collect()
+            }
+            DecisionTree::Threshold { at_most, above, .. } => vec![at_most, above],
+        }
+    }
+}
+
+/// The version of the format `DecisionTree::save` writes. It changes whenever the layout of a
+/// saved tree does, and `DecisionTree::load` refuses trees saved in any other version.
+#[cfg(feature = "serde")]
+pub const DECISION_TREE_FORMAT_VERSION: u32 = 1;
+
+/// A `DecisionTree` as saved, with the version of its format.
+#[cfg(feature = "serde")]
+#[derive(Serialize, Deserialize)]
+struct SavedTree<T> {
+    version: u32,
+    tree: T,
+}
+
+/// Figure 18.5
+///
+/// Decision-Tree-Learning grows a tree top-down. If the examples at a node all have the same
+/// class, or there are no attributes left to test, the node becomes a leaf predicting their most
+/// common class. Otherwise it tests the most important attribute, the one with the greatest
+/// information gain, and each of its subtrees is learned from the examples with the
+/// corresponding outcome. A subtree that no example reaches predicts the most common class at
+/// its parent.
+///
+/// A categorical attribute gets a branch for each value it takes anywhere in the DataSet, and is
+/// not tested again further down. A numeric attribute is split in two at the threshold with the
+/// greatest gain, halfway between adjacent values, and may be split again at another threshold
+/// below.
+///
+/// # Examples
+///
+/// On the restaurant examples, the tree tests `Pat` first, as in figure 18.6, and classifies
+/// every example correctly:
+///
+/// ```
+/// # use aima_rust::learning::{error_rate, restaurant, DecisionTree, DecisionTreeLearner, Learner};
+/// let dataset = restaurant();
+/// let mut learner = DecisionTreeLearner::new(None);
+/// learner.train(&dataset);
+///
+/// let tree = learner.tree().unwrap();
+/// match tree {
+///     DecisionTree::Split { attribute, .. } => assert_eq!(dataset.attr_names[*attribute], "Pat"),
+///     _ => panic!("expected a test of Pat at the root"),
+/// }
+/// assert_eq!(tree.size(), 12);
+/// assert_eq!(error_rate(&learner, &dataset), 0.0);
+/// ```
+///
+/// # Panics
+///
+/// `train` panics if the dataset has no examples, and `predict` panics if the learner hasn't been
+/// trained.
+#[derive(Clone, Debug)]
+pub struct DecisionTreeLearner {
+    significance: Option<f64>,
+    validation_fraction: Option<f64>,
+    tree: Option<DecisionTree>,
+}
+
+impl DecisionTreeLearner {
+    /// Creates a new, untrained DecisionTreeLearner given the `significance` level of the
+    /// $\chi^2$ test each split must pass, or `None` to split whenever there are examples of
+    /// more than one class and an attribute left to test. Without pruning, the full tree is
+    /// grown.
+    ///
+    /// The $\chi^2$ test stops the tree from growing where a split is no better than chance.
+    /// If a test with $d$ outcomes sends $N_k$ of the node's $N$ examples to outcome $k$, and
+    /// $N_{kc}$ of those are of class $c$, the deviation from what an irrelevant attribute would
+    /// give is
+    ///
+    /// $$ \Delta = \sum_k \sum_c \frac{(N_{kc} - \hat N_{kc})^2}{\hat N_{kc}}, \quad
+    /// \hat N_{kc} = N_c \frac{N_k}{N} $$
+    ///
+    /// which for an irrelevant attribute follows a $\chi^2$ distribution with
+    /// $(d - 1)(c - 1)$ degrees of freedom, counting only outcomes and classes the examples
+    /// have. The best split is made only if a deviation at least as large would happen by
+    /// chance with probability below `significance`; otherwise the node becomes a leaf. A
+    /// typical level is $0.05$.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `significance` is not between `0.0` and `1.0`.
+    ///
+    /// # Examples
+    ///
+    /// The class is a fair coin flip, and so is the only attribute. Without the test the tree
+    /// splits on the attribute anyway, fitting the noise; with it the tree is a single leaf:
+    ///
+    /// ```
+    /// # use aima_rust::learning::{DataSet, DecisionTree, DecisionTreeLearner, Learner, Value};
+    /// # use aima_rust::util::Rng;
+    /// use rand::Rng as _;
+    /// let side = |heads: bool| Value::from(if heads { "heads" } else { "tails" });
+    /// let mut rng = Rng::seeded(1);
+    /// let mut sample = |noise: f64| {
+    ///     let examples = (0..100)
+    ///         .map(|_| {
+    ///             let coin = rng.gen_bool(0.5);
+    ///             let class = if rng.gen_bool(noise) { rng.gen_bool(0.5) } else { coin };
+    ///             vec![side(coin), side(class)]
+    ///         })
+    ///         .collect();
+    ///     DataSet::new(&["coin", "class"], examples, 1)
+    /// };
+    /// let is_leaf = |learner: &DecisionTreeLearner| {
+    ///     matches!(learner.tree().unwrap(), DecisionTree::Leaf(_))
+    /// };
+    ///
+    /// let noise = sample(1.0);
+    /// let mut unchecked = DecisionTreeLearner::new(None);
+    /// unchecked.train(&noise);
+    /// assert!(!is_leaf(&unchecked));
+    /// let mut checked = DecisionTreeLearner::new(Some(0.05));
+    /// checked.train(&noise);
+    /// assert!(is_leaf(&checked));
+    ///
+    /// // An attribute that does predict the class still passes the test.
+    /// checked.train(&sample(0.3));
+    /// assert!(!is_leaf(&checked));
+    /// ```
+    pub fn new(significance: Option<f64>) -> Self {
+        assert!(
+            significance.is_none_or(|p| (0.0..=1.0).contains(&p)),
+            "the significance level must be between 0 and 1"
+        );
+        DecisionTreeLearner {
+            significance,
+            validation_fraction: None,
+            tree: None,
+        }
+    }
+
+    /// Prunes the tree by reduced-error pruning. A `validation_fraction` of the training
+    /// examples, stratified by class, is set aside and the tree is grown from the rest. Then,
+    /// working up from the bottom, each test is replaced by a leaf predicting the most common
+    /// class of the training examples that reached it, whenever that makes no more mistakes on
+    /// the validation examples that reach it. The validation examples are drawn with a fixed
+    /// seed, so training stays deterministic.
+    ///
+    /// A fully grown tree fits the noise in its examples as well as the pattern. Pruning cuts
+    /// back the tests that only the noise supports, which gives a smaller tree that usually does
+    /// at least as well on new examples, at the cost of growing it from fewer of them.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `validation_fraction` is not strictly between `0.0` and `1.0`.
+    ///
+    /// # Examples
+    ///
+    /// Two hundred examples labelled by whether $x > 0.5$, with a fifth of the labels flipped,
+    /// and a noise-free test set:
+    ///
+    /// ```
+    /// # use aima_rust::learning::{error_rate, DataSet, DecisionTreeLearner, Learner};
+    /// # use aima_rust::util::Rng;
+    /// use rand::Rng as _;
+    /// let mut rng = Rng::seeded(5);
+    /// let mut sample = |n: usize, noise: f64| {
+    ///     let examples = (0..n)
+    ///         .map(|_| {
+    ///             let (x, y): (f64, f64) = (rng.gen(), rng.gen());
+    ///             let high = (x > 0.5) != rng.gen_bool(noise);
+    ///             vec![x.into(), y.into(), (if high { "high" } else { "low" }).into()]
+    ///         })
+    ///         .collect();
+    ///     DataSet::new(&["x", "y", "class"], examples, 2)
+    /// };
+    /// let (train, test) = (sample(200, 0.2), sample(1000, 0.0));
+    ///
+    /// let mut full = DecisionTreeLearner::new(None);
+    /// full.train(&train);
+    /// let mut pruned = DecisionTreeLearner::new(None).with_pruning(0.3);
+    /// pruned.train(&train);
+    ///
+    /// assert!(pruned.tree().unwrap().size() < full.tree().unwrap().size());
+    /// assert!(error_rate(&pruned, &test) <= error_rate(&full, &test));
+    /// ```
+    pub fn with_pruning(mut self, validation_fraction: f64) -> Self {
+        assert!(
+            0.0 < validation_fraction && validation_fraction < 1.0,
+            "the validation fraction must be between 0 and 1"
+        );
+        self.validation_fraction = Some(validation_fraction);
+        self
+    }
+
+    /// The learned tree, or `None` before training.
+    pub fn tree(&self) -> Option<&DecisionTree> {
+        self.tree.as_ref()
+    }
+}
+
+impl Default for DecisionTreeLearner {
+    fn default() -> Self {
+        DecisionTreeLearner::new(None)
+    }
+}
+
+impl Learner for DecisionTreeLearner {
+    fn train(&mut self, dataset: &DataSet) {
+        assert!(
+            !dataset.examples.is_empty(),
+            "a decision tree needs at least one example"
+        );
+        let (train, validation) = match self.validation_fraction {
+            Some(fraction) => {
+                let (train, validation) = dataset.train_test_split(fraction, true, 0);
+                (train.examples, validation.examples)
+            }
+            None => (dataset.examples.clone(), Vec::new()),
+        };
+        let examples: Vec<&[Value]> = train.iter().map(Vec::as_slice).collect();
+        let tree = learn_tree(
+            dataset,
+            &examples,
+            &dataset.inputs(),
+            &examples,
+            self.significance,
+        );
+        self.tree = Some(if self.validation_fraction.is_some() {
+            let validation: Vec<&[Value]> = validation.iter().map(Vec::as_slice).collect();
+            prune(tree, &validation, dataset.target)
+        } else {
+            tree
+        });
+    }
+
+    fn predict(&self, example: &[Value]) -> Value {
+        self.tree
+            .as_ref()
+            .expect("the tree hasn't been trained")
+            .predict(example)
+    }
+}
+
+/// A test a node of a `DecisionTree` may apply.
+enum TreeTest {
+    Values(usize),
+    AtMost(usize, f64),
+}
+
+impl TreeTest {
+    /// Which of the test's outcomes `example` has: the index of its value among `values` for a
+    /// categorical test, and 0 or 1 for a threshold.
+    fn outcome(&self, example: &[Value], values: &[Value]) -> usize {
+        match *self {
+            TreeTest::Values(attribute) => values
+                .iter()
+                .position(|v| *v == example[attribute])
+                .unwrap(),
+            TreeTest::AtMost(attribute, threshold) => {
+                usize::from(example[attribute].as_f64().is_none_or(|x| x > threshold))
+            }
+        }
+    }
+}
+
+fn class_counts(examples: &[&[Value]], target: usize) -> Counts {
+    let mut counts = Counts::new();
+    for example in examples {
+        add_count(&mut counts, &example[target]);
+    }
+    counts
+}
+
+/// Decision-Tree-Learning on `examples`, testing only `attributes`. `parent` holds the examples
+/// at the node above, whose most common class is predicted if no example is left. With a
+/// `significance` level, a split that fails the $\chi^2$ test makes the node a leaf instead.
+fn learn_tree(
+    dataset: &DataSet,
+    examples: &[&[Value]],
+    attributes: &[usize],
+    parent: &[&[Value]],
+    significance: Option<f64>,
+) -> DecisionTree {
+    let target = dataset.target;
+    if examples.is_empty() {
+        let counts = class_counts(parent, target);
+        return DecisionTree::Leaf(most_common(&counts).unwrap().clone());
+    }
+    let counts = class_counts(examples, target);
+    let plurality = most_common(&counts).unwrap().clone();
+    if counts.len() == 1 {
+        return DecisionTree::Leaf(plurality);
+    }
+
+    let before = entropy(&counts.iter().map(|(_, n)| *n).collect::<Vec<_>>());
+    let mut best: Option<(TreeTest, Vec<Value>, f64)> = None;
+    for &attribute in attributes {
+        let (tests, values) = match attribute_values(dataset, attribute) {
+            Some(values) => (vec![TreeTest::Values(attribute)], values),
+            None => (thresholds(examples, attribute), Vec::new()),
+        };
+        for test in tests {
+            let groups = partition(examples, &test, &values, 2.max(values.len()));
+            let remainder: f64 = groups
+                .iter()
+                .map(|group| {
+                    let counts: Vec<usize> = class_counts(group, target)
+                        .iter()
+                        .map(|(_, n)| *n)
+                        .collect();
+                    group.len() as f64 / examples.len() as f64 * entropy(&counts)
+                })
+                .sum();
+            let gain = before - remainder;
+            if best.as_ref().is_none_or(|(_, _, b)| gain > *b) {
+                best = Some((test, values.clone(), gain));
+            }
+        }
+    }
+    let (test, values, _) = match best {
+        Some(best) => best,
+        None => return DecisionTree::Leaf(plurality),
+    };
+
+    let groups = partition(examples, &test, &values, 2.max(values.len()));
+    if significance.is_some_and(|level| split_p_value(&counts, &groups, target) >= level) {
+        return DecisionTree::Leaf(plurality);
+    }
+    match test {
+        TreeTest::Values(attribute) => {
+            let remaining: Vec<usize> = attributes
+                .iter()
+                .cloned()
+                .filter(|&a| a != attribute)
+                .collect();
+            DecisionTree::Split {
+                attribute,
+                branches: values
+                    .into_iter()
+                    .zip(&groups)
+                    .map(|(value, group)| {
+                        (
+                            value,
+                            learn_tree(dataset, group, &remaining, examples, significance),
+                        )
+                    })
+                    .collect(),
+                plurality,
+            }
+        }
+        TreeTest::AtMost(attribute, threshold) => DecisionTree::Threshold {
+            attribute,
+            threshold,
+            at_most: Box::new(learn_tree(
+                dataset,
+                &groups[0],
+                attributes,
+                examples,
+                significance,
+            )),
+            above: Box::new(learn_tree(
+                dataset,
+                &groups[1],
+                attributes,
+                examples,
+                significance,
+            )),
+            plurality,
+        },
+    }
+}
+
+/// The probability of a split of examples with class counts `counts` into `groups` deviating
+/// at least this much from the class proportions by chance, by the $\chi^2$ test.
+fn split_p_value(counts: &Counts, groups: &[Vec<&[Value]>], target: usize) -> f64 {
+    let total: usize = counts.iter().map(|(_, n)| n).sum();
+    let mut deviation = 0.0;
+    let mut outcomes = 0;
+    for group in groups.iter().filter(|group| !group.is_empty()) {
+        outcomes += 1;
+        let observed = class_counts(group, target);
+        for (class, n) in counts {
+            let expected = *n as f64 * group.len() as f64 / total as f64;
+            let seen = observed
+                .iter()
+                .find(|(c, _)| c == class)
+                .map_or(0, |(_, n)| *n);
+            deviation += (seen as f64 - expected).powi(2) / expected;
+        }
+    }
+    let freedom = (outcomes - 1) * (counts.len() - 1);
+    if freedom == 0 {
+        return 1.0;
+    }
+    chi_squared_tail(deviation, freedom)
+}
+
+/// The probability of a $\chi^2$ distribution with `freedom` degrees of freedom exceeding `x`.
+/// This is the regularized upper incomplete gamma function $Q(k/2, x/2)$, computed by its
+/// series for small $x$ and by its continued fraction otherwise.
+fn chi_squared_tail(x: f64, freedom: usize) -> f64 {
+    let (a, x) = (freedom as f64 / 2.0, x / 2.0);
+    if x <= 0.0 {
+        return 1.0;
+    }
+    let scale = (a * x.ln() - x - ln_gamma(a)).exp();
+    if x < a + 1.0 {
+        let (mut term, mut sum) = (1.0 / a, 1.0 / a);
+        for n in 1..1000 {
+            term *= x / (a + n as f64);
+            sum += term;
+            if term.abs() < sum.abs() * 1e-15 {
+                break;
+            }
+        }
+        (1.0 - sum * scale).max(0.0)
+    } else {
+        let tiny = 1e-300;
+        let mut b = x + 1.0 - a;
+        let (mut c, mut d) = (1.0 / tiny, 1.0 / b);
+        let mut fraction = d;
+        for i in 1..1000 {
+            let an = -(i as f64) * (i as f64 - a);
+            b += 2.0;
+            d = an * d + b;
+            if d.abs() < tiny {
+                d = tiny;
+            }
+            c = b + an / c;
+            if c.abs() < tiny {
+                c = tiny;
+            }
+            d = 1.0 / d;
+            fraction *= d * c;
+            if (d * c - 1.0).abs() < 1e-15 {
+                break;
+            }
+        }
+        scale * fraction
+    }
+}
+
+/// The natural logarithm of the gamma function, by the Lanczos approximation.
+fn ln_gamma(x: f64) -> f64 {
+    const COEFFICIENTS: [f64; 9] = [
+        0.999_999_999_999_809_9,
+        676.520_368_121_885_1,
+        -1_259.139_216_722_402_8,
+        771.323_428_777_653_1,
+        -176.615_029_162_140_6,
+        12.507_343_278_686_905,
+        -0.138_571_095_265_720_12,
+        9.984_369_578_019_572e-6,
+        1.505_632_735_149_311_6e-7,
+    ];
+    if x < 0.5 {
+        let pi = std::f64::consts::PI;
+        return (pi / (pi * x).sin()).ln() - ln_gamma(1.0 - x);
+    }
+    let x = x - 1.0;
+    let t = x + 7.5;
+    let series = COEFFICIENTS[1..]
+        .iter()
+        .enumerate()
+        .fold(COEFFICIENTS[0], |sum, (i, c)| {
+            sum + c / (x + i as f64 + 1.0)
+        });
+    0.5 * (2.0 * std::f64::consts::PI).ln() + (x + 0.5) * t.ln() - t + series.ln()
+}
+
+/// Reduced-error pruning of `tree`, given the `validation` examples that reach it. Its subtrees
+/// are pruned first, and then the tree itself becomes a leaf if that is no worse on `validation`.
+fn prune(tree: DecisionTree, validation: &[&[Value]], target: usize) -> DecisionTree {
+    let reaching = |keep: &dyn Fn(&Value) -> bool, attribute: usize| -> Vec<&[Value]> {
+        validation
+            .iter()
+            .filter(|example| keep(&example[attribute]))
+            .cloned()
+            .collect()
+    };
+    let (tree, plurality) = match tree {
+        DecisionTree::Leaf(_) => return tree,
+        DecisionTree::Split {
+            attribute,
+            branches,
+            plurality,
+        } => (
+            DecisionTree::Split {
+                attribute,
+                branches: branches
+                    .into_iter()
+                    .map(|(value, child)| {
+                        let reaching = reaching(&|v| *v == value, attribute);
+                        (value, prune(child, &reaching, target))
+                    })
+                    .collect(),
+                plurality: plurality.clone(),
+            },
+            plurality,
+        ),
+        DecisionTree::Threshold {
+            attribute,
+            threshold,
+            at_most,
+            above,
+            plurality,
+        } => {
+            let at_most_reaching =
+                reaching(&|v| v.as_f64().is_some_and(|x| x <= threshold), attribute);
+            let above_reaching =
+                reaching(&|v| v.as_f64().is_some_and(|x| x > threshold), attribute);
+            (
+                DecisionTree::Threshold {
+                    attribute,
+                    threshold,
+                    at_most: Box::new(prune(*at_most, &at_most_reaching, target)),
+                    above: Box::new(prune(*above, &above_reaching, target)),
+                    plurality: plurality.clone(),
+                },
+                plurality,
+            )
+        }
+    };
+    let mistakes = |tree: &DecisionTree| {
+        validation
+            .iter()
+            .filter(|example| tree.predict(example) != example[target])
+            .count()
+    };
+    let leaf = DecisionTree::Leaf(plurality);
+    if mistakes(&leaf) <= mistakes(&tree) {
+        leaf
+    } else {
+        tree
+    }
+}
+
+/// The distinct values `attribute` takes in the whole DataSet, in the order they first appear,
+/// or `None` if they are all numbers.
+fn attribute_values(dataset: &DataSet, attribute: usize) -> Option<Vec<Value>> {
+    let mut values = Vec::new();
+    let mut numeric = true;
+    for example in &dataset.examples {
+        numeric &= example[attribute].as_f64().is_some();
+        if !values.contains(&example[attribute]) {
+            values.push(example[attribute].clone());
+        }
+    }
+    if numeric {
+        None
+    } else {
+        Some(values)
+    }
+}
+
+/// Threshold tests on a numeric attribute, halfway between adjacent distinct values among
+/// `examples`.
+fn thresholds(examples: &[&[Value]], attribute: usize) -> Vec<TreeTest> {
+    let mut numbers: Vec<f64> = examples
+        .iter()
+        .filter_map(|example| example[attribute].as_f64())
+        .collect();
+    numbers.sort_by(f64::total_cmp);
+    numbers.dedup();
+    numbers
+        .windows(2)
+        .map(|pair| TreeTest::AtMost(attribute, (pair[0] + pair[1]) / 2.0))
+        .collect()
+}
+
+/// Groups `examples` by the outcome of `test`, into `outcomes` groups.
+fn partition<'a>(
+    examples: &[&'a [Value]],
+    test: &TreeTest,
+    values: &[Value],
+    outcomes: usize,
+) -> Vec<Vec<&'a [Value]>> {
+    let mut groups = vec![Vec::new(); outcomes];
+    for example in examples {
+        groups[test.outcome(example, values)].push(*example);
+    }
+    groups
+}
+
+/// An expert for `WeightedMajority`: any function predicting an outcome from an input.
+pub type Expert<X, Y> = Box<dyn Fn(&X) -> Y>;
+
+/// # Weighted Majority
+///
+/// An online learner that combines the advice of a panel of experts. Each expert has a weight,
+/// initially equal; the ensemble predicts whichever outcome has the greatest total weight behind
+/// it. Once the true outcome is known, every expert that got it wrong has its weight multiplied
+/// by $e^{-\eta}$, for a learning rate $\eta$:
+///
+/// $$ w_i \leftarrow w_i \, e^{-\eta \, [\text{expert } i \text{ was wrong}]} $$
+///
+/// Weights are then rescaled to sum to one. The ensemble's mistakes are bounded by a constant
+/// times those of the best expert, plus a term logarithmic in the number of experts, whatever the
+/// sequence of outcomes. A larger $\eta$ trusts recent mistakes more.
+///
+/// # Examples
+///
+/// ```
+/// # use aima_rust::learning::WeightedMajority;
+/// // Forecasting rain from the day number: one expert knows the pattern, the others don't.
+/// let truth = |day: &u32| day % 3 == 0;
+/// let mut forecast = WeightedMajority::new(vec![
+///     Box::new(|_: &u32| true),
+///     Box::new(|_: &u32| false),
+///     Box::new(|day: &u32| day % 2 == 0),
+///     Box::new(truth),
+/// ]);
+/// assert_eq!(forecast.learning_rate(), 0.5);
+///
+/// for day in 0..30 {
+///     forecast.update(&day, &truth(&day));
+/// }
+///
+/// assert!(forecast.weights()[3] > 0.99);
+/// assert!((30..60).all(|day| forecast.predict(&day) == truth(&day)));
+/// ```
+pub struct WeightedMajority<X, Y> {
+    experts: Vec<Expert<X, Y>>,
+    weights: Vec<f64>,
+    learning_rate: f64,
+}
+
+impl<X, Y: Clone + PartialEq> WeightedMajority<X, Y> {
+    /// Creates a new WeightedMajority given its experts, weighted equally, with a learning rate of
+    /// `0.5`.
+    pub fn new(experts: Vec<Expert<X, Y>>) -> Self {
+        let n = experts.len();
+        WeightedMajority {
+            experts,
+            weights: vec![1.0 / n as f64; n],
+            learning_rate: 0.5,
+        }
+    }
+
+    /// Sets the learning rate $\eta$.
+    pub fn with_learning_rate(mut self, learning_rate: f64) -> Self {
+        self.learning_rate = learning_rate;
+        self
+    }
+
+    /// The learning rate $\eta$.
+    pub fn learning_rate(&self) -> f64 {
+        self.learning_rate
+    }
+
+    /// The current weight of each expert, in the order they were given. The weights sum to one.
+    pub fn weights(&self) -> &[f64] {
+        &self.weights
+    }
+
+    /// The outcome the experts predict for `input` with the greatest total weight. Ties go to the
+    /// outcome predicted by the earliest expert.
+    ///
+    /// # Panics
+    ///
+    /// Panics if there are no experts.
+    pub fn predict(&self, input: &X) -> Y {
+        let mut votes: Vec<(Y, f64)> = Vec::new();
+        for (expert, &weight) in self.experts.iter().zip(&self.weights) {
+            let prediction = expert(input);
+            match votes.iter_mut().find(|(y, _)| *y == prediction) {
+                Some((_, total)) => *total += weight,
+                None => votes.push((prediction, weight)),
+            }
+        }
+        votes
+            .into_iter()
+            .fold(None, |best: Option<(Y, f64)>, vote| match best {
+                Some(b) if b.1 >= vote.1 => Some(b),
+                _ => Some(vote),
+            })
+            .expect("weighted majority needs at least one expert")
+            .0
+    }
+
+    /// Learns from the true `outcome` for `input`, penalizing every expert that predicted
+    /// otherwise.
+    pub fn update(&mut self, input: &X, outcome: &Y) {
+        let penalty = (-self.learning_rate).exp();
+        for (expert, weight) in self.experts.iter().zip(self.weights.iter_mut()) {
+            if expert(input) != *outcome {
+                *weight *= penalty;
+            }
+        }
+        let total: f64 = self.weights.iter().sum();
+        for weight in &mut self.weights {
+            *weight /= total;
+        }
+    }
+}
+
+/// A conjunctive hypothesis for `VersionSpace`: one constraint for each input attribute, in the
+/// order of `DataSet::inputs`. `Some(value)` requires the attribute to have that value, and `None`
+/// accepts any value.
+pub type Hypothesis = Vec<Option<Value>>;
+
+/// Figure 19.3
+///
+/// # Version Space
+///
+/// The set of every hypothesis consistent with the examples seen so far, drawn from the
+/// conjunctions of attribute tests. Each example removes the hypotheses that misclassify it, so
+/// the space shrinks until, given enough examples, a single hypothesis remains.
+///
+/// The hypotheses are enumerated outright, which is only practical for a handful of attributes
+/// with few values each.
+///
+/// # Examples
+///
+/// ```
+/// # use aima_rust::learning::{version_space_learning, DataSet, Value};
+/// # use aima_rust::logic::pl_true;
+/// let examples = vec![
+///     vec!["small".into(), "red".into(), "circle".into(), "yes".into()],
+///     vec!["large".into(), "red".into(), "circle".into(), "yes".into()],
+///     vec!["small".into(), "blue".into(), "circle".into(), "no".into()],
+///     vec!["small".into(), "red".into(), "square".into(), "no".into()],
+///     vec!["large".into(), "green".into(), "square".into(), "no".into()],
+/// ];
+/// let dataset = DataSet::new(&["size", "color", "shape", "match"], examples, 3);
+///
+/// let space = version_space_learning(&dataset, &Value::from("yes"));
+/// assert_eq!(space.hypotheses(), &[vec![None, Some("red".into()), Some("circle".into())]]);
+///
+/// let concept = space.to_expr().unwrap();
+/// assert_eq!(concept.to_string(), "color=red & shape=circle");
+/// for example in &dataset.examples {
+///     let positive = example[3] == Value::from("yes");
+///     assert_eq!(pl_true(&concept, &space.model(example)), Some(positive));
+/// }
+/// ```
+#[derive(Clone, Debug)]
+pub struct VersionSpace {
+    attr_names: Vec<String>,
+    inputs: Vec<usize>,
+    domains: Vec<Vec<Value>>,
+    hypotheses: Vec<Hypothesis>,
+}
+
+impl VersionSpace {
+    /// Creates a new version space holding every conjunction of tests on the input attributes
+    /// of `dataset`, using the values each attribute takes in its examples.
+    pub fn new(dataset: &DataSet) -> Self {
+        let inputs = dataset.inputs();
+        let domains: Vec<Vec<Value>> = inputs
+            .iter()
+            .map(|&i| {
+                let mut domain = Vec::new();
+                for example in &dataset.examples {
+                    if !domain.contains(&example[i]) {
+                        domain.push(example[i].clone());
+                    }
+                }
+                domain
+            })
+            .collect();
+        let hypotheses = domains.iter().fold(vec![vec![]], |partial, domain| {
+            let constraints: Vec<Option<Value>> = std::iter::once(None)
+                .chain(domain.iter().cloned().map(Some))
+                .collect();
+            partial
+                .iter()
+                .flat_map(|h: &Hypothesis| {
+                    constraints.iter().map(move |c| {
+                        let mut h = h.clone();
+                        h.push(c.clone());
+                        h
+                    })
+                })
+                .collect()
+        });
+        VersionSpace {
+            attr_names: dataset.attr_names.clone(),
+            inputs,
+            domains,
+            hypotheses,
+        }
+    }
+
+    /// The hypotheses still consistent with every example seen.
+    pub fn hypotheses(&self) -> &[Hypothesis] {
+        &self.hypotheses
+    }
+
+    fn matches(&self, hypothesis: &Hypothesis, example: &[Value]) -> bool {
+        hypothesis
+            .iter()
+            .zip(&self.inputs)
+            .all(|(constraint, &i)| constraint.as_ref().is_none_or(|v| *v == example[i]))
+    }
+
+    /// Removes every hypothesis that disagrees with `example` being `positive` or not.
+    pub fn update(&mut self, example: &[Value], positive: bool) {
+        let kept = self
+            .hypotheses
+            .iter()
+            .filter(|h| self.matches(h, example) == positive)
+            .cloned()
+            .collect();
+        self.hypotheses = kept;
+    }
+
+    /// Whether `example` belongs to the concept, if every remaining hypothesis agrees.
+    pub fn predict(&self, example: &[Value]) -> Option<bool> {
+        let mut votes = self.hypotheses.iter().map(|h| self.matches(h, example));
+        let first = votes.next()?;
+        votes.all(|vote| vote == first).then_some(first)
+    }
+
+    /// The learned concept as a propositional sentence, once the space has collapsed to a
+    /// single hypothesis. Each test becomes a symbol named `attribute=value`, and the sentence
+    /// is their conjunction, a one-term DNF that can be evaluated in the model given by `model`.
+    pub fn to_expr(&self) -> Option<Expr> {
+        match self.hypotheses.as_slice() {
+            [hypothesis] => {
+                let mut tests: Vec<Expr> = hypothesis
+                    .iter()
+                    .zip(&self.inputs)
+                    .filter_map(|(constraint, &i)| {
+                        let value = constraint.as_ref()?;
+                        Some(Expr::symbol(&self.symbol(i, value)))
+                    })
+                    .collect();
+                Some(if tests.len() == 1 {
+                    tests.remove(0)
+                } else {
+                    Expr::And(tests)
+                })
+            }
+            _ => None,
+        }
+    }
+
+    /// The truth assignment describing `example`: the symbol `attribute=value` is true for the
+    /// value the example has, and false for every other value of the attribute.
+    pub fn model(&self, example: &[Value]) -> Model {
+        let mut model = Model::new();
+        for (&i, domain) in self.inputs.iter().zip(&self.domains) {
+            for value in domain {
+                model.insert(self.symbol(i, value), example[i] == *value);
+            }
+        }
+        model
+    }
+
+    fn symbol(&self, attribute: usize, value: &Value) -> String {
+        format!("{}={}", self.attr_names[attribute], value)
+    }
+}
+
+/// Figure 19.3
+///
+/// Learns a `VersionSpace` from the examples of `dataset`, treating those whose target equals
+/// `positive` as positive examples of the concept and the rest as negative.
+pub fn version_space_learning(dataset: &DataSet, positive: &Value) -> VersionSpace {
+    let mut space = VersionSpace::new(dataset);
+    for example in &dataset.examples {
+        space.update(example, example[dataset.target] == *positive);
+    }
+    space
+}
+
+/// A differentiable activation function for the units of a `NeuralNetwork`, given with its
+/// derivative. Both take the unit's weighted input, $in_j = \sum_i w_{i,j} a_i$.
+///
+/// `Sigmoid`, `Tanh` and `Relu` are provided; other functions, including ones with parameters
+/// of their own, can be plugged in by implementing the trait. Check a new derivative with
+/// `gradient_check`.
+///
+/// # Examples
+///
+/// A leaky ReLU, which lets a little gradient through for negative inputs:
+///
+/// ```
+/// # use aima_rust::learning::{gradient_check, Activation, NeuralNetwork};
+/// #[derive(Debug)]
+/// struct LeakyRelu {
+///     slope: f64,
+/// }
+///
+/// impl Activation for LeakyRelu {
+///     fn value(&self, x: f64) -> f64 {
+///         if x > 0.0 {
+///             x
+///         } else {
+///             self.slope * x
+///         }
+///     }
+///
+///     fn derivative(&self, x: f64) -> f64 {
+///         if x > 0.0 {
+///             1.0
+///         } else {
+///             self.slope
+///         }
+///     }
+/// }
+///
+/// let network =
+///     NeuralNetwork::new(&[3, 4, 2], 42).with_layer_activation(0, LeakyRelu { slope: 0.01 });
+/// let example: (&[f64], &[f64]) = (&[0.5, -1.0, 2.0], &[1.0, 0.0]);
+///
+/// assert!(gradient_check(&network, example, 1e-5) < 1e-6);
+/// ```
+pub trait Activation: fmt::Debug {
+    /// The unit's output for the weighted input `x`.
+    fn value(&self, x: f64) -> f64;
+
+    /// The derivative of `value` at `x`.
+    fn derivative(&self, x: f64) -> f64;
+}
+
+/// The logistic function $g(x) = 1 / (1 + e^{-x})$, whose derivative is $g(x)(1 - g(x))$.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Sigmoid;
+
+impl Activation for Sigmoid {
+    fn value(&self, x: f64) -> f64 {
+        1.0 / (1.0 + (-x).exp())
+    }
+
+    fn derivative(&self, x: f64) -> f64 {
+        self.value(x) * (1.0 - self.value(x))
+    }
+}
+
+/// The hyperbolic tangent $g(x) = \tanh x$, whose derivative is $1 - g(x)^2$. It has the same S
+/// shape as the sigmoid, but ranges over $(-1, 1)$ and is centred on zero.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Tanh;
+
+impl Activation for Tanh {
+    fn value(&self, x: f64) -> f64 {
+        x.tanh()
+    }
+
+    fn derivative(&self, x: f64) -> f64 {
+        1.0 - x.tanh().powi(2)
+    }
+}
+
+/// The rectified linear unit $g(x) = \max(0, x)$, whose derivative is one for positive $x$ and
+/// zero otherwise. Its gradient doesn't vanish for large inputs as the sigmoid's does, which
+/// makes it the usual choice for the hidden layers of deep networks.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Relu;
+
+impl Activation for Relu {
+    fn value(&self, x: f64) -> f64 {
+        x.max(0.0)
+    }
+
+    fn derivative(&self, x: f64) -> f64 {
+        if x > 0.0 {
+            1.0
+        } else {
+            0.0
+        }
+    }
+}
+
+/// The weights of each layer of a `NeuralNetwork`: `weights[l][j]` holds the weights into unit
+/// `j` of layer `l + 1` from each unit of layer `l`, followed by the unit's bias weight.
+pub type LayerWeights = Vec<Vec<Vec<f64>>>;
+
+/// # Neural Network
+///
+/// A feed-forward network of fully connected layers, with every unit applying its layer's
+/// activation function to the weighted sum of the previous layer's outputs plus a bias. The loss
+/// of an output $a$ against a target $y$ is the squared error $\frac{1}{2} \sum_k (y_k - a_k)^2$.
+///
+/// For classification into more than two classes, the output layer can instead be a softmax,
+/// trained on cross-entropy loss: see `train_classifier`.
+///
+/// # Examples
+///
+/// ```
+/// # use aima_rust::learning::NeuralNetwork;
+/// let network = NeuralNetwork::new(&[2, 3, 1], 42);
+///
+/// assert_eq!(network.weights.len(), 2);
+/// assert_eq!(network.weights[0].len(), 3);
+/// assert_eq!(network.weights[0][0].len(), 3);
+///
+/// let output = network.output(&[0.5, -0.5]);
+/// assert_eq!(output.len(), 1);
+/// assert!(0.0 < output[0] && output[0] < 1.0);
+/// assert!(network.loss(&[0.5, -0.5], &[1.0]) > 0.0);
+/// ```
+#[derive(Clone, Debug)]
+pub struct NeuralNetwork {
+    pub weights: LayerWeights,
+    /// The activation function of each layer after the inputs, in the same order as `weights`.
+    pub activations: Vec<Rc<dyn Activation>>,
+    /// Whether the output layer is a softmax trained on cross-entropy loss, in place of its
+    /// activation function and squared error.
+    pub softmax: bool,
+}
+
+impl NeuralNetwork {
+    /// Creates a new network of sigmoid units given the number of units in each layer, inputs
+    /// first and outputs last. The initial weights are drawn uniformly from $[-0.5, 0.5)$ using
+    /// `seed`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if there are fewer than two layers.
+    pub fn new(layer_sizes: &[usize], seed: u64) -> Self {
+        assert!(
+            layer_sizes.len() >= 2,
+            "a network needs at least two layers"
+        );
+        let mut rng = Rng::seeded(seed);
+        let weights: LayerWeights = layer_sizes
+            .windows(2)
+            .map(|pair| {
+                (0..pair[1])
+                    .map(|_| (0..=pair[0]).map(|_| rng.gen_range(-0.5..0.5)).collect())
+                    .collect()
+            })
+            .collect();
+        NeuralNetwork {
+            activations: vec![Rc::new(Sigmoid) as Rc<dyn Activation>; weights.len()],
+            weights,
+            softmax: false,
+        }
+    }
+
+    /// Uses `activation` for every unit instead of the sigmoid.
+    pub fn with_activation<A: Activation + 'static>(mut self, activation: A) -> Self {
+        let activation: Rc<dyn Activation> = Rc::new(activation);
+        for a in &mut self.activations {
+            *a = Rc::clone(&activation);
+        }
+        self
+    }
+
+    /// Uses `activation` for the units of one layer, counting the first layer after the inputs
+    /// as layer `0`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if there is no such layer.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use aima_rust::learning::{gradient_check, NeuralNetwork, Relu, Tanh};
+    /// let network = NeuralNetwork::new(&[3, 4, 4, 2], 42)
+    ///     .with_layer_activation(0, Relu)
+    ///     .with_layer_activation(1, Tanh);
+    /// let example: (&[f64], &[f64]) = (&[0.5, -1.0, 2.0], &[1.0, 0.0]);
+    ///
+    /// assert!(gradient_check(&network, example, 1e-5) < 1e-6);
+    /// let output = network.output(example.0);
+    /// assert!(output.iter().all(|&a| 0.0 < a && a < 1.0));
+    /// ```
+    pub fn with_layer_activation<A: Activation + 'static>(
+        mut self,
+        layer: usize,
+        activation: A,
+    ) -> Self {
+        assert!(
+            layer < self.activations.len(),
+            "the network has no layer {}",
+            layer
+        );
+        self.activations[layer] = Rc::new(activation);
+        self
+    }
+
+    /// The weighted inputs and outputs of the units of each layer for `input`. The outputs start
+    /// with the input layer, so there is one more of them than of the weighted inputs.
+    fn forward(&self, input: &[f64]) -> (Vec<Vec<f64>>, Vec<Vec<f64>>) {
+        let mut weighted_inputs = Vec::with_capacity(self.weights.len());
+        let mut outputs = vec![input.to_vec()];
+        for (layer, activation) in self.weights.iter().zip(&self.activations) {
+            let previous = outputs.last().unwrap();
+            let z: Vec<f64> = layer
+                .iter()
+                .map(|unit| {
+                    let bias = unit[previous.len()];
+                    bias + unit.iter().zip(previous).map(|(w, a)| w * a).sum::<f64>()
+                })
+                .collect();
+            if self.softmax && outputs.len() == self.weights.len() {
+                outputs.push(softmax(&z));
+            } else {
+                outputs.push(z.iter().map(|&z| activation.value(z)).collect());
+            }
+            weighted_inputs.push(z);
+        }
+        (weighted_inputs, outputs)
+    }
+
+    /// The network's output for `input`.
+    pub fn output(&self, input: &[f64]) -> Vec<f64> {
+        self.forward(input).1.pop().unwrap()
+    }
+
+    /// The loss of the network's output for `input` against `target`: the squared error, or the
+    /// cross-entropy $-\sum_k y_k \log a_k$ for a softmax output.
+    pub fn loss(&self, input: &[f64], target: &[f64]) -> f64 {
+        let output = self.output(input);
+        if self.softmax {
+            return -output
+                .iter()
+                .zip(target)
+                .filter(|&(_, &y)| y != 0.0)
+                .map(|(a, y)| y * a.ln())
+                .sum::<f64>();
+        }
+        0.5 * output
+            .iter()
+            .zip(target)
+            .map(|(a, y)| (y - a).powi(2))
+            .sum::<f64>()
+    }
+
+    /// The gradient of `loss(input, target)` with respect to each weight, computed by
+    /// back-propagation, in the same layout as `weights`.
+    pub fn gradients(&self, input: &[f64], target: &[f64]) -> LayerWeights {
+        let (weighted_inputs, outputs) = self.forward(input);
+        let derivative = |l: usize, z: f64| self.activations[l].derivative(z);
+        let last = self.weights.len() - 1;
+        let mut deltas: Vec<Vec<f64>> = vec![Vec::new(); self.weights.len()];
+        deltas[last] = outputs[last + 1]
+            .iter()
+            .zip(target)
+            .zip(&weighted_inputs[last])
+            .map(|((a, y), &z)| {
+                // The softmax and cross-entropy derivatives cancel down to the plain error.
+                if self.softmax {
+                    a - y
+                } else {
+                    (a - y) * derivative(last, z)
+                }
+            })
+            .collect();
+        for l in (0..last).rev() {
+            deltas[l] = weighted_inputs[l]
+                .iter()
+                .enumerate()
+                .map(|(i, &z)| {
+                    let downstream: f64 = self.weights[l + 1]
+                        .iter()
+                        .zip(&deltas[l + 1])
+                        .map(|(unit, delta)| unit[i] * delta)
+                        .sum();
+                    downstream * derivative(l, z)
+                })
+                .collect();
+        }
+        deltas
+            .iter()
+            .zip(&outputs)
+            .map(|(layer_deltas, previous)| {
+                layer_deltas
+                    .iter()
+                    .map(|delta| {
+                        previous
+                            .iter()
+                            .chain(std::iter::once(&1.0))
+                            .map(|a| delta * a)
+                            .collect()
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+
+    /// Trains the network to classify `examples`, pairs of an input and a one-hot target with a
+    /// one for the example's class and zeros elsewhere, by `epochs` passes of stochastic gradient
+    /// descent in an order shuffled with `seed`. The output layer becomes a softmax,
+    ///
+    /// $$ a_k = \frac{e^{in_k}}{\sum_{k'} e^{in_{k'}}} $$
+    ///
+    /// so the outputs are positive and sum to one, and can be read as the probability of each
+    /// class. It is trained on the cross-entropy loss, the negative log probability given to the
+    /// right class. Unlike squared error through a sigmoid, its gradient doesn't vanish when an
+    /// output is confidently wrong.
+    ///
+    /// # Examples
+    ///
+    /// Three clusters of points, one per class:
+    ///
+    /// ```
+    /// # use aima_rust::learning::{gradient_check, NeuralNetwork};
+    /// let centres = [(0.0, 0.0), (3.0, 0.0), (0.0, 3.0)];
+    /// let mut examples = Vec::new();
+    /// for (class, &(x, y)) in centres.iter().enumerate() {
+    ///     for i in 0..10 {
+    ///         let (dx, dy) = ((i % 5) as f64 * 0.2 - 0.4, (i / 5) as f64 * 0.6 - 0.3);
+    ///         let mut target = vec![0.0; 3];
+    ///         target[class] = 1.0;
+    ///         examples.push((vec![x + dx, y + dy], target));
+    ///     }
+    /// }
+    ///
+    /// let mut network = NeuralNetwork::new(&[2, 5, 3], 7);
+    /// network.train_classifier(&examples, 0.1, 200, 7);
+    ///
+    /// let predict = |input: &[f64]| {
+    ///     let output = network.output(input);
+    ///     (0..3).max_by(|&i, &j| output[i].total_cmp(&output[j])).unwrap()
+    /// };
+    /// let correct = examples.iter().filter(|(x, y)| y[predict(x)] == 1.0).count();
+    /// assert_eq!(correct, examples.len());
+    ///
+    /// let output = network.output(&[1.5, 1.5]);
+    /// assert!((output.iter().sum::<f64>() - 1.0).abs() < 1e-12);
+    ///
+    /// // Back-propagation through the softmax agrees with finite differences.
+    /// let (x, y) = &examples[4];
+    /// assert!(gradient_check(&network, (x, y), 1e-5) < 1e-6);
+    /// ```
+    pub fn train_classifier(
+        &mut self,
+        examples: &[(Vec<f64>, Vec<f64>)],
+        learning_rate: f64,
+        epochs: usize,
+        seed: u64,
+    ) {
+        self.softmax = true;
+        let mut rng = Rng::seeded(seed);
+        let mut order: Vec<&(Vec<f64>, Vec<f64>)> = examples.iter().collect();
+        let mut velocity = self.zeros();
+        for _ in 0..epochs {
+            order.shuffle(&mut rng);
+            for (input, target) in &order {
+                let gradients = self.gradients(input, target);
+                self.descend(&gradients, &mut velocity, learning_rate, 0.0);
+            }
+        }
+    }
+
+    /// A zero for every weight, in the same layout as `weights`.
+    fn zeros(&self) -> LayerWeights {
+        self.weights
+            .iter()
+            .map(|layer| layer.iter().map(|unit| vec![0.0; unit.len()]).collect())
+            .collect()
+    }
+
+    /// The mean gradient of the loss over `batch`, in the same layout as `weights`.
+    fn batch_gradients(&self, batch: &[(Vec<f64>, Vec<f64>)]) -> LayerWeights {
+        let mut total = self.gradients(&batch[0].0, &batch[0].1);
+        for (input, target) in &batch[1..] {
+            let gradients = self.gradients(input, target);
+            for (layer, layer_gradients) in total.iter_mut().zip(gradients) {
+                for (unit, unit_gradients) in layer.iter_mut().zip(layer_gradients) {
+                    for (g, h) in unit.iter_mut().zip(unit_gradients) {
+                        *g += h;
+                    }
+                }
+            }
+        }
+        let n = batch.len() as f64;
+        for g in total.iter_mut().flatten().flatten() {
+            *g /= n;
+        }
+        total
+    }
+
+    /// Takes one step of gradient descent with momentum: each weight's `velocity` decays by
+    /// `momentum` and moves down its gradient, and the weight moves by the velocity.
+    fn descend(
+        &mut self,
+        gradients: &LayerWeights,
+        velocity: &mut LayerWeights,
+        learning_rate: f64,
+        momentum: f64,
+    ) {
+        let weights = self.weights.iter_mut().flatten().flatten();
+        let velocity = velocity.iter_mut().flatten().flatten();
+        for ((w, v), g) in weights
+            .zip(velocity)
+            .zip(gradients.iter().flatten().flatten())
+        {
+            *v = momentum * *v - learning_rate * g;
+            *w += *v;
+        }
+    }
+}
+
+/// The softmax of `z`, shifted by its maximum so that the exponentials can't overflow.
+fn softmax(z: &[f64]) -> Vec<f64> {
+    let max = z.iter().cloned().fold(f64::NEG_INFINITY, f64::max);
+    let exps: Vec<f64> = z.iter().map(|&z| (z - max).exp()).collect();
+    let total: f64 = exps.iter().sum();
+    exps.iter().map(|e| e / total).collect()
+}
+
+/// Figure 18.24
+///
+/// # Back-Propagation Learning
+///
+/// Trains a `NeuralNetwork` to classify examples by stochastic gradient descent, with the
+/// gradients computed by back-propagation. The inputs must be numeric, and there is one output
+/// unit per class, trained towards one for the example's class and zero for the others; the
+/// predicted class is the one whose output is highest.
+///
+/// By default the weights are updated after every example. `with_batch_size` averages the
+/// gradients over several examples before each update instead, and `with_momentum` carries
+/// part of each update over into the next.
+///
+/// # Examples
+///
+/// Unlike the perceptron, a network with a hidden layer can learn XOR:
+///
+/// ```
+/// # use aima_rust::learning::{error_rate, BackPropagationLearner, DataSet, Learner};
+/// let examples = vec![
+///     vec![0.0.into(), 0.0.into(), "no".into()],
+///     vec![0.0.into(), 1.0.into(), "yes".into()],
+///     vec![1.0.into(), 0.0.into(), "yes".into()],
+///     vec![1.0.into(), 1.0.into(), "no".into()],
+/// ];
+/// let dataset = DataSet::new(&["a", "b", "xor"], examples, 2);
+///
+/// let mut learner = BackPropagationLearner::new(&[4], 0.5, 5000, 1);
+/// learner.train(&dataset);
+///
+/// assert_eq!(error_rate(&learner, &dataset), 0.0);
+/// assert_eq!(learner.epochs_trained(), 5000);
+/// ```
+pub struct BackPropagationLearner {
+    hidden_layers: Vec<usize>,
+    learning_rate: f64,
+    epochs: usize,
+    seed: u64,
+    early_stopping: Option<(f64, usize)>,
+    batch_size: usize,
+    momentum: f64,
+    hidden_activation: Rc<dyn Activation>,
+    inputs: Vec<usize>,
+    classes: Vec<Value>,
+    network: Option<NeuralNetwork>,
+    epochs_trained: usize,
+    losses: Vec<f64>,
+}
+
+impl BackPropagationLearner {
+    /// Creates a new BackPropagationLearner given the sizes of the hidden layers, the learning
+    /// rate, and the number of passes over the examples to train for. The initial weights and
+    /// the order of the examples in each pass are drawn from `seed`.
+    pub fn new(hidden_layers: &[usize], learning_rate: f64, epochs: usize, seed: u64) -> Self {
+        BackPropagationLearner {
+            hidden_layers: hidden_layers.to_vec(),
+            learning_rate,
+            epochs,
+            seed,
+            early_stopping: None,
+            batch_size: 1,
+            momentum: 0.0,
+            hidden_activation: Rc::new(Sigmoid),
+            inputs: Vec::new(),
+            classes: Vec::new(),
+            network: None,
+            epochs_trained: 0,
+            losses: Vec::new(),
+        }
+    }
+
+    /// Updates the weights once per mini-batch of `size` examples, by the mean of their
+    /// gradients, rather than once per example. Averaging smooths out the noise in the
+    /// gradients of single examples; a batch size of one is plain stochastic gradient descent.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `size` is zero.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use aima_rust::learning::{error_rate, BackPropagationLearner, DataSet, Learner};
+    /// let examples = vec![
+    ///     vec![0.0.into(), 0.0.into(), "no".into()],
+    ///     vec![0.0.into(), 1.0.into(), "yes".into()],
+    ///     vec![1.0.into(), 0.0.into(), "yes".into()],
+    ///     vec![1.0.into(), 1.0.into(), "no".into()],
+    /// ];
+    /// let dataset = DataSet::new(&["a", "b", "xor"], examples, 2);
+    ///
+    /// let mut learner = BackPropagationLearner::new(&[4], 1.0, 5000, 1).with_batch_size(2);
+    /// learner.train(&dataset);
+    ///
+    /// assert_eq!(error_rate(&learner, &dataset), 0.0);
+    /// ```
+    pub fn with_batch_size(mut self, size: usize) -> Self {
+        assert!(size > 0, "a mini-batch needs at least one example");
+        self.batch_size = size;
+        self
+    }
+
+    /// Adds momentum to gradient descent. Each update is the learning rate times the gradient
+    /// plus `momentum` times the previous update, so steps build up speed along directions the
+    /// gradient keeps pointing in, and oscillations across a narrow valley of the loss cancel
+    /// out. Values around `0.9` are usual; zero, the default, is plain gradient descent.
+    ///
+    /// # Examples
+    ///
+    /// Momentum brings the loss on XOR below `0.01` in fewer epochs:
+    ///
+    /// ```
+    /// # use aima_rust::learning::{BackPropagationLearner, DataSet, Learner};
+    /// let examples = vec![
+    ///     vec![0.0.into(), 0.0.into(), "no".into()],
+    ///     vec![0.0.into(), 1.0.into(), "yes".into()],
+    ///     vec![1.0.into(), 0.0.into(), "yes".into()],
+    ///     vec![1.0.into(), 1.0.into(), "no".into()],
+    /// ];
+    /// let dataset = DataSet::new(&["a", "b", "xor"], examples, 2);
+    ///
+    /// let mut plain = BackPropagationLearner::new(&[4], 0.5, 5000, 1);
+    /// plain.train(&dataset);
+    /// let mut fast = BackPropagationLearner::new(&[4], 0.5, 5000, 1).with_momentum(0.9);
+    /// fast.train(&dataset);
+    ///
+    /// let epochs_to = |losses: &[f64]| losses.iter().position(|&loss| loss < 0.01).unwrap();
+    /// assert!(epochs_to(fast.losses()) < epochs_to(plain.losses()));
+    /// ```
+    pub fn with_momentum(mut self, momentum: f64) -> Self {
+        self.momentum = momentum;
+        self
+    }
+
+    /// Stops training early once the network stops improving on held-out examples. A random
+    /// `fraction` of the training examples is set aside for validation, and training stops when
+    /// the loss on them has not improved for `patience` epochs in a row. The network is then
+    /// returned to its weights after the best epoch.
+    ///
+    /// Training a large network for too long fits the noise in the examples along with the
+    /// pattern, which makes it worse on new examples; stopping early guards against this.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `fraction` is not strictly between `0.0` and `1.0`. Training then panics if
+    /// `fraction` of the examples rounds to none, as there would be nothing to validate on.
+    ///
+    /// # Examples
+    ///
+    /// Forty examples labelled by the sign of $x$, a quarter of them mislabelled:
+    ///
+    /// ```
+    /// # use aima_rust::learning::{error_rate, BackPropagationLearner, DataSet, Learner};
+    /// # use aima_rust::util::Rng;
+    /// use rand::Rng as _;
+    ///
+    /// let mut rng = Rng::seeded(0);
+    /// let mut sample = |n: usize, noise: f64| {
+    ///     let examples = (0..n)
+    ///         .map(|_| {
+    ///             let x: f64 = rng.gen_range(-3.0..3.0);
+    ///             let positive = (x > 0.0) != rng.gen_bool(noise);
+    ///             vec![x.into(), (if positive { "+" } else { "-" }).into()]
+    ///         })
+    ///         .collect();
+    ///     DataSet::new(&["x", "sign"], examples, 1)
+    /// };
+    /// let (train, test) = (sample(40, 0.25), sample(200, 0.0));
+    ///
+    /// let mut full = BackPropagationLearner::new(&[10], 1.0, 1000, 1);
+    /// full.train(&train);
+    /// let mut early =
+    ///     BackPropagationLearner::new(&[10], 1.0, 1000, 1).with_early_stopping(0.25, 50);
+    /// early.train(&train);
+    ///
+    /// assert_eq!(full.epochs_trained(), 1000);
+    /// assert!(early.epochs_trained() < 100);
+    /// assert!(error_rate(&early, &test) < error_rate(&full, &test));
+    /// ```
+    pub fn with_early_stopping(mut self, fraction: f64, patience: usize) -> Self {
+        assert!(
+            0.0 < fraction && fraction < 1.0,
+            "the validation fraction must be between 0 and 1"
+        );
+        self.early_stopping = Some((fraction, patience));
+        self
+    }
+
+    /// Uses `activation` for the units of the hidden layers instead of the sigmoid. The output
+    /// units stay sigmoid, so that they can be trained towards zero and one.
+    ///
+    /// # Examples
+    ///
+    /// A hidden layer of rectified linear units learns XOR too:
+    ///
+    /// ```
+    /// # use aima_rust::learning::{error_rate, BackPropagationLearner, DataSet, Learner, Relu};
+    /// let examples = vec![
+    ///     vec![0.0.into(), 0.0.into(), "no".into()],
+    ///     vec![0.0.into(), 1.0.into(), "yes".into()],
+    ///     vec![1.0.into(), 0.0.into(), "yes".into()],
+    ///     vec![1.0.into(), 1.0.into(), "no".into()],
+    /// ];
+    /// let dataset = DataSet::new(&["a", "b", "xor"], examples, 2);
+    ///
+    /// let mut learner =
+    ///     BackPropagationLearner::new(&[4], 0.5, 2000, 1).with_hidden_activation(Relu);
+    /// learner.train(&dataset);
+    ///
+    /// assert_eq!(error_rate(&learner, &dataset), 0.0);
+    /// ```
+    pub fn with_hidden_activation<A: Activation + 'static>(mut self, activation: A) -> Self {
+        self.hidden_activation = Rc::new(activation);
+        self
+    }
+
+    /// The trained network, if `train` has been called.
+    pub fn network(&self) -> Option<&NeuralNetwork> {
+        self.network.as_ref()
+    }
+
+    /// The number of passes over the examples made by the last call to `train`, which is less
+    /// than the number asked for if training stopped early.
+    pub fn epochs_trained(&self) -> usize {
+        self.epochs_trained
+    }
+
+    /// The mean loss over the training examples after each epoch of the last call to `train`.
+    pub fn losses(&self) -> &[f64] {
+        &self.losses
+    }
+
+    fn features(&self, example: &[Value]) -> Vec<f64> {
+        self.inputs
+            .iter()
+            .map(|&i| example[i].as_f64().expect("network inputs must be numeric"))
+            .collect()
+    }
+
+    /// The input and target vectors of each example.
+    fn encode(&self, dataset: &DataSet) -> Vec<(Vec<f64>, Vec<f64>)> {
+        dataset
+            .examples
+            .iter()
+            .map(|example| {
+                let target = self
+                    .classes
+                    .iter()
+                    .map(|class| (example[dataset.target] == *class) as u8 as f64)
+                    .collect();
+                (self.features(example), target)
+            })
+            .collect()
+    }
+}
+
+impl Learner for BackPropagationLearner {
+    fn train(&mut self, dataset: &DataSet) {
+        self.inputs = dataset.inputs();
+        self.classes = dataset.classes();
+        let mut sizes = vec![self.inputs.len()];
+        sizes.extend(&self.hidden_layers);
+        sizes.push(self.classes.len());
+        let mut network = NeuralNetwork::new(&sizes, self.seed);
+        for layer in 0..self.hidden_layers.len() {
+            network.activations[layer] = Rc::clone(&self.hidden_activation);
+        }
+
+        let (mut train, validation) = match self.early_stopping {
+            Some((fraction, _)) => {
+                let (train, validation) = dataset.train_test_split(fraction, false, self.seed);
+                assert!(
+                    !validation.examples.is_empty(),
+                    "early stopping needs at least one validation example"
+                );
+                (self.encode(&train), self.encode(&validation))
+            }
+            None => (self.encode(dataset), Vec::new()),
+        };
+        let validation_loss = |network: &NeuralNetwork| {
+            validation
+                .iter()
+                .map(|(x, y)| network.loss(x, y))
+                .sum::<f64>()
+                / validation.len().max(1) as f64
+        };
+
+        let mut rng = Rng::seeded(self.seed);
+        let mut best = (validation_loss(&network), network.weights.clone());
+        let mut since_best = 0;
+        let mut velocity = network.zeros();
+        self.epochs_trained = 0;
+        self.losses.clear();
+        while self.epochs_trained < self.epochs {
+            self.epochs_trained += 1;
+            train.shuffle(&mut rng);
+            for batch in train.chunks(self.batch_size) {
+                let gradients = network.batch_gradients(batch);
+                network.descend(&gradients, &mut velocity, self.learning_rate, self.momentum);
+            }
+            let loss = train.iter().map(|(x, y)| network.loss(x, y)).sum::<f64>();
+            self.losses.push(loss / train.len().max(1) as f64);
+            if let Some((_, patience)) = self.early_stopping {
+                let loss = validation_loss(&network);
+                if loss < best.0 {
+                    best = (loss, network.weights.clone());
+                    since_best = 0;
+                } else {
+                    since_best += 1;
+                    if since_best >= patience {
+                        break;
+                    }
+                }
+            }
+        }
+        if self.early_stopping.is_some() {
+            network.weights = best.1;
+        }
+        self.network = Some(network);
+    }
+
+    fn predict(&self, example: &[Value]) -> Value {
+        let network = self
+            .network
+            .as_ref()
+            .expect("the learner hasn't been trained");
+        let output = network.output(&self.features(example));
+        let best = (0..output.len())
+            .max_by(|&i, &j| output[i].total_cmp(&output[j]))
+            .unwrap();
+        self.classes[best].clone()
+    }
+}
+
+/// Compares the gradients `network` computes by back-propagation for one example with estimates
+/// from central differences,
+///
+/// $$ \frac{\partial L}{\partial w} \approx \frac{L(w + \epsilon) - L(w - \epsilon)}{2 \epsilon} $$
+///
+/// and returns the largest relative difference over all the weights,
+/// $|g - \hat{g}| / (|g| + |\hat{g}|)$. This is a check that a custom `Activation`, and in
+/// particular its derivative, is correct: a value around $10^{-7}$ or below means the two agree,
+/// while one above $10^{-3}$ almost certainly means a bug.
+///
+/// An `epsilon` between $10^{-7}$ and $10^{-4}$ works well, with $10^{-5}$ a good default. Much
+/// larger and the estimate suffers from the curvature of the loss; much smaller and it is lost
+/// in rounding error.
+///
+/// # Examples
+///
+/// ```
+/// # use aima_rust::learning::{gradient_check, Activation, NeuralNetwork, Sigmoid};
+/// let network = NeuralNetwork::new(&[3, 4, 2], 42);
+/// let example: (&[f64], &[f64]) = (&[0.5, -1.0, 2.0], &[1.0, 0.0]);
+///
+/// assert!(gradient_check(&network, example, 1e-5) < 1e-6);
+///
+/// // A derivative that forgets the chain rule through the sigmoid.
+/// #[derive(Debug)]
+/// struct Wrong;
+///
+/// impl Activation for Wrong {
+///     fn value(&self, x: f64) -> f64 {
+///         Sigmoid.value(x)
+///     }
+///
+///     fn derivative(&self, _: f64) -> f64 {
+///         1.0
+///     }
+/// }
+/// let broken = network.with_activation(Wrong);
+/// assert!(gradient_check(&broken, example, 1e-5) > 0.1);
+/// ```
+pub fn gradient_check(
+    network: &NeuralNetwork,
+    (input, target): (&[f64], &[f64]),
+    epsilon: f64,
+) -> f64 {
+    let gradients = network.gradients(input, target);
+    let mut nudged = network.clone();
+    let mut worst: f64 = 0.0;
+    for (l, layer) in gradients.iter().enumerate() {
+        for (j, unit) in layer.iter().enumerate() {
+            for (i, &gradient) in unit.iter().enumerate() {
+                let w = network.weights[l][j][i];
+                nudged.weights[l][j][i] = w + epsilon;
+                let above = nudged.loss(input, target);
+                nudged.weights[l][j][i] = w - epsilon;
+                let below = nudged.loss(input, target);
+                nudged.weights[l][j][i] = w;
+                let estimate = (above - below) / (2.0 * epsilon);
+                let difference = (gradient - estimate).abs();
+                if difference > 0.0 {
+                    worst = worst.max(difference / (gradient.abs() + estimate.abs()));
+                }
+            }
+        }
+    }
+    worst
+}
+
+/// The fraction of examples in `dataset` whose target value the learner predicts incorrectly.
+pub fn error_rate<L: Learner>(learner: &L, dataset: &DataSet) -> f64 {
+    if dataset.examples.is_empty() {
+        return 0.0;
+    }
+    let wrong = dataset
+        .examples
+        .iter()
+        .filter(|example| learner.predict(example) != example[dataset.target])
+        .count();
+    wrong as f64 / dataset.examples.len() as f64
+}
+
+/// Figure 18.8
+///
+/// Estimates how well `learner` generalizes by k-fold cross-validation. The examples are shuffled
+/// using `seed` and split into `k` folds; each fold in turn is held out for validation while the
+/// learner trains on the rest. The result is the mean validation error rate.
+///
+/// # Examples
+///
+/// ```
+/// # use aima_rust::learning::{cross_validation, DataSet, PerceptronLearner};
+/// let examples = (0..40)
+///     .map(|i| {
+///         let x = i as f64 / 40.0;
+///         vec![x.into(), (if x < 0.5 { "low" } else { "high" }).into()]
+///     })
+///     .collect();
+/// let dataset = DataSet::new(&["x", "level"], examples, 1);
+///
+/// let error = cross_validation(&mut PerceptronLearner::new(0.5, 1000, 7), &dataset, 5, 42);
+/// assert!(error < 0.1);
+/// ```
+pub fn cross_validation<L: Learner>(
+    learner: &mut L,
+    dataset: &DataSet,
+    k: usize,
+    seed: u64,
+) -> f64 {
+    cross_validation_with(learner, dataset, k, false, seed)
+}
+
+/// Cross-validates like `cross_validation`, but with stratified folds if `stratify` is true: see
+/// `DataSet::folds`. On an imbalanced dataset, random folds can leave a rare class out of some
+/// folds entirely, and how many they leave out varies from seed to seed, and with it the error.
+/// Stratified folds give a steadier estimate.
+///
+/// # Examples
+///
+/// ```
+/// # use aima_rust::learning::{cross_validation_with, DataSet, DecisionStump};
+/// let examples = (0..40)
+///     .map(|i| {
+///         let x = (i * 7 % 40) as f64;
+///         vec![x.into(), (if x < 6.0 { "rare" } else { "common" }).into()]
+///     })
+///     .collect();
+/// let dataset = DataSet::new(&["x", "kind"], examples, 1);
+///
+/// // The variance of the estimated error over twenty seeds.
+/// let spread = |stratify: bool| {
+///     let errors: Vec<f64> = (0..20)
+///         .map(|seed| {
+///             cross_validation_with(&mut DecisionStump::new(), &dataset, 8, stratify, seed)
+///         })
+///         .collect();
+///     let mean = errors.iter().sum::<f64>() / errors.len() as f64;
+///     errors.iter().map(|e| (e - mean).powi(2)).sum::<f64>() / errors.len() as f64
+/// };
+///
+/// assert!(spread(true) < spread(false));
+/// ```
+pub fn cross_validation_with<L: Learner>(
+    learner: &mut L,
+    dataset: &DataSet,
+    k: usize,
+    stratify: bool,
+    seed: u64,
+) -> f64 {
+    let folds: Vec<Vec<Vec<Value>>> = dataset
+        .folds(k, stratify, seed)
+        .into_iter()
+        .map(|fold| fold.examples)
+        .collect();
+    k_fold_error(learner, dataset, &folds, None)
+}
+
+/// Computes the mean validation error over `folds`, holding out each in turn. When `train_size`
+/// is given, the learner only sees that many of the training examples in each fold.
+fn k_fold_error<L: Learner>(
+    learner: &mut L,
+    dataset: &DataSet,
+    folds: &[Vec<Vec<Value>>],
+    train_size: Option<usize>,
+) -> f64 {
+    let mut total = 0.0;
+    for (held_out, validation) in folds.iter().enumerate() {
+        let mut train: Vec<Vec<Value>> = folds
+            .iter()
+            .enumerate()
+            .filter(|&(fold, _)| fold != held_out)
+            .flat_map(|(_, examples)| examples.iter().cloned())
+            .collect();
+        if let Some(size) = train_size {
+            train.truncate(size);
+        }
+        learner.train(&dataset.with_examples(train));
+        total += error_rate(learner, &dataset.with_examples(validation.clone()));
+    }
+    total / folds.len() as f64
+}
+
+/// Generates a learning curve: the validation error of `learner` as the number of training
+/// examples grows. For every size in `sizes`, the learner is cross-validated over the same
+/// (seeded) folds of `dataset`, but only trains on the first `size` examples outside the held-out
+/// fold. Sizes larger than a training fold are capped at the fold size.
+///
+/// The result pairs each requested size with its error, ready for plotting. Cross-validation
+/// needs at least two examples, so the curve of a smaller dataset is empty.
+///
+/// # Examples
+///
+/// For a concept the learner can represent, more data should mean fewer mistakes:
+///
+/// ```
+/// # use aima_rust::learning::{learning_curve, DataSet, PerceptronLearner};
+/// let examples = (0..100)
+///     .map(|i| {
+///         let (x, y) = ((i % 10) as f64, (i / 10) as f64);
+///         vec![x.into(), y.into(), (if x > y { "yes" } else { "no" }).into()]
+///     })
+///     .collect();
+/// let dataset = DataSet::new(&["x", "y", "x > y"], examples, 2);
+///
+/// let curve = learning_curve(&mut PerceptronLearner::new(0.1, 1000, 1), &dataset, &[2, 10, 80], 42);
+///
+/// assert_eq!(curve.iter().map(|&(size, _)| size).collect::<Vec<_>>(), vec![2, 10, 80]);
+/// assert!(curve[2].1 < curve[0].1);
+///
+/// let single = dataset.with_examples(dataset.examples[..1].to_vec());
+/// assert!(learning_curve(&mut PerceptronLearner::new(0.1, 1000, 1), &single, &[1], 42).is_empty());
+/// ```
+pub fn learning_curve<L: Learner>(
+    learner: &mut L,
+    dataset: &DataSet,
+    sizes: &[usize],
+    seed: u64,
+) -> Vec<(usize, f64)> {
+    if dataset.examples.len() < 2 {
+        return Vec::new();
+    }
+    let mut examples = dataset.examples.clone();
+    examples.shuffle(&mut Rng::seeded(seed));
+    let folds = split_into_folds(&examples, examples.len().min(10));
+    sizes
+        .iter()
+        .map(|&size| (size, k_fold_error(learner, dataset, &folds, Some(size))))
+        .collect()
+}
+
+/// A cluster center found by `kmeans`, with one coordinate per numeric input attribute, in
+/// attribute order.
+pub type Centroid = Vec<f64>;
+
+/// Groups the examples of `dataset` into `k` clusters with Lloyd's algorithm: assign each example
+/// to the nearest centroid by Euclidean distance, move each centroid to the mean of its examples,
+/// and repeat until no assignment changes or `max_iters` rounds have passed. Only the numeric
+/// input attributes count toward the distance; categorical ones and the target are ignored.
+///
+/// The initial centroids are chosen by k-means++: the first is an example picked at random, and
+/// each next one an example picked with probability proportional to its squared distance from
+/// the nearest centroid so far. Random choices are drawn from `seed`. Should a cluster lose all its
+/// examples, its centroid is moved to the example farthest from its own centroid.
+///
+/// Returns the centroids and, for each example, the index of its cluster.
+///
+/// # Panics
+///
+/// Panics if `k` is zero or greater than the number of examples.
+///
+/// # Examples
+///
+/// ```
+/// # use aima_rust::learning::{kmeans, DataSet};
+/// # use aima_rust::util::Rng;
+/// use rand::Rng as _;
+///
+/// let mut rng = Rng::seeded(3);
+/// let examples = (0..100)
+///     .map(|i| {
+///         let center = if i % 2 == 0 { 0.0 } else { 10.0 };
+///         let x: f64 = center + rng.gen_range(-1.0..1.0);
+///         let y: f64 = center + rng.gen_range(-1.0..1.0);
+///         vec![x.into(), y.into(), "?".into()]
+///     })
+///     .collect();
+/// let dataset = DataSet::new(&["x", "y", "label"], examples, 2);
+///
+/// let (mut centroids, assignments) = kmeans(&dataset, 2, 100, 42);
+/// assert!(assignments.iter().enumerate().all(|(i, &c)| c == assignments[i % 2]));
+///
+/// centroids.sort_by(|a, b| a[0].total_cmp(&b[0]));
+/// for (centroid, center) in centroids.iter().zip(&[0.0, 10.0]) {
+///     assert!(centroid.iter().all(|x| (x - center).abs() < 0.3));
+/// }
+/// ```
+pub fn kmeans(
+    dataset: &DataSet,
+    k: usize,
+    max_iters: usize,
+    seed: u64,
+) -> (Vec<Centroid>, Vec<usize>) {
+    let n = dataset.examples.len();
+    assert!(
+        k >= 1 && k <= n,
+        "k must be between 1 and the number of examples"
+    );
+    let attributes: Vec<usize> = dataset
+        .inputs()
+        .into_iter()
+        .filter(|&i| dataset.examples.iter().all(|e| e[i].as_f64().is_some()))
+        .collect();
+    let points: Vec<Vec<f64>> = dataset
+        .examples
+        .iter()
+        .map(|e| attributes.iter().map(|&i| e[i].as_f64().unwrap()).collect())
+        .collect();
+    let distance =
+        |a: &[f64], b: &[f64]| -> f64 { a.iter().zip(b).map(|(x, y)| (x - y).powi(2)).sum() };
+
+    let mut rng = Rng::seeded(seed);
+    let mut centroids: Vec<Centroid> = vec![points[rng.gen_range(0..n)].clone()];
+    while centroids.len() < k {
+        let weights: Vec<f64> = points
+            .iter()
+            .map(|p| {
+                centroids
+                    .iter()
+                    .map(|c| distance(p, c))
+                    .fold(f64::INFINITY, f64::min)
+            })
+            .collect();
+        let total: f64 = weights.iter().sum();
+        let next = if total > 0.0 {
+            let mut target = rng.gen_range(0.0..total);
+            weights
+                .iter()
+                .position(|&w| {
+                    target -= w;
+                    target < 0.0
+                })
+                .unwrap_or(n - 1)
+        } else {
+            rng.gen_range(0..n)
+        };
+        centroids.push(points[next].clone());
+    }
+
+    let nearest = |centroids: &[Centroid], p: &[f64]| -> usize {
+        (0..centroids.len())
+            .min_by(|&a, &b| distance(p, &centroids[a]).total_cmp(&distance(p, &centroids[b])))
+            .unwrap()
+    };
+    let mut assignments: Vec<usize> = points.iter().map(|p| nearest(&centroids, p)).collect();
+    for _ in 0..max_iters {
+        let mut sums = vec![vec![0.0; attributes.len()]; k];
+        let mut counts = vec![0; k];
+        for (p, &c) in points.iter().zip(&assignments) {
+            counts[c] += 1;
+            for (sum, x) in sums[c].iter_mut().zip(p) {
+                *sum += x;
+            }
+        }
+        for c in 0..k {
+            if counts[c] > 0 {
+                centroids[c] = sums[c].iter().map(|sum| sum / counts[c] as f64).collect();
+            } else {
+                let farthest = (0..n)
+                    .max_by(|&a, &b| {
+                        distance(&points[a], &centroids[assignments[a]])
+                            .total_cmp(&distance(&points[b], &centroids[assignments[b]]))
+                    })
+                    .unwrap();
+                centroids[c] = points[farthest].clone();
+                assignments[farthest] = c;
+            }
+        }
+
+        let next: Vec<usize> = points.iter().map(|p| nearest(&centroids, p)).collect();
+        if next == assignments {
+            break;
+        }
+        assignments = next;
+    }
+    (centroids, assignments)
+}
+
+/// A mixture of Gaussians with diagonal covariance, fitted by `gaussian_mixture`.
+#[derive(Clone, Debug)]
+pub struct GaussianMixture {
+    /// The mean of each component, one coordinate per dimension.
+    pub means: Vec<Vec<f64>>,
+    /// The variance of each component along each dimension.
+    pub variances: Vec<Vec<f64>>,
+    /// The mixing weight of each component. The weights sum to one.
+    pub weights: Vec<f64>,
+    /// The log-likelihood of the data under the initial parameters and then after each round of
+    /// EM, ending with that of the parameters returned. EM never makes it worse.
+    pub log_likelihoods: Vec<f64>,
+}
+
+impl GaussianMixture {
+    /// The log of the mixture's density at `point`, and that of each component weighted by
+    /// its mixing weight.
+    fn log_densities(&self, point: &[f64]) -> (f64, Vec<f64>) {
+        let parts: Vec<f64> = (0..self.weights.len())
+            .map(|c| {
+                let log_gaussian: f64 = point
+                    .iter()
+                    .zip(&self.means[c])
+                    .zip(&self.variances[c])
+                    .map(|((x, mean), variance)| {
+                        -0.5 * ((2.0 * std::f64::consts::PI * variance).ln()
+                            + (x - mean).powi(2) / variance)
+                    })
+                    .sum();
+                self.weights[c].ln() + log_gaussian
+            })
+            .collect();
+        let max = parts.iter().cloned().fold(f64::NEG_INFINITY, f64::max);
+        let total = max + parts.iter().map(|p| (p - max).exp()).sum::<f64>().ln();
+        (total, parts)
+    }
+}
+
+/// The smallest variance a component may shrink to, which keeps a component that has collapsed
+/// onto a single point from producing an infinite likelihood.
+const MIN_VARIANCE: f64 = 1e-6;
+
+/// Fits a mixture of `k` Gaussians with diagonal covariance to `data` by expectation
+/// maximization. The *E-step* computes each component's responsibility for each point, the
+/// probability that the point came from it; the *M-step* re-estimates every component's weight,
+/// mean, and variance from the points weighted by those responsibilities. Unlike the hard
+/// assignments of `kmeans`, every point contributes to every component.
+///
+/// The means start at `k` distinct points chosen using `seed`, the variances at the variance of
+/// the whole data, and the weights equal. Iteration stops after `max_iters` rounds or once the
+/// log-likelihood improves by less than $10^{-9}$.
+///
+/// # Panics
+///
+/// Panics if `k` is zero or greater than the number of points.
+///
+/// # Examples
+///
+/// ```
+/// # use aima_rust::learning::gaussian_mixture;
+/// # use aima_rust::util::Rng;
+/// use rand::Rng as _;
+///
+/// // Draw 300 points from N(0, 1) and 700 from N(6, 0.25) by the Box-Muller transform.
+/// let mut rng = Rng::seeded(11);
+/// let mut normal = || {
+///     let (u, v): (f64, f64) = (rng.gen(), rng.gen());
+///     (-2.0 * (1.0 - u).ln()).sqrt() * (2.0 * std::f64::consts::PI * v).cos()
+/// };
+/// let data: Vec<Vec<f64>> = (0..1000)
+///     .map(|i| vec![if i < 300 { normal() } else { 6.0 + 0.5 * normal() }])
+///     .collect();
+///
+/// let mixture = gaussian_mixture(&data, 2, 200, 42);
+///
+/// let (low, high) = if mixture.means[0][0] < mixture.means[1][0] { (0, 1) } else { (1, 0) };
+/// assert!((mixture.means[low][0] - 0.0).abs() < 0.2);
+/// assert!((mixture.means[high][0] - 6.0).abs() < 0.1);
+/// assert!((mixture.variances[low][0] - 1.0).abs() < 0.2);
+/// assert!((mixture.variances[high][0] - 0.25).abs() < 0.05);
+/// assert!((mixture.weights[low] - 0.3).abs() < 0.03);
+///
+/// let ll = &mixture.log_likelihoods;
+/// assert!(ll.windows(2).all(|pair| pair[1] >= pair[0] - 1e-9));
+/// ```
+pub fn gaussian_mixture(
+    data: &[Vec<f64>],
+    k: usize,
+    max_iters: usize,
+    seed: u64,
+) -> GaussianMixture {
+    let n = data.len();
+    assert!(
+        k >= 1 && k <= n,
+        "k must be between 1 and the number of points"
+    );
+    let dims = data[0].len();
+    let overall_mean: Vec<f64> = (0..dims)
+        .map(|d| data.iter().map(|p| p[d]).sum::<f64>() / n as f64)
+        .collect();
+    let overall_variance: Vec<f64> = (0..dims)
+        .map(|d| {
+            let variance = data
+                .iter()
+                .map(|p| (p[d] - overall_mean[d]).powi(2))
+                .sum::<f64>()
+                / n as f64;
+            variance.max(MIN_VARIANCE)
+        })
+        .collect();
+
+    let mut rng = Rng::seeded(seed);
+    let mut mixture = GaussianMixture {
+        means: data.choose_multiple(&mut rng, k).cloned().collect(),
+        variances: vec![overall_variance; k],
+        weights: vec![1.0 / k as f64; k],
+        log_likelihoods: Vec::new(),
+    };
+
+    for _ in 0..max_iters {
+        // E-step
+        let mut log_likelihood = 0.0;
+        let responsibilities: Vec<Vec<f64>> = data
+            .iter()
+            .map(|point| {
+                let (total, parts) = mixture.log_densities(point);
+                log_likelihood += total;
+                parts.iter().map(|p| (p - total).exp()).collect()
+            })
+            .collect();
+
+        // Stop once EM has settled; the parameters it settled on are kept.
+        if let Some(&last) = mixture.log_likelihoods.last() {
+            if log_likelihood - last < 1e-9 {
+                mixture.log_likelihoods.push(log_likelihood);
+                break;
+            }
+        }
+        mixture.log_likelihoods.push(log_likelihood);
+
+        // M-step
+        for c in 0..k {
+            let mass: f64 = responsibilities.iter().map(|r| r[c]).sum();
+            if mass <= 0.0 {
+                continue;
+            }
+            mixture.weights[c] = mass / n as f64;
+            for d in 0..dims {
+                let mean = data
+                    .iter()
+                    .zip(&responsibilities)
+                    .map(|(p, r)| r[c] * p[d])
+                    .sum::<f64>()
+                    / mass;
+                let variance = data
+                    .iter()
+                    .zip(&responsibilities)
+                    .map(|(p, r)| r[c] * (p[d] - mean).powi(2))
+                    .sum::<f64>()
+                    / mass;
+                mixture.means[c][d] = mean;
+                mixture.variances[c][d] = variance.max(MIN_VARIANCE);
+            }
+        }
+    }
+    mixture
+}
+
+/// A summary of how well a classifier's predictions match the actual classes: a confusion
+/// matrix, overall accuracy, and per-class precision, recall, and F1 score.
+///
+/// Precision for a class is the fraction of predictions of that class that were correct, and
+/// recall is the fraction of examples of that class that were found. F1 is their harmonic mean.
+/// When a ratio would divide by zero, for example the precision of a class that was never
+/// predicted, it is reported as `0.0`.
+///
+/// # Examples
+///
+/// ```
+/// # use aima_rust::learning::ClassificationReport;
+/// let actual = ["cat", "cat", "cat", "dog", "dog", "fish"];
+/// let predicted = ["cat", "dog", "cat", "dog", "dog", "cat"];
+///
+/// let report = ClassificationReport::from_predictions(&actual, &predicted);
+///
+/// assert_eq!(report.labels(), &["cat", "dog", "fish"]);
+/// assert_eq!(report.confusion_matrix(), &[vec![2, 1, 0], vec![0, 2, 0], vec![1, 0, 0]]);
+/// assert_eq!(report.accuracy(), 4.0 / 6.0);
+/// assert_eq!(report.precision(&"cat"), 2.0 / 3.0);
+/// assert_eq!(report.recall(&"dog"), 1.0);
+/// assert_eq!(report.f1(&"dog"), 0.8);
+/// assert_eq!(report.precision(&"fish"), 0.0);
+///
+/// assert_eq!(
+///     report.to_string(),
+///     "Confusion matrix (rows are actual, columns are predicted)
+///                 cat       dog      fish
+///       cat         2         1         0
+///       dog         0         2         0
+///      fish         1         0         0
+///
+///           precision    recall        f1
+///       cat     0.667     0.667     0.667
+///       dog     0.667     1.000     0.800
+///      fish     0.000     0.000     0.000
+///
+/// accuracy: 0.667"
+/// );
+/// ```
+#[derive(Clone, Debug)]
+pub struct ClassificationReport<T> {
+    labels: Vec<T>,
+    matrix: Vec<Vec<usize>>,
+}
+
+impl<T: Clone + Eq + Hash> ClassificationReport<T> {
+    /// Builds the report from the actual classes and the classifier's predictions of them. The
+    /// labels are ordered by their first appearance in `actual`, then in `predicted`.
+    pub fn from_predictions(actual: &[T], predicted: &[T]) -> Self {
+        assert_eq!(
+            actual.len(),
+            predicted.len(),
+            "every actual class needs a prediction"
+        );
+        let mut labels: Vec<T> = Vec::new();
+        for label in actual.iter().chain(predicted) {
+            if !labels.contains(label) {
+                labels.push(label.clone());
+            }
+        }
+        let index: HashMap<&T, usize> = labels.iter().enumerate().map(|(i, l)| (l, i)).collect();
+
+        let mut matrix = vec![vec![0; labels.len()]; labels.len()];
+        for (a, p) in actual.iter().zip(predicted) {
+            matrix[index[a]][index[p]] += 1;
+        }
+        ClassificationReport { labels, matrix }
+    }
+
+    /// The class labels, in the order used by the rows and columns of the confusion matrix.
+    pub fn labels(&self) -> &[T] {
+        &self.labels
+    }
+
+    /// The confusion matrix, where entry `[i][j]` counts examples of class `labels()[i]` that
+    /// were predicted to be `labels()[j]`.
+    pub fn confusion_matrix(&self) -> &[Vec<usize>] {
+        &self.matrix
+    }
+
+    /// The fraction of all predictions that were correct.
+    pub fn accuracy(&self) -> f64 {
+        let correct: usize = (0..self.labels.len()).map(|i| self.matrix[i][i]).sum();
+        let total: usize = self.matrix.iter().flatten().sum();
+        ratio(correct, total)
+    }
+
+    /// The fraction of predictions of `label` that were correct.
+    pub fn precision(&self, label: &T) -> f64 {
+        self.position(label).map_or(0.0, |i| {
+            ratio(
+                self.matrix[i][i],
+                self.matrix.iter().map(|row| row[i]).sum(),
+            )
+        })
+    }
+
+    /// The fraction of examples of `label` that were predicted as `label`.
+    pub fn recall(&self, label: &T) -> f64 {
+        self.position(label).map_or(0.0, |i| {
+            ratio(self.matrix[i][i], self.matrix[i].iter().sum())
+        })
+    }
+
+    /// The harmonic mean of the precision and recall of `label`.
+    pub fn f1(&self, label: &T) -> f64 {
+        let (p, r) = (self.precision(label), self.recall(label));
+        if p + r == 0.0 {
+            0.0
+        } else {
+            2.0 * p * r / (p + r)
+        }
+    }
+
+    fn position(&self, label: &T) -> Option<usize> {
+        self.labels.iter().position(|l| l == label)
+    }
+}
+
+fn ratio(numerator: usize, denominator: usize) -> f64 {
+    if denominator == 0 {
+        0.0
+    } else {
+        numerator as f64 / denominator as f64
+    }
+}
+
+impl<T: Clone + Eq + Hash + fmt::Display> fmt::Display for ClassificationReport<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let names: Vec<String> = self.labels.iter().map(|l| l.to_string()).collect();
+        let width = names.iter().map(|n| n.len()).max().unwrap_or(0).max(9);
+
+        writeln!(
+            f,
+            "Confusion matrix (rows are actual, columns are predicted)"
+        )?;
+        write!(f, "{:>w$}", "", w = width)?;
+        for name in &names {
+            write!(f, " {:>w$}", name, w = width)?;
+        }
+        writeln!(f)?;
+        for (name, row) in names.iter().zip(&self.matrix) {
+            write!(f, "{:>w$}", name, w = width)?;
+            for count in row {
+                write!(f, " {:>w$}", count, w = width)?;
+            }
+            writeln!(f)?;
+        }
+
+        writeln!(f)?;
+        writeln!(
+            f,
+            "{:>w$} {:>w$} {:>w$} {:>w$}",
+            "",
+            "precision",
+            "recall",
+            "f1",
+            w = width
+        )?;
+        for (name, label) in names.iter().zip(&self.labels) {
+            writeln!(
+                f,
+                "{:>w$} {:>w$.3} {:>w$.3} {:>w$.3}",
+                name,
+                self.precision(label),
+                self.recall(label),
+                self.f1(label),
+                w = width
+            )?;
+        }
+        writeln!(f)?;
+        write!(f, "accuracy: {:.3}", self.accuracy())
+    }
+}
+
+/// # Preprocessing
+///
+/// Transforms that turn a DataSet into one a numeric learner, such as the perceptron, can use.
+/// Each takes a dataset and the index of one input attribute and returns a new dataset; the
+/// target attribute is never changed.
+///
+/// ```
+/// # use aima_rust::learning::{DataSet, Value};
+/// # use aima_rust::learning::preprocess::{normalize, one_hot_encode, Scaling};
+/// let dataset = DataSet::new(
+///     &["size", "color", "price"],
+///     vec![
+///         vec![10.0.into(), "red".into(), "cheap".into()],
+///         vec![30.0.into(), "green".into(), "dear".into()],
+///         vec![20.0.into(), "blue".into(), "cheap".into()],
+///     ],
+///     2,
+/// );
+///
+/// let encoded = one_hot_encode(&dataset, 1);
+/// assert_eq!(encoded.attr_names, vec!["size", "color=red", "color=green", "color=blue", "price"]);
+/// assert_eq!(encoded.target, 4);
+/// assert_eq!(encoded.examples[1][1..4], [0.0.into(), 1.0.into(), 0.0.into()]);
+///
+/// let scaled = normalize(&encoded, 0, Scaling::MinMax);
+/// let sizes: Vec<Value> = scaled.examples.iter().map(|e| e[0].clone()).collect();
+/// assert_eq!(sizes, vec![0.0.into(), 1.0.into(), 0.5.into()]);
+/// assert_eq!(scaled.examples[1][4], Value::from("dear"));
+///
+/// let standardized = normalize(&encoded, 0, Scaling::ZScore);
+/// let mean: f64 = standardized.examples.iter().map(|e| e[0].as_f64().unwrap()).sum::<f64>() / 3.0;
+/// assert!(mean.abs() < 1e-12);
+/// ```
+pub mod preprocess {
+    use super::{DataSet, Value};
+
+    /// How `normalize` rescales a numeric attribute.
+    #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+    pub enum Scaling {
+        /// Maps the smallest value to $0$ and the largest to $1$.
+        MinMax,
+        /// Subtracts the mean and divides by the standard deviation, giving z-scores.
+        ZScore,
+    }
+
+    /// Replaces the categorical `attribute` with one numeric attribute per distinct value, in the
+    /// order the values first appear. In each example the column of its value is $1$ and the
+    /// others are $0$. The new attributes are named `attribute=value`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `attribute` is the target.
+    pub fn one_hot_encode(dataset: &DataSet, attribute: usize) -> DataSet {
+        assert_ne!(attribute, dataset.target, "the target can't be encoded");
+        let mut values: Vec<Value> = Vec::new();
+        for example in &dataset.examples {
+            if !values.contains(&example[attribute]) {
+                values.push(example[attribute].clone());
+            }
+        }
+
+        let mut attr_names = dataset.attr_names[..attribute].to_vec();
+        attr_names.extend(
+            values
+                .iter()
+                .map(|value| format!("{}={}", dataset.attr_names[attribute], value)),
+        );
+        attr_names.extend_from_slice(&dataset.attr_names[attribute + 1..]);
+
+        let examples = dataset
+            .examples
+            .iter()
+            .map(|example| {
+                let mut row = example[..attribute].to_vec();
+                row.extend(values.iter().map(|value| {
+                    Value::Numeric(if *value == example[attribute] {
+                        1.0
+                    } else {
+                        0.0
+                    })
+                }));
+                row.extend_from_slice(&example[attribute + 1..]);
+                row
+            })
+            .collect();
+
+        let target = if dataset.target > attribute {
+            dataset.target + values.len() - 1
+        } else {
+            dataset.target
+        };
+        DataSet {
+            attr_names,
+            examples,
+            target,
+        }
+    }
+
+    /// Rescales the numeric `attribute` as chosen by `scaling`. If every example has the same
+    /// value, there is nothing to scale by, and the attribute becomes $0$ throughout.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `attribute` is the target or holds a categorical value.
+    pub fn normalize(dataset: &DataSet, attribute: usize, scaling: Scaling) -> DataSet {
+        assert_ne!(attribute, dataset.target, "the target can't be normalized");
+        let xs: Vec<f64> = dataset
+            .examples
+            .iter()
+            .map(|example| {
+                example[attribute]
+                    .as_f64()
+                    .expect("only numeric attributes can be normalized")
+            })
+            .collect();
+
+        let (offset, scale) = match scaling {
+            Scaling::MinMax => {
+                let min = xs.iter().cloned().fold(f64::INFINITY, f64::min);
+                let max = xs.iter().cloned().fold(f64::NEG_INFINITY, f64::max);
+                (min, max - min)
+            }
+            Scaling::ZScore => {
+                let n = xs.len() as f64;
+                let mean = xs.iter().sum::<f64>() / n;
+                let variance = xs.iter().map(|x| (x - mean).powi(2)).sum::<f64>() / n;
+                (mean, variance.sqrt())
+            }
+        };
+
+        let mut normalized = dataset.clone();
+        for (example, x) in normalized.examples.iter_mut().zip(xs) {
+            example[attribute] = Value::Numeric(if scale > 0.0 {
+                (x - offset) / scale
+            } else {
+                0.0
+            });
+        }
+        normalized
+    }
+}
@@ -14,7 +14,7 @@
 //! the basic safety guarantees provided:
 //!
 //! 1) Programs that compile in standard Rust are guaranteed to be free of memory corruption. If you weren't aware,
-//! memory-corruption results in **undefined program behaviour**.
+//!    memory-corruption results in **undefined program behaviour**.
 //! 2) Concurrent mutable access _does not even compile_. This in most cases eliminates concerns of "data races".
 //!
 //! Furthermore, higher-level abstractions in the language make it possible to develop similar ad-hoc safety guarantees for
@@ -90,7 +90,7 @@
 //! | 10.2        | Spare-Tire-Problem                | `spare_tire`                  |
 //! | 10.3        | Three-Block-Tower                 | `three_block_tower`           |
 //! | 10.7        | Cake-Problem                      | `have_cake_and_eat_cake_too`  |
-//! | 10.9        | Graphplan                         | `GraphPlan`                   |
+//! | 10.9        | Graphplan                         | `graph_plan`                  |
 //! | 10.13       | Partial-Order-Planner             | `PartialOrderPlanner`         |
 //! | 11.1        | Job-Shop-Problem-With-Resources   | `job_shop_problem`            |
 //! | 11.5        | Hierarchical-Search               | `hierarchical_search`         |
@@ -142,4 +142,17 @@
 
 
 
-pub mod agents;
\ No newline at end of file
+pub mod agents;
+pub mod csp;
+pub mod fol;
+pub mod games;
+pub mod learning;
+pub mod logic;
+pub mod mdp;
+pub mod nlp;
+pub mod planning;
+pub mod probability;
+pub mod rl;
+pub mod robotics;
+pub mod search;
+pub mod util;
@@ -0,0 +1,1243 @@
+//! # Propositional Logic
+//!
+//! This module covers material in Chapter 7, logical agents.
+//!
+//! ## Index of Algorithms
+//!
+//! | **Figure** | **Name**                          | **Module Link**
+//! |:-----------|:----------------------------------|:-------------------------------
+//! | 7.7        | Propositional Logic Sentence      | `Expr`                                                   |
+//! | 7          | PL-True?                          | `pl_true`                                                |
+//! | 7.10       | TT-Entails                        | `tt_entails`                                             |
+//! | 7          | Model Enumeration                 | `all_models`                                             |
+//! | 7          | Validity and Satisfiability       | `is_valid`, `is_satisfiable`                             |
+//! | 7.12       | PL-Resolution                     | `pl_resolution`                                          |
+//! | 7.14       | Convert to CNF                    | `to_cnf`                                                 |
+//! | 7          | Convert to DNF                    | `to_dnf`                                                 |
+//! | 7.17       | DPLL-Satisfiable?                 | `dpll_satisfiable`                                       |
+//! | 7          | Conflict-Driven Clause Learning   | `cdcl_satisfiable`                                       |
+//! | 7.18       | WalkSAT                           | `walk_sat`                                               |
+//!
+use crate::util::Rng;
+use rand::seq::SliceRandom;
+use rand::Rng as _;
+use std::collections::{BTreeMap, BTreeSet};
+use std::error::Error;
+use std::fmt;
+use std::str::FromStr;
+
+/// # Propositional Logic Sentence
+///
+/// A sentence of propositional logic, built from proposition symbols with the connectives of
+/// figure 7.7. Conjunctions and disjunctions take any number of operands: an empty conjunction
+/// is true and an empty disjunction false.
+///
+/// Sentences are usually written as text and parsed, with `~` for negation, `&` for and, `|` for
+/// or, `==>` for implication, and `<=>` for the biconditional, in decreasing order of precedence.
+/// Implication groups to the right.
+///
+/// # Examples
+///
+/// ```
+/// # use aima_rust::logic::{expr, Expr};
+/// let sentence = expr("P & ~Q ==> R | S");
+///
+/// assert_eq!(
+///     sentence,
+///     Expr::Implies(
+///         Box::new(Expr::And(vec![expr("P"), Expr::Not(Box::new(expr("Q")))])),
+///         Box::new(Expr::Or(vec![expr("R"), expr("S")])),
+///     )
+/// );
+/// assert_eq!(sentence.to_string(), "P & ~Q ==> R | S");
+/// assert_eq!(sentence.symbols().len(), 4);
+/// assert!("P & | Q".parse::<Expr>().is_err());
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Expr {
+    Symbol(String),
+    Not(Box<Expr>),
+    And(Vec<Expr>),
+    Or(Vec<Expr>),
+    Implies(Box<Expr>, Box<Expr>),
+    Iff(Box<Expr>, Box<Expr>),
+}
+
+impl Expr {
+    /// Creates a new proposition symbol given its name.
+    pub fn symbol(name: &str) -> Self {
+        Expr::Symbol(name.to_string())
+    }
+
+    /// The proposition symbols of the sentence, in alphabetical order.
+    pub fn symbols(&self) -> Vec<String> {
+        let mut symbols = BTreeSet::new();
+        self.collect_symbols(&mut symbols);
+        symbols.into_iter().collect()
+    }
+
+    fn collect_symbols(&self, symbols: &mut BTreeSet<String>) {
+        match self {
+            Expr::Symbol(name) => {
+                symbols.insert(name.clone());
+            }
+            Expr::Not(p) => p.collect_symbols(symbols),
+            Expr::And(ps) | Expr::Or(ps) => ps.iter().for_each(|p| p.collect_symbols(symbols)),
+            Expr::Implies(p, q) | Expr::Iff(p, q) => {
+                p.collect_symbols(symbols);
+                q.collect_symbols(symbols);
+            }
+        }
+    }
+
+    /// How tightly the sentence's main connective binds, for deciding where parentheses go.
+    fn precedence(&self) -> u8 {
+        match self {
+            Expr::Iff(..) => 1,
+            Expr::Implies(..) => 2,
+            Expr::Or(ps) if ps.len() > 1 => 3,
+            Expr::And(ps) if ps.len() > 1 => 4,
+            _ => 5,
+        }
+    }
+
+    fn fmt_operand(&self, f: &mut fmt::Formatter, min_precedence: u8) -> fmt::Result {
+        if self.precedence() < min_precedence {
+            write!(f, "({})", self)
+        } else {
+            write!(f, "{}", self)
+        }
+    }
+}
+
+impl fmt::Display for Expr {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let join = |f: &mut fmt::Formatter, ps: &[Expr], op: &str, precedence: u8| {
+            for (i, p) in ps.iter().enumerate() {
+                if i > 0 {
+                    write!(f, " {} ", op)?;
+                }
+                p.fmt_operand(f, precedence + 1)?;
+            }
+            Ok(())
+        };
+        match self {
+            Expr::Symbol(name) => write!(f, "{}", name),
+            Expr::Not(p) => {
+                write!(f, "~")?;
+                p.fmt_operand(f, 5)
+            }
+            Expr::And(ps) if ps.is_empty() => write!(f, "True"),
+            Expr::Or(ps) if ps.is_empty() => write!(f, "False"),
+            Expr::And(ps) if ps.len() == 1 => write!(f, "{}", ps[0]),
+            Expr::Or(ps) if ps.len() == 1 => write!(f, "{}", ps[0]),
+            Expr::And(ps) => join(f, ps, "&", 4),
+            Expr::Or(ps) => join(f, ps, "|", 3),
+            Expr::Implies(p, q) => {
+                p.fmt_operand(f, 3)?;
+                write!(f, " ==> ")?;
+                q.fmt_operand(f, 2)
+            }
+            Expr::Iff(p, q) => {
+                p.fmt_operand(f, 2)?;
+                write!(f, " <=> ")?;
+                q.fmt_operand(f, 2)
+            }
+        }
+    }
+}
+
+/// Returned when text can't be parsed as a sentence.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParseExprError(String);
+
+impl fmt::Display for ParseExprError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "invalid sentence: {}", self.0)
+    }
+}
+
+impl Error for ParseExprError {}
+
+impl FromStr for Expr {
+    type Err = ParseExprError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parser = Parser {
+            tokens: tokenize(s)?,
+            position: 0,
+        };
+        let sentence = parser.iff()?;
+        match parser.peek() {
+            None => Ok(sentence),
+            Some(token) => Err(ParseExprError(format!("unexpected `{}`", token))),
+        }
+    }
+}
+
+/// Parses `s` as a sentence.
+///
+/// # Panics
+///
+/// Panics if `s` isn't a well-formed sentence.
+pub fn expr(s: &str) -> Expr {
+    s.parse().unwrap_or_else(|e| panic!("{}", e))
+}
+
+fn tokenize(s: &str) -> Result<Vec<String>, ParseExprError> {
+    let mut tokens = Vec::new();
+    let mut chars = s.char_indices().peekable();
+    while let Some(&(i, c)) = chars.peek() {
+        if c.is_whitespace() {
+            chars.next();
+        } else if c.is_alphanumeric() || c == '_' {
+            let mut end = i;
+            while let Some(&(j, c)) = chars.peek() {
+                if !(c.is_alphanumeric() || c == '_') {
+                    break;
+                }
+                end = j + c.len_utf8();
+                chars.next();
+            }
+            tokens.push(s[i..end].to_string());
+        } else if s[i..].starts_with("==>") || s[i..].starts_with("<=>") {
+            tokens.push(s[i..i + 3].to_string());
+            chars.nth(2);
+        } else if "~&|()".contains(c) {
+            tokens.push(c.to_string());
+            chars.next();
+        } else {
+            return Err(ParseExprError(format!("unexpected `{}`", c)));
+        }
+    }
+    Ok(tokens)
+}
+
+/// A recursive-descent parser with one function per level of precedence.
+struct Parser {
+    tokens: Vec<String>,
+    position: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&str> {
+        self.tokens.get(self.position).map(String::as_str)
+    }
+
+    fn eat(&mut self, token: &str) -> bool {
+        if self.peek() == Some(token) {
+            self.position += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn iff(&mut self) -> Result<Expr, ParseExprError> {
+        let mut p = self.implies()?;
+        while self.eat("<=>") {
+            p = Expr::Iff(Box::new(p), Box::new(self.implies()?));
+        }
+        Ok(p)
+    }
+
+    fn implies(&mut self) -> Result<Expr, ParseExprError> {
+        let p = self.or()?;
+        if self.eat("==>") {
+            Ok(Expr::Implies(Box::new(p), Box::new(self.implies()?)))
+        } else {
+            Ok(p)
+        }
+    }
+
+    fn or(&mut self) -> Result<Expr, ParseExprError> {
+        let mut ps = vec![self.and()?];
+        while self.eat("|") {
+            ps.push(self.and()?);
+        }
+        Ok(if ps.len() == 1 {
+            ps.remove(0)
+        } else {
+            Expr::Or(ps)
+        })
+    }
+
+    fn and(&mut self) -> Result<Expr, ParseExprError> {
+        let mut ps = vec![self.not()?];
+        while self.eat("&") {
+            ps.push(self.not()?);
+        }
+        Ok(if ps.len() == 1 {
+            ps.remove(0)
+        } else {
+            Expr::And(ps)
+        })
+    }
+
+    fn not(&mut self) -> Result<Expr, ParseExprError> {
+        if self.eat("~") {
+            return Ok(Expr::Not(Box::new(self.not()?)));
+        }
+        if self.eat("(") {
+            let p = self.iff()?;
+            if !self.eat(")") {
+                return Err(ParseExprError("missing `)`".to_string()));
+            }
+            return Ok(p);
+        }
+        match self.peek() {
+            Some(token) if token.chars().all(|c| c.is_alphanumeric() || c == '_') => {
+                let symbol = Expr::Symbol(token.to_string());
+                self.position += 1;
+                Ok(symbol)
+            }
+            Some(token) => Err(ParseExprError(format!("unexpected `{}`", token))),
+            None => Err(ParseExprError("unexpected end".to_string())),
+        }
+    }
+}
+
+/// A model, or truth assignment, mapping proposition symbols to truth values.
+pub type Model = BTreeMap<String, bool>;
+
+/// The truth value of `sentence` in `model`, or `None` if it depends on a symbol the model
+/// doesn't assign.
+///
+/// # Examples
+///
+/// ```
+/// # use aima_rust::logic::{expr, pl_true, Model};
+/// let mut model = Model::new();
+/// model.insert("P".to_string(), true);
+///
+/// assert_eq!(pl_true(&expr("P | Q"), &model), Some(true));
+/// assert_eq!(pl_true(&expr("P & Q"), &model), None);
+/// assert_eq!(pl_true(&expr("~P ==> Q"), &model), Some(true));
+/// ```
+pub fn pl_true(sentence: &Expr, model: &Model) -> Option<bool> {
+    match sentence {
+        Expr::Symbol(name) => model.get(name).cloned(),
+        Expr::Not(p) => pl_true(p, model).map(|v| !v),
+        Expr::And(ps) => {
+            let mut result = Some(true);
+            for p in ps {
+                match pl_true(p, model) {
+                    Some(false) => return Some(false),
+                    None => result = None,
+                    Some(true) => {}
+                }
+            }
+            result
+        }
+        Expr::Or(ps) => {
+            let mut result = Some(false);
+            for p in ps {
+                match pl_true(p, model) {
+                    Some(true) => return Some(true),
+                    None => result = None,
+                    Some(false) => {}
+                }
+            }
+            result
+        }
+        Expr::Implies(p, q) => match (pl_true(p, model), pl_true(q, model)) {
+            (Some(false), _) | (_, Some(true)) => Some(true),
+            (Some(true), Some(false)) => Some(false),
+            _ => None,
+        },
+        Expr::Iff(p, q) => Some(pl_true(p, model)? == pl_true(q, model)?),
+    }
+}
+
+/// Every truth assignment to `symbols`, one at a time: all $2^n$ of them for $n$ symbols, without
+/// ever holding more than one in memory. The first model makes every symbol false and the last
+/// makes every symbol true.
+///
+/// # Panics
+///
+/// Panics if there are 64 symbols or more, far more than could ever be enumerated.
+///
+/// # Examples
+///
+/// ```
+/// # use aima_rust::logic::all_models;
+/// let symbols = vec!["A".to_string(), "B".to_string(), "C".to_string()];
+/// let mut models = all_models(&symbols);
+///
+/// assert_eq!(models.size_hint(), (8, Some(8)));
+/// assert!(models.next().unwrap().values().all(|&value| !value));
+/// assert_eq!(models.count(), 7);
+/// ```
+pub fn all_models(symbols: &[String]) -> impl Iterator<Item = Model> {
+    assert!(symbols.len() < 64, "too many symbols to enumerate");
+    let symbols = symbols.to_vec();
+    (0..1u64 << symbols.len()).map(move |bits| {
+        symbols
+            .iter()
+            .enumerate()
+            .map(|(i, symbol)| (symbol.clone(), bits & (1 << i) != 0))
+            .collect()
+    })
+}
+
+/// Figure 7.10
+///
+/// Whether `kb` entails `alpha`, decided by truth-table enumeration: `alpha` must be true in every
+/// model of the symbols of both sentences in which `kb` is true. The time taken is exponential in
+/// the number of symbols.
+///
+/// # Examples
+///
+/// ```
+/// # use aima_rust::logic::{expr, tt_entails};
+/// let kb = expr("(P ==> Q) & P");
+///
+/// assert!(tt_entails(&kb, &expr("Q")));
+/// assert!(!tt_entails(&kb, &expr("~Q")));
+/// ```
+pub fn tt_entails(kb: &Expr, alpha: &Expr) -> bool {
+    let mut symbols = kb.symbols();
+    symbols.extend(alpha.symbols());
+    symbols.sort();
+    symbols.dedup();
+    all_models(&symbols)
+        .all(|model| pl_true(kb, &model) != Some(true) || pl_true(alpha, &model) == Some(true))
+}
+
+/// Whether `sentence` is valid: true in every model.
+///
+/// # Examples
+///
+/// ```
+/// # use aima_rust::logic::{expr, is_valid};
+/// assert!(is_valid(&expr("A | ~A")));
+/// assert!(is_valid(&expr("(A ==> B) <=> (~B ==> ~A)")));
+/// assert!(!is_valid(&expr("A | B")));
+/// ```
+pub fn is_valid(sentence: &Expr) -> bool {
+    all_models(&sentence.symbols()).all(|model| pl_true(sentence, &model) == Some(true))
+}
+
+/// Whether `sentence` is satisfiable: true in some model.
+///
+/// # Examples
+///
+/// ```
+/// # use aima_rust::logic::{expr, is_satisfiable};
+/// assert!(!is_satisfiable(&expr("A & ~A")));
+/// assert!(is_satisfiable(&expr("A & ~B")));
+/// ```
+pub fn is_satisfiable(sentence: &Expr) -> bool {
+    all_models(&sentence.symbols()).any(|model| pl_true(sentence, &model) == Some(true))
+}
+
+/// A proposition symbol or its negation.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Literal {
+    pub symbol: String,
+    pub positive: bool,
+}
+
+impl Literal {
+    /// Creates a new literal given its symbol and sign.
+    pub fn new(symbol: &str, positive: bool) -> Self {
+        Literal {
+            symbol: symbol.to_string(),
+            positive,
+        }
+    }
+
+    /// The complementary literal.
+    pub fn negate(&self) -> Self {
+        Literal {
+            symbol: self.symbol.clone(),
+            positive: !self.positive,
+        }
+    }
+
+    /// The literal as a sentence.
+    pub fn to_expr(&self) -> Expr {
+        let symbol = Expr::Symbol(self.symbol.clone());
+        if self.positive {
+            symbol
+        } else {
+            Expr::Not(Box::new(symbol))
+        }
+    }
+}
+
+impl fmt::Display for Literal {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}{}", if self.positive { "" } else { "~" }, self.symbol)
+    }
+}
+
+/// A clause: the disjunction of a set of literals. The empty clause is false.
+pub type Clause = BTreeSet<Literal>;
+
+/// The clauses of the conjunctive normal form of `sentence`, or of the negation of `sentence` if
+/// `positive` is false, with the literals of each clause in the order they appear.
+///
+/// Negations are pushed inwards as the sentence is walked, rather than by rewriting it first, and
+/// disjunctions are distributed over conjunctions by taking every combination of one clause from
+/// each disjunct.
+fn cnf(sentence: &Expr, positive: bool) -> Vec<Vec<Literal>> {
+    let conjoin = |clauses: Vec<Vec<Vec<Literal>>>| clauses.into_iter().flatten().collect();
+    let disjoin = |clauses: Vec<Vec<Vec<Literal>>>| {
+        clauses.into_iter().fold(vec![vec![]], |product, next| {
+            let mut combined = Vec::new();
+            for left in &product {
+                for right in &next {
+                    let mut clause: Vec<Literal> = left.clone();
+                    for literal in right {
+                        if !clause.contains(literal) {
+                            clause.push(literal.clone());
+                        }
+                    }
+                    combined.push(clause);
+                }
+            }
+            combined
+        })
+    };
+    match (sentence, positive) {
+        (Expr::Symbol(name), _) => vec![vec![Literal::new(name, positive)]],
+        (Expr::Not(p), _) => cnf(p, !positive),
+        (Expr::And(ps), true) | (Expr::Or(ps), false) => {
+            conjoin(ps.iter().map(|p| cnf(p, positive)).collect())
+        }
+        (Expr::Or(ps), true) | (Expr::And(ps), false) => {
+            disjoin(ps.iter().map(|p| cnf(p, positive)).collect())
+        }
+        (Expr::Implies(p, q), true) => disjoin(vec![cnf(p, false), cnf(q, true)]),
+        (Expr::Implies(p, q), false) => conjoin(vec![cnf(p, true), cnf(q, false)]),
+        (Expr::Iff(p, q), _) => conjoin(vec![
+            disjoin(vec![cnf(p, false), cnf(q, positive)]),
+            disjoin(vec![cnf(p, true), cnf(q, !positive)]),
+        ]),
+    }
+}
+
+/// Figure 7.14
+///
+/// Converts `sentence` to an equivalent sentence in conjunctive normal form: a conjunction of
+/// disjunctions of literals. Biconditionals and implications are eliminated, negations moved
+/// inwards until they apply only to symbols, and disjunctions distributed over conjunctions.
+///
+/// # Examples
+///
+/// ```
+/// # use aima_rust::logic::{expr, is_valid, to_cnf, Expr};
+/// let sentence = expr("B11 <=> (P12 | P21)");
+/// let cnf = to_cnf(&sentence);
+///
+/// assert_eq!(cnf.to_string(), "(~B11 | P12 | P21) & (B11 | ~P12) & (B11 | ~P21)");
+/// assert!(is_valid(&Expr::Iff(Box::new(sentence), Box::new(cnf))));
+/// assert_eq!(to_cnf(&expr("~(A ==> B)")).to_string(), "A & ~B");
+/// ```
+pub fn to_cnf(sentence: &Expr) -> Expr {
+    normal_form(cnf(sentence, true), Expr::Or, Expr::And)
+}
+
+/// Converts `sentence` to an equivalent sentence in disjunctive normal form: a disjunction of
+/// conjunctions of literals. By De Morgan's laws, the terms of the DNF of $\alpha$ are the
+/// clauses of the CNF of $\neg\alpha$ with every literal negated, so the conversion shares its
+/// elimination and negation steps with `to_cnf`, and ends by distributing conjunctions over
+/// disjunctions.
+///
+/// Terms are not simplified, so a term may contain a literal along with its negation.
+///
+/// # Examples
+///
+/// ```
+/// # use aima_rust::logic::{expr, to_dnf, tt_entails};
+/// let dnf = expr("(A & B) | C");
+/// assert_eq!(to_dnf(&dnf), dnf);
+///
+/// let sentence = expr("(A | B) & (~A | C)");
+/// let converted = to_dnf(&sentence);
+/// assert_eq!(converted.to_string(), "A & ~A | A & C | B & ~A | B & C");
+/// assert!(tt_entails(&sentence, &converted) && tt_entails(&converted, &sentence));
+/// assert_eq!(to_dnf(&converted), converted);
+///
+/// assert_eq!(to_dnf(&expr("~(A ==> B)")).to_string(), "A & ~B");
+/// ```
+pub fn to_dnf(sentence: &Expr) -> Expr {
+    let terms = cnf(sentence, false)
+        .into_iter()
+        .map(|clause| clause.iter().map(Literal::negate).collect())
+        .collect();
+    normal_form(terms, Expr::And, Expr::Or)
+}
+
+/// Joins each group of literals with `inner`, and the groups with `outer`, leaving out the
+/// connective wherever there is only one operand.
+fn normal_form(
+    groups: Vec<Vec<Literal>>,
+    inner: fn(Vec<Expr>) -> Expr,
+    outer: fn(Vec<Expr>) -> Expr,
+) -> Expr {
+    let join = |mut operands: Vec<Expr>, connective: fn(Vec<Expr>) -> Expr| {
+        if operands.len() == 1 {
+            operands.remove(0)
+        } else {
+            connective(operands)
+        }
+    };
+    let operands = groups
+        .into_iter()
+        .map(|group| join(group.iter().map(Literal::to_expr).collect(), inner))
+        .collect();
+    join(operands, outer)
+}
+
+/// The clauses of the conjunctive normal form of `sentence`, with duplicates removed.
+///
+/// # Examples
+///
+/// ```
+/// # use aima_rust::logic::{expr, to_clauses};
+/// assert_eq!(to_clauses(&expr("(A | B) & (B | A) & C")).len(), 2);
+/// assert_eq!(to_clauses(&expr("A | ~A")).len(), 1);
+/// ```
+pub fn to_clauses(sentence: &Expr) -> Vec<Clause> {
+    let mut clauses: Vec<Clause> = Vec::new();
+    for clause in cnf(sentence, true) {
+        let clause: Clause = clause.into_iter().collect();
+        if !clauses.contains(&clause) {
+            clauses.push(clause);
+        }
+    }
+    clauses
+}
+
+/// Whether `clause` contains some literal along with its negation, making it true in every
+/// model.
+fn is_tautology(clause: &Clause) -> bool {
+    clause
+        .iter()
+        .any(|literal| clause.contains(&literal.negate()))
+}
+
+/// Every clause obtained by resolving `ci` with `cj` on a complementary pair of literals.
+fn pl_resolve(ci: &Clause, cj: &Clause) -> Vec<Clause> {
+    ci.iter()
+        .filter(|literal| cj.contains(&literal.negate()))
+        .map(|literal| {
+            let complement = literal.negate();
+            ci.iter()
+                .filter(|&l| l != literal)
+                .chain(cj.iter().filter(|&l| *l != complement))
+                .cloned()
+                .collect()
+        })
+        .collect()
+}
+
+/// What a run of `pl_resolution_report` found.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ResolutionReport {
+    /// Whether the knowledge base entails the query.
+    pub entailed: bool,
+    /// How many clauses were kept, including the clauses of the knowledge base and the negated
+    /// query, by the time the search stopped.
+    pub clauses: usize,
+}
+
+/// Figure 7.12
+///
+/// Whether `kb` entails `alpha`, decided by resolution. The negation of `alpha` is added to the
+/// clauses of `kb` and resolvents are derived until either the empty clause appears, proving
+/// entailment, or no new clauses can be added.
+///
+/// Redundant clauses are discarded as they appear: tautologies, and any clause that contains
+/// every literal of a clause already kept. See `pl_resolution_report` to compare with the plain
+/// algorithm.
+///
+/// # Examples
+///
+/// ```
+/// # use aima_rust::logic::{expr, pl_resolution};
+/// let kb = expr("(B11 <=> (P12 | P21)) & ~B11");
+///
+/// assert!(pl_resolution(&kb, &expr("~P12")));
+/// assert!(!pl_resolution(&kb, &expr("P12")));
+/// ```
+pub fn pl_resolution(kb: &Expr, alpha: &Expr) -> bool {
+    pl_resolution_report(kb, alpha, true).entailed
+}
+
+/// Decides entailment by resolution like `pl_resolution`, with subsumption and tautology
+/// elimination only if `subsumption` is true, and counts the clauses kept along the way.
+///
+/// Without subsumption, every distinct resolvent is kept, as in figure 7.12, and the clause set
+/// can grow quickly even on small knowledge bases. The answer is the same either way.
+///
+/// # Examples
+///
+/// ```
+/// # use aima_rust::logic::{expr, pl_resolution_report};
+/// let kb = expr("(B11 <=> (P12 | P21)) & ~B11");
+///
+/// let plain = pl_resolution_report(&kb, &expr("~P12"), false);
+/// let pruned = pl_resolution_report(&kb, &expr("~P12"), true);
+/// assert!(plain.entailed && pruned.entailed);
+/// assert!(pruned.clauses * 4 < plain.clauses);
+///
+/// let plain = pl_resolution_report(&kb, &expr("P12"), false);
+/// let pruned = pl_resolution_report(&kb, &expr("P12"), true);
+/// assert!(!plain.entailed && !pruned.entailed);
+/// assert!(pruned.clauses * 4 < plain.clauses);
+/// ```
+pub fn pl_resolution_report(kb: &Expr, alpha: &Expr, subsumption: bool) -> ResolutionReport {
+    let query = Expr::And(vec![kb.clone(), Expr::Not(Box::new(alpha.clone()))]);
+    let mut clauses: Vec<Clause> = Vec::new();
+    for clause in to_clauses(&query) {
+        if subsumption {
+            add_unsubsumed(&mut clauses, clause);
+        } else {
+            clauses.push(clause);
+        }
+    }
+    loop {
+        let mut new: Vec<Clause> = Vec::new();
+        for i in 0..clauses.len() {
+            for j in i + 1..clauses.len() {
+                for resolvent in pl_resolve(&clauses[i], &clauses[j]) {
+                    if resolvent.is_empty() {
+                        let clauses = clauses.len() + new.len() + 1;
+                        return ResolutionReport {
+                            entailed: true,
+                            clauses,
+                        };
+                    }
+                    if !(subsumption && is_tautology(&resolvent)) && !new.contains(&resolvent) {
+                        new.push(resolvent);
+                    }
+                }
+            }
+        }
+        let before = clauses.clone();
+        for clause in new {
+            if subsumption {
+                add_unsubsumed(&mut clauses, clause);
+            } else if !clauses.contains(&clause) {
+                clauses.push(clause);
+            }
+        }
+        if clauses == before {
+            return ResolutionReport {
+                entailed: false,
+                clauses: clauses.len(),
+            };
+        }
+    }
+}
+
+/// Adds `clause` to `clauses` unless it is a tautology or subsumed by a clause already there, and
+/// removes any clauses it subsumes.
+fn add_unsubsumed(clauses: &mut Vec<Clause>, clause: Clause) {
+    if is_tautology(&clause) || clauses.iter().any(|kept| kept.is_subset(&clause)) {
+        return;
+    }
+    clauses.retain(|kept| !clause.is_subset(kept));
+    clauses.push(clause);
+}
+
+/// A literal over symbol indices: symbol `v` is `2 * v` and its negation `2 * v + 1`.
+type Lit = usize;
+
+/// The state of a DPLL search with two watched literals per clause.
+///
+/// Every clause of two or more literals watches its first two; a clause only needs looking at
+/// when one of its watched literals becomes false, at which point it either finds another
+/// literal to watch, becomes unit, or is in conflict. Backtracking never has to touch the
+/// watches.
+struct Dpll {
+    clauses: Vec<Vec<Lit>>,
+    watches: Vec<Vec<usize>>,
+    assignment: Vec<Option<bool>>,
+    /// The number of choices in force when each symbol was assigned.
+    level: Vec<usize>,
+    /// The clause that forced each symbol's value, if it wasn't chosen.
+    reason: Vec<Option<usize>>,
+    trail: Vec<Lit>,
+    propagated: usize,
+    /// The length of the trail before each choice still in force, the literal chosen, and
+    /// whether its other sign has already been tried.
+    choices: Vec<(usize, Lit, bool)>,
+}
+
+impl Dpll {
+    fn value(&self, lit: Lit) -> Option<bool> {
+        self.assignment[lit / 2].map(|value| value == (lit & 1 == 0))
+    }
+
+    fn assign(&mut self, lit: Lit, reason: Option<usize>) {
+        self.assignment[lit / 2] = Some(lit & 1 == 0);
+        self.level[lit / 2] = self.choices.len();
+        self.reason[lit / 2] = reason;
+        self.trail.push(lit);
+    }
+
+    /// Undoes every choice after the first `level`, and everything they forced.
+    fn backjump(&mut self, level: usize) {
+        if let Some(&(length, _, _)) = self.choices.get(level) {
+            for lit in self.trail.drain(length..) {
+                self.assignment[lit / 2] = None;
+            }
+            self.propagated = length;
+            self.choices.truncate(level);
+        }
+    }
+
+    /// Adds a clause, watching its first two literals, and returns its index.
+    fn add_clause(&mut self, clause: Vec<Lit>) -> usize {
+        let c = self.clauses.len();
+        if clause.len() > 1 {
+            self.watches[clause[0]].push(c);
+            self.watches[clause[1]].push(c);
+        }
+        self.clauses.push(clause);
+        c
+    }
+
+    /// Assigns every literal forced by a unit clause, returning the clause in conflict if one
+    /// becomes false.
+    fn propagate(&mut self) -> Option<usize> {
+        while self.propagated < self.trail.len() {
+            let falsified = self.trail[self.propagated] ^ 1;
+            self.propagated += 1;
+            let mut watching = std::mem::take(&mut self.watches[falsified]);
+            let mut i = 0;
+            while i < watching.len() {
+                let c = watching[i];
+                let clause = &mut self.clauses[c];
+                if clause[0] == falsified {
+                    clause.swap(0, 1);
+                }
+                let other = clause[0];
+                if self.assignment[other / 2] == Some(other & 1 == 0) {
+                    i += 1;
+                    continue;
+                }
+                let assignment = &self.assignment;
+                let replacement = (2..clause.len())
+                    .find(|&k| assignment[clause[k] / 2] != Some(clause[k] & 1 == 1));
+                if let Some(k) = replacement {
+                    clause.swap(1, k);
+                    self.watches[clause[1]].push(c);
+                    watching.swap_remove(i);
+                    continue;
+                }
+                if self.value(other) == Some(false) {
+                    self.watches[falsified] = watching;
+                    return Some(c);
+                }
+                self.assign(other, Some(c));
+                i += 1;
+            }
+            self.watches[falsified] = watching;
+        }
+        None
+    }
+
+    /// Works back from a conflict to the first unique implication point, returning a clause
+    /// implied by the others whose first literal is forced once the search jumps back to the
+    /// level returned.
+    fn analyze(&self, conflict: usize, activity: &mut [f64], bump: f64) -> (Vec<Lit>, usize) {
+        let current = self.choices.len();
+        let mut seen = vec![false; self.assignment.len()];
+        let mut learned = vec![0];
+        let mut pending = 0;
+        let mut clause = conflict;
+        let mut position = self.trail.len();
+        loop {
+            for &lit in &self.clauses[clause] {
+                let v = lit / 2;
+                if seen[v] || self.level[v] == 0 || self.value(lit) == Some(true) {
+                    continue;
+                }
+                seen[v] = true;
+                activity[v] += bump;
+                if self.level[v] == current {
+                    pending += 1;
+                } else {
+                    learned.push(lit);
+                }
+            }
+            loop {
+                position -= 1;
+                if seen[self.trail[position] / 2] {
+                    break;
+                }
+            }
+            let implied = self.trail[position];
+            pending -= 1;
+            if pending == 0 {
+                learned[0] = implied ^ 1;
+                break;
+            }
+            clause = self.reason[implied / 2].expect("only the last choice is unexplained");
+        }
+        let deepest = (1..learned.len()).max_by_key(|&i| self.level[learned[i] / 2]);
+        let level = match deepest {
+            Some(i) => {
+                learned.swap(1, i);
+                self.level[learned[1] / 2]
+            }
+            None => 0,
+        };
+        (learned, level)
+    }
+}
+
+/// What a run of `dpll_report` found.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SatReport {
+    /// A model of the sentence, or `None` if it is unsatisfiable.
+    pub model: Option<Model>,
+    /// How many times the search chose a value for a symbol, counting each retry of a choice
+    /// with the other sign.
+    pub decisions: usize,
+    /// How many clauses were learned from conflicts.
+    pub learned_clauses: usize,
+    /// How many times the search started again from the top.
+    pub restarts: usize,
+}
+
+/// Figure 7.17
+///
+/// A model of `sentence`, found by the Davis-Putnam-Logemann-Loveland procedure, or `None` if the
+/// sentence is unsatisfiable.
+///
+/// The search assigns symbols one at a time, most frequent first, trying the sign they most
+/// often appear with, and after every choice propagates unit clauses with two watched literals
+/// per clause, so that the cost of propagation depends on the clauses affected rather than on
+/// every clause. On a conflict it undoes the most recent choice not yet tried both ways.
+///
+/// # Examples
+///
+/// ```
+/// # use aima_rust::logic::{dpll_satisfiable, expr, is_satisfiable, pl_true};
+/// for sentence in &[
+///     "A & B & ~C",
+///     "(A | B) & (~A | C) & (~B | C) & ~C",
+///     "(A <=> B) & (B <=> ~C) & (C | A)",
+///     "(A ==> B) & (B ==> C) & (C ==> ~A) & A",
+///     "(A | B | C) & (~A | ~B) & (~B | ~C) & (~A | ~C)",
+///     "A | ~A",
+/// ] {
+///     let sentence = expr(sentence);
+///     let model = dpll_satisfiable(&sentence);
+///
+///     assert_eq!(model.is_some(), is_satisfiable(&sentence));
+///     if let Some(model) = model {
+///         assert_eq!(pl_true(&sentence, &model), Some(true));
+///     }
+/// }
+/// ```
+///
+/// A random 3-SAT instance with 150 symbols, far too many for truth tables:
+///
+/// ```
+/// # use aima_rust::logic::{dpll_satisfiable, pl_true, Expr};
+/// # use aima_rust::util::Rng;
+/// use rand::Rng as _;
+///
+/// let mut rng = Rng::seeded(7);
+/// let clauses = (0..450)
+///     .map(|_| {
+///         let literals = (0..3)
+///             .map(|_| {
+///                 let symbol = Expr::symbol(&format!("X{}", rng.gen_range(0..150)));
+///                 if rng.gen() { symbol } else { Expr::Not(Box::new(symbol)) }
+///             })
+///             .collect();
+///         Expr::Or(literals)
+///     })
+///     .collect();
+/// let sentence = Expr::And(clauses);
+///
+/// let model = dpll_satisfiable(&sentence).unwrap();
+/// assert_eq!(pl_true(&sentence, &model), Some(true));
+/// ```
+pub fn dpll_satisfiable(sentence: &Expr) -> Option<Model> {
+    dpll_report(sentence, false).model
+}
+
+/// A model of `sentence`, found by conflict-driven clause learning, or `None` if the sentence is
+/// unsatisfiable.
+///
+/// This is DPLL with three changes. Each conflict is traced back through the unit clauses that
+/// caused it to a new clause, which is learned so that the same combination of choices is never
+/// tried again. The search then jumps straight back to the choice that clause depends on, rather
+/// than the most recent one. And every so often it restarts from scratch, keeping what it
+/// learned and preferring the symbols involved in recent conflicts.
+///
+/// # Examples
+///
+/// ```
+/// # use aima_rust::logic::{cdcl_satisfiable, dpll_satisfiable, expr, pl_true};
+/// for sentence in &[
+///     "(A | B) & (~A | C) & (~B | C) & ~C",
+///     "(A <=> B) & (B <=> ~C) & (C | A)",
+///     "(A | B | C) & (~A | ~B) & (~B | ~C) & (~A | ~C)",
+/// ] {
+///     let sentence = expr(sentence);
+///     let model = cdcl_satisfiable(&sentence);
+///
+///     assert_eq!(model.is_some(), dpll_satisfiable(&sentence).is_some());
+///     if let Some(model) = model {
+///         assert_eq!(pl_true(&sentence, &model), Some(true));
+///     }
+/// }
+/// ```
+pub fn cdcl_satisfiable(sentence: &Expr) -> Option<Model> {
+    dpll_report(sentence, true).model
+}
+
+/// Searches for a model of `sentence` like `dpll_satisfiable`, or like `cdcl_satisfiable` if
+/// `learning` is true, and counts the work done.
+///
+/// # Examples
+///
+/// Six pigeons can't sit in five holes, one to a hole. Plain DPLL has to try a great many
+/// seatings to see it; clause learning needs far fewer.
+///
+/// ```
+/// # use aima_rust::logic::{dpll_report, Expr};
+/// let sits = |p: usize, h: usize| Expr::symbol(&format!("P{}H{}", p, h));
+/// let not = |e: Expr| Expr::Not(Box::new(e));
+///
+/// let mut clauses = Vec::new();
+/// for p in 0..6 {
+///     clauses.push(Expr::Or((0..5).map(|h| sits(p, h)).collect()));
+/// }
+/// for h in 0..5 {
+///     for p in 0..6 {
+///         for q in p + 1..6 {
+///             clauses.push(Expr::Or(vec![not(sits(p, h)), not(sits(q, h))]));
+///         }
+///     }
+/// }
+/// let pigeonhole = Expr::And(clauses);
+///
+/// let dpll = dpll_report(&pigeonhole, false);
+/// let cdcl = dpll_report(&pigeonhole, true);
+///
+/// assert!(dpll.model.is_none() && cdcl.model.is_none());
+/// assert_eq!(dpll.learned_clauses, 0);
+/// assert!(cdcl.learned_clauses > 0);
+/// assert!(cdcl.decisions < 1000);
+/// assert!(cdcl.decisions * 2 < dpll.decisions);
+/// ```
+pub fn dpll_report(sentence: &Expr, learning: bool) -> SatReport {
+    let symbols = sentence.symbols();
+    let index: BTreeMap<&str, usize> = symbols
+        .iter()
+        .enumerate()
+        .map(|(i, symbol)| (symbol.as_str(), i))
+        .collect();
+    let mut occurrences = vec![0usize; 2 * symbols.len()];
+    let clauses: Vec<Vec<Lit>> = to_clauses(sentence)
+        .iter()
+        .filter(|clause| !is_tautology(clause))
+        .map(|clause| {
+            clause
+                .iter()
+                .map(|literal| 2 * index[literal.symbol.as_str()] + !literal.positive as usize)
+                .inspect(|&lit| occurrences[lit] += 1)
+                .collect()
+        })
+        .collect();
+
+    let mut report = SatReport {
+        model: None,
+        decisions: 0,
+        learned_clauses: 0,
+        restarts: 0,
+    };
+    let mut dpll = Dpll {
+        clauses: Vec::new(),
+        watches: vec![Vec::new(); 2 * symbols.len()],
+        assignment: vec![None; symbols.len()],
+        level: vec![0; symbols.len()],
+        reason: vec![None; symbols.len()],
+        trail: Vec::new(),
+        propagated: 0,
+        choices: Vec::new(),
+    };
+    for clause in clauses {
+        let c = dpll.add_clause(clause);
+        match dpll.clauses[c][..] {
+            [] => return report,
+            [lit] => match dpll.value(lit) {
+                Some(false) => return report,
+                Some(true) => {}
+                None => dpll.assign(lit, Some(c)),
+            },
+            _ => {}
+        }
+    }
+
+    let mut order: Vec<usize> = (0..symbols.len()).collect();
+    order.sort_by_key(|&v| std::cmp::Reverse(occurrences[2 * v] + occurrences[2 * v + 1]));
+    let mut activity = vec![0.0; symbols.len()];
+    let mut bump = 1.0;
+    let mut conflicts = 0;
+    let mut next_restart = 100;
+    loop {
+        if let Some(conflict) = dpll.propagate() {
+            if dpll.choices.is_empty() {
+                return report;
+            }
+            if learning {
+                let (learned, level) = dpll.analyze(conflict, &mut activity, bump);
+                dpll.backjump(level);
+                let asserting = learned[0];
+                let c = dpll.add_clause(learned);
+                dpll.assign(asserting, Some(c));
+                report.learned_clauses += 1;
+                bump *= 1.05;
+                conflicts += 1;
+                if conflicts == next_restart {
+                    dpll.backjump(0);
+                    report.restarts += 1;
+                    next_restart += next_restart / 2;
+                }
+            } else {
+                while let Some((_, _, true)) = dpll.choices.last() {
+                    dpll.choices.pop();
+                }
+                let (length, lit, _) = match dpll.choices.last() {
+                    Some(&choice) => choice,
+                    None => return report,
+                };
+                dpll.backjump(dpll.choices.len() - 1);
+                dpll.choices.push((length, lit ^ 1, true));
+                dpll.assign(lit ^ 1, None);
+                report.decisions += 1;
+            }
+            continue;
+        }
+        let unassigned = order.iter().filter(|&&v| dpll.assignment[v].is_none());
+        let next = if learning {
+            unassigned.fold(None, |best: Option<usize>, &v| match best {
+                Some(b) if activity[b] >= activity[v] => Some(b),
+                _ => Some(v),
+            })
+        } else {
+            unassigned.cloned().next()
+        };
+        let v = match next {
+            Some(v) => v,
+            None => break,
+        };
+        let lit = if occurrences[2 * v] >= occurrences[2 * v + 1] {
+            2 * v
+        } else {
+            2 * v + 1
+        };
+        dpll.choices.push((dpll.trail.len(), lit, false));
+        dpll.assign(lit, None);
+        report.decisions += 1;
+    }
+
+    let model: Model = symbols
+        .into_iter()
+        .zip(dpll.assignment)
+        .map(|(symbol, value)| (symbol, value.unwrap_or(false)))
+        .collect();
+    debug_assert_eq!(pl_true(sentence, &model), Some(true));
+    report.model = Some(model);
+    report
+}
+
+/// Figure 7.18
+///
+/// A model satisfying every one of `clauses`, found by local search, or `None` if none turned up
+/// within `max_flips` flips. `None` doesn't mean the clauses are unsatisfiable.
+///
+/// WalkSAT starts from a random model and repeatedly picks a clause the model falsifies. With
+/// probability `p` it flips a random symbol of that clause; otherwise it flips whichever of the
+/// clause's symbols leaves the most clauses satisfied. The sentences of `clauses` needn't be
+/// clauses themselves: each is converted to conjunctive normal form first. `seed` fixes the
+/// random choices.
+///
+/// # Examples
+///
+/// ```
+/// # use aima_rust::logic::{expr, pl_true, walk_sat};
+/// let clauses = vec![expr("A | B"), expr("~A | C"), expr("~C"), expr("D <=> A")];
+///
+/// let model = walk_sat(&clauses, 0.5, 1000, 42).unwrap();
+///
+/// assert!(clauses.iter().all(|clause| pl_true(clause, &model) == Some(true)));
+/// // The only model: C is false, so A is, so B must be true and D false.
+/// let values: Vec<bool> = model.values().cloned().collect();
+/// assert_eq!(values, vec![false, true, false, false]);
+///
+/// assert_eq!(walk_sat(&[expr("A | B"), expr("~A"), expr("~B")], 0.5, 1000, 42), None);
+/// ```
+pub fn walk_sat(clauses: &[Expr], p: f64, max_flips: usize, seed: u64) -> Option<Model> {
+    let mut rng = Rng::seeded(seed);
+    let cnf: Vec<Clause> = clauses
+        .iter()
+        .flat_map(to_clauses)
+        .filter(|clause| !is_tautology(clause))
+        .collect();
+    if cnf.iter().any(Clause::is_empty) {
+        return None;
+    }
+    let satisfies = |model: &Model, clause: &Clause| {
+        clause
+            .iter()
+            .any(|literal| model[&literal.symbol] == literal.positive)
+    };
+    let satisfied_after_flip = |model: &mut Model, symbol: &str| {
+        let value = model.get_mut(symbol).unwrap();
+        *value = !*value;
+        let count = cnf.iter().filter(|clause| satisfies(model, clause)).count();
+        let value = model.get_mut(symbol).unwrap();
+        *value = !*value;
+        count
+    };
+
+    let mut model: Model = clauses
+        .iter()
+        .flat_map(Expr::symbols)
+        .map(|symbol| (symbol, rng.gen()))
+        .collect();
+    for _ in 0..=max_flips {
+        let falsified: Vec<&Clause> = cnf
+            .iter()
+            .filter(|clause| !satisfies(&model, clause))
+            .collect();
+        let clause = match falsified.choose(&mut rng) {
+            Some(clause) => clause,
+            None => {
+                assert!(
+                    clauses
+                        .iter()
+                        .all(|clause| pl_true(clause, &model) == Some(true)),
+                    "WalkSAT returned a model that falsifies a clause"
+                );
+                return Some(model);
+            }
+        };
+        let symbols: Vec<&str> = clause.iter().map(|l| l.symbol.as_str()).collect();
+        let symbol = if rng.gen_bool(p) {
+            symbols.choose(&mut rng).unwrap().to_string()
+        } else {
+            symbols
+                .iter()
+                .max_by_key(|symbol| satisfied_after_flip(&mut model, symbol))
+                .unwrap()
+                .to_string()
+        };
+        let value = model.get_mut(&symbol).unwrap();
+        *value = !*value;
+    }
+    None
+}
@@ -0,0 +1,1177 @@
+//! # Markov Decision Processes
+//!
+//! This module covers material in Chapter 17, making complex decisions.
+//!
+//! ## Index of Algorithms
+//!
+//! | **Figure** | **Name**                          | **Module Link**
+//! |:-----------|:----------------------------------|:-------------------------------
+//! | 17         | Markov-Decision-Process           | `MDP`                                                    |
+//! | 17.1       | Sequential-Decision-Environment   | `sequential_decision_environment`                        |
+//! | 17.4       | Value-Iteration                   | `value_iteration`                                        |
+//! | 17         | Q-Values and Greedy Policy        | `q_values`, `greedy_policy`                              |
+//! | 17.4       | Prioritized-Sweeping              | `prioritized_sweeping_value_iteration`                   |
+//! | 17.7       | Policy-Iteration                  | `policy_iteration`                                       |
+//! | 17.7       | Modified-Policy-Iteration         | `modified_policy_iteration`                              |
+//! | 17.7       | Policy-Evaluation                 | `policy_evaluation`                                      |
+//! | 17         | Linear-Programming-MDP            | `lp_solve` (needs the `lp` feature)                      |
+//! | 17         | Cliff-Walking                     | `cliff_walking`                                          |
+//! | 17         | Reward Shaping                    | `ShapedMDP`                                              |
+//!
+use std::cmp::Ordering;
+use std::collections::{BinaryHeap, HashMap, HashSet};
+use std::hash::Hash;
+
+/// A Markov decision process: a fully observable, stochastic environment with a Markovian
+/// transition model and additive rewards.
+///
+/// States without any actions are terminal.
+pub trait MDP {
+    type State: Clone + Eq + Hash;
+    type Action: Clone + Eq + Hash;
+
+    /// The state the agent starts in.
+    fn initial_state(&self) -> Self::State;
+
+    /// Every state of the process.
+    fn states(&self) -> Vec<Self::State>;
+
+    /// The actions available in `state`, or none if the state is terminal.
+    fn actions(&self, state: &Self::State) -> Vec<Self::Action>;
+
+    /// The transition model $P(s' \mid s, a)$, as a list of `(probability, next state)` pairs.
+    fn transition(&self, state: &Self::State, action: &Self::Action) -> Vec<(f64, Self::State)>;
+
+    /// The reward $R(s)$ for being in `state`.
+    fn reward(&self, state: &Self::State) -> f64;
+
+    /// An extra reward $F(s, a, s')$ for reaching `next` by taking `action` in `state`, collected
+    /// along with $R(s)$. With it, the Bellman equation becomes
+    ///
+    /// $$ U(s) = R(s) + \max_a \sum_{s'} P(s' \mid s, a) \left( F(s, a, s') + \gamma U(s') \right) $$
+    ///
+    /// Most processes reward states alone, and have none; `ShapedMDP` uses it to add a shaping
+    /// reward.
+    fn transition_reward(
+        &self,
+        _state: &Self::State,
+        _action: &Self::Action,
+        _next: &Self::State,
+    ) -> f64 {
+        0.0
+    }
+
+    /// The discount factor $\gamma$.
+    fn gamma(&self) -> f64;
+}
+
+/// A policy maps every non-terminal state to the action to take there.
+pub type Policy<M> = HashMap<<M as MDP>::State, <M as MDP>::Action>;
+
+/// A cell of a `GridMDP`, as `(x, y)` with `(0, 0)` in the bottom left corner.
+pub type Cell = (i32, i32);
+
+/// The moves available to an agent in a `GridMDP`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Direction {
+    Up,
+    Down,
+    Left,
+    Right,
+}
+
+impl Direction {
+    fn offset(self) -> Cell {
+        match self {
+            Direction::Up => (0, 1),
+            Direction::Down => (0, -1),
+            Direction::Left => (-1, 0),
+            Direction::Right => (1, 0),
+        }
+    }
+
+    fn perpendicular(self) -> [Direction; 2] {
+        match self {
+            Direction::Up | Direction::Down => [Direction::Left, Direction::Right],
+            Direction::Left | Direction::Right => [Direction::Up, Direction::Down],
+        }
+    }
+}
+
+const DIRECTIONS: [Direction; 4] = [
+    Direction::Up,
+    Direction::Down,
+    Direction::Left,
+    Direction::Right,
+];
+
+/// A two-dimensional grid world like the one in figure 17.1. Each move goes in the intended
+/// direction with probability `1 - noise` and otherwise slips to one of the two perpendicular
+/// directions with equal probability. Moving into a wall or off the grid leaves the agent where
+/// it is.
+///
+/// Besides terminal cells, a grid can have _reset_ cells: landing on one gives its reward and
+/// then any action sends the agent back to the initial cell.
+///
+/// Grids are put together with a `GridMDPBuilder`:
+///
+/// ```
+/// # use aima_rust::mdp::{Direction, GridMDP, MDP};
+/// let mdp = GridMDP::builder(3, 1)
+///     .reward(-0.1)
+///     .terminal((2, 0), 1.0)
+///     .noise(0.0)
+///     .build();
+///
+/// assert_eq!(mdp.transition(&(0, 0), &Direction::Right), vec![(1.0, (1, 0))]);
+/// assert_eq!(mdp.reward(&(2, 0)), 1.0);
+/// assert!(mdp.actions(&(2, 0)).is_empty());
+/// ```
+#[derive(Clone, Debug)]
+pub struct GridMDP {
+    rewards: HashMap<Cell, f64>,
+    terminals: HashSet<Cell>,
+    resets: HashSet<Cell>,
+    init: Cell,
+    gamma: f64,
+    noise: f64,
+}
+
+impl GridMDP {
+    /// Starts building a grid `width` cells wide and `height` cells tall.
+    pub fn builder(width: i32, height: i32) -> GridMDPBuilder {
+        GridMDPBuilder {
+            width,
+            height,
+            reward: 0.0,
+            rewards: HashMap::new(),
+            walls: HashSet::new(),
+            terminals: HashSet::new(),
+            resets: HashSet::new(),
+            init: (0, 0),
+            gamma: 0.9,
+            noise: 0.2,
+        }
+    }
+
+    fn go(&self, cell: Cell, direction: Direction) -> Cell {
+        let (dx, dy) = direction.offset();
+        let next = (cell.0 + dx, cell.1 + dy);
+        if self.rewards.contains_key(&next) {
+            next
+        } else {
+            cell
+        }
+    }
+}
+
+impl MDP for GridMDP {
+    type State = Cell;
+    type Action = Direction;
+
+    fn initial_state(&self) -> Cell {
+        self.init
+    }
+
+    fn states(&self) -> Vec<Cell> {
+        let mut states: Vec<Cell> = self.rewards.keys().cloned().collect();
+        states.sort_unstable();
+        states
+    }
+
+    fn actions(&self, state: &Cell) -> Vec<Direction> {
+        if self.terminals.contains(state) {
+            Vec::new()
+        } else {
+            DIRECTIONS.to_vec()
+        }
+    }
+
+    fn transition(&self, state: &Cell, action: &Direction) -> Vec<(f64, Cell)> {
+        if self.resets.contains(state) {
+            return vec![(1.0, self.init)];
+        }
+        let [left, right] = action.perpendicular();
+        vec![
+            (1.0 - self.noise, self.go(*state, *action)),
+            (self.noise / 2.0, self.go(*state, left)),
+            (self.noise / 2.0, self.go(*state, right)),
+        ]
+        .into_iter()
+        .filter(|&(p, _)| p > 0.0)
+        .collect()
+    }
+
+    fn reward(&self, state: &Cell) -> f64 {
+        self.rewards[state]
+    }
+
+    fn gamma(&self) -> f64 {
+        self.gamma
+    }
+}
+
+/// Builds a `GridMDP`. Every cell not given a reward of its own gets the default `reward`, and
+/// unless changed the grid starts at `(0, 0)` with $\gamma = 0.9$ and a noise of `0.2`.
+#[derive(Clone, Debug)]
+pub struct GridMDPBuilder {
+    width: i32,
+    height: i32,
+    reward: f64,
+    rewards: HashMap<Cell, f64>,
+    walls: HashSet<Cell>,
+    terminals: HashSet<Cell>,
+    resets: HashSet<Cell>,
+    init: Cell,
+    gamma: f64,
+    noise: f64,
+}
+
+impl GridMDPBuilder {
+    /// Sets the default reward for being in a cell.
+    pub fn reward(mut self, reward: f64) -> Self {
+        self.reward = reward;
+        self
+    }
+
+    /// Sets the reward for being in `cell`.
+    pub fn cell_reward(mut self, cell: Cell, reward: f64) -> Self {
+        self.rewards.insert(cell, reward);
+        self
+    }
+
+    /// Blocks `cell` so it can't be entered.
+    pub fn wall(mut self, cell: Cell) -> Self {
+        self.walls.insert(cell);
+        self
+    }
+
+    /// Makes `cell` a terminal state with the given reward.
+    pub fn terminal(mut self, cell: Cell, reward: f64) -> Self {
+        self.terminals.insert(cell);
+        self.cell_reward(cell, reward)
+    }
+
+    /// Makes `cell` a reset state with the given reward: from there, every action leads back to
+    /// the initial cell.
+    pub fn reset(mut self, cell: Cell, reward: f64) -> Self {
+        self.resets.insert(cell);
+        self.cell_reward(cell, reward)
+    }
+
+    /// Sets the initial cell.
+    pub fn init(mut self, cell: Cell) -> Self {
+        self.init = cell;
+        self
+    }
+
+    /// Sets the discount factor.
+    pub fn gamma(mut self, gamma: f64) -> Self {
+        self.gamma = gamma;
+        self
+    }
+
+    /// Sets the probability of slipping sideways instead of moving as intended.
+    pub fn noise(mut self, noise: f64) -> Self {
+        self.noise = noise;
+        self
+    }
+
+    /// Creates the GridMDP.
+    pub fn build(self) -> GridMDP {
+        let mut rewards = HashMap::new();
+        for x in 0..self.width {
+            for y in 0..self.height {
+                if !self.walls.contains(&(x, y)) {
+                    let reward = self.rewards.get(&(x, y)).cloned();
+                    rewards.insert((x, y), reward.unwrap_or(self.reward));
+                }
+            }
+        }
+        GridMDP {
+            rewards,
+            terminals: self.terminals,
+            resets: self.resets,
+            init: self.init,
+            gamma: self.gamma,
+            noise: self.noise,
+        }
+    }
+}
+
+/// Figure 17.1
+///
+/// The 4x3 environment of figure 17.1: a reward of `-0.04` in every non-terminal cell, a wall at
+/// `(1, 1)`, and terminals worth `+1` at `(3, 2)` and `-1` at `(3, 1)`.
+pub fn sequential_decision_environment() -> GridMDP {
+    GridMDP::builder(4, 3)
+        .reward(-0.04)
+        .wall((1, 1))
+        .terminal((3, 2), 1.0)
+        .terminal((3, 1), -1.0)
+        .build()
+}
+
+/// The number of moves on the shortest route from start to goal in `cliff_walking`: one step
+/// up, eleven along the edge of the cliff, and one back down.
+pub const CLIFF_WALKING_OPTIMAL_PATH_LENGTH: usize = 13;
+
+/// The cliff walking grid from Sutton and Barto's _Reinforcement Learning_, often used to show
+/// the difference between on-policy and off-policy learners.
+///
+/// The grid is 12 cells wide and 4 tall. The agent starts in the bottom left corner and the goal
+/// is in the bottom right; the cells between them are a cliff. Every move costs `-1`, and
+/// stepping off the cliff costs `-100` and sends the agent back to the start. Moves are
+/// deterministic.
+///
+/// # Examples
+///
+/// Value iteration finds the shortest route, which runs right along the edge of the cliff:
+///
+/// ```
+/// # use aima_rust::mdp::{best_policy, cliff_walking, value_iteration, CLIFF_WALKING_OPTIMAL_PATH_LENGTH, MDP};
+/// let mdp = cliff_walking();
+/// let policy = best_policy(&mdp, &value_iteration(&mdp, 0.001));
+///
+/// let mut path = vec![mdp.initial_state()];
+/// while let Some(action) = policy.get(path.last().unwrap()) {
+///     let (_, next) = mdp.transition(path.last().unwrap(), action)[0];
+///     path.push(next);
+/// }
+///
+/// assert_eq!(path.len() - 1, CLIFF_WALKING_OPTIMAL_PATH_LENGTH);
+/// assert_eq!(*path.last().unwrap(), (11, 0));
+/// assert!(path[1..path.len() - 1].iter().all(|&(_, y)| y == 1));
+/// ```
+///
+/// Stepping off the edge is costly:
+///
+/// ```
+/// # use aima_rust::mdp::{cliff_walking, Direction, MDP};
+/// let mdp = cliff_walking();
+///
+/// assert_eq!(mdp.transition(&(5, 1), &Direction::Down), vec![(1.0, (5, 0))]);
+/// assert_eq!(mdp.reward(&(5, 0)), -100.0);
+/// assert_eq!(mdp.transition(&(5, 0), &Direction::Right), vec![(1.0, mdp.initial_state())]);
+/// ```
+pub fn cliff_walking() -> GridMDP {
+    let mut builder = GridMDP::builder(12, 4)
+        .reward(-1.0)
+        .terminal((11, 0), 0.0)
+        .noise(0.0);
+    for x in 1..11 {
+        builder = builder.reset((x, 0), -100.0);
+    }
+    builder.build()
+}
+
+/// # Reward Shaping
+///
+/// Wraps an MDP, adding the potential-based shaping reward
+///
+/// $$ F(s, a, s') = \gamma \Phi(s') - \Phi(s) $$
+///
+/// to every transition, for a `potential` function $\Phi$ over states. A good potential, such as
+/// an estimate of how close a state is to the goal, gives the agent a hint after every step
+/// rather than only at the end, which can make learning much faster.
+///
+/// Shaping of this form never changes which policies are optimal (Ng, Harada and Russell, 1999).
+/// Along any run from $s_0$ the shaping rewards telescope to $-\Phi(s_0)$, plus a discounted
+/// $\Phi$ of wherever the run ends, so every policy's utility in each state shifts by the same
+/// amount, $U'(s) = U(s) - \Phi(s)$. The potential of terminal states is taken to be zero, since
+/// otherwise which terminal a run ends in would shift its utility too.
+///
+/// # Examples
+///
+/// Shaping the 4x3 world by the distance to the +1 square leaves the optimal policy as it was:
+///
+/// ```
+/// # use aima_rust::mdp::{best_policy, sequential_decision_environment, value_iteration, Cell, ShapedMDP, MDP};
+/// let mdp = sequential_decision_environment();
+/// let potential = |&(x, y): &Cell| -0.1 * ((3 - x).abs() + (2 - y).abs()) as f64;
+/// let shaped = ShapedMDP::new(mdp.clone(), potential);
+///
+/// let utilities = value_iteration(&mdp, 0.0001);
+/// let shaped_utilities = value_iteration(&shaped, 0.0001);
+/// assert_eq!(best_policy(&shaped, &shaped_utilities), best_policy(&mdp, &utilities));
+///
+/// assert!((shaped_utilities[&(0, 0)] - (utilities[&(0, 0)] - potential(&(0, 0)))).abs() < 0.001);
+/// assert_eq!(shaped.transition_reward(&(2, 2), &aima_rust::mdp::Direction::Right, &(3, 2)), 0.1);
+/// ```
+#[derive(Clone, Debug)]
+pub struct ShapedMDP<M, P> {
+    mdp: M,
+    potential: P,
+}
+
+impl<M: MDP, P: Fn(&M::State) -> f64> ShapedMDP<M, P> {
+    /// Creates a new shaped MDP given the process to shape and the potential function $\Phi$.
+    pub fn new(mdp: M, potential: P) -> Self {
+        ShapedMDP { mdp, potential }
+    }
+
+    /// The process being shaped.
+    pub fn inner(&self) -> &M {
+        &self.mdp
+    }
+
+    /// $\Phi(s)$, which is zero for terminal states.
+    fn phi(&self, state: &M::State) -> f64 {
+        if self.mdp.actions(state).is_empty() {
+            0.0
+        } else {
+            (self.potential)(state)
+        }
+    }
+}
+
+impl<M: MDP, P: Fn(&M::State) -> f64> MDP for ShapedMDP<M, P> {
+    type State = M::State;
+    type Action = M::Action;
+
+    fn initial_state(&self) -> M::State {
+        self.mdp.initial_state()
+    }
+
+    fn states(&self) -> Vec<M::State> {
+        self.mdp.states()
+    }
+
+    fn actions(&self, state: &M::State) -> Vec<M::Action> {
+        self.mdp.actions(state)
+    }
+
+    fn transition(&self, state: &M::State, action: &M::Action) -> Vec<(f64, M::State)> {
+        self.mdp.transition(state, action)
+    }
+
+    fn reward(&self, state: &M::State) -> f64 {
+        self.mdp.reward(state)
+    }
+
+    fn transition_reward(&self, state: &M::State, action: &M::Action, next: &M::State) -> f64 {
+        self.mdp.transition_reward(state, action, next) + self.gamma() * self.phi(next)
+            - self.phi(state)
+    }
+
+    fn gamma(&self) -> f64 {
+        self.mdp.gamma()
+    }
+}
+
+/// The expected value of taking `action` in `state` and continuing with `utilities`, not
+/// counting the reward of `state` itself:
+///
+/// $$ \sum_{s'} P(s' \mid s, a) \left( F(s, a, s') + \gamma U(s') \right) $$
+fn action_value<M: MDP>(
+    mdp: &M,
+    state: &M::State,
+    action: &M::Action,
+    utilities: &HashMap<M::State, f64>,
+) -> f64 {
+    mdp.transition(state, action)
+        .iter()
+        .map(|(p, next)| {
+            p * (mdp.transition_reward(state, action, next) + mdp.gamma() * utilities[next])
+        })
+        .sum()
+}
+
+/// The change in utility below which value iteration may stop with its utilities within `epsilon`
+/// of the true ones: $\epsilon(1 - \gamma)/\gamma$, or just `epsilon` when there is no discount.
+fn stopping_threshold(epsilon: f64, gamma: f64) -> f64 {
+    if gamma < 1.0 {
+        epsilon * (1.0 - gamma) / gamma
+    } else {
+        epsilon
+    }
+}
+
+/// Figure 17.4
+///
+/// Solves an MDP by repeatedly applying the Bellman update
+///
+/// $$ U(s) \leftarrow R(s) + \gamma \max_a \sum_{s'} P(s' \mid s, a) \, U(s') $$
+///
+/// to every state until the largest change in a sweep is less than $\epsilon(1 - \gamma)/\gamma$,
+/// which bounds the error of the returned utilities by `epsilon`. For undiscounted processes
+/// ($\gamma = 1$) iteration stops once the largest change is below `epsilon`.
+///
+/// # Examples
+///
+/// ```
+/// # use aima_rust::mdp::{sequential_decision_environment, value_iteration};
+/// let utilities = value_iteration(&sequential_decision_environment(), 0.001);
+///
+/// assert!((utilities[&(0, 0)] - 0.2960).abs() < 0.001);
+/// assert!((utilities[&(2, 2)] - 0.7954).abs() < 0.001);
+/// assert_eq!(utilities[&(3, 2)], 1.0);
+/// ```
+pub fn value_iteration<M: MDP>(mdp: &M, epsilon: f64) -> HashMap<M::State, f64> {
+    let states = mdp.states();
+    let threshold = stopping_threshold(epsilon, mdp.gamma());
+
+    let mut utilities: HashMap<M::State, f64> = states.iter().map(|s| (s.clone(), 0.0)).collect();
+    loop {
+        let mut next = HashMap::new();
+        let mut delta: f64 = 0.0;
+        for state in &states {
+            let best = greedy_action(mdp, state, &utilities).map_or(0.0, |(_, value)| value);
+            let utility = mdp.reward(state) + best;
+            delta = delta.max((utility - utilities[state]).abs());
+            next.insert(state.clone(), utility);
+        }
+        utilities = next;
+        if delta <= threshold {
+            return utilities;
+        }
+    }
+}
+
+/// The action with the highest `action_value` in `state`, with its value, or `None` if the
+/// state is terminal. Ties go to the action listed first by `MDP::actions`.
+fn greedy_action<M: MDP>(
+    mdp: &M,
+    state: &M::State,
+    utilities: &HashMap<M::State, f64>,
+) -> Option<(M::Action, f64)> {
+    let mut best: Option<(M::Action, f64)> = None;
+    for action in mdp.actions(state) {
+        let value = action_value(mdp, state, &action, utilities);
+        if best.as_ref().is_none_or(|&(_, b)| value > b) {
+            best = Some((action, value));
+        }
+    }
+    best
+}
+
+/// The action values $Q(s, a)$ of every non-terminal state and available action, given the
+/// `utilities` of the states:
+///
+/// $$ Q(s, a) = R(s) + \sum_{s'} P(s' \mid s, a) \left( F(s, a, s') + \gamma U(s') \right) $$
+///
+/// With the utilities from `value_iteration`, $\max_a Q(s, a) = U(s)$ up to its tolerance. These
+/// are the values the reinforcement learning agents of `rl` estimate without a model.
+///
+/// # Examples
+///
+/// ```
+/// # use aima_rust::mdp::{q_values, sequential_decision_environment, value_iteration, Direction};
+/// let mdp = sequential_decision_environment();
+/// let utilities = value_iteration(&mdp, 0.0001);
+/// let q = q_values(&mdp, &utilities);
+///
+/// let best = [Direction::Up, Direction::Down, Direction::Left, Direction::Right]
+///     .iter()
+///     .map(|&a| q[&((0, 0), a)])
+///     .fold(f64::MIN, f64::max);
+/// assert!((best - utilities[&(0, 0)]).abs() < 0.0001);
+/// assert!(q[&((0, 0), Direction::Up)] > q[&((0, 0), Direction::Down)]);
+/// assert!(!q.contains_key(&((3, 2), Direction::Up)));
+/// ```
+pub fn q_values<M: MDP>(
+    mdp: &M,
+    utilities: &HashMap<M::State, f64>,
+) -> HashMap<(M::State, M::Action), f64> {
+    let mut q = HashMap::new();
+    for state in mdp.states() {
+        for action in mdp.actions(&state) {
+            let value = mdp.reward(&state) + action_value(mdp, &state, &action, utilities);
+            q.insert((state.clone(), action), value);
+        }
+    }
+    q
+}
+
+/// Extracts the policy that is greedy with respect to `utilities`: in each non-terminal state,
+/// the action with the highest `q_values` entry. When several actions have exactly the same
+/// value, the one listed first by `MDP::actions` is taken, so the result is deterministic.
+///
+/// # Examples
+///
+/// The greedy policy for the utilities found by value iteration is the one policy iteration
+/// settles on:
+///
+/// ```
+/// # use aima_rust::mdp::{greedy_policy, policy_iteration, sequential_decision_environment, value_iteration, Direction};
+/// let mdp = sequential_decision_environment();
+/// let policy = greedy_policy(&mdp, &value_iteration(&mdp, 0.001));
+///
+/// assert_eq!(policy, policy_iteration(&mdp, 100).policy);
+/// assert_eq!(policy[&(0, 0)], Direction::Up);
+/// assert_eq!(policy[&(2, 2)], Direction::Right);
+/// assert!(!policy.contains_key(&(3, 2)));
+/// ```
+pub fn greedy_policy<M: MDP>(mdp: &M, utilities: &HashMap<M::State, f64>) -> Policy<M> {
+    mdp.states()
+        .into_iter()
+        .filter_map(|state| {
+            let (action, _) = greedy_action(mdp, &state, utilities)?;
+            Some((state, action))
+        })
+        .collect()
+}
+
+/// Figure 17.4
+///
+/// The best policy given the utilities found by value iteration; the same as `greedy_policy`.
+///
+/// # Examples
+///
+/// ```
+/// # use aima_rust::mdp::{best_policy, sequential_decision_environment, value_iteration, Direction};
+/// let mdp = sequential_decision_environment();
+/// let policy = best_policy(&mdp, &value_iteration(&mdp, 0.001));
+///
+/// assert_eq!(policy[&(0, 0)], Direction::Up);
+/// ```
+pub fn best_policy<M: MDP>(mdp: &M, utilities: &HashMap<M::State, f64>) -> Policy<M> {
+    greedy_policy(mdp, utilities)
+}
+
+/// What `prioritized_sweeping_value_iteration` found, and how much work it took.
+pub struct PrioritizedSweepingReport<M: MDP> {
+    pub utilities: HashMap<M::State, f64>,
+    /// The number of Bellman updates applied to single states. Recomputing the errors of
+    /// predecessors takes as much work again for each of them, but isn't counted.
+    pub backups: usize,
+}
+
+/// A queue entry ordered so that `BinaryHeap` pops the state with the largest Bellman error first
+/// and, among equal errors, the state listed first.
+struct BellmanError {
+    error: f64,
+    state: usize,
+}
+
+impl PartialEq for BellmanError {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for BellmanError {}
+
+impl PartialOrd for BellmanError {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for BellmanError {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.error
+            .total_cmp(&other.error)
+            .then_with(|| other.state.cmp(&self.state))
+    }
+}
+
+/// Value iteration with *prioritized sweeping*: instead of updating every state in each sweep,
+/// repeatedly apply the Bellman update to the single state whose Bellman error
+///
+/// $$ \left| R(s) + \gamma \max_a \sum_{s'} P(s' \mid s, a) \, U(s') - U(s) \right| $$
+///
+/// is largest, then recompute the errors of the states that can lead to it, the only ones the
+/// update can affect. Iteration stops once no error reaches $\epsilon(1 - \gamma)/\gamma$, the
+/// same bound `value_iteration` stops at; for undiscounted processes ($\gamma = 1$) it stops once
+/// no error reaches `epsilon`.
+///
+/// Prioritization pays off most on large, sparse MDPs whose rewards are localized: updates spread
+/// outward from the few states where utilities change, and states far from any change are left
+/// alone, where synchronous value iteration would update every state in every sweep. When each
+/// state has many predecessors, keeping their errors current can cost more than it saves.
+///
+/// # Examples
+///
+/// ```
+/// # use aima_rust::mdp::{prioritized_sweeping_value_iteration, sequential_decision_environment, value_iteration, MDP};
+/// let mdp = sequential_decision_environment();
+/// let report = prioritized_sweeping_value_iteration(&mdp, 0.001);
+///
+/// let utilities = value_iteration(&mdp, 0.001);
+/// for state in mdp.states() {
+///     assert!((report.utilities[&state] - utilities[&state]).abs() < 0.001);
+/// }
+///
+/// // Value iteration takes 16 full sweeps over the 11 states to get there.
+/// assert!(report.backups < 16 * 11);
+/// ```
+pub fn prioritized_sweeping_value_iteration<M: MDP>(
+    mdp: &M,
+    epsilon: f64,
+) -> PrioritizedSweepingReport<M> {
+    let states = mdp.states();
+    let index: HashMap<&M::State, usize> = states.iter().enumerate().map(|(i, s)| (s, i)).collect();
+    let threshold = stopping_threshold(epsilon, mdp.gamma());
+
+    let mut predecessors = vec![Vec::new(); states.len()];
+    for (i, state) in states.iter().enumerate() {
+        for action in mdp.actions(state) {
+            for (_, next) in mdp.transition(state, &action) {
+                let j = index[&next];
+                if !predecessors[j].contains(&i) {
+                    predecessors[j].push(i);
+                }
+            }
+        }
+    }
+
+    let mut utilities: HashMap<M::State, f64> = states.iter().map(|s| (s.clone(), 0.0)).collect();
+    let backup = |i: usize, utilities: &HashMap<M::State, f64>| {
+        let best = greedy_action(mdp, &states[i], utilities).map_or(0.0, |(_, value)| value);
+        mdp.reward(&states[i]) + best
+    };
+    let bellman_error = |i: usize, utilities: &HashMap<M::State, f64>| {
+        (backup(i, utilities) - utilities[&states[i]]).abs()
+    };
+
+    let mut errors: Vec<f64> = (0..states.len())
+        .map(|i| bellman_error(i, &utilities))
+        .collect();
+    let mut queue: BinaryHeap<BellmanError> = errors
+        .iter()
+        .enumerate()
+        .map(|(state, &error)| BellmanError { error, state })
+        .collect();
+    let mut backups = 0;
+    while let Some(BellmanError { error, state }) = queue.pop() {
+        if error != errors[state] {
+            continue;
+        }
+        if error < threshold {
+            break;
+        }
+        let utility = backup(state, &utilities);
+        utilities.insert(states[state].clone(), utility);
+        backups += 1;
+        errors[state] = 0.0;
+        for &i in &predecessors[state] {
+            let error = bellman_error(i, &utilities);
+            if error != errors[i] {
+                errors[i] = error;
+                queue.push(BellmanError { error, state: i });
+            }
+        }
+    }
+
+    PrioritizedSweepingReport { utilities, backups }
+}
+
+/// What `policy_iteration` or `modified_policy_iteration` found, and how it got there.
+pub struct PolicyIterationReport<M: MDP> {
+    pub policy: Policy<M>,
+    /// The utilities of `policy`.
+    pub utilities: HashMap<M::State, f64>,
+    /// The number of evaluate-and-improve rounds performed.
+    pub iterations: usize,
+    /// Whether the last round left the policy unchanged. If it didn't, the iteration limit was
+    /// reached first and the policy may not be optimal.
+    pub stabilized: bool,
+    /// The number of Bellman backups performed, counting one for each non-terminal state in each
+    /// evaluation sweep and in each round's improvement step. Solving for the utilities exactly
+    /// takes no backups, nor does checking the exactly evaluated policy for improvement, but the
+    /// solve costs time cubic in the number of states.
+    pub backups: usize,
+}
+
+/// The utilities of following `policy`, found exactly by solving the linear system
+///
+/// $$ U(s) = R(s) + \gamma \sum_{s'} P(s' \mid s, \pi(s)) \, U(s') $$
+///
+/// with Gaussian elimination. States without an action in `policy` keep just their reward.
+fn evaluate_policy_exactly<M: MDP>(mdp: &M, policy: &Policy<M>) -> HashMap<M::State, f64> {
+    let states = mdp.states();
+    let index: HashMap<&M::State, usize> = states.iter().enumerate().map(|(i, s)| (s, i)).collect();
+    let n = states.len();
+    let mut a = vec![vec![0.0; n]; n];
+    let mut b = vec![0.0; n];
+    for (i, state) in states.iter().enumerate() {
+        a[i][i] = 1.0;
+        b[i] = mdp.reward(state);
+        if let Some(action) = policy.get(state) {
+            for (p, next) in mdp.transition(state, action) {
+                a[i][index[&next]] -= mdp.gamma() * p;
+                b[i] += p * mdp.transition_reward(state, action, &next);
+            }
+        }
+    }
+    states.into_iter().zip(solve_linear_system(a, b)).collect()
+}
+
+/// Solves $Ax = b$ by Gaussian elimination with partial pivoting.
+///
+/// # Panics
+///
+/// Panics if $A$ is singular, which for policy evaluation happens only when $\gamma = 1$ and the
+/// policy can keep the agent away from the terminal states forever.
+fn solve_linear_system(mut a: Vec<Vec<f64>>, mut b: Vec<f64>) -> Vec<f64> {
+    let n = b.len();
+    for column in 0..n {
+        let pivot = (column..n)
+            .max_by(|&i, &j| a[i][column].abs().total_cmp(&a[j][column].abs()))
+            .unwrap();
+        assert!(
+            a[pivot][column].abs() > 1e-12,
+            "the linear system is singular"
+        );
+        a.swap(column, pivot);
+        b.swap(column, pivot);
+        let pivot_row = a[column].clone();
+        for row in column + 1..n {
+            let factor = a[row][column] / pivot_row[column];
+            if factor != 0.0 {
+                for (x, p) in a[row][column..].iter_mut().zip(&pivot_row[column..]) {
+                    *x -= factor * p;
+                }
+                b[row] -= factor * b[column];
+            }
+        }
+    }
+    let mut x = vec![0.0; n];
+    for row in (0..n).rev() {
+        let rest: f64 = (row + 1..n).map(|k| a[row][k] * x[k]).sum();
+        x[row] = (b[row] - rest) / a[row][row];
+    }
+    x
+}
+
+/// Figure 17.7
+///
+/// Solves an MDP by alternating two steps, starting from the policy that takes the first listed
+/// action everywhere: *policy evaluation* computes the utilities of the current policy exactly,
+/// and *policy improvement* switches each state to the action with the highest expected utility
+/// under them. When an improvement step changes nothing, the policy is optimal.
+///
+/// Policy iteration usually stabilizes after only a handful of rounds. `max_iterations` guards
+/// against an MDP on which it would not; the report says whether the policy stabilized.
+///
+/// # Examples
+///
+/// ```
+/// # use aima_rust::mdp::{best_policy, policy_iteration, sequential_decision_environment, value_iteration};
+/// let mdp = sequential_decision_environment();
+///
+/// let report = policy_iteration(&mdp, 100);
+/// assert!(report.stabilized);
+/// assert!(report.iterations < 10);
+/// assert_eq!(report.policy, best_policy(&mdp, &value_iteration(&mdp, 0.001)));
+/// assert!((report.utilities[&(0, 0)] - 0.2960).abs() < 0.001);
+///
+/// let cut_short = policy_iteration(&mdp, 1);
+/// assert!(!cut_short.stabilized);
+/// assert_eq!(cut_short.iterations, 1);
+/// ```
+pub fn policy_iteration<M: MDP>(mdp: &M, max_iterations: usize) -> PolicyIterationReport<M> {
+    let mut policy = initial_policy(mdp);
+    let mut iterations = 0;
+    let mut backups = 0;
+    loop {
+        let utilities = evaluate_policy_exactly(mdp, &policy);
+        iterations += 1;
+        backups += policy.len();
+        let unchanged = !improve_policy(mdp, &mut policy, &utilities);
+        if unchanged || iterations >= max_iterations {
+            return PolicyIterationReport {
+                utilities: if unchanged {
+                    utilities
+                } else {
+                    evaluate_policy_exactly(mdp, &policy)
+                },
+                policy,
+                iterations,
+                stabilized: unchanged,
+                backups,
+            };
+        }
+    }
+}
+
+/// The policy that takes the first listed action in every non-terminal state.
+fn initial_policy<M: MDP>(mdp: &M) -> Policy<M> {
+    let mut policy = HashMap::new();
+    for state in mdp.states() {
+        if let Some(action) = mdp.actions(&state).into_iter().next() {
+            policy.insert(state, action);
+        }
+    }
+    policy
+}
+
+/// The policy improvement step: switches each state of `policy` to the action with the highest
+/// expected utility, unless its current action is as good. Returns whether anything changed.
+fn improve_policy<M: MDP>(
+    mdp: &M,
+    policy: &mut Policy<M>,
+    utilities: &HashMap<M::State, f64>,
+) -> bool {
+    let mut changed = false;
+    for state in mdp.states() {
+        let current = match policy.get(&state) {
+            Some(action) => action_value(mdp, &state, action, utilities),
+            None => continue,
+        };
+        if let Some((action, utility)) = greedy_action(mdp, &state, utilities) {
+            if utility > current + 1e-12 {
+                policy.insert(state, action);
+                changed = true;
+            }
+        }
+    }
+    changed
+}
+
+/// Modified policy iteration: policy iteration with the exact evaluation step replaced by `k`
+/// sweeps of the simplified Bellman update
+///
+/// $$ U(s) \leftarrow R(s) + \gamma \sum_{s'} P(s' \mid s, \pi(s)) \, U(s') $$
+///
+/// starting from the utilities of the previous round. Each round is much cheaper than solving the
+/// linear system, at the price of only approximate utilities, so a small `k` tends to take more
+/// rounds to settle on a policy.
+///
+/// An improvement step that changes nothing under approximate utilities doesn't prove the policy
+/// optimal, so before stopping the policy is evaluated exactly and improved once more. Only if
+/// that changes nothing either is the policy reported as `stabilized`; otherwise the rounds go
+/// on from the exact utilities. After `max_iterations` rounds it stops regardless, like
+/// `policy_iteration`, with `stabilized` false. The returned utilities are always the exact
+/// utilities of the returned policy.
+///
+/// # Panics
+///
+/// Panics if `k` is zero: without any evaluation sweeps the utilities would never move from
+/// zero.
+///
+/// # Examples
+///
+/// Even a single sweep per round ends on the policy that `policy_iteration` finds, and with fewer
+/// backups than a thorough evaluation:
+///
+/// ```
+/// # use aima_rust::mdp::{modified_policy_iteration, policy_iteration, sequential_decision_environment};
+/// let mdp = sequential_decision_environment();
+/// let exact = policy_iteration(&mdp, 100);
+///
+/// let thorough = modified_policy_iteration(&mdp, 100, 100);
+/// assert!(thorough.stabilized);
+/// assert_eq!(thorough.policy, exact.policy);
+/// assert!((thorough.utilities[&(0, 0)] - exact.utilities[&(0, 0)]).abs() < 1e-9);
+///
+/// for k in 1..=3 {
+///     let hasty = modified_policy_iteration(&mdp, k, 100);
+///     assert!(hasty.stabilized);
+///     assert_eq!(hasty.policy, exact.policy);
+///     assert!(hasty.backups < thorough.backups);
+/// }
+///
+/// assert!(modified_policy_iteration(&mdp, 1, 100).iterations > thorough.iterations);
+///
+/// let cut_short = modified_policy_iteration(&mdp, 1, 1);
+/// assert!(!cut_short.stabilized);
+/// assert_eq!(cut_short.iterations, 1);
+/// ```
+pub fn modified_policy_iteration<M: MDP>(
+    mdp: &M,
+    k: usize,
+    max_iterations: usize,
+) -> PolicyIterationReport<M> {
+    assert!(
+        k > 0,
+        "modified policy iteration needs at least one sweep per round"
+    );
+    let mut policy = initial_policy(mdp);
+    let mut utilities: HashMap<M::State, f64> =
+        mdp.states().into_iter().map(|s| (s, 0.0)).collect();
+    let mut iterations = 0;
+    let mut backups = 0;
+    loop {
+        for _ in 0..k {
+            utilities = evaluation_sweep(mdp, &policy, &utilities);
+        }
+        iterations += 1;
+        backups += (k + 1) * policy.len();
+        let mut stabilized = false;
+        if !improve_policy(mdp, &mut policy, &utilities) {
+            utilities = evaluate_policy_exactly(mdp, &policy);
+            stabilized = !improve_policy(mdp, &mut policy, &utilities);
+        }
+        if stabilized || iterations >= max_iterations {
+            return PolicyIterationReport {
+                utilities: if stabilized {
+                    utilities
+                } else {
+                    evaluate_policy_exactly(mdp, &policy)
+                },
+                policy,
+                iterations,
+                stabilized,
+                backups,
+            };
+        }
+    }
+}
+
+/// One synchronous sweep of the simplified Bellman update for `policy` over every state.
+fn evaluation_sweep<M: MDP>(
+    mdp: &M,
+    policy: &Policy<M>,
+    utilities: &HashMap<M::State, f64>,
+) -> HashMap<M::State, f64> {
+    mdp.states()
+        .into_iter()
+        .map(|state| {
+            let future = policy
+                .get(&state)
+                .map_or(0.0, |action| action_value(mdp, &state, action, utilities));
+            let utility = mdp.reward(&state) + future;
+            (state, utility)
+        })
+        .collect()
+}
+
+/// How `policy_evaluation` computes the utilities of a policy.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PolicyEvaluation {
+    /// Solve the linear system of the simplified Bellman equations exactly. This takes time cubic
+    /// in the number of states.
+    Exact,
+    /// Apply the given number of sweeps of the simplified Bellman update to every state,
+    /// starting from utilities of zero. The result approaches the exact utilities as the number
+    /// of sweeps grows.
+    Sweeps(usize),
+}
+
+/// Policy evaluation: the utilities of following the fixed `policy` in `mdp`,
+///
+/// $$ U^\pi(s) = R(s) + \gamma \sum_{s'} P(s' \mid s, \pi(s)) \, U^\pi(s') $$
+///
+/// computed as `mode` says. This is the evaluation step of `policy_iteration` and
+/// `modified_policy_iteration`, on its own. States without an action in `policy` are treated as
+/// terminal, and keep just their reward.
+///
+/// # Panics
+///
+/// With `PolicyEvaluation::Exact`, panics if $\gamma = 1$ and the policy can keep the agent away
+/// from the terminal states forever, since its utilities are then unbounded.
+///
+/// # Examples
+///
+/// ```
+/// # use aima_rust::mdp::{best_policy, policy_evaluation, sequential_decision_environment, value_iteration, Direction, PolicyEvaluation, MDP};
+/// let mdp = sequential_decision_environment();
+/// let utilities = value_iteration(&mdp, 0.001);
+/// let optimal = best_policy(&mdp, &utilities);
+///
+/// let exact = policy_evaluation(&optimal, &mdp, PolicyEvaluation::Exact);
+/// let swept = policy_evaluation(&optimal, &mdp, PolicyEvaluation::Sweeps(100));
+/// for state in mdp.states() {
+///     assert!((exact[&state] - utilities[&state]).abs() < 0.001);
+///     assert!((swept[&state] - exact[&state]).abs() < 0.001);
+/// }
+///
+/// // Always heading down, into the wall or the edge of the grid, is no way to reach the +1.
+/// let down = optimal.keys().map(|&state| (state, Direction::Down)).collect();
+/// let bad = policy_evaluation(&down, &mdp, PolicyEvaluation::Exact);
+/// assert!(mdp.states().iter().all(|s| bad[s] <= exact[s]));
+/// assert!(bad[&(0, 0)] < exact[&(0, 0)] - 0.5);
+/// ```
+pub fn policy_evaluation<M: MDP>(
+    policy: &Policy<M>,
+    mdp: &M,
+    mode: PolicyEvaluation,
+) -> HashMap<M::State, f64> {
+    match mode {
+        PolicyEvaluation::Exact => evaluate_policy_exactly(mdp, policy),
+        PolicyEvaluation::Sweeps(k) => {
+            let mut utilities = mdp.states().into_iter().map(|s| (s, 0.0)).collect();
+            for _ in 0..k {
+                utilities = evaluation_sweep(mdp, policy, &utilities);
+            }
+            utilities
+        }
+    }
+}
+
+/// The solution of an MDP found by `lp_solve`.
+#[cfg(feature = "lp")]
+pub struct LpSolution<M: MDP> {
+    pub utilities: HashMap<M::State, f64>,
+    /// The greedy policy for `utilities`, as chosen by `best_policy`.
+    pub policy: Policy<M>,
+}
+
+/// Solves an MDP exactly as a linear program: the utilities are the smallest values satisfying
+/// the Bellman inequalities
+///
+/// $$ U(s) \ge R(s) + \gamma \sum_{s'} P(s' \mid s, a) \, U(s') $$
+///
+/// for every state $s$ and action $a$, with $U(s) = R(s)$ in terminal states. Minimizing
+/// $\sum_s U(s)$ makes the inequality tight for the best action in every state, which is just
+/// the Bellman equation.
+///
+/// Unlike value iteration there is no `epsilon` to choose, and the answer is exact up to
+/// floating-point error. The program has one constraint per state-action pair, though, and the
+/// simplex method scales worse than a few sweeps of value iteration on large MDPs. It fails with
+/// an error only for undiscounted processes whose utilities are unbounded.
+///
+/// This function needs the `lp` feature, which adds a dependency on the `minilp` solver.
+///
+/// # Examples
+///
+/// ```
+/// # use aima_rust::mdp::{best_policy, lp_solve, sequential_decision_environment, value_iteration, MDP};
+/// let mdp = sequential_decision_environment();
+/// let solution = lp_solve(&mdp).unwrap();
+///
+/// let utilities = value_iteration(&mdp, 0.0001);
+/// for state in mdp.states() {
+///     assert!((solution.utilities[&state] - utilities[&state]).abs() < 0.001);
+/// }
+/// assert_eq!(solution.policy, best_policy(&mdp, &utilities));
+/// ```
+#[cfg(feature = "lp")]
+pub fn lp_solve<M: MDP>(mdp: &M) -> Result<LpSolution<M>, minilp::Error> {
+    use minilp::{ComparisonOp, OptimizationDirection, Problem};
+
+    let states = mdp.states();
+    let index: HashMap<&M::State, usize> = states.iter().enumerate().map(|(i, s)| (s, i)).collect();
+    let mut problem = Problem::new(OptimizationDirection::Minimize);
+    let variables: Vec<_> = states
+        .iter()
+        .map(|_| problem.add_var(1.0, (f64::NEG_INFINITY, f64::INFINITY)))
+        .collect();
+
+    for (i, state) in states.iter().enumerate() {
+        let actions = mdp.actions(state);
+        if actions.is_empty() {
+            problem.add_constraint([(variables[i], 1.0)], ComparisonOp::Eq, mdp.reward(state));
+        }
+        for action in actions {
+            // A variable may appear only once in a constraint, so merge repeated successors.
+            let mut coefficients = vec![0.0; states.len()];
+            coefficients[i] = 1.0;
+            let mut reward = mdp.reward(state);
+            for (p, next) in mdp.transition(state, &action) {
+                coefficients[index[&next]] -= mdp.gamma() * p;
+                reward += p * mdp.transition_reward(state, &action, &next);
+            }
+            let terms: Vec<_> = coefficients
+                .into_iter()
+                .enumerate()
+                .filter(|&(_, c)| c != 0.0)
+                .map(|(j, c)| (variables[j], c))
+                .collect();
+            problem.add_constraint(terms, ComparisonOp::Ge, reward);
+        }
+    }
+
+    let solution = problem.solve()?;
+    let utilities: HashMap<M::State, f64> = states
+        .into_iter()
+        .zip(&variables)
+        .map(|(state, &variable)| (state, *solution.var_value(variable)))
+        .collect();
+    Ok(LpSolution {
+        policy: best_policy(mdp, &utilities),
+        utilities,
+    })
+}
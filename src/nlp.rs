@@ -0,0 +1,1476 @@
+//! # Natural Language Processing
+//!
+//! This module covers material in Chapters 22 & 23.
+//!
+//! ## Index of Algorithms
+//!
+//! | **Figure** | **Name**                          | **Module Link**
+//! |:-----------|:----------------------------------|:-------------------------------
+//! | 22         | Tokenizer                         | `words`                                                  |
+//! | 22         | N-Gram-Model                      | `NGramModel`                                             |
+//! | 22         | PageRank                          | `page_rank`                                              |
+//! | 22         | TF-IDF-Ranking                    | `TfIdf`                                                  |
+//! | 22         | Edit-Distance                     | `edit_distance`                                          |
+//! | 23         | Context-Free Grammar              | `Grammar`                                                |
+//! | 23.5       | Chart-Parse                       | `Chart`                                                  |
+//! | 23         | CYK-Parse                         | `most_probable_parse`                                    |
+//! | 23.2       | Probabilistic E0                  | `e0`                                                     |
+//! | 23         | Definite Clause Grammar           | `dcg`                                                    |
+//!
+use crate::util::Rng;
+use rand::distributions::{Distribution, WeightedIndex};
+use rand::seq::SliceRandom;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fmt;
+use std::hash::Hash;
+
+/// The token standing in for words that never appeared in the training corpus.
+pub const UNKNOWN: &str = "<UNK>";
+
+/// Splits text into lowercase words, treating anything that isn't a letter, digit, or apostrophe
+/// as a separator.
+///
+/// # Examples
+///
+/// ```
+/// # use aima_rust::nlp::words;
+/// assert_eq!(words("The cat's hat. It's RED!"), vec!["the", "cat's", "hat", "it's", "red"]);
+/// ```
+pub fn words(text: &str) -> Vec<String> {
+    text.split(|c: char| !(c.is_alphanumeric() || c == '\''))
+        .filter(|word| !word.is_empty())
+        .map(|word| word.to_lowercase())
+        .collect()
+}
+
+/// # N-Gram Model
+///
+/// A language model assigns a probability to a sequence of words. The bigram model approximates
+/// it by conditioning each word only on the one before:
+///
+/// $$ P(w_1 \ldots w_n) \approx P(w_1) \prod_{i=2}^n P(w_i \mid w_{i-1}) $$
+///
+/// Both the unigram and bigram probabilities are estimated from counts in a training corpus
+/// with add-one (Laplace) smoothing, so unseen word pairs still get a small probability. Words
+/// that never appeared in the corpus are treated as the single `<UNK>` token.
+///
+/// # Examples
+///
+/// ```
+/// # use aima_rust::nlp::NGramModel;
+/// let model = NGramModel::new("the cat sat on the mat. the dog sat on the log.");
+///
+/// assert!(model.probability("the cat sat") > model.probability("mat the on"));
+/// assert!(model.probability("the zebra sat") > 0.0);
+///
+/// let sentence = model.generate(5, 42);
+/// assert_eq!(sentence.len(), 5);
+/// assert_eq!(sentence, model.generate(5, 42));
+/// ```
+#[derive(Clone, Debug)]
+pub struct NGramModel {
+    unigrams: BTreeMap<String, usize>,
+    bigrams: BTreeMap<String, BTreeMap<String, usize>>,
+    total: usize,
+}
+
+impl NGramModel {
+    /// Creates a new NGramModel from the word counts of `corpus`.
+    pub fn new(corpus: &str) -> Self {
+        let tokens = words(corpus);
+        let mut unigrams = BTreeMap::new();
+        let mut bigrams: BTreeMap<String, BTreeMap<String, usize>> = BTreeMap::new();
+        for token in &tokens {
+            *unigrams.entry(token.clone()).or_insert(0) += 1;
+        }
+        for pair in tokens.windows(2) {
+            *bigrams
+                .entry(pair[0].clone())
+                .or_default()
+                .entry(pair[1].clone())
+                .or_insert(0) += 1;
+        }
+        NGramModel {
+            unigrams,
+            bigrams,
+            total: tokens.len(),
+        }
+    }
+
+    /// The number of distinct words known to the model, including `<UNK>`.
+    pub fn vocabulary_size(&self) -> usize {
+        self.unigrams.len() + 1
+    }
+
+    /// Maps `word` to itself if it was seen in training, or to `<UNK>` otherwise.
+    pub fn token<'a>(&self, word: &'a str) -> &'a str {
+        if self.unigrams.contains_key(word) {
+            word
+        } else {
+            UNKNOWN
+        }
+    }
+
+    /// The smoothed probability $P(w)$ of a single word.
+    pub fn unigram_probability(&self, word: &str) -> f64 {
+        let count = self.unigrams.get(self.token(word)).cloned().unwrap_or(0);
+        (count + 1) as f64 / (self.total + self.vocabulary_size()) as f64
+    }
+
+    /// The smoothed probability $P(w \mid previous)$ of `word` following `previous`.
+    pub fn bigram_probability(&self, previous: &str, word: &str) -> f64 {
+        let (previous, word) = (self.token(previous), self.token(word));
+        let count = self
+            .bigrams
+            .get(previous)
+            .and_then(|next| next.get(word))
+            .cloned()
+            .unwrap_or(0);
+        let context = self.unigrams.get(previous).cloned().unwrap_or(0);
+        (count + 1) as f64 / (context + self.vocabulary_size()) as f64
+    }
+
+    /// The probability of `sentence` under the bigram model. The sentence is tokenized with
+    /// `words`; the empty sentence has probability `1`.
+    pub fn probability(&self, sentence: &str) -> f64 {
+        let tokens = words(sentence);
+        match tokens.first() {
+            None => 1.0,
+            Some(first) => tokens
+                .windows(2)
+                .fold(self.unigram_probability(first), |p, pair| {
+                    p * self.bigram_probability(&pair[0], &pair[1])
+                }),
+        }
+    }
+
+    /// Generates `n` words by sampling the first from the unigram counts and each following word
+    /// from the bigram counts of the word before it. A word that was never followed by anything
+    /// in the corpus is followed by a fresh unigram sample. The same seed always generates the
+    /// same words.
+    pub fn generate(&self, n: usize, seed: u64) -> Vec<String> {
+        let mut rng = Rng::seeded(seed);
+        let mut output: Vec<String> = Vec::with_capacity(n);
+        while output.len() < n && self.total > 0 {
+            let counts = output
+                .last()
+                .and_then(|previous| self.bigrams.get(previous))
+                .unwrap_or(&self.unigrams);
+            let choices: Vec<(&String, &usize)> = counts.iter().collect();
+            let index = WeightedIndex::new(choices.iter().map(|&(_, &count)| count))
+                .expect("every counted word has a positive count")
+                .sample(&mut rng);
+            output.push(choices[index].0.clone());
+        }
+        output
+    }
+
+    /// Decodes the most probable sentence satisfying `constraints` by beam search. Sentences are
+    /// built a word at a time; after each step only the `beam_width` most probable partial
+    /// sentences are kept, and those that can no longer fit the missing required words are
+    /// dropped. A beam of width `1` is greedy decoding, always taking the likeliest next word.
+    ///
+    /// Returns the final beam, best first, as each sentence with its log probability. Candidates
+    /// with equal scores are ordered by a shuffle drawn from `seed`. The beam is empty if no
+    /// sentence satisfies the constraints.
+    ///
+    /// # Examples
+    ///
+    /// "a" is the most common word, but what follows it is spread over four words, while "b" is
+    /// always followed by "c". Greedy decoding commits to "a" and misses "b c":
+    ///
+    /// ```
+    /// # use aima_rust::nlp::{Constraints, NGramModel};
+    /// let model = NGramModel::new("a x. a y. a z. a w. b c. b c. b c.");
+    /// let two_words = Constraints::length(2);
+    ///
+    /// let greedy = model.decode_beam(&two_words, 1, 7);
+    /// assert_eq!(greedy.len(), 1);
+    /// assert_eq!(greedy[0].0[0], "a");
+    ///
+    /// let beam = model.decode_beam(&two_words, 3, 7);
+    /// assert_eq!(beam[0].0, vec!["b", "c"]);
+    /// assert!(beam[0].1 > greedy[0].1);
+    /// assert!((beam[0].1.exp() - model.probability("b c")).abs() < 1e-12);
+    /// assert!(beam.windows(2).all(|pair| pair[0].1 >= pair[1].1));
+    ///
+    /// let with_w = model.decode_beam(&Constraints::length(3).require("w"), 3, 7);
+    /// assert_eq!(with_w[0].0, vec!["b", "c", "w"]);
+    /// ```
+    ///
+    /// With a beam of width 1 the decoder takes the likeliest word at every step:
+    ///
+    /// ```
+    /// # use aima_rust::nlp::{Constraints, NGramModel};
+    /// let model = NGramModel::new("the cat sat on the mat. the cat sat on the log. the dog ran.");
+    ///
+    /// let mut greedy = vec!["the".to_string()];
+    /// while greedy.len() < 4 {
+    ///     let previous = greedy.last().unwrap().clone();
+    ///     let next = ["cat", "dog", "log", "mat", "on", "ran", "sat", "the"]
+    ///         .iter()
+    ///         .max_by(|a, b| {
+    ///             let pa = model.bigram_probability(&previous, a);
+    ///             pa.partial_cmp(&model.bigram_probability(&previous, b)).unwrap()
+    ///         })
+    ///         .unwrap();
+    ///     greedy.push(next.to_string());
+    /// }
+    ///
+    /// assert_eq!(greedy, vec!["the", "cat", "sat", "on"]);
+    /// assert_eq!(model.decode_beam(&Constraints::length(4), 1, 0)[0].0, greedy);
+    /// ```
+    pub fn decode_beam(
+        &self,
+        constraints: &Constraints,
+        beam_width: usize,
+        seed: u64,
+    ) -> Vec<(Vec<String>, f64)> {
+        let mut rng = Rng::seeded(seed);
+        let missing = |sentence: &[String]| {
+            constraints
+                .required
+                .iter()
+                .filter(|word| !sentence.contains(word))
+                .count()
+        };
+        let mut beam: Vec<(Vec<String>, f64)> = vec![(Vec::new(), 0.0)];
+        for step in 0..constraints.length {
+            let remaining = constraints.length - step - 1;
+            let mut candidates: Vec<(Vec<String>, f64)> = Vec::new();
+            for (sentence, score) in &beam {
+                for word in self.unigrams.keys() {
+                    let p = match sentence.last() {
+                        None => self.unigram_probability(word),
+                        Some(previous) => self.bigram_probability(previous, word),
+                    };
+                    let mut next = sentence.clone();
+                    next.push(word.clone());
+                    if missing(&next) <= remaining {
+                        candidates.push((next, score + p.ln()));
+                    }
+                }
+            }
+            candidates.shuffle(&mut rng);
+            candidates.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap());
+            candidates.truncate(beam_width);
+            beam = candidates;
+        }
+        beam
+    }
+}
+
+/// Constraints on the sentences decoded by `NGramModel::decode_beam`: their length, and words
+/// they must contain.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Constraints {
+    length: usize,
+    required: Vec<String>,
+}
+
+impl Constraints {
+    /// Creates a new set of constraints on sentences of exactly `length` words.
+    pub fn length(length: usize) -> Self {
+        Constraints {
+            length,
+            required: Vec::new(),
+        }
+    }
+
+    /// Requires `word` to appear somewhere in the sentence.
+    pub fn require(mut self, word: &str) -> Self {
+        self.required.push(word.to_string());
+        self
+    }
+}
+
+/// # PageRank
+///
+/// PageRank scores a page by how likely a random surfer is to be on it. At every step the surfer
+/// follows one of the current page's links at random with probability `damping`, and otherwise
+/// jumps to a page chosen uniformly at random:
+///
+/// $$ PR(p) = \frac{1 - d}{N} + d \sum_{q \to p} \frac{PR(q)}{C(q)} $$
+///
+/// where $C(q)$ is the number of links out of $q$. A _dangling_ page, one without any outlinks,
+/// would leak probability out of the system, so its rank is spread evenly over every page
+/// instead. The ranks therefore always sum to `1`.
+///
+/// `graph` maps each page to the pages it links to. Pages that only appear as link targets are
+/// included in the result. The ranks start uniform and are updated `iterations` times; see
+/// `page_rank_until` to stop early once they settle.
+///
+/// # Examples
+///
+/// ```
+/// # use aima_rust::nlp::page_rank;
+/// # use std::collections::HashMap;
+/// let mut graph = HashMap::new();
+/// graph.insert("home", vec!["about"]);
+/// graph.insert("about", vec!["home"]);
+/// graph.insert("blog", vec!["home", "about"]);
+/// graph.insert("shop", vec!["home"]);
+///
+/// let ranks = page_rank(&graph, 0.85, 50);
+///
+/// let top = ranks.iter().max_by(|a, b| a.1.partial_cmp(b.1).unwrap()).unwrap();
+/// assert_eq!(*top.0, "home");
+/// assert!((ranks.values().sum::<f64>() - 1.0).abs() < 1e-9);
+/// ```
+pub fn page_rank<P: Clone + Eq + Hash>(
+    graph: &HashMap<P, Vec<P>>,
+    damping: f64,
+    iterations: usize,
+) -> HashMap<P, f64> {
+    page_rank_until(graph, damping, iterations, 0.0).0
+}
+
+/// Runs `page_rank` for at most `max_iterations`, stopping early once an update changes the
+/// ranks by less than `tolerance` in total (the L1 distance between successive rank vectors).
+/// Returns the ranks together with the change made by the final update, so callers can tell
+/// whether the ranks converged.
+///
+/// # Examples
+///
+/// ```
+/// # use aima_rust::nlp::page_rank_until;
+/// # use std::collections::HashMap;
+/// let mut graph = HashMap::new();
+/// graph.insert('a', vec!['b', 'c']);
+/// graph.insert('b', vec!['c']);
+/// graph.insert('c', vec!['a']);
+/// graph.insert('d', vec![]);
+///
+/// let (ranks, delta) = page_rank_until(&graph, 0.85, 1000, 1e-10);
+/// assert!(delta < 1e-10);
+/// assert!(ranks[&'c'] > ranks[&'d']);
+/// ```
+pub fn page_rank_until<P: Clone + Eq + Hash>(
+    graph: &HashMap<P, Vec<P>>,
+    damping: f64,
+    max_iterations: usize,
+    tolerance: f64,
+) -> (HashMap<P, f64>, f64) {
+    let mut pages: Vec<&P> = Vec::new();
+    let mut seen = HashSet::new();
+    for (page, links) in graph {
+        for p in std::iter::once(page).chain(links) {
+            if seen.insert(p) {
+                pages.push(p);
+            }
+        }
+    }
+    let n = pages.len() as f64;
+    let mut ranks: HashMap<P, f64> = pages.iter().map(|&p| (p.clone(), 1.0 / n)).collect();
+    let mut delta = f64::INFINITY;
+
+    for _ in 0..max_iterations {
+        let mut dangling = 0.0;
+        let mut next: HashMap<P, f64> = pages.iter().map(|&p| (p.clone(), 0.0)).collect();
+        for &page in &pages {
+            match graph.get(page).filter(|links| !links.is_empty()) {
+                Some(links) => {
+                    let share = ranks[page] / links.len() as f64;
+                    for link in links {
+                        *next.get_mut(link).unwrap() += share;
+                    }
+                }
+                None => dangling += ranks[page],
+            }
+        }
+        for rank in next.values_mut() {
+            *rank = (1.0 - damping) / n + damping * (*rank + dangling / n);
+        }
+        delta = pages.iter().map(|&p| (next[p] - ranks[p]).abs()).sum();
+        ranks = next;
+        if delta < tolerance {
+            break;
+        }
+    }
+    (ranks, delta)
+}
+
+/// Identifies a document by its position in the collection given to `TfIdf::new`.
+pub type DocId = usize;
+
+/// # TF-IDF Ranking
+///
+/// An information retrieval system answers a query with the documents most relevant to it. TF-IDF
+/// weighs each term of a document by how often it appears there (the _term frequency_) and by
+/// how rare it is across the whole collection (the _inverse document frequency_):
+///
+/// $$ \textrm{tfidf}(t, d) = \textrm{tf}(t, d) \cdot \log \frac{N}{\textrm{df}(t)} $$
+///
+/// A term that appears in every document has weight zero, since it can't tell documents apart.
+/// Documents are ranked by the cosine similarity between their weight vector and the query's.
+///
+/// # Examples
+///
+/// ```
+/// # use aima_rust::nlp::TfIdf;
+/// let index = TfIdf::new(&[
+///     "the quick brown fox",
+///     "the lazy dog sleeps",
+///     "the fox and the dog",
+///     "",
+/// ]);
+///
+/// // Only the two documents mentioning the fox match.
+/// let mut matches: Vec<_> = index.rank("fox").iter().map(|&(doc, _)| doc).collect();
+/// matches.sort();
+/// assert_eq!(matches, vec![0, 2]);
+///
+/// // "sleeps" appears in one document and "the" in three, so "sleeps" decides the ranking.
+/// assert_eq!(index.rank("the sleeps")[0].0, 1);
+///
+/// assert!(index.rank("").is_empty());
+/// ```
+#[derive(Clone, Debug)]
+pub struct TfIdf {
+    idf: HashMap<String, f64>,
+    documents: Vec<HashMap<String, f64>>,
+}
+
+impl TfIdf {
+    /// Indexes a collection of documents.
+    pub fn new<S: AsRef<str>>(documents: &[S]) -> Self {
+        let counts: Vec<HashMap<String, f64>> = documents
+            .iter()
+            .map(|document| term_counts(document.as_ref()))
+            .collect();
+
+        let mut df: HashMap<String, usize> = HashMap::new();
+        for terms in &counts {
+            for term in terms.keys() {
+                *df.entry(term.clone()).or_insert(0) += 1;
+            }
+        }
+        let n = documents.len() as f64;
+        let idf: HashMap<String, f64> = df
+            .into_iter()
+            .map(|(term, df)| (term, (n / df as f64).ln()))
+            .collect();
+
+        let documents = counts.into_iter().map(|terms| weigh(terms, &idf)).collect();
+        TfIdf { idf, documents }
+    }
+
+    /// The inverse document frequency of `term`, or `None` if no document contains it.
+    pub fn idf(&self, term: &str) -> Option<f64> {
+        self.idf.get(term).cloned()
+    }
+
+    /// Scores every document against `query` and returns those with a positive score, most
+    /// relevant first. Ties are broken by document order. A query without any known,
+    /// discriminating terms matches nothing.
+    pub fn rank(&self, query: &str) -> Vec<(DocId, f64)> {
+        let query = weigh(term_counts(query), &self.idf);
+        let mut ranking: Vec<(DocId, f64)> = self
+            .documents
+            .iter()
+            .map(|document| cosine_similarity(&query, document))
+            .enumerate()
+            .filter(|&(_, score)| score > 0.0)
+            .collect();
+        ranking.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap().then(a.0.cmp(&b.0)));
+        ranking
+    }
+}
+
+fn term_counts(text: &str) -> HashMap<String, f64> {
+    let mut counts = HashMap::new();
+    for word in words(text) {
+        *counts.entry(word).or_insert(0.0) += 1.0;
+    }
+    counts
+}
+
+fn weigh(counts: HashMap<String, f64>, idf: &HashMap<String, f64>) -> HashMap<String, f64> {
+    counts
+        .into_iter()
+        .filter_map(|(term, tf)| idf.get(&term).map(|idf| (term, tf * idf)))
+        .collect()
+}
+
+fn cosine_similarity(a: &HashMap<String, f64>, b: &HashMap<String, f64>) -> f64 {
+    let dot: f64 = a
+        .iter()
+        .filter_map(|(term, x)| b.get(term).map(|y| x * y))
+        .sum();
+    let norm = |v: &HashMap<String, f64>| v.values().map(|x| x * x).sum::<f64>().sqrt();
+    let (na, nb) = (norm(a), norm(b));
+    if na == 0.0 || nb == 0.0 {
+        0.0
+    } else {
+        dot / (na * nb)
+    }
+}
+
+/// The Levenshtein distance between `a` and `b`: the fewest single-character insertions,
+/// deletions, and substitutions that turn one into the other. It is computed with the standard
+/// dynamic programming table, one row at a time.
+///
+/// Swapping two adjacent characters is not a single edit here, so a transposition costs `2`.
+///
+/// # Examples
+///
+/// ```
+/// # use aima_rust::nlp::edit_distance;
+/// assert_eq!(edit_distance("kitten", "sitting"), 3);
+/// assert_eq!(edit_distance("form", "from"), 2);
+/// assert_eq!(edit_distance("spelling", "speling"), 1);
+/// assert_eq!(edit_distance("cat", "cart"), 1);
+/// assert_eq!(edit_distance("", "abc"), 3);
+/// ```
+pub fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut current = vec![i + 1; b.len() + 1];
+        for (j, &cb) in b.iter().enumerate() {
+            let substitution = previous[j] + if ca == cb { 0 } else { 1 };
+            current[j + 1] = substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+        }
+        previous = current;
+    }
+    previous[b.len()]
+}
+
+/// Corrects the spelling of `word` to the closest word in `dictionary`, as long as it is at most
+/// `max_distance` edits away. Ties go to the word that comes first in the dictionary, and a word
+/// that is already in the dictionary is returned unchanged.
+///
+/// # Examples
+///
+/// ```
+/// # use aima_rust::nlp::correct;
+/// let dictionary = ["spelling", "spell", "smelling", "selling"];
+///
+/// assert_eq!(correct("speling", &dictionary, 2), Some("spelling".to_string()));
+/// assert_eq!(correct("xylophone", &dictionary, 2), None);
+/// ```
+pub fn correct<S: AsRef<str>>(word: &str, dictionary: &[S], max_distance: usize) -> Option<String> {
+    let mut best: Option<(&str, usize)> = None;
+    for candidate in dictionary.iter().map(|s| s.as_ref()) {
+        let distance = edit_distance(word, candidate);
+        if distance <= max_distance && best.is_none_or(|(_, d)| distance < d) {
+            best = Some((candidate, distance));
+        }
+    }
+    best.map(|(candidate, _)| candidate.to_string())
+}
+
+/// A parse tree: a word labeled with its lexical category, or a phrase labeled with its
+/// category and made of sub-phrases. It prints in bracketed form, such as
+/// `[NP [Det the] [N cat]]`.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Tree {
+    Leaf(String, String),
+    Node(String, Vec<Tree>),
+}
+
+impl Tree {
+    /// The category at the root of the tree.
+    pub fn label(&self) -> &str {
+        match self {
+            Tree::Leaf(category, _) | Tree::Node(category, _) => category,
+        }
+    }
+}
+
+impl fmt::Display for Tree {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Tree::Leaf(category, word) => write!(f, "[{} {}]", category, word),
+            Tree::Node(category, children) => {
+                write!(f, "[{}", category)?;
+                for child in children {
+                    write!(f, " {}", child)?;
+                }
+                write!(f, "]")
+            }
+        }
+    }
+}
+
+/// Builds the meaning of a phrase from the meanings of its constituents, in order.
+pub type Compose<M> = fn(&[M]) -> M;
+
+/// A rule of a context-free `Grammar`, rewriting the category `lhs` as the sequence of
+/// categories `rhs`.
+#[derive(Clone, Debug)]
+pub enum Rule<M> {
+    /// A rule with no semantics. The phrase it builds takes the meaning of its first constituent
+    /// that has one, so a rule like `NP -> Name` passes the meaning of the name through.
+    Syntactic { lhs: String, rhs: Vec<String> },
+    /// A rule with a semantic attachment: the meaning of the phrase is `compose` applied to the
+    /// meanings of its constituents. If any of them has no meaning, neither does the phrase.
+    Semantic {
+        lhs: String,
+        rhs: Vec<String>,
+        compose: Compose<M>,
+    },
+}
+
+impl<M> Rule<M> {
+    fn lhs(&self) -> &str {
+        match self {
+            Rule::Syntactic { lhs, .. } | Rule::Semantic { lhs, .. } => lhs,
+        }
+    }
+
+    fn rhs(&self) -> &[String] {
+        match self {
+            Rule::Syntactic { rhs, .. } | Rule::Semantic { rhs, .. } => rhs,
+        }
+    }
+}
+
+impl<M: Clone> Rule<M> {
+    fn meaning(&self, constituents: &[Option<M>]) -> Option<M> {
+        match self {
+            Rule::Syntactic { .. } => constituents.iter().flatten().next().cloned(),
+            Rule::Semantic { compose, .. } => {
+                let meanings: Option<Vec<M>> = constituents.iter().cloned().collect();
+                meanings.map(|meanings| compose(&meanings))
+            }
+        }
+    }
+}
+
+/// A context-free grammar with a lexicon, whose rules and words may carry meanings of type `M`.
+/// Right-hand sides are written as categories separated by spaces.
+///
+/// Rules and words can also be given probabilities, making the grammar a probabilistic
+/// context-free grammar for `most_probable_parse`. Those added without one count as certain.
+///
+/// ```
+/// # use aima_rust::nlp::Grammar;
+/// let grammar: Grammar<()> = Grammar::new()
+///     .rule("S", "NP VP")
+///     .rule("NP", "Name")
+///     .rule("VP", "Verb")
+///     .word("Name", "john")
+///     .word("Verb", "sleeps");
+///
+/// assert_eq!(grammar.categories("john"), vec!["Name"]);
+/// ```
+#[derive(Clone, Debug)]
+pub struct Grammar<M> {
+    rules: Vec<Rule<M>>,
+    lexicon: Vec<(String, String, Option<M>)>,
+    /// The probability of each rule, in the same order as `rules`.
+    rule_probabilities: Vec<f64>,
+    /// The probability of each word given its category, in the same order as `lexicon`.
+    word_probabilities: Vec<f64>,
+}
+
+impl<M: Clone> Default for Grammar<M> {
+    fn default() -> Self {
+        Grammar::new()
+    }
+}
+
+impl<M: Clone> Grammar<M> {
+    /// Creates a new grammar with no rules or words.
+    pub fn new() -> Self {
+        Grammar {
+            rules: Vec::new(),
+            lexicon: Vec::new(),
+            rule_probabilities: Vec::new(),
+            word_probabilities: Vec::new(),
+        }
+    }
+
+    /// Adds the rule `lhs -> rhs`, without semantics.
+    pub fn rule(self, lhs: &str, rhs: &str) -> Self {
+        self.probabilistic_rule(lhs, rhs, 1.0)
+    }
+
+    /// Adds the rule `lhs -> rhs`, without semantics, chosen with probability `p` when
+    /// rewriting `lhs`.
+    pub fn probabilistic_rule(mut self, lhs: &str, rhs: &str, p: f64) -> Self {
+        self.rules.push(Rule::Syntactic {
+            lhs: lhs.to_string(),
+            rhs: rhs.split_whitespace().map(String::from).collect(),
+        });
+        self.rule_probabilities.push(p);
+        self
+    }
+
+    /// Adds the rule `lhs -> rhs`, whose meaning is built by `compose`.
+    pub fn semantic_rule(mut self, lhs: &str, rhs: &str, compose: Compose<M>) -> Self {
+        self.rules.push(Rule::Semantic {
+            lhs: lhs.to_string(),
+            rhs: rhs.split_whitespace().map(String::from).collect(),
+            compose,
+        });
+        self.rule_probabilities.push(1.0);
+        self
+    }
+
+    /// Adds `word` to the lexicon as a member of `category`, with no meaning.
+    pub fn word(self, category: &str, word: &str) -> Self {
+        self.probabilistic_word(category, word, 1.0)
+    }
+
+    /// Adds `word` to the lexicon as a member of `category`, with no meaning, chosen with
+    /// probability `p` when rewriting `category` as a word.
+    pub fn probabilistic_word(mut self, category: &str, word: &str, p: f64) -> Self {
+        self.lexicon
+            .push((word.to_string(), category.to_string(), None));
+        self.word_probabilities.push(p);
+        self
+    }
+
+    /// Adds `word` to the lexicon as a member of `category` with the given meaning.
+    pub fn word_with_meaning(mut self, category: &str, word: &str, meaning: M) -> Self {
+        self.lexicon
+            .push((word.to_string(), category.to_string(), Some(meaning)));
+        self.word_probabilities.push(1.0);
+        self
+    }
+
+    /// The rules of the grammar.
+    pub fn rules(&self) -> &[Rule<M>] {
+        &self.rules
+    }
+
+    /// The lexical categories of `word`.
+    pub fn categories(&self, word: &str) -> Vec<&str> {
+        self.lexicon
+            .iter()
+            .filter(|(w, _, _)| w == word)
+            .map(|(_, category, _)| category.as_str())
+            .collect()
+    }
+
+    /// The probability of the derivation `tree`: the product of the probabilities of the rules
+    /// and words it uses. It is zero if the tree uses a rule or word the grammar doesn't have.
+    pub fn probability(&self, tree: &Tree) -> f64 {
+        match tree {
+            Tree::Leaf(category, word) => self
+                .lexicon
+                .iter()
+                .zip(&self.word_probabilities)
+                .filter(|((w, c, _), _)| w == word && c == category)
+                .map(|(_, &p)| p)
+                .fold(0.0, f64::max),
+            Tree::Node(category, children) => {
+                let rule = self
+                    .rules
+                    .iter()
+                    .zip(&self.rule_probabilities)
+                    .filter(|(rule, _)| {
+                        rule.lhs() == category
+                            && rule
+                                .rhs()
+                                .iter()
+                                .map(String::as_str)
+                                .eq(children.iter().map(Tree::label))
+                    })
+                    .map(|(_, &p)| p)
+                    .fold(0.0, f64::max);
+                rule * children
+                    .iter()
+                    .map(|child| self.probability(child))
+                    .product::<f64>()
+            }
+        }
+    }
+}
+
+/// An edge of the chart: the constituents found so far for a rule, spanning `start..end`, and
+/// the categories it still expects. `rule` is `None` for the edge of the start symbol.
+#[derive(Clone, Debug)]
+struct Edge<M> {
+    start: usize,
+    end: usize,
+    rule: Option<usize>,
+    lhs: String,
+    found: Vec<(Tree, Option<M>)>,
+    expects: Vec<String>,
+}
+
+impl<M> Edge<M> {
+    fn same_as(&self, other: &Edge<M>) -> bool {
+        self.start == other.start
+            && self.end == other.end
+            && self.rule == other.rule
+            && self.expects == other.expects
+            && self.found.len() == other.found.len()
+            && self.found.iter().zip(&other.found).all(|(a, b)| a.0 == b.0)
+    }
+}
+
+/// Figure 23.5
+///
+/// # Chart Parser
+///
+/// A chart parser finds every parse of a sentence without redoing work, by keeping a chart of
+/// edges: partial phrases, each recording what it has found and what it still expects. Parsing
+/// proceeds left to right, as in Earley's algorithm. The _predictor_ adds an empty edge for each
+/// rule that could build an expected category, the _scanner_ adds an edge for each category of
+/// the next word, and the _extender_ advances every edge waiting for a phrase just completed.
+///
+/// When the grammar has semantic attachments, the meaning of each phrase is composed as it is
+/// completed, so each parse comes with the meaning of the whole sentence.
+///
+/// # Examples
+///
+/// Quantified noun phrases and verbs carry meanings that the rules put together into a
+/// first-order formula:
+///
+/// ```
+/// # use aima_rust::fol::Term;
+/// # use aima_rust::nlp::{Chart, Grammar};
+/// #[derive(Clone, Debug)]
+/// enum Sem {
+///     Word(&'static str),
+///     Np(&'static str, &'static str),
+///     Vp(&'static str, (&'static str, &'static str)),
+///     Formula(String),
+/// }
+///
+/// fn quantify(det: &str, noun: &str, var: &str, body: String) -> String {
+///     let restriction = Term::compound(noun, vec![Term::var(var)]);
+///     match det {
+///         "every" => format!("∀{} ({} ⇒ {})", var, restriction, body),
+///         _ => format!("∃{} ({} ∧ {})", var, restriction, body),
+///     }
+/// }
+///
+/// let grammar = Grammar::new()
+///     .semantic_rule("S", "NP VP", |m| match m {
+///         [Sem::Np(det, noun), Sem::Vp(verb, (object_det, object_noun))] => {
+///             let atom = Term::compound(verb, vec![Term::var("x"), Term::var("y")]);
+///             let object = quantify(object_det, object_noun, "y", atom.to_string());
+///             Sem::Formula(quantify(det, noun, "x", object))
+///         }
+///         _ => unreachable!(),
+///     })
+///     .semantic_rule("NP", "Det N", |m| match m {
+///         [Sem::Word(det), Sem::Word(noun)] => Sem::Np(det, noun),
+///         _ => unreachable!(),
+///     })
+///     .semantic_rule("VP", "V NP", |m| match m {
+///         [Sem::Word(verb), Sem::Np(det, noun)] => Sem::Vp(verb, (det, noun)),
+///         _ => unreachable!(),
+///     })
+///     .word_with_meaning("Det", "every", Sem::Word("every"))
+///     .word_with_meaning("Det", "a", Sem::Word("a"))
+///     .word_with_meaning("N", "man", Sem::Word("Man"))
+///     .word_with_meaning("N", "woman", Sem::Word("Woman"))
+///     .word_with_meaning("V", "loves", Sem::Word("Loves"));
+///
+/// let parses = Chart::new(&grammar).parses("every man loves a woman", "S");
+/// assert_eq!(parses.len(), 1);
+///
+/// let (tree, meaning) = &parses[0];
+/// assert_eq!(
+///     tree.to_string(),
+///     "[S [NP [Det every] [N man]] [VP [V loves] [NP [Det a] [N woman]]]]"
+/// );
+/// match meaning {
+///     Some(Sem::Formula(formula)) => {
+///         assert_eq!(formula, "∀x (Man(x) ⇒ ∃y (Woman(y) ∧ Loves(x, y)))")
+///     }
+///     _ => panic!("the sentence should have a formula as its meaning"),
+/// }
+/// ```
+///
+/// Without semantics, meanings pass through unchanged, and an ambiguous sentence gets a parse
+/// for each reading:
+///
+/// ```
+/// # use aima_rust::nlp::{Chart, Grammar};
+/// let grammar = Grammar::new()
+///     .rule("S", "NP VP")
+///     .rule("NP", "Name")
+///     .rule("NP", "NP PP")
+///     .rule("NP", "Det N")
+///     .rule("VP", "V NP")
+///     .rule("VP", "VP PP")
+///     .rule("PP", "P NP")
+///     .word_with_meaning("Name", "john", "John")
+///     .word("V", "saw")
+///     .word("Det", "the")
+///     .word("N", "man")
+///     .word("N", "telescope")
+///     .word("P", "with");
+///
+/// let parses = Chart::new(&grammar).parses("john saw the man with the telescope", "S");
+/// assert_eq!(parses.len(), 2);
+/// assert!(parses.iter().all(|(_, meaning)| *meaning == Some("John")));
+///
+/// assert!(Chart::new(&grammar).parses("saw john", "S").is_empty());
+/// ```
+pub struct Chart<'a, M> {
+    grammar: &'a Grammar<M>,
+}
+
+impl<'a, M: Clone> Chart<'a, M> {
+    /// Creates a new parser given the grammar to parse with.
+    pub fn new(grammar: &'a Grammar<M>) -> Self {
+        Chart { grammar }
+    }
+
+    /// Every parse of `sentence` as a `start` phrase, with its meaning. Words are separated by
+    /// whitespace.
+    pub fn parses(&self, sentence: &str, start: &str) -> Vec<(Tree, Option<M>)> {
+        let words: Vec<&str> = sentence.split_whitespace().collect();
+        let mut chart: Vec<Vec<Edge<M>>> = vec![Vec::new(); words.len() + 1];
+        self.add_edge(
+            &mut chart,
+            Edge {
+                start: 0,
+                end: 0,
+                rule: None,
+                lhs: String::new(),
+                found: Vec::new(),
+                expects: vec![start.to_string()],
+            },
+        );
+        for (i, word) in words.iter().enumerate() {
+            for (w, category, meaning) in &self.grammar.lexicon {
+                if w == word {
+                    let leaf = Tree::Leaf(category.clone(), w.clone());
+                    self.extend(&mut chart, i, i + 1, category, (leaf, meaning.clone()));
+                }
+            }
+        }
+        chart[words.len()]
+            .iter()
+            .filter(|edge| edge.rule.is_none() && edge.start == 0 && edge.expects.is_empty())
+            .map(|edge| edge.found[0].clone())
+            .collect()
+    }
+
+    fn add_edge(&self, chart: &mut [Vec<Edge<M>>], edge: Edge<M>) {
+        if chart[edge.end].iter().any(|e| e.same_as(&edge)) {
+            return;
+        }
+        chart[edge.end].push(edge.clone());
+        match (&edge.rule, edge.expects.first()) {
+            (Some(rule), None) => {
+                let rule = &self.grammar.rules[*rule];
+                let meanings: Vec<Option<M>> = edge.found.iter().map(|(_, m)| m.clone()).collect();
+                let trees = edge.found.into_iter().map(|(tree, _)| tree).collect();
+                let phrase = (Tree::Node(edge.lhs.clone(), trees), rule.meaning(&meanings));
+                self.extend(chart, edge.start, edge.end, &edge.lhs, phrase);
+            }
+            (_, Some(next)) => {
+                for (i, rule) in self.grammar.rules.iter().enumerate() {
+                    if rule.lhs() == next {
+                        let prediction = Edge {
+                            start: edge.end,
+                            end: edge.end,
+                            rule: Some(i),
+                            lhs: rule.lhs().to_string(),
+                            found: Vec::new(),
+                            expects: rule.rhs().to_vec(),
+                        };
+                        self.add_edge(chart, prediction);
+                    }
+                }
+            }
+            (None, None) => {}
+        }
+    }
+
+    /// Advances every edge ending at `start` that expects `category` over the phrase found from
+    /// `start` to `end`.
+    fn extend(
+        &self,
+        chart: &mut [Vec<Edge<M>>],
+        start: usize,
+        end: usize,
+        category: &str,
+        phrase: (Tree, Option<M>),
+    ) {
+        let waiting: Vec<Edge<M>> = chart[start]
+            .iter()
+            .filter(|edge| edge.expects.first().is_some_and(|c| c == category))
+            .cloned()
+            .collect();
+        for edge in waiting {
+            let mut found = edge.found;
+            found.push(phrase.clone());
+            self.add_edge(
+                chart,
+                Edge {
+                    start: edge.start,
+                    end,
+                    rule: edge.rule,
+                    lhs: edge.lhs,
+                    found,
+                    expects: edge.expects[1..].to_vec(),
+                },
+            );
+        }
+    }
+}
+
+/// How the best phrase of a category over a span of words was built, for reading the parse back
+/// out of the CYK table.
+#[derive(Clone, Debug)]
+enum Back {
+    Word,
+    Unary(String),
+    Binary(usize, String, String),
+}
+
+/// A rule of a grammar in the binary form CYK works with: `lhs -> left right`.
+struct BinaryRule {
+    lhs: String,
+    left: String,
+    right: String,
+    p: f64,
+}
+
+/// The best probability of each category over one span of words, and how it was built.
+type Cell = HashMap<String, (f64, Back)>;
+
+/// Marks the categories made up when rules are split into binary ones.
+const SPLIT: char = '|';
+
+/// # CYK Parse
+///
+/// Finds the most probable parse of `sentence` as a `start` phrase under a probabilistic
+/// grammar, and its probability, or `None` if the sentence has no parse. Words are separated by
+/// whitespace.
+///
+/// The CYK algorithm fills a table with the most probable phrase of each category over each span
+/// of words, shortest spans first. A phrase over a longer span is built by a rule from two
+/// phrases over the spans either side of a split point, so each entry only needs the table
+/// entries below it, and the whole table takes $O(n^3 m)$ time for $n$ words and $m$ rules.
+/// Keeping a backpointer with each entry lets the best tree be read back out.
+///
+/// CYK wants a grammar in Chomsky normal form, with two categories on the right of every rule.
+/// Longer rules are split into binary ones here, with made-up categories that are removed again
+/// from the tree returned, and rules with one category on the right are applied to each entry
+/// after it is filled, until none improves.
+///
+/// # Panics
+///
+/// Panics if a rule has nothing on its right-hand side.
+///
+/// # Examples
+///
+/// ```
+/// # use aima_rust::nlp::{e0, most_probable_parse};
+/// let grammar = e0();
+///
+/// let (tree, p) = most_probable_parse("the wumpus is dead", &grammar, "S").unwrap();
+/// assert_eq!(
+///     tree.to_string(),
+///     "[S [NP [Article the] [Noun wumpus]] [VP [VP [Verb is]] [Adjective dead]]]"
+/// );
+/// // S -> NP VP, NP -> Article Noun, VP -> VP Adjective, VP -> Verb, and the four words.
+/// let by_hand = 0.90 * (0.25 * 0.40 * 0.15) * (0.05 * 0.40 * 0.10 * 0.05);
+/// assert!((p - by_hand).abs() < 1e-15);
+///
+/// assert!(most_probable_parse("wumpus the dead is", &grammar, "S").is_none());
+/// ```
+///
+/// The chart parser finds every reading of an ambiguous sentence, and the most probable parse is
+/// the likeliest of them. $\mathcal{E}_0$ gives attaching a prepositional
+/// phrase to a noun phrase and to a verb phrase the same probability, 0.10, so here the breeze
+/// being near the pits and the feeling happening there come out equally likely:
+///
+/// ```
+/// # use aima_rust::nlp::{e0, most_probable_parse, Chart};
+/// let grammar = e0();
+/// let sentence = "i feel a breeze near the pits";
+///
+/// let readings: Vec<_> = Chart::new(&grammar)
+///     .parses(sentence, "S")
+///     .into_iter()
+///     .map(|(reading, _)| reading)
+///     .collect();
+/// assert_eq!(readings.len(), 2);
+/// let (first, second) = (grammar.probability(&readings[0]), grammar.probability(&readings[1]));
+/// assert!((first - second).abs() < 1e-12 * first);
+///
+/// let (tree, p) = most_probable_parse(sentence, &grammar, "S").unwrap();
+/// assert!(readings.contains(&tree));
+/// assert!((p - first.max(second)).abs() < 1e-12 * p);
+/// ```
+///
+/// Telling the two apart takes rule probabilities that depend on the words involved, such as
+/// those of a lexicalized PCFG, or ones learned from a treebank that favour one attachment.
+pub fn most_probable_parse<M: Clone>(
+    sentence: &str,
+    grammar: &Grammar<M>,
+    start: &str,
+) -> Option<(Tree, f64)> {
+    let words: Vec<&str> = sentence.split_whitespace().collect();
+    let n = words.len();
+    if n == 0 {
+        return None;
+    }
+
+    let mut unary: Vec<(&str, &str, f64)> = Vec::new();
+    let mut binary: Vec<BinaryRule> = Vec::new();
+    for (i, (rule, &p)) in grammar
+        .rules
+        .iter()
+        .zip(&grammar.rule_probabilities)
+        .enumerate()
+    {
+        let rhs = rule.rhs();
+        assert!(
+            !rhs.is_empty(),
+            "CYK can't parse with the empty rule for {}",
+            rule.lhs()
+        );
+        if rhs.len() == 1 {
+            unary.push((rule.lhs(), &rhs[0], p));
+            continue;
+        }
+        // lhs -> A B C D becomes lhs -> A lhs|i.1, lhs|i.1 -> B lhs|i.2, lhs|i.2 -> C D.
+        let mut lhs = rule.lhs().to_string();
+        let mut p = p;
+        for (j, left) in rhs[..rhs.len() - 2].iter().enumerate() {
+            let rest = format!("{}{}{}.{}", rule.lhs(), SPLIT, i, j + 1);
+            binary.push(BinaryRule {
+                lhs,
+                left: left.clone(),
+                right: rest.clone(),
+                p,
+            });
+            lhs = rest;
+            p = 1.0;
+        }
+        let (left, right) = (rhs[rhs.len() - 2].clone(), rhs[rhs.len() - 1].clone());
+        binary.push(BinaryRule {
+            lhs,
+            left,
+            right,
+            p,
+        });
+    }
+    let close = |cell: &mut Cell| loop {
+        let mut improved = false;
+        for &(lhs, rhs, p) in &unary {
+            if let Some(&(q, _)) = cell.get(rhs) {
+                if cell.get(lhs).is_none_or(|&(best, _)| p * q > best) {
+                    cell.insert(lhs.to_string(), (p * q, Back::Unary(rhs.to_string())));
+                    improved = true;
+                }
+            }
+        }
+        if !improved {
+            break;
+        }
+    };
+
+    // table[i][j] covers words i..i + j + 1.
+    let mut table: Vec<Vec<Cell>> = vec![vec![Cell::new(); n]; n];
+    for (i, word) in words.iter().enumerate() {
+        let cell = &mut table[i][0];
+        for ((w, category, _), &p) in grammar.lexicon.iter().zip(&grammar.word_probabilities) {
+            if w == word && cell.get(category).is_none_or(|&(best, _)| p > best) {
+                cell.insert(category.clone(), (p, Back::Word));
+            }
+        }
+        close(cell);
+    }
+    for length in 2..=n {
+        for i in 0..=n - length {
+            let mut cell = Cell::new();
+            for split in 1..length {
+                let (left, right) = (&table[i][split - 1], &table[i + split][length - split - 1]);
+                for rule in &binary {
+                    if let (Some(&(p, _)), Some(&(q, _))) =
+                        (left.get(&rule.left), right.get(&rule.right))
+                    {
+                        let p = rule.p * p * q;
+                        if cell.get(&rule.lhs).is_none_or(|&(best, _)| p > best) {
+                            let back = Back::Binary(split, rule.left.clone(), rule.right.clone());
+                            cell.insert(rule.lhs.clone(), (p, back));
+                        }
+                    }
+                }
+            }
+            close(&mut cell);
+            table[i][length - 1] = cell;
+        }
+    }
+
+    let &(p, _) = table[0][n - 1].get(start)?;
+    let mut trees = read_back(&table, &words, 0, n, start);
+    Some((trees.remove(0), p))
+}
+
+/// The best tree for `category` over words `start..end`, read back out of the CYK table. The
+/// made-up categories of split rules give their children instead, to splice into their parent.
+fn read_back(
+    table: &[Vec<Cell>],
+    words: &[&str],
+    start: usize,
+    end: usize,
+    category: &str,
+) -> Vec<Tree> {
+    let (_, back) = &table[start][end - start - 1][category];
+    match back {
+        Back::Word => vec![Tree::Leaf(category.to_string(), words[start].to_string())],
+        Back::Unary(child) => {
+            let children = read_back(table, words, start, end, child);
+            vec![Tree::Node(category.to_string(), children)]
+        }
+        Back::Binary(split, left, right) => {
+            let mut children = read_back(table, words, start, start + split, left);
+            children.extend(read_back(table, words, start + split, end, right));
+            if category.contains(SPLIT) {
+                children
+            } else {
+                vec![Tree::Node(category.to_string(), children)]
+            }
+        }
+    }
+}
+
+/// Figure 23.2
+///
+/// The grammar $\mathcal{E}_0$ for a fragment of English about the wumpus world, with the rule
+/// probabilities of figure 23.2 and the lexicon of figure 23.1. The lexicon lists only the words
+/// shown in the book, so the probabilities of each category's words sum to less than one.
+pub fn e0() -> Grammar<()> {
+    let rules: &[(&str, &str, f64)] = &[
+        ("S", "NP VP", 0.90),
+        ("S", "S Conj S", 0.10),
+        ("NP", "Pronoun", 0.30),
+        ("NP", "Name", 0.10),
+        ("NP", "Noun", 0.10),
+        ("NP", "Article Noun", 0.25),
+        ("NP", "Article Adjs Noun", 0.05),
+        ("NP", "Digit Digit", 0.05),
+        ("NP", "NP PP", 0.10),
+        ("NP", "NP RelClause", 0.05),
+        ("VP", "Verb", 0.40),
+        ("VP", "VP NP", 0.35),
+        ("VP", "VP Adjective", 0.05),
+        ("VP", "VP PP", 0.10),
+        ("VP", "VP Adverb", 0.10),
+        ("Adjs", "Adjective", 0.80),
+        ("Adjs", "Adjective Adjs", 0.20),
+        ("PP", "Prep NP", 1.00),
+        ("RelClause", "RelPro VP", 1.00),
+    ];
+    let lexicon: &[(&str, &[(&str, f64)])] = &[
+        (
+            "Noun",
+            &[
+                ("stench", 0.05),
+                ("breeze", 0.10),
+                ("wumpus", 0.15),
+                ("pits", 0.05),
+            ],
+        ),
+        (
+            "Verb",
+            &[
+                ("is", 0.10),
+                ("feel", 0.10),
+                ("smells", 0.10),
+                ("stinks", 0.05),
+            ],
+        ),
+        (
+            "Adjective",
+            &[
+                ("right", 0.10),
+                ("dead", 0.05),
+                ("smelly", 0.02),
+                ("breezy", 0.02),
+            ],
+        ),
+        (
+            "Adverb",
+            &[("here", 0.05), ("ahead", 0.05), ("nearby", 0.02)],
+        ),
+        (
+            "Pronoun",
+            &[("me", 0.10), ("you", 0.03), ("i", 0.10), ("it", 0.10)],
+        ),
+        (
+            "RelPro",
+            &[
+                ("that", 0.40),
+                ("which", 0.15),
+                ("who", 0.20),
+                ("whom", 0.02),
+            ],
+        ),
+        ("Name", &[("john", 0.01), ("mary", 0.01), ("boston", 0.01)]),
+        (
+            "Article",
+            &[("the", 0.40), ("a", 0.30), ("an", 0.10), ("every", 0.05)],
+        ),
+        (
+            "Prep",
+            &[("to", 0.20), ("in", 0.10), ("on", 0.05), ("near", 0.10)],
+        ),
+        (
+            "Conj",
+            &[("and", 0.50), ("or", 0.10), ("but", 0.20), ("yet", 0.02)],
+        ),
+        (
+            "Digit",
+            &[
+                ("0", 0.20),
+                ("1", 0.20),
+                ("2", 0.20),
+                ("3", 0.20),
+                ("4", 0.20),
+            ],
+        ),
+    ];
+    let mut grammar = Grammar::new();
+    for &(lhs, rhs, p) in rules {
+        grammar = grammar.probabilistic_rule(lhs, rhs, p);
+    }
+    for &(category, words) in lexicon {
+        for &(word, p) in words {
+            grammar = grammar.probabilistic_word(category, word, p);
+        }
+    }
+    grammar
+}
+
+/// # Definite Clause Grammar
+///
+/// A definite clause grammar writes each grammar rule as a definite clause of first-order logic,
+/// so that parsing becomes backward chaining with `fol_bc_ask`. Categories can take arguments,
+/// such as the meaning of a phrase, and unification passes them between the rules.
+///
+/// Every category gets two extra arguments, the words before and after the phrase, as lists
+/// built from `Cons` and `Nil`. The rule $S(sem) \rightarrow NP(subj) \; VP(subj, sem)$ becomes
+///
+/// $$ NP(subj, s_0, s_1) \land VP(subj, sem, s_1, s_2) \Rightarrow S(sem, s_0, s_2) $$
+///
+/// which says that the words from $s_0$ to $s_2$ are a sentence if some prefix of them, up to
+/// $s_1$, is a noun phrase and the rest is a verb phrase. A word is a fact that consumes it:
+/// $Name(John, Cons(john, s), s)$.
+///
+/// # Examples
+///
+/// ```
+/// # use aima_rust::fol::Term;
+/// # use aima_rust::nlp::dcg::Dcg;
+/// let cat = |name: &str, args: &[&Term]| {
+///     Term::compound(name, args.iter().map(|&arg| arg.clone()).collect())
+/// };
+/// let (subj, obj, sem) = (Term::var("subj"), Term::var("obj"), Term::var("sem"));
+/// let loves = cat("Loves", &[&subj, &obj]);
+/// let grammar = Dcg::new()
+///     .rule(cat("S", &[&sem]), vec![cat("NP", &[&subj]), cat("VP", &[&subj, &sem])])
+///     .rule(
+///         cat("VP", &[&subj, &sem]),
+///         vec![cat("Verb", &[&subj, &obj, &sem]), cat("NP", &[&obj])],
+///     )
+///     .word(cat("Verb", &[&subj, &obj, &loves]), "loves")
+///     .word(cat("NP", &[&Term::constant("John")]), "john")
+///     .word(cat("NP", &[&Term::constant("Mary")]), "mary");
+///
+/// assert_eq!(
+///     grammar.kb().clauses()[0].to_string(),
+///     "NP(subj, _0, _1) ∧ VP(subj, sem, _1, _2) ⇒ S(sem, _0, _2)"
+/// );
+///
+/// let sentence = cat("S", &[&Term::var("meaning")]);
+/// let parses = grammar.parse(&sentence, "John loves Mary", 5);
+/// assert_eq!(parses.len(), 1);
+/// assert_eq!(parses[0]["meaning"].to_string(), "Loves(John, Mary)");
+///
+/// // Ungrammatical sentences have no parse.
+/// assert!(grammar.parse(&sentence, "loves John Mary", 5).is_empty());
+/// assert!(grammar.parse(&sentence, "John loves", 5).is_empty());
+/// ```
+pub mod dcg {
+    use super::words;
+    use crate::fol::{fol_bc_ask, DefiniteClause, FolKB, Substitution, Term};
+
+    /// A definite clause grammar, held as the knowledge base of its clauses.
+    #[derive(Clone, Debug, Default)]
+    pub struct Dcg {
+        kb: FolKB,
+    }
+
+    impl Dcg {
+        /// Creates a new, empty grammar.
+        pub fn new() -> Self {
+            Dcg::default()
+        }
+
+        /// Adds the rule $head \rightarrow body_1 \ldots body_n$. Each category is a constant, or
+        /// a compound term whose arguments are the category's own; the variables `_0`, `_1`,
+        /// and so on are used for the word lists.
+        ///
+        /// # Panics
+        ///
+        /// Panics if the body is empty, or a category is a variable.
+        pub fn rule(mut self, head: Term, body: Vec<Term>) -> Self {
+            assert!(
+                !body.is_empty(),
+                "a rule needs at least one category in its body"
+            );
+            let position = |i: usize| Term::var(&format!("_{}", i));
+            let body: Vec<Term> = body
+                .iter()
+                .enumerate()
+                .map(|(i, category)| with_words(category, position(i), position(i + 1)))
+                .collect();
+            let head = with_words(&head, position(0), position(body.len()));
+            self.kb.tell(DefiniteClause::rule(body, head));
+            self
+        }
+
+        /// Adds the rule $category \rightarrow word$.
+        ///
+        /// # Panics
+        ///
+        /// Panics if the category is a variable.
+        pub fn word(mut self, category: Term, word: &str) -> Self {
+            let rest = Term::var("_0");
+            let words = Term::compound("Cons", vec![Term::constant(word), rest.clone()]);
+            self.kb
+                .tell(DefiniteClause::fact(with_words(&category, words, rest)));
+            self
+        }
+
+        /// The clauses the grammar has been translated into.
+        pub fn kb(&self) -> &FolKB {
+            &self.kb
+        }
+
+        /// Parses `sentence`, split into words with `words`, as the category `goal`. Each parse
+        /// gives a binding for the variables of `goal`; an ungrammatical sentence gives none.
+        /// Proofs are limited to `max_depth` rules deep, as in `fol_bc_ask`.
+        pub fn parse(&self, goal: &Term, sentence: &str, max_depth: usize) -> Vec<Substitution> {
+            let list = words(sentence)
+                .iter()
+                .rev()
+                .fold(Term::constant("Nil"), |rest, word| {
+                    Term::compound("Cons", vec![Term::constant(word), rest])
+                });
+            let query = with_words(goal, list, Term::constant("Nil"));
+            fol_bc_ask(&self.kb, &query, max_depth).answers
+        }
+    }
+
+    /// `category` with the word lists `before` and `after` appended to its arguments.
+    fn with_words(category: &Term, before: Term, after: Term) -> Term {
+        match category {
+            Term::Const(name) => Term::compound(name, vec![before, after]),
+            Term::Compound(name, args) => {
+                let mut args = args.clone();
+                args.push(before);
+                args.push(after);
+                Term::compound(name, args)
+            }
+            Term::Var(_) => panic!("a category can't be a variable"),
+        }
+    }
+}
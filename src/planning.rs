@@ -0,0 +1,1982 @@
+//! # Planning and Acting in the Real World
+//!
+//! This module covers material in Chapter 10, classical planning, and Chapter 11, scheduling
+//! with time and resources.
+//!
+//! ## Index of Algorithms
+//!
+//! | **Figure** | **Name**                          | **Module Link**
+//! |:-----------|:----------------------------------|:-------------------------------
+//! | 10         | Planning Problem                  | `PlanningProblem`                                        |
+//! | 10.1       | Air-Cargo-Problem                 | `air_cargo`                                              |
+//! | 10.2       | Spare-Tire-Problem                | `spare_tire`                                             |
+//! | 10.7       | Cake-Problem                      | `have_cake_and_eat_cake_too`                             |
+//! | 10         | Max-Level Heuristic               | `h_max`                                                  |
+//! | 10         | Level-Sum Heuristic               | `h_level`                                                |
+//! | 10         | Goal-Count Heuristic              | `goal_count`                                             |
+//! | 10         | Forward State-Space Search        | `forward_search`                                         |
+//! | 10         | Backward State-Space Search       | `backward_search`                                        |
+//! | 10         | Planning Search Report            | `search_report`                                          |
+//! | 10         | PDDL Loader                       | `PlanningProblem::from_pddl`                             |
+//! | 10         | Plan to String                    | `plan_to_string`                                         |
+//! | 10         | Planning Graph                    | `PlanningGraph`                                          |
+//! | 10.9       | Graphplan                         | `graph_plan`                                             |
+//! | 11.1       | Job-Shop-Problem-With-Resources   | `job_shop_problem`                                       |
+//! | 11.2       | Critical-Path-Method              | `critical_path`                                          |
+//! | 11         | Schedule Simulation               | `simulate_schedule`                                      |
+//!
+use crate::logic::Literal;
+use crate::search::{astar_search, InstrumentedProblem, Problem};
+use crate::util::topological_sort;
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::error::Error;
+use std::fmt;
+
+/// A state of a planning problem: the fluents that hold in it. Every other fluent is false.
+pub type State = BTreeSet<String>;
+
+/// Reads a literal written as a fluent such as `At(C1, SFO)`, negated with a leading `~`.
+fn literal(s: &str) -> Literal {
+    match s.trim().strip_prefix('~') {
+        Some(fluent) => Literal::new(fluent.trim(), false),
+        None => Literal::new(s.trim(), true),
+    }
+}
+
+/// Whether `literal` holds in `state`.
+fn holds(literal: &Literal, state: &State) -> bool {
+    state.contains(&literal.symbol) == literal.positive
+}
+
+/// A ground action: a precondition that must hold for the action to be applicable, and an
+/// effect that makes its positive literals true and its negative literals false.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Action {
+    pub name: String,
+    pub precondition: Vec<Literal>,
+    pub effect: Vec<Literal>,
+}
+
+impl Action {
+    /// Creates a new action given its name, precondition and effect. Each literal is written as
+    /// a fluent such as `At(C1, SFO)`, negated with a leading `~`.
+    ///
+    /// ```
+    /// # use aima_rust::planning::{Action, State};
+    /// let eat = Action::new("Eat(Cake)", &["Have(Cake)"], &["~Have(Cake)", "Eaten(Cake)"]);
+    /// let state: State = vec!["Have(Cake)".to_string()].into_iter().collect();
+    ///
+    /// assert!(eat.is_applicable(&state));
+    /// let next = eat.apply(&state);
+    /// assert!(!eat.is_applicable(&next));
+    /// assert_eq!(next.into_iter().collect::<Vec<_>>(), vec!["Eaten(Cake)"]);
+    /// ```
+    pub fn new(name: &str, precondition: &[&str], effect: &[&str]) -> Self {
+        Action {
+            name: name.to_string(),
+            precondition: precondition.iter().map(|s| literal(s)).collect(),
+            effect: effect.iter().map(|s| literal(s)).collect(),
+        }
+    }
+
+    /// Whether the precondition holds in `state`.
+    pub fn is_applicable(&self, state: &State) -> bool {
+        self.precondition.iter().all(|l| holds(l, state))
+    }
+
+    /// The state that results from taking the action in `state`, which it doesn't check the
+    /// action is applicable in.
+    pub fn apply(&self, state: &State) -> State {
+        let mut next = state.clone();
+        for l in self.effect.iter().filter(|l| !l.positive) {
+            next.remove(&l.symbol);
+        }
+        for l in self.effect.iter().filter(|l| l.positive) {
+            next.insert(l.symbol.clone());
+        }
+        next
+    }
+}
+
+impl fmt::Display for Action {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.name)
+    }
+}
+
+/// # Classical Planning Problem
+///
+/// A planning problem in the style of STRIPS: an initial state, a goal made up of literals that
+/// must all hold, and a set of ground actions. Action schemas with variables, such as
+/// `Fly(p, from, to)`, are written out once for each way of filling them in.
+///
+/// A planning problem is also a search `Problem`, whose states are `State`s and each of whose
+/// steps costs one, so the searches in `search` find plans:
+///
+/// ```
+/// # use aima_rust::planning::have_cake_and_eat_cake_too;
+/// # use aima_rust::search::breadth_first_search;
+/// let problem = have_cake_and_eat_cake_too();
+/// let plan = breadth_first_search(&problem).unwrap().solution();
+///
+/// let names: Vec<_> = plan.iter().map(|a| a.name.as_str()).collect();
+/// assert_eq!(names, vec!["Eat(Cake)", "Bake(Cake)"]);
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct PlanningProblem {
+    initial: State,
+    goal: Vec<Literal>,
+    actions: Vec<Action>,
+}
+
+impl PlanningProblem {
+    /// Creates a new problem given the fluents that hold initially, the goal literals, written
+    /// as for `Action::new`, and the actions.
+    pub fn new(initial: &[&str], goal: &[&str], actions: Vec<Action>) -> Self {
+        PlanningProblem {
+            initial: initial.iter().map(|s| s.trim().to_string()).collect(),
+            goal: goal.iter().map(|s| literal(s)).collect(),
+            actions,
+        }
+    }
+
+    /// Reads a problem written in PDDL, given the text of its domain and of the problem. Only a
+    /// STRIPS subset is supported: untyped parameters, and preconditions, effects and goals that
+    /// are conjunctions of literals, with negative preconditions allowed. Each action schema is
+    /// written out once for every way of giving its parameters the problem's objects, and ground
+    /// actions and fluents are named as in the rest of this module, so `(At ?c ?a)` becomes
+    /// `At(C1, SFO)`.
+    ///
+    /// Returns an error giving the line of the domain or problem text that can't be read.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use aima_rust::planning::{forward_search, have_cake_and_eat_cake_too, PlanningProblem};
+    /// let domain = "
+    /// (define (domain cake)
+    ///   (:requirements :strips :negative-preconditions)
+    ///   (:predicates (Have ?c) (Eaten ?c))
+    ///   (:action Eat
+    ///     :parameters (?c)
+    ///     :precondition (Have ?c)
+    ///     :effect (and (not (Have ?c)) (Eaten ?c)))
+    ///   (:action Bake
+    ///     :parameters (?c)
+    ///     :precondition (not (Have ?c))
+    ///     :effect (Have ?c)))";
+    /// let problem = "
+    /// (define (problem have-cake) ; and eat it too
+    ///   (:domain cake)
+    ///   (:objects Cake)
+    ///   (:init (Have Cake))
+    ///   (:goal (and (Have Cake) (Eaten Cake))))";
+    ///
+    /// let loaded = PlanningProblem::from_pddl(domain, problem).unwrap();
+    /// assert_eq!(loaded, have_cake_and_eat_cake_too());
+    /// assert_eq!(forward_search(&loaded).unwrap().len(), 2);
+    ///
+    /// let misspelled = domain.replace("(Eaten ?c)))\n", "(Eaten ?cake)))\n");
+    /// let error = PlanningProblem::from_pddl(&misspelled, problem).unwrap_err();
+    /// assert_eq!(error.line(), 8);
+    /// assert_eq!(error.to_string(), "invalid PDDL domain at line 8: unknown parameter `?cake`");
+    ///
+    /// let unclosed = problem.replace("(:init (Have Cake))", "(:init (Have Cake)");
+    /// let error = PlanningProblem::from_pddl(domain, &unclosed).unwrap_err();
+    /// assert_eq!(error.line(), 2);
+    /// ```
+    pub fn from_pddl(domain: &str, problem: &str) -> Result<Self, ParsePddlError> {
+        let (name, schemas) = read_domain(domain)?;
+        let (objects, initial, goal) = read_problem(problem, &name)?;
+        let mut actions = Vec::new();
+        for schema in &schemas {
+            for binding in bindings(&objects, schema.parameters.len()) {
+                let ground = |literals: &[SchemaLiteral]| {
+                    literals
+                        .iter()
+                        .map(|l| ground(l, &schema.parameters, &binding))
+                        .collect()
+                };
+                actions.push(Action {
+                    name: fluent(&schema.name, &binding),
+                    precondition: ground(&schema.precondition),
+                    effect: ground(&schema.effect),
+                });
+            }
+        }
+        Ok(PlanningProblem {
+            initial: initial.into_iter().collect(),
+            goal,
+            actions,
+        })
+    }
+
+    /// The fluents that hold initially.
+    pub fn initial(&self) -> &State {
+        &self.initial
+    }
+
+    /// The literals that must all hold in a goal state.
+    pub fn goal(&self) -> &[Literal] {
+        &self.goal
+    }
+
+    /// Every ground action, applicable or not.
+    pub fn ground_actions(&self) -> &[Action] {
+        &self.actions
+    }
+
+    /// The state reached by taking the actions of `plan` in turn from the initial state, or
+    /// `None` if one of them isn't applicable when its turn comes.
+    pub fn execute(&self, plan: &[Action]) -> Option<State> {
+        plan.iter().try_fold(self.initial.clone(), |state, action| {
+            action.is_applicable(&state).then(|| action.apply(&state))
+        })
+    }
+
+    /// Every fluent the problem mentions.
+    pub fn fluents(&self) -> BTreeSet<String> {
+        let mut fluents = self.initial.clone();
+        let literals = self.goal.iter().chain(
+            self.actions
+                .iter()
+                .flat_map(|a| a.precondition.iter().chain(&a.effect)),
+        );
+        fluents.extend(literals.map(|l| l.symbol.clone()));
+        fluents
+    }
+}
+
+impl Problem for PlanningProblem {
+    type State = State;
+    type Action = Action;
+
+    fn initial_state(&self) -> State {
+        self.initial.clone()
+    }
+
+    fn actions(&self, state: &State) -> Vec<Action> {
+        self.actions
+            .iter()
+            .filter(|a| a.is_applicable(state))
+            .cloned()
+            .collect()
+    }
+
+    fn result(&self, state: &State, action: &Action) -> State {
+        action.apply(state)
+    }
+
+    fn goal_test(&self, state: &State) -> bool {
+        self.goal.iter().all(|l| holds(l, state))
+    }
+}
+
+/// Figure 10.1
+///
+/// Air cargo transport: two planes flying two pieces of cargo between two airports, SFO and JFK.
+/// Each piece of cargo starts at one airport and must end up at the other. The type fluents
+/// `Cargo`, `Plane` and `Airport` are left out, since only well-typed actions are written out.
+pub fn air_cargo() -> PlanningProblem {
+    let cargo = ["C1", "C2"];
+    let planes = ["P1", "P2"];
+    let airports = ["SFO", "JFK"];
+    let mut actions = Vec::new();
+    for c in &cargo {
+        for p in &planes {
+            for a in &airports {
+                actions.push(Action::new(
+                    &format!("Load({}, {}, {})", c, p, a),
+                    &[&format!("At({}, {})", c, a), &format!("At({}, {})", p, a)],
+                    &[&format!("~At({}, {})", c, a), &format!("In({}, {})", c, p)],
+                ));
+                actions.push(Action::new(
+                    &format!("Unload({}, {}, {})", c, p, a),
+                    &[&format!("In({}, {})", c, p), &format!("At({}, {})", p, a)],
+                    &[&format!("At({}, {})", c, a), &format!("~In({}, {})", c, p)],
+                ));
+            }
+        }
+    }
+    for p in &planes {
+        for from in &airports {
+            for to in airports.iter().filter(|&to| to != from) {
+                actions.push(Action::new(
+                    &format!("Fly({}, {}, {})", p, from, to),
+                    &[&format!("At({}, {})", p, from)],
+                    &[
+                        &format!("~At({}, {})", p, from),
+                        &format!("At({}, {})", p, to),
+                    ],
+                ));
+            }
+        }
+    }
+    PlanningProblem::new(
+        &["At(C1, SFO)", "At(C2, JFK)", "At(P1, SFO)", "At(P2, JFK)"],
+        &["At(C1, JFK)", "At(C2, SFO)"],
+        actions,
+    )
+}
+
+/// Figure 10.2
+///
+/// Changing a flat tire: the spare has to go from the trunk onto the axle, where the flat is.
+/// Leaving the car overnight gets everything stolen.
+pub fn spare_tire() -> PlanningProblem {
+    let mut actions = Vec::new();
+    for obj in &["Flat", "Spare"] {
+        for loc in &["Axle", "Trunk"] {
+            actions.push(Action::new(
+                &format!("Remove({}, {})", obj, loc),
+                &[&format!("At({}, {})", obj, loc)],
+                &[
+                    &format!("~At({}, {})", obj, loc),
+                    &format!("At({}, Ground)", obj),
+                ],
+            ));
+        }
+        actions.push(Action::new(
+            &format!("PutOn({}, Axle)", obj),
+            &[
+                &format!("At({}, Ground)", obj),
+                "~At(Flat, Axle)",
+                "~At(Spare, Axle)",
+            ],
+            &[
+                &format!("~At({}, Ground)", obj),
+                &format!("At({}, Axle)", obj),
+            ],
+        ));
+    }
+    actions.push(Action::new(
+        "LeaveOvernight",
+        &[],
+        &[
+            "~At(Spare, Ground)",
+            "~At(Spare, Axle)",
+            "~At(Spare, Trunk)",
+            "~At(Flat, Ground)",
+            "~At(Flat, Axle)",
+            "~At(Flat, Trunk)",
+        ],
+    ));
+    PlanningProblem::new(
+        &["At(Flat, Axle)", "At(Spare, Trunk)"],
+        &["At(Spare, Axle)"],
+        actions,
+    )
+}
+
+/// Figure 10.7
+///
+/// Having your cake and eating it too: eating the cake and then baking another.
+pub fn have_cake_and_eat_cake_too() -> PlanningProblem {
+    PlanningProblem::new(
+        &["Have(Cake)"],
+        &["Have(Cake)", "Eaten(Cake)"],
+        vec![
+            Action::new(
+                "Eat(Cake)",
+                &["Have(Cake)"],
+                &["~Have(Cake)", "Eaten(Cake)"],
+            ),
+            Action::new("Bake(Cake)", &["~Have(Cake)"], &["Have(Cake)"]),
+        ],
+    )
+}
+
+/// Returned when text can't be read as a PDDL domain or problem.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParsePddlError {
+    part: &'static str,
+    line: usize,
+    message: String,
+}
+
+impl ParsePddlError {
+    /// The line the error is on, counting from one.
+    pub fn line(&self) -> usize {
+        self.line
+    }
+}
+
+impl fmt::Display for ParsePddlError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "invalid PDDL {} at line {}: {}",
+            self.part, self.line, self.message
+        )
+    }
+}
+
+impl Error for ParsePddlError {}
+
+/// An s-expression of PDDL text, with the line it starts on.
+enum Sexp {
+    Atom(String, usize),
+    List(Vec<Sexp>, usize),
+}
+
+impl Sexp {
+    fn line(&self) -> usize {
+        match self {
+            Sexp::Atom(_, line) | Sexp::List(_, line) => *line,
+        }
+    }
+}
+
+/// A literal of an action schema or problem, before grounding: whether it is positive, its
+/// predicate, and its arguments, which may be parameters such as `?c`.
+type SchemaLiteral = (bool, String, Vec<String>);
+
+/// Reads PDDL text, reporting errors as being in the `part` it is reading, "domain" or
+/// "problem".
+struct PddlReader {
+    part: &'static str,
+}
+
+impl PddlReader {
+    fn error<T>(&self, line: usize, message: String) -> Result<T, ParsePddlError> {
+        Err(ParsePddlError {
+            part: self.part,
+            line,
+            message,
+        })
+    }
+
+    /// Reads `text` as a single s-expression. Comments run from `;` to the end of the line.
+    fn parse(&self, text: &str) -> Result<Sexp, ParsePddlError> {
+        let mut stack: Vec<(Vec<Sexp>, usize)> = Vec::new();
+        let mut done = None;
+        for (i, line) in text.lines().enumerate() {
+            let n = i + 1;
+            let code = line.split(';').next().unwrap_or("");
+            let spaced = code.replace('(', " ( ").replace(')', " ) ");
+            for token in spaced.split_whitespace() {
+                if done.is_some() {
+                    return self.error(n, format!("unexpected `{}` after the definition", token));
+                }
+                match token {
+                    "(" => stack.push((Vec::new(), n)),
+                    ")" => {
+                        let (items, start) = match stack.pop() {
+                            Some(list) => list,
+                            None => return self.error(n, "unmatched `)`".to_string()),
+                        };
+                        let list = Sexp::List(items, start);
+                        match stack.last_mut() {
+                            Some((parent, _)) => parent.push(list),
+                            None => done = Some(list),
+                        }
+                    }
+                    atom if stack.is_empty() => {
+                        return self.error(n, format!("expected `(`, found `{}`", atom))
+                    }
+                    atom => stack
+                        .last_mut()
+                        .unwrap()
+                        .0
+                        .push(Sexp::Atom(atom.to_string(), n)),
+                }
+            }
+        }
+        match (stack.last(), done) {
+            (Some(&(_, start)), _) => self.error(start, "unmatched `(`".to_string()),
+            (None, Some(sexp)) => Ok(sexp),
+            (None, None) => self.error(1, "the text is empty".to_string()),
+        }
+    }
+
+    fn list<'s>(&self, sexp: &'s Sexp, what: &str) -> Result<&'s [Sexp], ParsePddlError> {
+        match sexp {
+            Sexp::List(items, _) => Ok(items),
+            Sexp::Atom(atom, line) => {
+                self.error(*line, format!("expected {}, found `{}`", what, atom))
+            }
+        }
+    }
+
+    fn atom<'s>(&self, sexp: &'s Sexp, what: &str) -> Result<&'s str, ParsePddlError> {
+        match sexp {
+            Sexp::Atom(atom, _) => Ok(atom),
+            Sexp::List(_, line) => self.error(*line, format!("expected {}, found a list", what)),
+        }
+    }
+
+    /// Reads `(define (<kind> <name>) <sections>...)`, returning the name and the sections.
+    fn definition<'s>(
+        &self,
+        sexp: &'s Sexp,
+        kind: &str,
+    ) -> Result<(&'s str, &'s [Sexp]), ParsePddlError> {
+        let items = self.list(sexp, "`(define ...)`")?;
+        match items {
+            [Sexp::Atom(define, _), header, sections @ ..] if define == "define" => {
+                match self.list(header, &format!("`({} <name>)`", kind))? {
+                    [Sexp::Atom(k, _), Sexp::Atom(name, _)] if k == kind => Ok((name, sections)),
+                    _ => self.error(header.line(), format!("expected `({} <name>)`", kind)),
+                }
+            }
+            _ => self.error(sexp.line(), "expected `(define ...)`".to_string()),
+        }
+    }
+
+    /// Reads a section `(<keyword> <items>...)`, returning the keyword and the items.
+    fn section<'s>(&self, sexp: &'s Sexp) -> Result<(&'s str, &'s [Sexp]), ParsePddlError> {
+        match self.list(sexp, "a section")? {
+            [Sexp::Atom(keyword, _), items @ ..] if keyword.starts_with(':') => {
+                Ok((keyword, items))
+            }
+            _ => self.error(
+                sexp.line(),
+                "expected a section such as `(:action ...)`".to_string(),
+            ),
+        }
+    }
+
+    /// Reads a conjunction of literals: `(and <literal>...)`, a single literal, or `()`. Terms
+    /// starting with `?` must be among `parameters`.
+    fn conjunction(
+        &self,
+        sexp: &Sexp,
+        parameters: &[&str],
+    ) -> Result<Vec<SchemaLiteral>, ParsePddlError> {
+        match self.list(sexp, "a formula")? {
+            [] => Ok(Vec::new()),
+            [Sexp::Atom(and, _), conjuncts @ ..] if and == "and" => conjuncts
+                .iter()
+                .map(|c| self.literal(c, parameters))
+                .collect(),
+            _ => Ok(vec![self.literal(sexp, parameters)?]),
+        }
+    }
+
+    /// Reads `(<predicate> <term>...)` or `(not (<predicate> <term>...))`.
+    fn literal(&self, sexp: &Sexp, parameters: &[&str]) -> Result<SchemaLiteral, ParsePddlError> {
+        let items = self.list(sexp, "a literal")?;
+        if let [Sexp::Atom(not, _), inner] = items {
+            if not == "not" {
+                let (_, predicate, terms) = self.literal(inner, parameters)?;
+                return Ok((false, predicate, terms));
+            }
+        }
+        let (predicate, terms) = match items.split_first() {
+            Some((predicate, terms)) => (self.atom(predicate, "a predicate")?, terms),
+            None => return self.error(sexp.line(), "expected a literal, found `()`".to_string()),
+        };
+        if ["and", "or", "not", "imply", "forall", "exists", "when"].contains(&predicate) {
+            return self.error(
+                sexp.line(),
+                format!("`{}` isn't supported here; only literals are", predicate),
+            );
+        }
+        let mut args = Vec::with_capacity(terms.len());
+        for term in terms {
+            let term = self.atom(term, "a term")?;
+            if term.starts_with('?') && !parameters.contains(&term) {
+                return self.error(sexp.line(), format!("unknown parameter `{}`", term));
+            }
+            args.push(term.to_string());
+        }
+        Ok((true, predicate.to_string(), args))
+    }
+}
+
+/// An action schema read from a PDDL domain.
+struct Schema {
+    name: String,
+    parameters: Vec<String>,
+    precondition: Vec<SchemaLiteral>,
+    effect: Vec<SchemaLiteral>,
+}
+
+/// The fluent `predicate(arg1, arg2, ...)`, or just `predicate` if it has no arguments.
+fn fluent(predicate: &str, args: &[&str]) -> String {
+    if args.is_empty() {
+        predicate.to_string()
+    } else {
+        format!("{}({})", predicate, args.join(", "))
+    }
+}
+
+/// The literal `literal` becomes with each parameter replaced by the object `binding` gives it.
+fn ground(literal: &SchemaLiteral, parameters: &[String], binding: &[&str]) -> Literal {
+    let (positive, predicate, terms) = literal;
+    let args: Vec<&str> = terms
+        .iter()
+        .map(|t| match parameters.iter().position(|p| p == t) {
+            Some(i) => binding[i],
+            None => t.as_str(),
+        })
+        .collect();
+    Literal::new(&fluent(predicate, &args), *positive)
+}
+
+fn read_domain(text: &str) -> Result<(String, Vec<Schema>), ParsePddlError> {
+    let reader = PddlReader { part: "domain" };
+    let sexp = reader.parse(text)?;
+    let (name, sections) = reader.definition(&sexp, "domain")?;
+    let mut schemas = Vec::new();
+    for section in sections {
+        let (keyword, items) = reader.section(section)?;
+        match keyword {
+            ":requirements" => {
+                for item in items {
+                    let requirement = reader.atom(item, "a requirement")?;
+                    if ![":strips", ":negative-preconditions"].contains(&requirement) {
+                        return reader.error(
+                            item.line(),
+                            format!("the requirement `{}` isn't supported", requirement),
+                        );
+                    }
+                }
+            }
+            ":predicates" => {}
+            ":action" => schemas.push(read_action(&reader, section.line(), items)?),
+            _ => {
+                return reader.error(
+                    section.line(),
+                    format!("the section `{}` isn't supported", keyword),
+                )
+            }
+        }
+    }
+    Ok((name.to_string(), schemas))
+}
+
+/// Reads the part of `(:action <name> :parameters (...) :precondition ... :effect ...)` after
+/// the keyword.
+fn read_action(reader: &PddlReader, line: usize, items: &[Sexp]) -> Result<Schema, ParsePddlError> {
+    let name = match items.first() {
+        Some(name) => reader.atom(name, "an action name")?,
+        None => return reader.error(line, "the action has no name".to_string()),
+    };
+    let mut schema = Schema {
+        name: name.to_string(),
+        parameters: Vec::new(),
+        precondition: Vec::new(),
+        effect: Vec::new(),
+    };
+    let mut rest = &items[1..];
+    while let [key, value, tail @ ..] = rest {
+        let parameters: Vec<&str> = schema.parameters.iter().map(String::as_str).collect();
+        match reader.atom(key, "a keyword such as `:effect`")? {
+            ":parameters" => {
+                for parameter in reader.list(value, "a list of parameters")? {
+                    let parameter = reader.atom(parameter, "a parameter")?;
+                    if !parameter.starts_with('?') {
+                        return reader.error(
+                            value.line(),
+                            format!("parameters start with `?`, but `{}` doesn't", parameter),
+                        );
+                    }
+                    schema.parameters.push(parameter.to_string());
+                }
+            }
+            ":precondition" => schema.precondition = reader.conjunction(value, &parameters)?,
+            ":effect" => schema.effect = reader.conjunction(value, &parameters)?,
+            other => {
+                return reader.error(key.line(), format!("unexpected `{}` in the action", other))
+            }
+        }
+        rest = tail;
+    }
+    if let Some(extra) = rest.first() {
+        return reader.error(extra.line(), "a keyword is missing its value".to_string());
+    }
+    Ok(schema)
+}
+
+/// The objects, initial fluents and goal of a PDDL problem.
+type ProblemParts = (Vec<String>, Vec<String>, Vec<Literal>);
+
+fn read_problem(text: &str, domain: &str) -> Result<ProblemParts, ParsePddlError> {
+    let reader = PddlReader { part: "problem" };
+    let sexp = reader.parse(text)?;
+    let (_, sections) = reader.definition(&sexp, "problem")?;
+    let (mut objects, mut initial, mut goal) = (Vec::new(), Vec::new(), None);
+    for section in sections {
+        let (keyword, items) = reader.section(section)?;
+        match keyword {
+            ":domain" => {
+                let name = match items {
+                    [name] => reader.atom(name, "a domain name")?,
+                    _ => {
+                        return reader
+                            .error(section.line(), "expected `(:domain <name>)`".to_string())
+                    }
+                };
+                if name != domain {
+                    return reader.error(
+                        section.line(),
+                        format!(
+                            "the problem is for `{}`, but the domain is `{}`",
+                            name, domain
+                        ),
+                    );
+                }
+            }
+            ":objects" => {
+                for object in items {
+                    objects.push(reader.atom(object, "an object")?.to_string());
+                }
+            }
+            ":init" => {
+                for item in items {
+                    let (positive, predicate, args) = reader.literal(item, &[])?;
+                    if !positive {
+                        return reader.error(
+                            item.line(),
+                            "the initial state lists only the fluents that hold".to_string(),
+                        );
+                    }
+                    let args: Vec<&str> = args.iter().map(String::as_str).collect();
+                    initial.push(fluent(&predicate, &args));
+                }
+            }
+            ":goal" => {
+                let formula = match items {
+                    [formula] => formula,
+                    _ => {
+                        return reader
+                            .error(section.line(), "expected `(:goal <formula>)`".to_string())
+                    }
+                };
+                let literals = reader.conjunction(formula, &[])?;
+                goal = Some(literals.iter().map(|l| ground(l, &[], &[])).collect());
+            }
+            _ => {
+                return reader.error(
+                    section.line(),
+                    format!("the section `{}` isn't supported", keyword),
+                )
+            }
+        }
+    }
+    match goal {
+        Some(goal) => Ok((objects, initial, goal)),
+        None => reader.error(sexp.line(), "the problem has no `(:goal ...)`".to_string()),
+    }
+}
+
+/// Every way of giving each of `n` parameters one of `objects`.
+fn bindings(objects: &[String], n: usize) -> Vec<Vec<&str>> {
+    let mut bindings = vec![Vec::new()];
+    for _ in 0..n {
+        bindings = bindings
+            .into_iter()
+            .flat_map(|binding| {
+                objects.iter().map(move |object| {
+                    let mut extended = binding.clone();
+                    extended.push(object.as_str());
+                    extended
+                })
+            })
+            .collect();
+    }
+    bindings
+}
+
+/// A plan written as PDDL, one action to a line: `Load(C1, P1, SFO)` becomes
+/// `(Load C1 P1 SFO)`.
+///
+/// ```
+/// # use aima_rust::planning::{forward_search, have_cake_and_eat_cake_too, plan_to_string};
+/// let plan = forward_search(&have_cake_and_eat_cake_too()).unwrap();
+/// assert_eq!(plan_to_string(&plan), "(Eat Cake)\n(Bake Cake)\n");
+/// ```
+pub fn plan_to_string(actions: &[Action]) -> String {
+    actions
+        .iter()
+        .map(|action| {
+            let words: Vec<&str> = action
+                .name
+                .split(|c: char| c == '(' || c == ')' || c == ',' || c.is_whitespace())
+                .filter(|word| !word.is_empty())
+                .collect();
+            format!("({})\n", words.join(" "))
+        })
+        .collect()
+}
+
+/// An action that carries `literal` over from one level of a planning graph to the next.
+fn persistence(literal: &Literal) -> Action {
+    Action {
+        name: format!("Persist({})", literal),
+        precondition: vec![literal.clone()],
+        effect: vec![literal.clone()],
+    }
+}
+
+/// A pair written in order, so it can be looked up either way round.
+fn ordered<T: Ord>(a: T, b: T) -> (T, T) {
+    if a <= b {
+        (a, b)
+    } else {
+        (b, a)
+    }
+}
+
+/// # Planning Graph
+///
+/// Alternating levels of literals and actions, grown from a state of a planning problem.
+/// Literal level zero holds the literals true in the state, including the negations of the
+/// fluents that don't hold. Action level `i` holds a persistence action for each literal at
+/// literal level `i`, named `Persist(...)`, that carries it over unchanged, followed by every
+/// action whose precondition appears at literal level `i` with no two of its literals mutually
+/// exclusive. Literal level `i + 1` holds their effects.
+///
+/// Two actions at a level are mutually exclusive, or _mutex_, if one's effect negates the
+/// other's effect or precondition, or a precondition of one is mutex with a precondition of the
+/// other. Two literals are mutex if they negate each other, or every pair of actions that could
+/// achieve them is mutex.
+///
+/// # Examples
+///
+/// Eating the cake and baking another can't happen at once, since one undoes the other. Baking
+/// needs the cake gone, so the two first appear together, and mutex, at action level one:
+///
+/// ```
+/// # use aima_rust::planning::{have_cake_and_eat_cake_too, PlanningGraph};
+/// let problem = have_cake_and_eat_cake_too();
+/// let mut graph = PlanningGraph::new(&problem, problem.initial());
+/// graph.expand();
+/// graph.expand();
+///
+/// let names = |level| -> Vec<(String, String)> {
+///     let mutexes = graph.action_mutexes(level);
+///     mutexes.iter().map(|(a, b)| (a.name.clone(), b.name.clone())).collect()
+/// };
+/// let eat_bake = ("Eat(Cake)".to_string(), "Bake(Cake)".to_string());
+/// assert!(!graph.actions(0).iter().any(|a| a.name == "Bake(Cake)"));
+/// assert!(!names(0).contains(&eat_bake));
+/// assert!(names(1).contains(&eat_bake));
+///
+/// // Having the cake and having eaten it are mutex after one step, but not after two.
+/// let (have, eaten) = (&problem.goal()[0], &problem.goal()[1]);
+/// assert!(graph.literal_mutexes(1).contains(&(eaten, have)));
+/// assert!(!graph.literal_mutexes(2).contains(&(eaten, have)));
+/// ```
+pub struct PlanningGraph<'a> {
+    problem: &'a PlanningProblem,
+    literals: Vec<BTreeSet<Literal>>,
+    literal_mutexes: Vec<HashSet<(Literal, Literal)>>,
+    actions: Vec<Vec<Action>>,
+    action_mutexes: Vec<HashSet<(usize, usize)>>,
+}
+
+impl<'a> PlanningGraph<'a> {
+    /// Creates a new planning graph with just literal level zero, given the problem and the state
+    /// to grow it from.
+    pub fn new(problem: &'a PlanningProblem, state: &State) -> Self {
+        let literals = problem
+            .fluents()
+            .into_iter()
+            .map(|f| {
+                let positive = state.contains(&f);
+                Literal::new(&f, positive)
+            })
+            .collect();
+        PlanningGraph {
+            problem,
+            literals: vec![literals],
+            literal_mutexes: vec![HashSet::new()],
+            actions: Vec::new(),
+            action_mutexes: Vec::new(),
+        }
+    }
+
+    /// The index of the last literal level. There is one fewer action level.
+    pub fn depth(&self) -> usize {
+        self.literals.len() - 1
+    }
+
+    /// The literals at literal level `level`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the graph hasn't been grown to `level`.
+    pub fn literals(&self, level: usize) -> &BTreeSet<Literal> {
+        &self.literals[level]
+    }
+
+    /// The actions at action level `level`, persistence actions first.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the graph hasn't been grown past `level`.
+    pub fn actions(&self, level: usize) -> &[Action] {
+        &self.actions[level]
+    }
+
+    /// The mutex pairs of literals at literal level `level`, each pair in order and the pairs
+    /// sorted.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the graph hasn't been grown to `level`.
+    pub fn literal_mutexes(&self, level: usize) -> Vec<(&Literal, &Literal)> {
+        let mut mutexes: Vec<_> = self.literal_mutexes[level]
+            .iter()
+            .map(|(a, b)| (a, b))
+            .collect();
+        mutexes.sort();
+        mutexes
+    }
+
+    /// The mutex pairs of actions at action level `level`, each pair in the order the actions
+    /// appear at the level and the pairs sorted likewise.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the graph hasn't been grown past `level`.
+    pub fn action_mutexes(&self, level: usize) -> Vec<(&Action, &Action)> {
+        let mut mutexes: Vec<_> = self.action_mutexes[level].iter().collect();
+        mutexes.sort();
+        let actions = &self.actions[level];
+        mutexes
+            .into_iter()
+            .map(|&(i, j)| (&actions[i], &actions[j]))
+            .collect()
+    }
+
+    /// Whether `a` and `b` are mutex at literal level `level`.
+    fn literals_mutex(&self, level: usize, a: &Literal, b: &Literal) -> bool {
+        self.literal_mutexes[level].contains(&ordered(a.clone(), b.clone()))
+    }
+
+    /// Whether the literals all appear at literal level `level` with no two of them mutex.
+    fn supports(&self, level: usize, literals: &[Literal]) -> bool {
+        literals.iter().enumerate().all(|(i, a)| {
+            self.literals[level].contains(a)
+                && literals[i + 1..]
+                    .iter()
+                    .all(|b| !self.literals_mutex(level, a, b))
+        })
+    }
+
+    /// Adds an action level and the literal level after it.
+    pub fn expand(&mut self) {
+        let level = self.depth();
+        let mut actions: Vec<Action> = self.literals[level].iter().map(persistence).collect();
+        actions.extend(
+            self.problem
+                .actions
+                .iter()
+                .filter(|a| self.supports(level, &a.precondition))
+                .cloned(),
+        );
+
+        let mut action_mutexes = HashSet::new();
+        for (i, a) in actions.iter().enumerate() {
+            for (j, b) in actions.iter().enumerate().skip(i + 1) {
+                if self.actions_conflict(level, a, b) {
+                    action_mutexes.insert((i, j));
+                }
+            }
+        }
+
+        let mut achievers: HashMap<&Literal, Vec<usize>> = HashMap::new();
+        for (i, a) in actions.iter().enumerate() {
+            for l in &a.effect {
+                achievers.entry(l).or_default().push(i);
+            }
+        }
+        let literals: BTreeSet<Literal> = achievers.keys().map(|&l| l.clone()).collect();
+        let mut literal_mutexes = HashSet::new();
+        for (i, a) in literals.iter().enumerate() {
+            for b in literals.iter().skip(i + 1) {
+                let inconsistent = achievers[a].iter().all(|&x| {
+                    achievers[b]
+                        .iter()
+                        .all(|&y| x != y && action_mutexes.contains(&ordered(x, y)))
+                });
+                if a.symbol == b.symbol || inconsistent {
+                    literal_mutexes.insert((a.clone(), b.clone()));
+                }
+            }
+        }
+
+        self.actions.push(actions);
+        self.action_mutexes.push(action_mutexes);
+        self.literals.push(literals);
+        self.literal_mutexes.push(literal_mutexes);
+    }
+
+    /// Whether two actions at action level `level` are mutex.
+    fn actions_conflict(&self, level: usize, a: &Action, b: &Action) -> bool {
+        let negates = |x: &Action, y: &Action| {
+            x.effect.iter().any(|l| {
+                let not = l.negate();
+                y.effect.contains(&not) || y.precondition.contains(&not)
+            })
+        };
+        negates(a, b)
+            || negates(b, a)
+            || a.precondition.iter().any(|p| {
+                b.precondition
+                    .iter()
+                    .any(|q| self.literals_mutex(level, p, q))
+            })
+    }
+
+    /// Grows the planning graph from `state` until it levels off.
+    fn leveled(problem: &'a PlanningProblem, state: &State) -> Self {
+        let mut graph = PlanningGraph::new(problem, state);
+        while !graph.leveled_off() {
+            graph.expand();
+        }
+        graph
+    }
+
+    /// The level at which each literal first appears. Literals that never appear have no level.
+    fn first_levels(&self) -> HashMap<Literal, usize> {
+        let mut levels = HashMap::new();
+        for (level, literals) in self.literals.iter().enumerate() {
+            for l in literals {
+                levels.entry(l.clone()).or_insert(level);
+            }
+        }
+        levels
+    }
+
+    /// Whether the last two literal levels are the same, mutexes included, so growing the graph
+    /// further changes nothing.
+    pub fn leveled_off(&self) -> bool {
+        let depth = self.depth();
+        depth > 0
+            && self.literals[depth] == self.literals[depth - 1]
+            && self.literal_mutexes[depth].len() == self.literal_mutexes[depth - 1].len()
+    }
+
+    /// Looks for a plan that makes `goals` hold at literal level `level`, working back a level
+    /// at a time. Returns the steps of the plan as indices of the actions at each action level,
+    /// persistence actions included. Goal sets found to have no plan at a level are remembered
+    /// in `nogoods` so they aren't searched again.
+    fn extract(
+        &self,
+        goals: &[Literal],
+        level: usize,
+        nogoods: &mut Vec<HashSet<Vec<Literal>>>,
+    ) -> Option<Vec<Vec<usize>>> {
+        if level == 0 {
+            return Some(Vec::new());
+        }
+        if nogoods[level].contains(goals) {
+            return None;
+        }
+        let plan = self.choose(goals, level - 1, &mut Vec::new(), nogoods);
+        if plan.is_none() {
+            nogoods[level].insert(goals.to_vec());
+        }
+        plan
+    }
+
+    /// Chooses pairwise non-mutex actions at action level `level`, extending `chosen`, that
+    /// together achieve `goals`, and then extracts a plan for their preconditions.
+    fn choose(
+        &self,
+        goals: &[Literal],
+        level: usize,
+        chosen: &mut Vec<usize>,
+        nogoods: &mut Vec<HashSet<Vec<Literal>>>,
+    ) -> Option<Vec<Vec<usize>>> {
+        let actions = &self.actions[level];
+        let (goal, rest) = match goals.split_first() {
+            Some(split) => split,
+            None => {
+                let preconditions: BTreeSet<Literal> = chosen
+                    .iter()
+                    .flat_map(|&i| actions[i].precondition.iter().cloned())
+                    .collect();
+                let subgoals: Vec<Literal> = preconditions.into_iter().collect();
+                let mut plan = self.extract(&subgoals, level, nogoods)?;
+                plan.push(chosen.clone());
+                return Some(plan);
+            }
+        };
+        if chosen.iter().any(|&i| actions[i].effect.contains(goal)) {
+            return self.choose(rest, level, chosen, nogoods);
+        }
+        for (i, action) in actions.iter().enumerate() {
+            let compatible = chosen
+                .iter()
+                .all(|&j| !self.action_mutexes[level].contains(&ordered(i, j)));
+            if action.effect.contains(goal) && compatible {
+                chosen.push(i);
+                if let Some(plan) = self.choose(rest, level, chosen, nogoods) {
+                    return Some(plan);
+                }
+                chosen.pop();
+            }
+        }
+        None
+    }
+}
+
+/// Figure 10.9
+///
+/// GraphPlan: grows a planning graph from the initial state until the goal literals all appear
+/// with no two of them mutex, then searches back through the graph for a plan, growing it
+/// another level whenever the search fails. Sets of goals found to have no plan at a level are
+/// remembered as _no-goods_. Once the graph has leveled off, a round that adds no no-goods at
+/// the level where it did means there is no plan, and `None` is returned.
+///
+/// The plan is a list of steps, each a set of actions that can be taken in any order.
+///
+/// # Examples
+///
+/// ```
+/// # use aima_rust::planning::{graph_plan, spare_tire};
+/// let plan = graph_plan(&spare_tire()).unwrap();
+/// let steps: Vec<Vec<_>> = plan
+///     .iter()
+///     .map(|step| step.iter().map(|a| a.name.as_str()).collect())
+///     .collect();
+///
+/// assert_eq!(steps.len(), 2);
+/// assert_eq!(steps[0].len(), 2);
+/// assert!(steps[0].contains(&"Remove(Flat, Axle)") && steps[0].contains(&"Remove(Spare, Trunk)"));
+/// assert_eq!(steps[1], vec!["PutOn(Spare, Axle)"]);
+/// ```
+///
+/// The steps of a plan can be taken in sequence:
+///
+/// ```
+/// # use aima_rust::planning::{air_cargo, graph_plan, have_cake_and_eat_cake_too};
+/// # use aima_rust::search::Problem;
+/// for (problem, steps) in vec![(air_cargo(), 3), (have_cake_and_eat_cake_too(), 2)] {
+///     let plan = graph_plan(&problem).unwrap();
+///     assert_eq!(plan.len(), steps);
+///     let sequence: Vec<_> = plan.into_iter().flatten().collect();
+///     assert!(problem.goal_test(&problem.execute(&sequence).unwrap()));
+/// }
+/// ```
+///
+/// Without baking, the cake can't be had and eaten too:
+///
+/// ```
+/// # use aima_rust::planning::{graph_plan, Action, PlanningProblem};
+/// let eat = Action::new("Eat(Cake)", &["Have(Cake)"], &["~Have(Cake)", "Eaten(Cake)"]);
+/// let problem = PlanningProblem::new(&["Have(Cake)"], &["Have(Cake)", "Eaten(Cake)"], vec![eat]);
+/// assert_eq!(graph_plan(&problem), None);
+/// ```
+pub fn graph_plan(problem: &PlanningProblem) -> Option<Vec<Vec<Action>>> {
+    let mut graph = PlanningGraph::new(problem, &problem.initial);
+    let mut nogoods = vec![HashSet::new()];
+    let mut goals: Vec<Literal> = problem.goal.clone();
+    goals.sort();
+    goals.dedup();
+    let mut leveled: Option<(usize, usize)> = None;
+    loop {
+        let depth = graph.depth();
+        if graph.supports(depth, &goals) {
+            if let Some(steps) = graph.extract(&goals, depth, &mut nogoods) {
+                let plan = steps
+                    .into_iter()
+                    .enumerate()
+                    .map(|(level, step)| {
+                        let persisting = graph.literals[level].len();
+                        step.into_iter()
+                            .filter(|&i| i >= persisting)
+                            .map(|i| graph.actions[level][i].clone())
+                            .collect::<Vec<_>>()
+                    })
+                    .filter(|step| !step.is_empty())
+                    .collect();
+                return Some(plan);
+            }
+        }
+        if graph.leveled_off() {
+            let level = leveled.map_or(depth, |(level, _)| level);
+            if !graph.supports(depth, &goals) || leveled == Some((level, nogoods[level].len())) {
+                return None;
+            }
+            leveled = Some((level, nogoods[level].len()));
+        }
+        graph.expand();
+        nogoods.push(HashSet::new());
+    }
+}
+
+/// The levels at which the goal literals first appear in the planning graph grown from
+/// `state`, or `None` if some goal never does.
+fn goal_levels(problem: &PlanningProblem, state: &State) -> Option<Vec<usize>> {
+    let mut graph = PlanningGraph::new(problem, state);
+    let mut levels = vec![None; problem.goal.len()];
+    loop {
+        let depth = graph.depth();
+        for (level, goal) in levels.iter_mut().zip(&problem.goal) {
+            if level.is_none() && graph.literals[depth].contains(goal) {
+                *level = Some(depth);
+            }
+        }
+        if levels.iter().all(Option::is_some) || graph.leveled_off() {
+            return levels.into_iter().collect();
+        }
+        graph.expand();
+    }
+}
+
+/// The max-level heuristic: the highest level at which a goal literal first appears in the
+/// planning graph grown from `state`. Each level takes at least one action to reach, so this
+/// never overestimates the length of a plan and is admissible. It is infinite if some goal can
+/// never be reached.
+///
+/// ```
+/// # use aima_rust::planning::{air_cargo, h_max};
+/// let problem = air_cargo();
+/// assert_eq!(h_max(&problem, problem.initial()), 3.0);
+/// ```
+pub fn h_max(problem: &PlanningProblem, state: &State) -> f64 {
+    goal_levels(problem, state).map_or(f64::INFINITY, |levels| {
+        levels.into_iter().max().unwrap_or(0) as f64
+    })
+}
+
+/// The level-sum heuristic: the sum over the goal literals of the level at which each first
+/// appears in the planning graph grown from `state`. Unlike `h_max`, this additive heuristic is
+/// not admissible, since one action can achieve several goals, but it is much better informed
+/// when goals are nearly independent, as they often are. It is infinite if some goal can never
+/// be reached.
+///
+/// # Examples
+///
+/// Both pieces of air cargo take a load, a flight and an unload to deliver, and the level sum
+/// counts them separately, while the number of goals left is at most two. Guided by the level
+/// sum, A* expands less than half as many states:
+///
+/// ```
+/// # use aima_rust::planning::{air_cargo, goal_count, h_level};
+/// # use aima_rust::search::{astar_search, InstrumentedProblem};
+/// let problem = air_cargo();
+/// assert_eq!(h_level(&problem, problem.initial()), 6.0);
+///
+/// let leveled = InstrumentedProblem::new(&problem);
+/// let plan = astar_search(&leveled, |s| h_level(&problem, s)).unwrap();
+/// assert_eq!(plan.path_cost, 6.0);
+///
+/// let counted = InstrumentedProblem::new(&problem);
+/// let plan = astar_search(&counted, |s| goal_count(&problem, s)).unwrap();
+/// assert_eq!(plan.path_cost, 6.0);
+///
+/// assert!(leveled.expansions() * 2 < counted.expansions());
+/// ```
+pub fn h_level(problem: &PlanningProblem, state: &State) -> f64 {
+    goal_levels(problem, state).map_or(f64::INFINITY, |levels| {
+        levels.into_iter().sum::<usize>() as f64
+    })
+}
+
+/// The goal-count heuristic: the number of goal literals that don't hold in `state`. It ignores
+/// how far away each goal is, and it isn't admissible either, since one action can achieve
+/// several goals.
+///
+/// ```
+/// # use aima_rust::planning::{goal_count, spare_tire};
+/// let problem = spare_tire();
+/// assert_eq!(goal_count(&problem, problem.initial()), 1.0);
+/// ```
+pub fn goal_count(problem: &PlanningProblem, state: &State) -> f64 {
+    problem.goal.iter().filter(|l| !holds(l, state)).count() as f64
+}
+
+/// A planning problem as a search problem over the states it reaches, with each action referred
+/// to by its number. The actions are indexed by the fluents their preconditions need, so finding
+/// the actions applicable in a state only looks at the actions its fluents can enable.
+struct ForwardPlanner<'a> {
+    problem: &'a PlanningProblem,
+    /// For each fluent, the actions whose precondition needs it to hold.
+    needing: HashMap<&'a str, Vec<usize>>,
+    /// The number of fluents the precondition of each action needs to hold.
+    needs: Vec<usize>,
+}
+
+impl<'a> ForwardPlanner<'a> {
+    /// Creates a new ForwardPlanner given the problem to search.
+    fn new(problem: &'a PlanningProblem) -> Self {
+        let mut needing: HashMap<&str, Vec<usize>> = HashMap::new();
+        let mut needs = Vec::with_capacity(problem.actions.len());
+        for (i, action) in problem.actions.iter().enumerate() {
+            let positive: Vec<_> = action.precondition.iter().filter(|l| l.positive).collect();
+            for l in &positive {
+                needing.entry(&l.symbol).or_default().push(i);
+            }
+            needs.push(positive.len());
+        }
+        ForwardPlanner {
+            problem,
+            needing,
+            needs,
+        }
+    }
+}
+
+impl Problem for ForwardPlanner<'_> {
+    type State = State;
+    type Action = usize;
+
+    fn initial_state(&self) -> State {
+        self.problem.initial.clone()
+    }
+
+    fn actions(&self, state: &State) -> Vec<usize> {
+        let mut held = vec![0; self.needs.len()];
+        for fluent in state {
+            for &i in self.needing.get(fluent.as_str()).into_iter().flatten() {
+                held[i] += 1;
+            }
+        }
+        (0..self.needs.len())
+            .filter(|&i| {
+                held[i] == self.needs[i]
+                    && self.problem.actions[i]
+                        .precondition
+                        .iter()
+                        .all(|l| l.positive || !state.contains(&l.symbol))
+            })
+            .collect()
+    }
+
+    fn result(&self, state: &State, &action: &usize) -> State {
+        self.problem.actions[action].apply(state)
+    }
+
+    fn goal_test(&self, state: &State) -> bool {
+        self.problem.goal_test(state)
+    }
+}
+
+/// Forward state-space planning: A* search from the initial state through the states the
+/// actions reach, guided by `h_max`. Since `h_max` is admissible, the plan found is a shortest
+/// one. Returns `None` if there is no plan.
+///
+/// # Examples
+///
+/// ```
+/// # use aima_rust::planning::{forward_search, have_cake_and_eat_cake_too, spare_tire};
+/// let plan = forward_search(&spare_tire()).unwrap();
+/// assert_eq!(plan.len(), 3);
+/// assert_eq!(plan[2].name, "PutOn(Spare, Axle)");
+///
+/// let plan = forward_search(&have_cake_and_eat_cake_too()).unwrap();
+/// let names: Vec<_> = plan.iter().map(|a| a.name.as_str()).collect();
+/// assert_eq!(names, vec!["Eat(Cake)", "Bake(Cake)"]);
+/// ```
+///
+/// The plans are as short as those breadth-first search finds:
+///
+/// ```
+/// # use aima_rust::planning::{air_cargo, forward_search, spare_tire};
+/// # use aima_rust::search::breadth_first_search;
+/// for problem in &[air_cargo(), spare_tire()] {
+///     let shortest = breadth_first_search(problem).unwrap().solution();
+///     assert_eq!(forward_search(problem).unwrap().len(), shortest.len());
+/// }
+/// ```
+pub fn forward_search(problem: &PlanningProblem) -> Option<Vec<Action>> {
+    search_report(problem, SearchDirection::Forward).plan
+}
+
+/// Backward state-space planning: A* search from the goal through subgoals, each regressed
+/// through an action that achieves part of the one before, until a subgoal holds in the initial
+/// state. Only actions relevant to the goal are ever considered. A planning graph grown once
+/// from the initial state, until it levels off, prunes subgoals it shows can't be reached, and
+/// guides the search by the highest level at which a literal of the subgoal appears. That is
+/// admissible, so the plan found is a shortest one. Returns `None` if there is no plan.
+///
+/// # Examples
+///
+/// ```
+/// # use aima_rust::planning::{air_cargo, backward_search};
+/// let problem = air_cargo();
+/// let plan = backward_search(&problem).unwrap();
+///
+/// assert_eq!(plan.len(), 6);
+/// let state = problem.execute(&plan).unwrap();
+/// assert!(state.contains("At(C1, JFK)") && state.contains("At(C2, SFO)"));
+/// ```
+pub fn backward_search(problem: &PlanningProblem) -> Option<Vec<Action>> {
+    search_report(problem, SearchDirection::Backward).plan
+}
+
+/// A planning problem as a search problem over subgoals: sets of literals that must all hold.
+/// The search starts from the goal and regresses through actions until it reaches a subgoal the
+/// initial state satisfies, so a plan is found last action first.
+struct BackwardPlanner<'a> {
+    problem: &'a PlanningProblem,
+    /// The level at which each literal first appears in the planning graph grown from the
+    /// initial state.
+    levels: HashMap<Literal, usize>,
+    /// The planning graph grown from the initial state, leveled off. No state the actions reach
+    /// has a literal missing from its last level, or two literals mutually exclusive there.
+    graph: PlanningGraph<'a>,
+}
+
+impl<'a> BackwardPlanner<'a> {
+    /// Creates a new BackwardPlanner given the problem to search.
+    fn new(problem: &'a PlanningProblem) -> Self {
+        let graph = PlanningGraph::leveled(problem, &problem.initial);
+        BackwardPlanner {
+            problem,
+            levels: graph.first_levels(),
+            graph,
+        }
+    }
+
+    /// The subgoal that must hold before taking action `i`, for `goal` to hold after it, or
+    /// `None` if the action doesn't help or can't be taken: it must achieve some literal of the
+    /// goal and undo none, and the subgoal must be reachable from the initial state, as far as
+    /// the planning graph can tell.
+    fn regress(&self, goal: &BTreeSet<Literal>, i: usize) -> Option<BTreeSet<Literal>> {
+        let action = &self.problem.actions[i];
+        let relevant = action.effect.iter().any(|l| goal.contains(l));
+        if !relevant || action.effect.iter().any(|l| goal.contains(&l.negate())) {
+            return None;
+        }
+        let mut regressed: BTreeSet<Literal> = goal
+            .iter()
+            .filter(|l| !action.effect.contains(l))
+            .cloned()
+            .collect();
+        regressed.extend(action.precondition.iter().cloned());
+        let literals: Vec<Literal> = regressed.iter().cloned().collect();
+        self.graph
+            .supports(self.graph.depth(), &literals)
+            .then_some(regressed)
+    }
+}
+
+impl Problem for BackwardPlanner<'_> {
+    type State = BTreeSet<Literal>;
+    type Action = usize;
+
+    fn initial_state(&self) -> BTreeSet<Literal> {
+        self.problem.goal.iter().cloned().collect()
+    }
+
+    fn actions(&self, goal: &BTreeSet<Literal>) -> Vec<usize> {
+        (0..self.problem.actions.len())
+            .filter(|&i| self.regress(goal, i).is_some())
+            .collect()
+    }
+
+    fn result(&self, goal: &BTreeSet<Literal>, &action: &usize) -> BTreeSet<Literal> {
+        self.regress(goal, action)
+            .expect("the action is relevant to the goal")
+    }
+
+    fn goal_test(&self, goal: &BTreeSet<Literal>) -> bool {
+        goal.iter().all(|l| holds(l, &self.problem.initial))
+    }
+}
+
+/// Which way `search_report` searches.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SearchDirection {
+    /// Forward from the initial state, as in `forward_search`.
+    Forward,
+    /// Backward from the goal, as in `backward_search`.
+    Backward,
+}
+
+/// What a run of `search_report` found.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PlanReport {
+    /// The plan, or `None` if there is none.
+    pub plan: Option<Vec<Action>>,
+    /// How many states or subgoals the search expanded.
+    pub expansions: usize,
+}
+
+/// Plans like `forward_search` or `backward_search`, depending on `direction`, and counts the
+/// states or subgoals the search expands.
+///
+/// # Examples
+///
+/// Regression only considers actions relevant to the subgoal, but its subgoals are partial
+/// states, and its heuristic reads levels off one planning graph rather than one per state. On
+/// air cargo the two planners find equally short plans and expand a similar number of nodes,
+/// each within twice the other:
+///
+/// ```
+/// # use aima_rust::planning::{air_cargo, search_report, SearchDirection};
+/// let problem = air_cargo();
+/// let forward = search_report(&problem, SearchDirection::Forward);
+/// let backward = search_report(&problem, SearchDirection::Backward);
+///
+/// assert_eq!(forward.plan.unwrap().len(), 6);
+/// assert_eq!(backward.plan.unwrap().len(), 6);
+/// assert!(backward.expansions < 2 * forward.expansions);
+/// assert!(forward.expansions < 2 * backward.expansions);
+/// ```
+pub fn search_report(problem: &PlanningProblem, direction: SearchDirection) -> PlanReport {
+    let (solution, expansions) = match direction {
+        SearchDirection::Forward => {
+            let planner = InstrumentedProblem::new(ForwardPlanner::new(problem));
+            let node = astar_search(&planner, |state| h_max(problem, state));
+            (node.map(|node| node.solution()), planner.expansions())
+        }
+        SearchDirection::Backward => {
+            let backward = BackwardPlanner::new(problem);
+            let planner = InstrumentedProblem::new(&backward);
+            let node = astar_search(&planner, |goal| {
+                goal.iter()
+                    .map(|l| backward.levels[l] as f64)
+                    .fold(0.0, f64::max)
+            });
+            let solution = node.map(|node| node.solution().into_iter().rev().collect());
+            (solution, planner.expansions())
+        }
+    };
+    PlanReport {
+        plan: solution.map(|actions: Vec<usize>| {
+            actions
+                .into_iter()
+                .map(|i| problem.actions[i].clone())
+                .collect()
+        }),
+        expansions,
+    }
+}
+
+/// One action of a `JobShopProblem`: how long it takes, and how many units of each resource it
+/// holds while it runs.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Activity {
+    pub name: String,
+    pub duration: u32,
+    pub uses: Vec<(String, usize)>,
+}
+
+/// # Job-Shop Scheduling
+///
+/// A scheduling problem: a set of actions, each with a duration, ordering constraints saying
+/// which actions must finish before others start, and reusable resources of limited capacity
+/// that actions hold while they run. Actions are numbered in the order they were added.
+///
+/// Problems are put together by chaining `resource`, `action` and `order`:
+///
+/// ```
+/// # use aima_rust::planning::JobShopProblem;
+/// let problem = JobShopProblem::new()
+///     .resource("Oven", 1)
+///     .action("Mix", 10, &[])
+///     .action("Bake", 30, &[("Oven", 1)])
+///     .order("Mix", "Bake");
+///
+/// assert_eq!(problem.activities()[1].duration, 30);
+/// assert_eq!(problem.predecessors(1), vec![0]);
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct JobShopProblem {
+    activities: Vec<Activity>,
+    orderings: Vec<(usize, usize)>,
+    resources: Vec<(String, usize)>,
+}
+
+impl JobShopProblem {
+    /// Creates a new problem with no actions or resources.
+    pub fn new() -> Self {
+        JobShopProblem::default()
+    }
+
+    /// Adds a resource with `capacity` units.
+    pub fn resource(mut self, name: &str, capacity: usize) -> Self {
+        self.resources.push((name.to_string(), capacity));
+        self
+    }
+
+    /// Adds an action taking `duration` time units and holding the given units of resources.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a resource hasn't been added, or the action needs more of it than there is.
+    pub fn action(mut self, name: &str, duration: u32, uses: &[(&str, usize)]) -> Self {
+        for &(resource, units) in uses {
+            let capacity = self.capacity(resource);
+            assert!(
+                units <= capacity,
+                "{} needs {} of {}, but there are only {}",
+                name,
+                units,
+                resource,
+                capacity
+            );
+        }
+        self.activities.push(Activity {
+            name: name.to_string(),
+            duration,
+            uses: uses.iter().map(|&(r, n)| (r.to_string(), n)).collect(),
+        });
+        self
+    }
+
+    /// Requires the action `before` to finish before the action `after` starts.
+    ///
+    /// # Panics
+    ///
+    /// Panics if either action hasn't been added.
+    pub fn order(mut self, before: &str, after: &str) -> Self {
+        let ordering = (self.index(before), self.index(after));
+        self.orderings.push(ordering);
+        self
+    }
+
+    /// The actions, in the order they were added.
+    pub fn activities(&self) -> &[Activity] {
+        &self.activities
+    }
+
+    /// The resources and their capacities.
+    pub fn resources(&self) -> &[(String, usize)] {
+        &self.resources
+    }
+
+    /// The actions that must finish before action `i` starts.
+    pub fn predecessors(&self, i: usize) -> Vec<usize> {
+        self.orderings
+            .iter()
+            .filter(|&&(_, after)| after == i)
+            .map(|&(before, _)| before)
+            .collect()
+    }
+
+    /// The number of the action called `name`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if there is no such action.
+    pub fn index(&self, name: &str) -> usize {
+        self.activities
+            .iter()
+            .position(|a| a.name == name)
+            .unwrap_or_else(|| panic!("there is no action called {}", name))
+    }
+
+    fn capacity(&self, resource: &str) -> usize {
+        self.resources
+            .iter()
+            .find(|(r, _)| r == resource)
+            .map(|&(_, capacity)| capacity)
+            .unwrap_or_else(|| panic!("there is no resource called {}", resource))
+    }
+
+    /// The units of `resource` that action `i` holds.
+    fn units(&self, i: usize, resource: &str) -> usize {
+        self.activities[i]
+            .uses
+            .iter()
+            .filter(|(r, _)| r == resource)
+            .map(|&(_, n)| n)
+            .sum()
+    }
+}
+
+/// Figure 11.1
+///
+/// The job-shop problem of assembling two cars. Each car has its engine added, then its wheels,
+/// and is then inspected. There is one engine hoist, one wheel station and two inspectors. The
+/// book's lug nuts, a consumable resource, are left out, since there are plenty of them.
+pub fn job_shop_problem() -> JobShopProblem {
+    JobShopProblem::new()
+        .resource("EngineHoists", 1)
+        .resource("WheelStations", 1)
+        .resource("Inspectors", 2)
+        .action("AddEngine1", 30, &[("EngineHoists", 1)])
+        .action("AddEngine2", 60, &[("EngineHoists", 1)])
+        .action("AddWheels1", 30, &[("WheelStations", 1)])
+        .action("AddWheels2", 15, &[("WheelStations", 1)])
+        .action("Inspect1", 10, &[("Inspectors", 1)])
+        .action("Inspect2", 10, &[("Inspectors", 1)])
+        .order("AddEngine1", "AddWheels1")
+        .order("AddWheels1", "Inspect1")
+        .order("AddEngine2", "AddWheels2")
+        .order("AddWheels2", "Inspect2")
+}
+
+/// The result of the critical path method.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CriticalPath {
+    /// The earliest each action can start.
+    pub earliest: Vec<u32>,
+    /// The latest each action can start without delaying the whole schedule.
+    pub latest: Vec<u32>,
+    /// The length of the shortest schedule the ordering constraints allow.
+    pub makespan: u32,
+}
+
+impl CriticalPath {
+    /// The actions with no slack, which make up the critical path: delaying any of them delays
+    /// the whole schedule.
+    pub fn critical(&self) -> Vec<usize> {
+        (0..self.earliest.len())
+            .filter(|&i| self.earliest[i] == self.latest[i])
+            .collect()
+    }
+}
+
+/// Figure 11.2
+///
+/// The critical path method: the earliest and latest start time of every action, taking the
+/// ordering constraints into account but not the resources. Each action can start as soon as all
+/// its predecessors have finished,
+///
+/// $$ ES(B) = \max_{A \prec B} ES(A) + Duration(A) $$
+///
+/// and must start in time for all its successors to,
+///
+/// $$ LS(A) = \min_{B \succ A} LS(B) - Duration(A) $$
+///
+/// # Panics
+///
+/// Panics if the ordering constraints form a cycle.
+///
+/// # Examples
+///
+/// ```
+/// # use aima_rust::planning::{critical_path, job_shop_problem};
+/// let problem = job_shop_problem();
+/// let path = critical_path(&problem);
+///
+/// assert_eq!(path.makespan, 85);
+/// assert_eq!(path.earliest[problem.index("Inspect1")], 60);
+/// assert_eq!(path.latest[problem.index("AddEngine1")], 15);
+/// let critical: Vec<_> = path.critical().iter().map(|&i| &problem.activities()[i].name).collect();
+/// assert_eq!(critical, vec!["AddEngine2", "AddWheels2", "Inspect2"]);
+/// ```
+pub fn critical_path(problem: &JobShopProblem) -> CriticalPath {
+    let n = problem.activities.len();
+    let durations: Vec<u32> = problem.activities.iter().map(|a| a.duration).collect();
+    let order = topological_sort(&(0..n).collect::<Vec<_>>(), &problem.orderings)
+        .expect("the ordering constraints form a cycle");
+
+    let mut earliest = vec![0; n];
+    for &i in &order {
+        earliest[i] = problem
+            .predecessors(i)
+            .iter()
+            .map(|&p| earliest[p] + durations[p])
+            .max()
+            .unwrap_or(0);
+    }
+    let makespan = (0..n)
+        .map(|i| earliest[i] + durations[i])
+        .max()
+        .unwrap_or(0);
+    let mut latest = vec![0; n];
+    for &i in order.iter().rev() {
+        let finish = problem
+            .orderings
+            .iter()
+            .filter(|&&(before, _)| before == i)
+            .map(|&(_, after)| latest[after])
+            .min()
+            .unwrap_or(makespan);
+        latest[i] = finish - durations[i];
+    }
+    CriticalPath {
+        earliest,
+        latest,
+        makespan,
+    }
+}
+
+/// How a schedule played out in `simulate_schedule`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Timeline {
+    /// When each action actually started.
+    pub starts: Vec<u32>,
+    /// When each action finished.
+    pub finishes: Vec<u32>,
+    /// When the last action finished.
+    pub makespan: u32,
+    /// The actions that started later than planned, waiting for a predecessor or a resource.
+    pub delayed: Vec<usize>,
+    /// How each resource was used.
+    pub usage: Vec<ResourceUsage>,
+}
+
+/// How one resource was used over a `Timeline`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ResourceUsage {
+    pub resource: String,
+    /// The number of units the resource has.
+    pub capacity: usize,
+    /// The number of units in use from each time on, as a list of `(time, units)` changes
+    /// starting at time zero.
+    pub changes: Vec<(u32, usize)>,
+}
+
+impl Timeline {
+    /// The fraction of the capacity of `resource` in use over the whole timeline.
+    ///
+    /// # Panics
+    ///
+    /// Panics if there is no such resource.
+    pub fn utilization(&self, resource: &str) -> f64 {
+        let usage = self
+            .usage
+            .iter()
+            .find(|usage| usage.resource == resource)
+            .unwrap_or_else(|| panic!("there is no resource called {}", resource));
+        let busy: u32 = usage
+            .changes
+            .iter()
+            .zip(
+                usage
+                    .changes
+                    .iter()
+                    .skip(1)
+                    .map(|&(t, _)| t)
+                    .chain(Some(self.makespan)),
+            )
+            .map(|(&(start, units), end)| (end - start) * units as u32)
+            .sum();
+        busy as f64 / (usage.capacity as u32 * self.makespan).max(1) as f64
+    }
+}
+
+/// Plays out a schedule, given as a planned start time for each action, with a discrete-event
+/// clock. At each event, every action whose planned time has come starts, unless it has to wait
+/// for a predecessor to finish or for enough units of a resource to come free; actions planned
+/// to start earlier get first claim, and ties go to the lower-numbered action. The clock then
+/// jumps to the next time an action finishes or is planned to start.
+///
+/// A schedule that respects every constraint plays out exactly as planned. One that doesn't has
+/// some actions delayed, which shows up in `Timeline::delayed`.
+///
+/// # Panics
+///
+/// Panics if `schedule` doesn't give a time for every action, or the ordering constraints form a
+/// cycle.
+///
+/// # Examples
+///
+/// The critical path ignores resources, so it plans both engines to go in at once. There is only
+/// one hoist, so the second engine has to wait:
+///
+/// ```
+/// # use aima_rust::planning::{critical_path, job_shop_problem, simulate_schedule};
+/// let problem = job_shop_problem();
+/// let path = critical_path(&problem);
+///
+/// let timeline = simulate_schedule(&problem, &path.earliest);
+/// assert!(timeline.delayed.contains(&problem.index("AddEngine2")));
+/// assert_eq!(timeline.starts[problem.index("AddEngine2")], 30);
+/// assert_eq!(timeline.makespan, 115);
+/// assert!(timeline.utilization("EngineHoists") > 0.75);
+///
+/// // The realized timeline respects the resources, and so plays out as it is.
+/// let replayed = simulate_schedule(&problem, &timeline.starts);
+/// assert!(replayed.delayed.is_empty());
+/// assert_eq!(replayed.starts, timeline.starts);
+/// ```
+///
+/// Without the resources, the critical path is a valid schedule and its makespan is achieved:
+///
+/// ```
+/// # use aima_rust::planning::{critical_path, simulate_schedule, JobShopProblem};
+/// let problem = JobShopProblem::new()
+///     .action("AddEngine1", 30, &[])
+///     .action("AddEngine2", 60, &[])
+///     .action("AddWheels1", 30, &[])
+///     .action("AddWheels2", 15, &[])
+///     .order("AddEngine1", "AddWheels1")
+///     .order("AddEngine2", "AddWheels2");
+/// let path = critical_path(&problem);
+///
+/// let timeline = simulate_schedule(&problem, &path.earliest);
+/// assert!(timeline.delayed.is_empty());
+/// assert_eq!(timeline.makespan, path.makespan);
+/// ```
+pub fn simulate_schedule(problem: &JobShopProblem, schedule: &[u32]) -> Timeline {
+    let n = problem.activities.len();
+    assert_eq!(
+        schedule.len(),
+        n,
+        "the schedule needs a time for every action"
+    );
+    let mut priority: Vec<usize> = (0..n).collect();
+    priority.sort_by_key(|&i| (schedule[i], i));
+
+    let mut starts: Vec<Option<u32>> = vec![None; n];
+    let mut finishes: Vec<Option<u32>> = vec![None; n];
+    let mut time = 0;
+    while starts.iter().any(Option::is_none) {
+        for &i in &priority {
+            if starts[i].is_some() || schedule[i] > time {
+                continue;
+            }
+            let ready = problem
+                .predecessors(i)
+                .iter()
+                .all(|&p| finishes[p].is_some_and(|f| f <= time));
+            let free = problem.activities[i].uses.iter().all(|(resource, units)| {
+                let in_use: usize = (0..n)
+                    .filter(|&j| {
+                        starts[j].is_some_and(|s| s <= time)
+                            && finishes[j].is_some_and(|f| f > time)
+                    })
+                    .map(|j| problem.units(j, resource))
+                    .sum();
+                in_use + units <= problem.capacity(resource)
+            });
+            if ready && free {
+                starts[i] = Some(time);
+                finishes[i] = Some(time + problem.activities[i].duration);
+            }
+        }
+        let next = finishes
+            .iter()
+            .flatten()
+            .chain(schedule)
+            .filter(|&&t| t > time)
+            .min();
+        match next {
+            Some(&next) => time = next,
+            None if starts.iter().any(Option::is_none) => {
+                panic!("the ordering constraints form a cycle")
+            }
+            None => {}
+        }
+    }
+
+    let starts: Vec<u32> = starts.into_iter().flatten().collect();
+    let finishes: Vec<u32> = finishes.into_iter().flatten().collect();
+    let makespan = finishes.iter().cloned().max().unwrap_or(0);
+    let mut times: Vec<u32> = starts.iter().chain(&finishes).cloned().collect();
+    times.push(0);
+    times.sort_unstable();
+    times.dedup();
+    let usage = problem
+        .resources
+        .iter()
+        .map(|(resource, capacity)| {
+            let mut changes: Vec<(u32, usize)> = Vec::new();
+            for &t in times.iter().filter(|&&t| t < makespan || makespan == 0) {
+                let units = (0..n)
+                    .filter(|&j| starts[j] <= t && t < finishes[j])
+                    .map(|j| problem.units(j, resource))
+                    .sum();
+                if changes.last().is_none_or(|&(_, last)| last != units) {
+                    changes.push((t, units));
+                }
+            }
+            ResourceUsage {
+                resource: resource.clone(),
+                capacity: *capacity,
+                changes,
+            }
+        })
+        .collect();
+    Timeline {
+        delayed: (0..n).filter(|&i| starts[i] > schedule[i]).collect(),
+        starts,
+        finishes,
+        makespan,
+        usage,
+    }
+}
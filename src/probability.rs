@@ -0,0 +1,1089 @@
+//! # Probability
+//!
+//! This module covers material in Chapters 13, 14 & 15.
+//!
+//! ## Index of Algorithms
+//!
+//! | **Figure** | **Name**                          | **Module Link**
+//! |:-----------|:----------------------------------|:-------------------------------
+//! | 13         | Discrete Probability Distribution | `ProbDist`                                               |
+//! | 15         | Sensor Model                      | `SensorModel`                                            |
+//! | 15         | Recursive Bayesian Estimation     | `belief_update`                                          |
+//! | 13         | KL-Divergence                     | `kl_divergence`                                          |
+//! | 13         | Cross-Entropy                     | `cross_entropy`                                          |
+//! | 13         | Full Joint Distribution           | `JointProbDist`                                          |
+//! | 14.2       | Bayesian Network                  | `BayesNet`                                               |
+//! | 14         | D-Separation                      | `BayesNet::d_separated`                                  |
+//! | 14.9       | Enumeration-Ask                   | `enumeration_ask`                                        |
+//! | 14         | Mutual Information                | `mutual_information`                                     |
+//! | 15         | Dynamic Bayesian Network          | `DBN`                                                    |
+//! | 15         | DBN Filtering                     | `dbn_filter`                                             |
+//!
+use crate::util::topological_sort;
+use std::collections::{HashMap, HashSet};
+use std::error::Error;
+use std::fmt;
+use std::hash::Hash;
+
+/// Returned when a distribution can't be normalized because every outcome has probability zero.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ZeroProbabilityError;
+
+impl fmt::Display for ZeroProbabilityError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "every outcome has zero probability, so the distribution can't be normalized"
+        )
+    }
+}
+
+impl Error for ZeroProbabilityError {}
+
+/// # Discrete Probability Distribution
+///
+/// A discrete probability distribution over the values of a single random variable. Values are
+/// kept in the order they were first given a probability.
+///
+/// # Examples
+///
+/// ```
+/// # use aima_rust::probability::ProbDist;
+/// let mut coin = ProbDist::new("Flip");
+/// coin.set("heads", 25.0);
+/// coin.set("tails", 75.0);
+/// coin.normalize().unwrap();
+///
+/// assert_eq!(coin.prob(&"heads"), 0.25);
+/// assert_eq!(coin.prob(&"edge"), 0.0);
+/// assert_eq!(coin.values(), &["heads", "tails"]);
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct ProbDist<T: Eq + Hash> {
+    pub var_name: String,
+    values: Vec<T>,
+    probs: HashMap<T, f64>,
+}
+
+impl<T: Clone + Eq + Hash> ProbDist<T> {
+    /// Creates an empty distribution for the variable `var_name`.
+    pub fn new(var_name: &str) -> Self {
+        ProbDist {
+            var_name: var_name.to_string(),
+            values: Vec::new(),
+            probs: HashMap::new(),
+        }
+    }
+
+    /// Creates a distribution that gives every one of `values` the same probability.
+    pub fn uniform<I: IntoIterator<Item = T>>(var_name: &str, values: I) -> Self {
+        let mut dist = ProbDist::new(var_name);
+        for value in values {
+            dist.set(value, 1.0);
+        }
+        let _ = dist.normalize();
+        dist
+    }
+
+    /// Sets the probability, or unnormalized frequency, of `value`.
+    pub fn set(&mut self, value: T, p: f64) {
+        if !self.probs.contains_key(&value) {
+            self.values.push(value.clone());
+        }
+        self.probs.insert(value, p);
+    }
+
+    /// The probability of `value`, which is zero for values that were never set.
+    pub fn prob(&self, value: &T) -> f64 {
+        self.probs.get(value).cloned().unwrap_or(0.0)
+    }
+
+    /// The values of the variable, in the order they were first set.
+    pub fn values(&self) -> &[T] {
+        &self.values
+    }
+
+    /// Scales the probabilities so that they sum to one.
+    pub fn normalize(&mut self) -> Result<(), ZeroProbabilityError> {
+        let total: f64 = self.probs.values().sum();
+        if total == 0.0 {
+            return Err(ZeroProbabilityError);
+        }
+        for p in self.probs.values_mut() {
+            *p /= total;
+        }
+        Ok(())
+    }
+
+    /// Performs one step of Bayesian updating: multiplies the probability of each value by the
+    /// `likelihood` of the evidence given that value, and renormalizes.
+    ///
+    /// $$ P(X \mid e) = \alpha \, P(e \mid X) \, P(X) $$
+    ///
+    /// If the evidence is impossible under every value, there is no posterior; the distribution
+    /// is left untouched and an error is returned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use aima_rust::probability::{ProbDist, ZeroProbabilityError};
+    /// let mut room = ProbDist::uniform("Room", vec!["kitchen", "hall", "study"]);
+    ///
+    /// // A sensor that hears the kettle is much more likely to do so in the kitchen.
+    /// room.update_with_likelihood(|&r| if r == "kitchen" { 0.9 } else { 0.1 }).unwrap();
+    ///
+    /// assert!(room.prob(&"kitchen") > room.prob(&"hall"));
+    /// assert!((room.prob(&"kitchen") - 0.9 / 1.1).abs() < 1e-9);
+    /// assert!((room.values().iter().map(|r| room.prob(r)).sum::<f64>() - 1.0).abs() < 1e-9);
+    ///
+    /// let before = room.clone();
+    /// assert_eq!(room.update_with_likelihood(|_| 0.0), Err(ZeroProbabilityError));
+    /// assert_eq!(room, before);
+    /// ```
+    pub fn update_with_likelihood<F: Fn(&T) -> f64>(
+        &mut self,
+        likelihood: F,
+    ) -> Result<(), ZeroProbabilityError> {
+        let posterior: Vec<f64> = self
+            .values
+            .iter()
+            .map(|value| self.prob(value) * likelihood(value))
+            .collect();
+        let total: f64 = posterior.iter().sum();
+        if total == 0.0 {
+            return Err(ZeroProbabilityError);
+        }
+        for (value, p) in self.values.iter().zip(posterior) {
+            self.probs.insert(value.clone(), p / total);
+        }
+        Ok(())
+    }
+}
+
+/// A sensor model: how likely each percept is in each state, $P(e \mid x)$.
+pub trait SensorModel<State, Percept> {
+    /// The probability of perceiving `percept` in `state`.
+    fn likelihood(&self, state: &State, percept: &Percept) -> f64;
+}
+
+impl<State, Percept, F: Fn(&State, &Percept) -> f64> SensorModel<State, Percept> for F {
+    fn likelihood(&self, state: &State, percept: &Percept) -> f64 {
+        self(state, percept)
+    }
+}
+
+/// One step of recursive Bayesian estimation, or filtering, over a discrete state space. The
+/// belief is first projected forward through `transition`, which lists the probability of each
+/// successor of a state, and then conditioned on `percept` using `model`:
+///
+/// $$ P(X_{t+1} \mid e_{1:t+1}) = \alpha \, P(e_{t+1} \mid X_{t+1}) \sum_{x_t} P(X_{t+1} \mid x_t) \, P(x_t \mid e_{1:t}) $$
+///
+/// Returns an error if the percept is impossible in every state the belief allows.
+///
+/// # Examples
+///
+/// An agent on a ring of five cells stays put, and its sensor reports the right cell nine times in
+/// ten:
+///
+/// ```
+/// # use aima_rust::probability::{belief_update, ProbDist};
+/// let sensor = |cell: &usize, reading: &usize| if cell == reading { 0.9 } else { 0.025 };
+/// let stay = |cell: &usize| vec![(1.0, *cell)];
+///
+/// let mut belief = ProbDist::uniform("Cell", 0..5);
+/// for _ in 0..3 {
+///     belief = belief_update(&belief, &2, &sensor, stay).unwrap();
+/// }
+///
+/// assert!(belief.prob(&2) > 0.99);
+/// ```
+pub fn belief_update<State, Percept, M, T>(
+    prior: &ProbDist<State>,
+    percept: &Percept,
+    model: &M,
+    transition: T,
+) -> Result<ProbDist<State>, ZeroProbabilityError>
+where
+    State: Clone + Eq + Hash,
+    M: SensorModel<State, Percept>,
+    T: Fn(&State) -> Vec<(f64, State)>,
+{
+    let mut predicted = ProbDist::new(&prior.var_name);
+    for state in prior.values() {
+        for (p, next) in transition(state) {
+            let mass = predicted.prob(&next) + p * prior.prob(state);
+            predicted.set(next, mass);
+        }
+    }
+    predicted.update_with_likelihood(|state| model.likelihood(state, percept))?;
+    Ok(predicted)
+}
+
+/// The Kullback-Leibler divergence of `q` from `p`, in nats:
+///
+/// $$ D_{KL}(P \parallel Q) = \sum_x P(x) \ln \frac{P(x)}{Q(x)} $$
+///
+/// It measures how much is lost by using `q` in place of `p`, such as an approximate answer in
+/// place of the exact one: it is zero only when the two agree, and grows as they drift apart.
+/// It is not symmetric.
+///
+/// Both distributions should be normalized. By convention, outcomes with $P(x) = 0$ contribute
+/// nothing, and if `q` gives probability zero to an outcome that `p` supports, the divergence is
+/// infinite.
+///
+/// # Examples
+///
+/// ```
+/// # use aima_rust::probability::{kl_divergence, ProbDist};
+/// let coin = |heads: f64| {
+///     let mut dist = ProbDist::new("Coin");
+///     dist.set("heads", heads);
+///     dist.set("tails", 1.0 - heads);
+///     dist
+/// };
+/// let fair = coin(0.5);
+///
+/// assert_eq!(kl_divergence(&fair, &fair), 0.0);
+/// assert!(kl_divergence(&fair, &coin(0.6)) < kl_divergence(&fair, &coin(0.9)));
+/// assert_eq!(kl_divergence(&fair, &coin(1.0)), f64::INFINITY);
+/// assert!(kl_divergence(&coin(1.0), &fair).is_finite());
+/// ```
+pub fn kl_divergence<T: Clone + Eq + Hash>(p: &ProbDist<T>, q: &ProbDist<T>) -> f64 {
+    p.values()
+        .iter()
+        .map(|x| (p.prob(x), q.prob(x)))
+        .filter(|&(px, _)| px > 0.0)
+        .map(|(px, qx)| px * (px / qx).ln())
+        .sum()
+}
+
+/// The cross-entropy of `q` relative to `p`, in nats:
+///
+/// $$ H(P, Q) = -\sum_x P(x) \ln Q(x) $$
+///
+/// This is the entropy of `p` plus `kl_divergence(p, q)`, so for a fixed `p` it is smallest when
+/// `q` equals `p`. The same conventions apply: outcomes `p` rules out contribute nothing, and
+/// the cross-entropy is infinite if `q` rules out an outcome `p` supports.
+///
+/// # Examples
+///
+/// ```
+/// # use aima_rust::probability::{cross_entropy, kl_divergence, ProbDist};
+/// let p = ProbDist::uniform("Die", 1..=4);
+/// let mut q = ProbDist::new("Die");
+/// for (face, prob) in (1..=4).zip(&[0.1, 0.2, 0.3, 0.4]) {
+///     q.set(face, *prob);
+/// }
+///
+/// let entropy = 4f64.ln();
+/// assert!((cross_entropy(&p, &p) - entropy).abs() < 1e-12);
+/// assert!((cross_entropy(&p, &q) - (entropy + kl_divergence(&p, &q))).abs() < 1e-12);
+/// ```
+pub fn cross_entropy<T: Clone + Eq + Hash>(p: &ProbDist<T>, q: &ProbDist<T>) -> f64 {
+    p.values()
+        .iter()
+        .map(|x| (p.prob(x), q.prob(x)))
+        .filter(|&(px, _)| px > 0.0)
+        .map(|(px, qx)| -px * qx.ln())
+        .sum()
+}
+
+/// An assignment of values to some of the boolean variables of a `BayesNet`.
+pub type Event = HashMap<String, bool>;
+
+/// Builds an `Event` from pairs of variable names and values.
+///
+/// # Examples
+///
+/// ```
+/// # use aima_rust::probability::event;
+/// let e = event(&[("JohnCalls", true), ("MaryCalls", false)]);
+/// assert_eq!(e["MaryCalls"], false);
+/// ```
+pub fn event(assignments: &[(&str, bool)]) -> Event {
+    assignments
+        .iter()
+        .map(|&(variable, value)| (variable.to_string(), value))
+        .collect()
+}
+
+/// Returned by `BayesNet::to_joint` when the net has more variables than its joint cap allows.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TooManyVariablesError {
+    pub variables: usize,
+    pub cap: usize,
+}
+
+impl fmt::Display for TooManyVariablesError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "the net has {} variables, but joint distributions are capped at {}",
+            self.variables, self.cap
+        )
+    }
+}
+
+impl Error for TooManyVariablesError {}
+
+/// A node of a `BayesNet`: a boolean variable with its parents and conditional probability
+/// table. The table gives $P(X = true \mid parents)$ for each assignment to the parents, listed
+/// in the same order as `parents`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct BayesNode {
+    pub variable: String,
+    pub parents: Vec<String>,
+    cpt: HashMap<Vec<bool>, f64>,
+}
+
+impl BayesNode {
+    /// The conditional probability $P(X = value \mid parents)$, with the parents' values read
+    /// from `event`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `event` doesn't assign every parent.
+    pub fn p(&self, value: bool, event: &Event) -> f64 {
+        let key: Vec<bool> = self.parents.iter().map(|parent| event[parent]).collect();
+        let p_true = self.cpt[&key];
+        if value {
+            p_true
+        } else {
+            1.0 - p_true
+        }
+    }
+}
+
+/// Figure 14.2
+///
+/// # Bayesian Network
+///
+/// A Bayesian network represents a full joint distribution over its variables compactly, as a
+/// directed acyclic graph in which each variable depends directly only on its parents:
+///
+/// $$ P(x_1, \ldots, x_n) = \prod_{i=1}^n P(x_i \mid parents(X_i)) $$
+///
+/// Variables are boolean and may be added in any order. The nodes are kept in topological order,
+/// parents before children, with `util::topological_sort`; that is the order inference visits
+/// them in, and a node that would close a cycle is caught as soon as it is added.
+///
+/// # Examples
+///
+/// ```
+/// # use aima_rust::probability::{burglary, event};
+/// let net = burglary();
+///
+/// assert_eq!(net.parents("Alarm"), vec!["Burglary", "Earthquake"]);
+/// assert_eq!(net.children("Alarm"), vec!["JohnCalls", "MaryCalls"]);
+///
+/// // P(j, m, a, ¬b, ¬e), from page 514.
+/// let e = event(&[
+///     ("Burglary", false),
+///     ("Earthquake", false),
+///     ("Alarm", true),
+///     ("JohnCalls", true),
+///     ("MaryCalls", true),
+/// ]);
+/// assert!((net.joint_probability(&e) - 0.000628).abs() < 1e-6);
+/// ```
+///
+/// Adding the children first gives the same order, and the same answers:
+///
+/// ```
+/// # use aima_rust::probability::{burglary, enumeration_ask, event, BayesNet};
+/// let calls_first = BayesNet::new()
+///     .node("MaryCalls", &["Alarm"], &[(&[true], 0.70), (&[false], 0.01)])
+///     .node("JohnCalls", &["Alarm"], &[(&[true], 0.90), (&[false], 0.05)])
+///     .node(
+///         "Alarm",
+///         &["Burglary", "Earthquake"],
+///         &[
+///             (&[true, true], 0.95),
+///             (&[true, false], 0.94),
+///             (&[false, true], 0.29),
+///             (&[false, false], 0.001),
+///         ],
+///     )
+///     .node("Earthquake", &[], &[(&[], 0.002)])
+///     .node("Burglary", &[], &[(&[], 0.001)]);
+///
+/// assert_eq!(
+///     calls_first.variables(),
+///     vec!["Earthquake", "Burglary", "Alarm", "MaryCalls", "JohnCalls"]
+/// );
+/// let position = |v| calls_first.variables().iter().position(|&w| w == v);
+/// for variable in burglary().variables() {
+///     for parent in calls_first.parents(variable) {
+///         assert!(position(parent) < position(variable));
+///     }
+/// }
+/// let evidence = event(&[("JohnCalls", true), ("MaryCalls", true)]);
+/// let burglary = enumeration_ask("Burglary", &evidence, &calls_first);
+/// assert!((burglary.prob(&true) - 0.284).abs() < 1e-3);
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct BayesNet {
+    nodes: Vec<BayesNode>,
+    joint_cap: usize,
+}
+
+impl Default for BayesNet {
+    fn default() -> Self {
+        BayesNet::new()
+    }
+}
+
+impl BayesNet {
+    /// Creates a new, empty network. Its joint distribution is capped at 20 variables.
+    pub fn new() -> Self {
+        BayesNet {
+            nodes: Vec::new(),
+            joint_cap: 20,
+        }
+    }
+
+    /// Adds the variable `variable` with the given parents and conditional probability table,
+    /// listing $P(X = true \mid parents)$ for each assignment to the parents.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the variable is already in the net, it would close a cycle, or the table doesn't
+    /// cover every assignment to the parents. A parent may be added later, but inference panics
+    /// if it never is.
+    pub fn node(mut self, variable: &str, parents: &[&str], cpt: &[(&[bool], f64)]) -> Self {
+        assert!(
+            self.index(variable).is_none(),
+            "{} is already in the net",
+            variable
+        );
+        let cpt: HashMap<Vec<bool>, f64> = cpt
+            .iter()
+            .map(|&(values, p)| (values.to_vec(), p))
+            .collect();
+        assert!(
+            cpt.len() == 1 << parents.len() && cpt.keys().all(|k| k.len() == parents.len()),
+            "the table of {} must cover every assignment to its parents",
+            variable
+        );
+        self.nodes.push(BayesNode {
+            variable: variable.to_string(),
+            parents: parents.iter().map(|p| p.to_string()).collect(),
+            cpt,
+        });
+
+        let variables: Vec<&str> = self.variables();
+        let edges: Vec<(&str, &str)> = self
+            .nodes
+            .iter()
+            .flat_map(|node| {
+                node.parents
+                    .iter()
+                    .map(move |parent| (parent.as_str(), node.variable.as_str()))
+            })
+            .collect();
+        let order: Vec<usize> = topological_sort(&variables, &edges)
+            .unwrap_or_else(|_| panic!("adding {} closes a cycle", variable))
+            .iter()
+            .map(|v| self.index(v).unwrap())
+            .collect();
+        let mut nodes: Vec<Option<BayesNode>> = self.nodes.drain(..).map(Some).collect();
+        self.nodes = order
+            .into_iter()
+            .map(|i| nodes[i].take().unwrap())
+            .collect();
+        self
+    }
+
+    /// Sets the largest number of variables `to_joint` will build a joint distribution for.
+    pub fn with_joint_cap(mut self, cap: usize) -> Self {
+        self.joint_cap = cap;
+        self
+    }
+
+    fn index(&self, variable: &str) -> Option<usize> {
+        self.nodes.iter().position(|node| node.variable == variable)
+    }
+
+    /// The nodes of the net, in topological order.
+    pub fn nodes(&self) -> &[BayesNode] {
+        &self.nodes
+    }
+
+    /// The variables of the net, in topological order.
+    pub fn variables(&self) -> Vec<&str> {
+        self.nodes
+            .iter()
+            .map(|node| node.variable.as_str())
+            .collect()
+    }
+
+    /// The node for `variable`, if it is in the net.
+    pub fn variable_node(&self, variable: &str) -> Option<&BayesNode> {
+        self.index(variable).map(|i| &self.nodes[i])
+    }
+
+    /// The parents of `variable`.
+    pub fn parents(&self, variable: &str) -> Vec<&str> {
+        self.variable_node(variable)
+            .map(|node| node.parents.iter().map(String::as_str).collect())
+            .unwrap_or_default()
+    }
+
+    /// The children of `variable`, in topological order.
+    pub fn children(&self, variable: &str) -> Vec<&str> {
+        self.nodes
+            .iter()
+            .filter(|node| node.parents.iter().any(|p| p == variable))
+            .map(|node| node.variable.as_str())
+            .collect()
+    }
+
+    /// The probability of `event`, which must assign every variable of the net.
+    pub fn joint_probability(&self, event: &Event) -> f64 {
+        self.nodes
+            .iter()
+            .map(|node| node.p(event[&node.variable], event))
+            .product()
+    }
+
+    /// Multiplies out the conditional probability tables into the full joint distribution, so
+    /// inference results can be checked against it directly. The joint has $2^n$ entries, so
+    /// this is only meant for small nets: above the cap set with `with_joint_cap`, an error is
+    /// returned instead.
+    ///
+    /// # Examples
+    ///
+    /// Summing out the joint recovers the marginals implied by the tables, and conditioning it
+    /// agrees with `enumeration_ask`:
+    ///
+    /// ```
+    /// # use aima_rust::probability::{burglary, enumeration_ask, event, TooManyVariablesError};
+    /// let net = burglary();
+    /// let joint = net.to_joint().unwrap();
+    ///
+    /// assert!((joint.prob(&event(&[])) - 1.0).abs() < 1e-12);
+    /// assert!((joint.prob(&event(&[("Burglary", true)])) - 0.001).abs() < 1e-12);
+    /// // P(a) = Σ_b,e P(a | b, e) P(b) P(e)
+    /// let p_alarm = 0.95 * 0.001 * 0.002 + 0.94 * 0.001 * 0.998
+    ///     + 0.29 * 0.999 * 0.002 + 0.001 * 0.999 * 0.998;
+    /// assert!((joint.prob(&event(&[("Alarm", true)])) - p_alarm).abs() < 1e-12);
+    ///
+    /// let evidence = event(&[("JohnCalls", true), ("MaryCalls", true)]);
+    /// let from_joint = joint.ask("Burglary", &evidence).unwrap();
+    /// let from_net = enumeration_ask("Burglary", &evidence, &net);
+    /// assert!((from_joint.prob(&true) - from_net.prob(&true)).abs() < 1e-12);
+    ///
+    /// assert_eq!(
+    ///     net.with_joint_cap(4).to_joint().unwrap_err(),
+    ///     TooManyVariablesError { variables: 5, cap: 4 }
+    /// );
+    /// ```
+    pub fn to_joint(&self) -> Result<JointProbDist, TooManyVariablesError> {
+        let n = self.nodes.len();
+        if n > self.joint_cap {
+            return Err(TooManyVariablesError {
+                variables: n,
+                cap: self.joint_cap,
+            });
+        }
+        let mut probs = HashMap::new();
+        for bits in 0..1usize << n {
+            let values: Vec<bool> = (0..n).map(|i| bits & (1 << i) != 0).collect();
+            let event: Event = self
+                .nodes
+                .iter()
+                .zip(&values)
+                .map(|(node, &value)| (node.variable.clone(), value))
+                .collect();
+            probs.insert(values, self.joint_probability(&event));
+        }
+        Ok(JointProbDist {
+            variables: self.nodes.iter().map(|n| n.variable.clone()).collect(),
+            probs,
+        })
+    }
+
+    /// Tests whether `x` and `y` are d-separated given the variables `given`: whether every
+    /// undirected path between them is blocked. A path is blocked at a chain or fork whose middle
+    /// variable is observed, or at a collider ($\to Z \leftarrow$) when neither it nor any of its
+    /// descendants is observed. D-separated variables are conditionally independent in every
+    /// distribution the net can represent, so this answers independence queries from the
+    /// structure alone, without looking at the tables.
+    ///
+    /// This uses the Bayes-ball, or reachable, algorithm: a search from `x` over pairs of a
+    /// variable and the direction the path arrived from, which finds every variable connected to
+    /// `x` by an active path in time linear in the size of the net.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use aima_rust::probability::burglary;
+    /// # use std::collections::HashSet;
+    /// let net = burglary();
+    /// let given = |variables: &[&str]| -> HashSet<String> {
+    ///     variables.iter().map(|v| v.to_string()).collect()
+    /// };
+    ///
+    /// // A collider: the causes of the alarm are independent until it, or a call it causes, is
+    /// // observed, and then one explains the other away.
+    /// assert!(net.d_separated("Burglary", "Earthquake", &given(&[])));
+    /// assert!(!net.d_separated("Burglary", "Earthquake", &given(&["Alarm"])));
+    /// assert!(!net.d_separated("Burglary", "Earthquake", &given(&["JohnCalls"])));
+    ///
+    /// // A chain, blocked by observing the middle.
+    /// assert!(!net.d_separated("Burglary", "JohnCalls", &given(&[])));
+    /// assert!(net.d_separated("Burglary", "JohnCalls", &given(&["Alarm"])));
+    ///
+    /// // A fork, blocked by observing the common cause.
+    /// assert!(!net.d_separated("JohnCalls", "MaryCalls", &given(&[])));
+    /// assert!(net.d_separated("JohnCalls", "MaryCalls", &given(&["Alarm"])));
+    /// assert!(!net.d_separated("JohnCalls", "MaryCalls", &given(&["Burglary"])));
+    /// ```
+    ///
+    /// On this net, d-separation agrees with the independencies found in the joint distribution
+    /// for every pair of variables and every set of observed variables:
+    ///
+    /// ```
+    /// # use aima_rust::probability::{burglary, conditionally_independent};
+    /// # use std::collections::HashSet;
+    /// let net = burglary();
+    /// let variables = net.variables();
+    /// for (i, x) in variables.iter().enumerate() {
+    ///     for y in &variables[i + 1..] {
+    ///         let others: Vec<&str> =
+    ///             variables.iter().cloned().filter(|v| v != x && v != y).collect();
+    ///         for bits in 0..1 << others.len() {
+    ///             let given: Vec<&str> = (0..others.len())
+    ///                 .filter(|j| bits & (1 << j) != 0)
+    ///                 .map(|j| others[j])
+    ///                 .collect();
+    ///             let set: HashSet<String> = given.iter().map(|v| v.to_string()).collect();
+    ///             assert_eq!(
+    ///                 net.d_separated(x, y, &set),
+    ///                 conditionally_independent(&net, x, y, &given, 1e-9),
+    ///                 "{} and {} given {:?}",
+    ///                 x,
+    ///                 y,
+    ///                 given
+    ///             );
+    ///         }
+    ///     }
+    /// }
+    /// ```
+    pub fn d_separated(&self, x: &str, y: &str, given: &HashSet<String>) -> bool {
+        let mut observed_or_ancestor: HashSet<&str> = HashSet::new();
+        let mut frontier: Vec<&str> = given.iter().map(String::as_str).collect();
+        while let Some(variable) = frontier.pop() {
+            if observed_or_ancestor.insert(variable) {
+                frontier.extend(self.parents(variable));
+            }
+        }
+
+        // Each entry is a variable and whether the path reached it from a child, heading up.
+        let mut visited: HashSet<(&str, bool)> = HashSet::new();
+        let mut frontier: Vec<(&str, bool)> = vec![(x, true)];
+        while let Some((variable, up)) = frontier.pop() {
+            if !visited.insert((variable, up)) {
+                continue;
+            }
+            let observed = given.contains(variable);
+            if variable == y && !observed {
+                return false;
+            }
+            if up && !observed {
+                frontier.extend(self.parents(variable).into_iter().map(|p| (p, true)));
+                frontier.extend(self.children(variable).into_iter().map(|c| (c, false)));
+            } else if !up {
+                if !observed {
+                    frontier.extend(self.children(variable).into_iter().map(|c| (c, false)));
+                }
+                if observed_or_ancestor.contains(variable) {
+                    frontier.extend(self.parents(variable).into_iter().map(|p| (p, true)));
+                }
+            }
+        }
+        true
+    }
+}
+
+/// # Full Joint Distribution
+///
+/// A full joint distribution over boolean variables, giving the probability of every complete
+/// assignment. Any query can be answered from it by summing entries, at a cost exponential in
+/// the number of variables.
+#[derive(Clone, Debug, PartialEq)]
+pub struct JointProbDist {
+    variables: Vec<String>,
+    probs: HashMap<Vec<bool>, f64>,
+}
+
+impl JointProbDist {
+    /// The variables of the distribution.
+    pub fn variables(&self) -> &[String] {
+        &self.variables
+    }
+
+    /// The probability of `event`, summing over the variables it leaves unassigned.
+    pub fn prob(&self, event: &Event) -> f64 {
+        self.probs
+            .iter()
+            .filter(|(values, _)| {
+                self.variables
+                    .iter()
+                    .zip(values.iter())
+                    .all(|(variable, value)| event.get(variable).is_none_or(|v| v == value))
+            })
+            .map(|(_, p)| p)
+            .sum()
+    }
+
+    /// The distribution of `variable` given `evidence`, found by summing out the rest of the
+    /// joint. Returns an error if the evidence is impossible.
+    pub fn ask(
+        &self,
+        variable: &str,
+        evidence: &Event,
+    ) -> Result<ProbDist<bool>, ZeroProbabilityError> {
+        let mut dist = ProbDist::new(variable);
+        for value in [false, true] {
+            let mut e = evidence.clone();
+            e.insert(variable.to_string(), value);
+            dist.set(value, self.prob(&e));
+        }
+        dist.normalize()?;
+        Ok(dist)
+    }
+}
+
+/// Figure 14.9
+///
+/// # Enumeration-Ask
+///
+/// Answers the query $P(X \mid e)$ exactly by summing the product of conditional probabilities
+/// over every assignment to the hidden variables, one variable at a time in topological order:
+///
+/// $$ P(X \mid e) = \alpha \sum_y \prod_i P(x_i \mid parents(X_i)) $$
+///
+/// # Examples
+///
+/// ```
+/// # use aima_rust::probability::{burglary, enumeration_ask, event};
+/// let net = burglary();
+/// let evidence = event(&[("JohnCalls", true), ("MaryCalls", true)]);
+///
+/// let burglary = enumeration_ask("Burglary", &evidence, &net);
+/// assert!((burglary.prob(&true) - 0.284).abs() < 1e-3);
+/// ```
+///
+/// # Panics
+///
+/// Panics if the evidence is impossible.
+pub fn enumeration_ask(variable: &str, evidence: &Event, net: &BayesNet) -> ProbDist<bool> {
+    fn enumerate_all(nodes: &[BayesNode], event: &mut Event) -> f64 {
+        match nodes.split_first() {
+            None => 1.0,
+            Some((node, rest)) => match event.get(&node.variable).cloned() {
+                Some(value) => node.p(value, event) * enumerate_all(rest, event),
+                None => [false, true]
+                    .iter()
+                    .map(|&value| {
+                        event.insert(node.variable.clone(), value);
+                        let p = node.p(value, event) * enumerate_all(rest, event);
+                        event.remove(&node.variable);
+                        p
+                    })
+                    .sum(),
+            },
+        }
+    }
+
+    let mut dist = ProbDist::new(variable);
+    for value in [false, true] {
+        let mut event = evidence.clone();
+        event.insert(variable.to_string(), value);
+        dist.set(value, enumerate_all(&net.nodes, &mut event));
+    }
+    dist.normalize().expect("the evidence should be possible");
+    dist
+}
+
+/// Figure 14.2
+///
+/// The burglary network: an alarm that goes off on burglaries and, less reliably, earthquakes,
+/// and two neighbours, John and Mary, who may call when they hear it.
+pub fn burglary() -> BayesNet {
+    BayesNet::new()
+        .node("Burglary", &[], &[(&[], 0.001)])
+        .node("Earthquake", &[], &[(&[], 0.002)])
+        .node(
+            "Alarm",
+            &["Burglary", "Earthquake"],
+            &[
+                (&[true, true], 0.95),
+                (&[true, false], 0.94),
+                (&[false, true], 0.29),
+                (&[false, false], 0.001),
+            ],
+        )
+        .node(
+            "JohnCalls",
+            &["Alarm"],
+            &[(&[true], 0.90), (&[false], 0.05)],
+        )
+        .node(
+            "MaryCalls",
+            &["Alarm"],
+            &[(&[true], 0.70), (&[false], 0.01)],
+        )
+}
+
+/// The conditional mutual information between the variables `x` and `y` of `net` given the
+/// variables `given`, in nats:
+///
+/// $$ I(X; Y \mid Z) = \sum_{x,y,z} P(x, y, z) \ln \frac{P(z) \, P(x, y, z)}{P(x, z) \, P(y, z)} $$
+///
+/// It is zero exactly when $X$ and $Y$ are independent given $Z$, and grows the more one
+/// tells about the other. It is computed from the full joint distribution of the net, so it is
+/// only practical for small nets.
+///
+/// # Panics
+///
+/// Panics if the net has more variables than its joint cap; see `BayesNet::to_joint`.
+pub fn mutual_information(net: &BayesNet, x: &str, y: &str, given: &[&str]) -> f64 {
+    let joint = net.to_joint().expect("the net should be small enough");
+    let mut information = 0.0;
+    for bits in 0..1usize << given.len() {
+        let z = event(
+            &given
+                .iter()
+                .enumerate()
+                .map(|(i, &variable)| (variable, bits & (1 << i) != 0))
+                .collect::<Vec<_>>(),
+        );
+        let p_z = joint.prob(&z);
+        for x_value in [false, true] {
+            for y_value in [false, true] {
+                let with = |assignments: &[(&str, bool)]| {
+                    let mut e = z.clone();
+                    e.extend(event(assignments));
+                    joint.prob(&e)
+                };
+                let p_xyz = with(&[(x, x_value), (y, y_value)]);
+                if p_xyz > 0.0 {
+                    let p_xz = with(&[(x, x_value)]);
+                    let p_yz = with(&[(y, y_value)]);
+                    information += p_xyz * (p_z * p_xyz / (p_xz * p_yz)).ln();
+                }
+            }
+        }
+    }
+    information
+}
+
+/// Tests whether `x` and `y` are conditionally independent given `given` in `net`, by checking
+/// that their `mutual_information` is at most `epsilon`. Floating-point error keeps the mutual
+/// information of independent variables from being exactly zero, so `epsilon` should be small
+/// but positive, such as `1e-9`. The same small-network limitation applies.
+///
+/// # Examples
+///
+/// John and Mary call independently of each other once the alarm is known, but either call
+/// makes the other more likely when it isn't:
+///
+/// ```
+/// # use aima_rust::probability::{burglary, conditionally_independent, mutual_information};
+/// let net = burglary();
+///
+/// assert!(mutual_information(&net, "JohnCalls", "MaryCalls", &["Alarm"]).abs() < 1e-12);
+/// assert!(conditionally_independent(&net, "JohnCalls", "MaryCalls", &["Alarm"], 1e-9));
+///
+/// assert!(mutual_information(&net, "JohnCalls", "MaryCalls", &[]) > 1e-3);
+/// assert!(!conditionally_independent(&net, "JohnCalls", "MaryCalls", &[], 1e-9));
+///
+/// // Burglaries and earthquakes are independent until the alarm is observed.
+/// assert!(conditionally_independent(&net, "Burglary", "Earthquake", &[], 1e-9));
+/// assert!(!conditionally_independent(&net, "Burglary", "Earthquake", &["Alarm"], 1e-9));
+/// ```
+///
+/// # Panics
+///
+/// Panics if the net has more variables than its joint cap; see `BayesNet::to_joint`.
+pub fn conditionally_independent(
+    net: &BayesNet,
+    x: &str,
+    y: &str,
+    given: &[&str],
+    epsilon: f64,
+) -> bool {
+    mutual_information(net, x, y, given) <= epsilon
+}
+
+/// The name of `variable` in slice `t` of an unrolled `DBN`.
+fn slice_name(variable: &str, t: usize) -> String {
+    format!("{}_{}", variable, t)
+}
+
+/// # Dynamic Bayesian Network
+///
+/// A process that unfolds over time steps $t = 0, 1, 2, \ldots$, described by a two-slice
+/// template: a `BayesNet` whose unprimed variables, such as `Rain`, are the state at time $t$,
+/// and whose primed variables, such as `Rain'` and `Umbrella'`, are the state and evidence at
+/// time $t + 1$. The unprimed variables have no parents, and their tables give the prior over
+/// the state at time 0. The primed variables may have parents in either slice, and the same
+/// tables apply at every step.
+///
+/// An HMM is the special case with a single state variable; a DBN can split the state into
+/// several variables that each depend on only a few others, which keeps the tables small.
+///
+/// # Examples
+///
+/// The umbrella world of figure 15.2, whose unrolled network for five days has the initial state
+/// and then a state and an observation for each day:
+///
+/// ```
+/// # use aima_rust::probability::{BayesNet, DBN};
+/// let umbrella = DBN::new(
+///     BayesNet::new()
+///         .node("Rain", &[], &[(&[], 0.5)])
+///         .node("Rain'", &["Rain"], &[(&[true], 0.7), (&[false], 0.3)])
+///         .node("Umbrella'", &["Rain'"], &[(&[true], 0.9), (&[false], 0.2)]),
+/// );
+///
+/// let unrolled = umbrella.unroll(5);
+/// assert_eq!(unrolled.nodes().len(), 11);
+/// assert_eq!(unrolled.parents("Rain_3"), vec!["Rain_2"]);
+/// assert_eq!(unrolled.parents("Umbrella_3"), vec!["Rain_3"]);
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct DBN {
+    template: BayesNet,
+}
+
+impl DBN {
+    /// Creates a new DBN given its two-slice template.
+    ///
+    /// # Panics
+    ///
+    /// Panics if an unprimed variable has parents, or has no primed counterpart to carry it
+    /// into the next slice.
+    pub fn new(template: BayesNet) -> Self {
+        for node in template.nodes() {
+            if !node.variable.ends_with('\'') {
+                assert!(
+                    node.parents.is_empty(),
+                    "{} is in the first slice, so it can't have parents",
+                    node.variable
+                );
+                assert!(
+                    template.index(&format!("{}'", node.variable)).is_some(),
+                    "{} has no counterpart {}' in the next slice",
+                    node.variable,
+                    node.variable
+                );
+            }
+        }
+        DBN { template }
+    }
+
+    /// Unrolls the network for `steps` time steps into an ordinary `BayesNet`, naming each
+    /// variable after its time step, as in `Rain_0` or `Umbrella_3`. It has the state variables
+    /// of time 0 followed by every primed variable once for each step, so its size grows
+    /// linearly with `steps`.
+    pub fn unroll(&self, steps: usize) -> BayesNet {
+        // Adds `node` to `net` under a new name and parents, with the same table.
+        let copy = |net: BayesNet, node: &BayesNode, variable: String, parents: Vec<String>| {
+            let parents: Vec<&str> = parents.iter().map(String::as_str).collect();
+            let rows: Vec<(&[bool], f64)> = node
+                .cpt
+                .iter()
+                .map(|(values, &p)| (values.as_slice(), p))
+                .collect();
+            net.node(&variable, &parents, &rows)
+        };
+
+        let mut net = BayesNet::new();
+        let (current, next): (Vec<&BayesNode>, Vec<&BayesNode>) = self
+            .template
+            .nodes()
+            .iter()
+            .partition(|node| !node.variable.ends_with('\''));
+        for node in current {
+            net = copy(net, node, slice_name(&node.variable, 0), Vec::new());
+        }
+        for t in 1..=steps {
+            for node in &next {
+                let parents: Vec<String> = node
+                    .parents
+                    .iter()
+                    .map(|parent| match parent.strip_suffix('\'') {
+                        Some(parent) => slice_name(parent, t),
+                        None => slice_name(parent, t - 1),
+                    })
+                    .collect();
+                let variable = slice_name(node.variable.trim_end_matches('\''), t);
+                net = copy(net, node, variable, parents);
+            }
+        }
+        net
+    }
+}
+
+/// Filtering in a `DBN`: the distribution of the state variable `variable` at time $t$ given
+/// the evidence of every step so far, $P(X_t \mid e_{1:t})$. The evidence for step $i$ is
+/// `evidence[i - 1]`, keyed by the unprimed names of the evidence variables, and $t$ is the
+/// number of steps of evidence.
+///
+/// The answer is exact: the network is unrolled for $t$ steps and queried with
+/// `enumeration_ask`. The unrolled network grows with $t$, and enumeration sums over every
+/// earlier state, so the cost grows exponentially with the length of the sequence. That is fine
+/// for checking answers on a few steps, but for long sequences or large DBNs the usual choice
+/// is particle filtering (figure 15.17), which approximates the same distribution from samples
+/// at a constant cost per step.
+///
+/// # Examples
+///
+/// The umbrella world as a DBN agrees with filtering the same model as an HMM, step by step:
+///
+/// ```
+/// # use aima_rust::probability::{belief_update, dbn_filter, event, BayesNet, Event, ProbDist, DBN};
+/// let umbrella = DBN::new(
+///     BayesNet::new()
+///         .node("Rain", &[], &[(&[], 0.5)])
+///         .node("Rain'", &["Rain"], &[(&[true], 0.7), (&[false], 0.3)])
+///         .node("Umbrella'", &["Rain'"], &[(&[true], 0.9), (&[false], 0.2)]),
+/// );
+/// let seen = [true, true, false, true, true];
+/// let evidence: Vec<Event> = seen.iter().map(|&u| event(&[("Umbrella", u)])).collect();
+///
+/// let sensor = |&rain: &bool, &umbrella: &bool| {
+///     let p = if rain { 0.9 } else { 0.2 };
+///     if umbrella { p } else { 1.0 - p }
+/// };
+/// let transition = |&rain: &bool| {
+///     let p = if rain { 0.7 } else { 0.3 };
+///     vec![(p, true), (1.0 - p, false)]
+/// };
+/// let mut belief = ProbDist::uniform("Rain", vec![true, false]);
+/// for (t, umbrella_seen) in seen.iter().enumerate() {
+///     belief = belief_update(&belief, umbrella_seen, &sensor, &transition).unwrap();
+///     let filtered = dbn_filter(&umbrella, "Rain", &evidence[..=t]);
+///     assert!((filtered.prob(&true) - belief.prob(&true)).abs() < 1e-12);
+/// }
+///
+/// // P(R_2 | u_1, u_2), from page 573.
+/// assert!((dbn_filter(&umbrella, "Rain", &evidence[..2]).prob(&true) - 0.883).abs() < 1e-3);
+/// ```
+///
+/// # Panics
+///
+/// Panics if the evidence is impossible.
+pub fn dbn_filter(dbn: &DBN, variable: &str, evidence: &[Event]) -> ProbDist<bool> {
+    let t = evidence.len();
+    let mut observed = Event::new();
+    for (i, step) in evidence.iter().enumerate() {
+        for (name, &value) in step {
+            observed.insert(slice_name(name, i + 1), value);
+        }
+    }
+    enumeration_ask(&slice_name(variable, t), &observed, &dbn.unroll(t))
+}
@@ -0,0 +1,1236 @@
+//! # Reinforcement Learning
+//!
+//! This module covers material in Chapter 21, reinforcement learning.
+//!
+//! ## Index of Algorithms
+//!
+//! | **Figure** | **Name**                          | **Module Link**
+//! |:-----------|:----------------------------------|:-------------------------------
+//! | 21         | RL-Environment                    | `RLEnvironment`                                          |
+//! | 21         | MDP Environment                   | `MdpEnvironment`                                         |
+//! | 21.2       | Passive-ADP-Agent                 | `PassiveADPAgent`                                        |
+//! | 21.4       | Passive-TD-Agent                  | `PassiveTDAgent`                                         |
+//! | 21         | TD(λ)                             | `PassiveTDLambdaAgent`                                   |
+//! | 21.8       | Q-Learning-Agent                  | `QLearningAgent`                                         |
+//! | 21         | SARSA                             | `SarsaAgent`                                             |
+//! | 21         | Approximate Q-Learning            | `ApproxQLearningAgent`                                   |
+//!
+use crate::agents::table::Table;
+use crate::mdp::MDP;
+use crate::util::Rng;
+use rand::seq::SliceRandom;
+use rand::Rng as _;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::hash::Hash;
+use std::marker::PhantomData;
+
+/// The most steps an agent takes in one training episode before moving on to the next, so that a
+/// policy that never reaches a terminal state can't keep an episode running forever.
+pub const MAX_EPISODE_STEPS: usize = 10_000;
+
+/// An environment a reinforcement learning agent can act in without knowing how it works. The
+/// agent only sees the states it visits and the rewards it collects; unlike an `MDP`, there is no
+/// transition model to consult.
+pub trait RLEnvironment {
+    type State: Clone + Eq + Hash;
+    type Action: Clone + Eq + Hash;
+
+    /// Starts a new episode and returns the state it begins in. Any randomness in the episode is
+    /// drawn from a generator seeded with `seed`.
+    fn reset(&mut self, seed: u64) -> Self::State;
+
+    /// The actions available in `state`.
+    fn actions(&self, state: &Self::State) -> Vec<Self::Action>;
+
+    /// Takes `action` in the current state and returns the next state, the reward received on
+    /// reaching it, and whether the episode is over.
+    fn step(&mut self, action: &Self::Action) -> (Self::State, f64, bool);
+}
+
+/// Runs an `MDP` as an `RLEnvironment`, sampling each move from its transition model.
+///
+/// The reward returned by `step` is $R(s')$ for the state reached, plus any transition reward
+/// $F(s, a, s')$, and an episode ends when it reaches a terminal state. The reward of the initial
+/// state is never handed out, but as every episode starts there it doesn't change which policy is
+/// best. Stepping from a terminal state leaves the environment where it is, with no reward, and
+/// reports the episode over.
+///
+/// # Examples
+///
+/// ```
+/// # use aima_rust::mdp::{sequential_decision_environment, Direction};
+/// # use aima_rust::rl::{MdpEnvironment, RLEnvironment};
+/// let mut env = MdpEnvironment::new(sequential_decision_environment());
+///
+/// assert_eq!(env.reset(7), (0, 0));
+/// let (state, reward, done) = env.step(&Direction::Up);
+/// assert!([(0, 1), (0, 0), (1, 0)].contains(&state));
+/// assert_eq!(reward, -0.04);
+/// assert!(!done);
+/// ```
+#[derive(Clone, Debug)]
+pub struct MdpEnvironment<M: MDP> {
+    mdp: M,
+    state: M::State,
+    rng: Rng,
+}
+
+impl<M: MDP> MdpEnvironment<M> {
+    /// Creates a new environment given the `mdp` to run.
+    pub fn new(mdp: M) -> Self {
+        let state = mdp.initial_state();
+        MdpEnvironment {
+            mdp,
+            state,
+            rng: Rng::seeded(0),
+        }
+    }
+
+    /// The process being run.
+    pub fn mdp(&self) -> &M {
+        &self.mdp
+    }
+}
+
+impl<M: MDP> RLEnvironment for MdpEnvironment<M> {
+    type State = M::State;
+    type Action = M::Action;
+
+    fn reset(&mut self, seed: u64) -> M::State {
+        self.rng = Rng::seeded(seed);
+        self.state = self.mdp.initial_state();
+        self.state.clone()
+    }
+
+    fn actions(&self, state: &M::State) -> Vec<M::Action> {
+        self.mdp.actions(state)
+    }
+
+    fn step(&mut self, action: &M::Action) -> (M::State, f64, bool) {
+        if self.mdp.actions(&self.state).is_empty() {
+            return (self.state.clone(), 0.0, true);
+        }
+        let outcomes = self.mdp.transition(&self.state, action);
+        let mut r = self.rng.gen::<f64>();
+        let mut next = outcomes[outcomes.len() - 1].1.clone();
+        for (p, state) in outcomes {
+            if r < p {
+                next = state;
+                break;
+            }
+            r -= p;
+        }
+        let reward =
+            self.mdp.transition_reward(&self.state, action, &next) + self.mdp.reward(&next);
+        self.state = next.clone();
+        let done = self.mdp.actions(&next).is_empty();
+        (next, reward, done)
+    }
+}
+
+/// Runs `episodes` episodes in `env` following `policy`, passing every step to `learn`, and
+/// returns the total reward collected in each. An episode ends when the environment says it is
+/// done, when it reaches a state `policy` has no action for, or after `MAX_EPISODE_STEPS` steps.
+fn follow_policy<E, F>(
+    env: &mut E,
+    policy: &HashMap<E::State, E::Action>,
+    episodes: usize,
+    seed: u64,
+    mut learn: F,
+) -> Vec<f64>
+where
+    E: RLEnvironment,
+    F: FnMut(&E::State, f64, &E::State, bool),
+{
+    (0..episodes)
+        .map(|episode| {
+            let mut state = env.reset(seed.wrapping_add(episode as u64));
+            let mut total = 0.0;
+            for _ in 0..MAX_EPISODE_STEPS {
+                let action = match policy.get(&state) {
+                    Some(action) => action,
+                    None => break,
+                };
+                let (next, reward, done) = env.step(action);
+                learn(&state, reward, &next, done);
+                total += reward;
+                if done {
+                    break;
+                }
+                state = next;
+            }
+            total
+        })
+        .collect()
+}
+
+/// The table of a `TableDrivenAgent` that acts by `policy` for up to `horizon` steps from
+/// `start`. Only the percept sequences the agent could actually see are included: each sequence
+/// is extended by the states `successors` says the policy's action can lead to, and stops at a
+/// state the policy has no action for.
+fn trajectory_table<S, A, F>(
+    start: &S,
+    horizon: usize,
+    policy: &HashMap<S, A>,
+    successors: F,
+) -> Table<S, A>
+where
+    S: Clone + Eq + Hash,
+    A: Clone,
+    F: Fn(&S, &A) -> Vec<S>,
+{
+    let mut table = Table::new();
+    let mut sequences = vec![vec![start.clone()]];
+    for step in 1..=horizon {
+        let mut longer = Vec::new();
+        for sequence in sequences {
+            let last = sequence.last().unwrap();
+            let action = match policy.get(last) {
+                Some(action) => action,
+                None => continue,
+            };
+            if step < horizon {
+                for next in successors(last, action) {
+                    let mut extended = sequence.clone();
+                    extended.push(next);
+                    longer.push(extended);
+                }
+            }
+            table.insert(sequence, action.clone());
+        }
+        sequences = longer;
+    }
+    table
+}
+
+/// Figure 21.2
+///
+/// A passive adaptive dynamic programming agent: it follows a fixed policy $\pi$ and learns a
+/// model of the environment from what it sees, the frequency with which each state follows
+/// another and the reward for each move. After every step it solves the Bellman equations of
+/// the policy under that model,
+///
+/// $$ U(s) = \sum_{s'} P(s' \mid s, \pi(s)) \left( r(s, s') + \gamma U(s') \right) $$
+///
+/// where $r(s, s')$ is the average reward seen on moving from $s$ to $s'$, and $U(s')$ is zero
+/// for a state an episode has ended in. As with `PassiveTDAgent`, $U(s)$ is the discounted
+/// reward still to come after leaving $s$. Each step costs a solve of the model, but the agent
+/// makes full use of every observation and so needs far fewer episodes than TD learning.
+///
+/// # Examples
+///
+/// After a hundred episodes, the utilities along the route of the optimal 4x3 policy are closer
+/// to the true ones than those `PassiveTDAgent` learns from the same episodes:
+///
+/// ```
+/// # use aima_rust::mdp::{best_policy, policy_evaluation, sequential_decision_environment, value_iteration, PolicyEvaluation, MDP};
+/// # use aima_rust::rl::{MdpEnvironment, PassiveADPAgent, PassiveTDAgent};
+/// let mdp = sequential_decision_environment();
+/// let policy = best_policy(&mdp, &value_iteration(&mdp, 0.001));
+/// let utilities = policy_evaluation(&policy, &mdp, PolicyEvaluation::Exact);
+///
+/// let mut adp = PassiveADPAgent::new(policy.clone(), mdp.gamma());
+/// adp.train(&mut MdpEnvironment::new(mdp.clone()), 100, 1);
+/// let mut td = PassiveTDAgent::new(policy, |n| 60.0 / (59.0 + n as f64), mdp.gamma());
+/// td.train(&mut MdpEnvironment::new(mdp.clone()), 100, 1);
+///
+/// let route = [(0, 0), (0, 1), (0, 2), (1, 2), (2, 2)];
+/// let error = |learned: &dyn Fn(&(i32, i32)) -> f64| {
+///     route
+///         .iter()
+///         .map(|s| (mdp.reward(s) + mdp.gamma() * learned(s) - utilities[s]).abs())
+///         .fold(0.0, f64::max)
+/// };
+/// assert!(error(&|s| adp.utility(s)) < 0.05);
+/// assert!(error(&|s| adp.utility(s)) < error(&|s| td.utility(s)));
+/// ```
+#[derive(Clone, Debug)]
+pub struct PassiveADPAgent<S, A> {
+    policy: HashMap<S, A>,
+    utilities: HashMap<S, f64>,
+    outcomes: HashMap<S, HashMap<S, usize>>,
+    rewards: HashMap<(S, S), f64>,
+    terminals: HashSet<S>,
+    gamma: f64,
+}
+
+impl<S: Clone + Eq + Hash, A: Clone + Eq + Hash> PassiveADPAgent<S, A> {
+    /// Creates a new agent given the policy to follow and the discount factor `gamma`.
+    pub fn new(policy: HashMap<S, A>, gamma: f64) -> Self {
+        PassiveADPAgent {
+            policy,
+            utilities: HashMap::new(),
+            outcomes: HashMap::new(),
+            rewards: HashMap::new(),
+            terminals: HashSet::new(),
+            gamma,
+        }
+    }
+
+    /// Learns from one step from `state` to `next` with `reward`, where `done` says whether the
+    /// episode ended there: updates the model, then re-solves the utilities under it. `train`
+    /// calls this for every step it takes.
+    pub fn learn(&mut self, state: &S, reward: f64, next: &S, done: bool) {
+        *self
+            .outcomes
+            .entry(state.clone())
+            .or_default()
+            .entry(next.clone())
+            .or_insert(0) += 1;
+        *self
+            .rewards
+            .entry((state.clone(), next.clone()))
+            .or_insert(0.0) += reward;
+        if done {
+            self.terminals.insert(next.clone());
+        }
+        self.evaluate();
+    }
+
+    /// Solves the Bellman equations of the policy under the learned model by iterating them,
+    /// starting from the current utilities, until no utility changes by more than $10^{-9}$.
+    /// The sweeps are capped, as with $\gamma = 1$ a policy that loops forever has no solution.
+    fn evaluate(&mut self) {
+        for _ in 0..1_000 {
+            let updated: Vec<(S, f64)> = self
+                .outcomes
+                .iter()
+                .map(|(state, outcomes)| {
+                    let total = outcomes.values().sum::<usize>() as f64;
+                    let utility = outcomes
+                        .iter()
+                        .map(|(next, &n)| {
+                            let key = (state.clone(), next.clone());
+                            let reward = self.rewards[&key] / n as f64;
+                            let future = if self.terminals.contains(next) {
+                                0.0
+                            } else {
+                                self.utility(next)
+                            };
+                            n as f64 / total * (reward + self.gamma * future)
+                        })
+                        .sum();
+                    (state.clone(), utility)
+                })
+                .collect();
+            let mut change: f64 = 0.0;
+            for (state, utility) in updated {
+                change = change.max((utility - self.utility(&state)).abs());
+                self.utilities.insert(state, utility);
+            }
+            if change <= 1e-9 {
+                break;
+            }
+        }
+    }
+
+    /// Runs `episodes` episodes in `env` following the policy, learning from every step, and
+    /// returns the total reward collected in each. An episode ends when the environment says it
+    /// is done, when it reaches a state the policy has no action for, or after
+    /// `MAX_EPISODE_STEPS` steps.
+    pub fn train<E>(&mut self, env: &mut E, episodes: usize, seed: u64) -> Vec<f64>
+    where
+        E: RLEnvironment<State = S, Action = A>,
+    {
+        let policy = self.policy.clone();
+        follow_policy(env, &policy, episodes, seed, |state, reward, next, done| {
+            self.learn(state, reward, next, done)
+        })
+    }
+
+    /// The learned probability of each state that has been seen to follow `state`, under the
+    /// policy's action there. It is empty for a state that has never been left.
+    pub fn transition(&self, state: &S) -> Vec<(f64, S)> {
+        self.outcomes.get(state).map_or_else(Vec::new, |outcomes| {
+            let total = outcomes.values().sum::<usize>() as f64;
+            outcomes
+                .iter()
+                .map(|(next, &n)| (n as f64 / total, next.clone()))
+                .collect()
+        })
+    }
+
+    /// The learned utility of `state`, which is zero until it has been visited.
+    pub fn utility(&self, state: &S) -> f64 {
+        self.utilities.get(state).cloned().unwrap_or(0.0)
+    }
+
+    /// The learned utility of every state visited so far.
+    pub fn utilities(&self) -> &HashMap<S, f64> {
+        &self.utilities
+    }
+
+    /// Freezes the policy into the table of a `TableDrivenAgent` that starts in `start` and acts
+    /// for up to `horizon` steps. The table holds every sequence of states the agent has seen
+    /// the policy lead through, each mapped to the policy's action in its last state; see
+    /// `QLearningAgent::to_table`.
+    pub fn to_table(&self, start: &S, horizon: usize) -> Table<S, A> {
+        trajectory_table(start, horizon, &self.policy, |state, _| {
+            self.outcomes
+                .get(state)
+                .map_or_else(Vec::new, |outcomes| outcomes.keys().cloned().collect())
+        })
+    }
+}
+
+/// Figure 21.4
+///
+/// A passive temporal-difference agent: it follows a fixed policy $\pi$ and learns the utility
+/// of each state under it, without a model of the environment. After every step from $s$ to
+/// $s'$ with reward $r$:
+///
+/// $$ U(s) \leftarrow U(s) + \alpha(N_s) \left( r + \gamma U(s') - U(s) \right) $$
+///
+/// where $N_s$ counts the visits to $s$, and $U(s')$ is zero once the episode is over. The
+/// environment hands out each state's reward on arrival, so $U(s)$ is the discounted reward
+/// still to come after leaving $s$. The utility of chapter 17, which counts the reward of $s$
+/// too, is $R(s) + \gamma U(s)$.
+///
+/// # Examples
+///
+/// Following the optimal policy of the 4x3 world, the learned utilities of the states along its
+/// route approach the true ones. The states off the route are rarely visited, so theirs settle
+/// more slowly.
+///
+/// ```
+/// # use aima_rust::mdp::{best_policy, policy_evaluation, sequential_decision_environment, value_iteration, PolicyEvaluation, MDP};
+/// # use aima_rust::rl::{MdpEnvironment, PassiveTDAgent};
+/// let mdp = sequential_decision_environment();
+/// let policy = best_policy(&mdp, &value_iteration(&mdp, 0.001));
+/// let utilities = policy_evaluation(&policy, &mdp, PolicyEvaluation::Exact);
+///
+/// let mut agent = PassiveTDAgent::new(policy.clone(), |n| 60.0 / (59.0 + n as f64), mdp.gamma());
+/// agent.train(&mut MdpEnvironment::new(mdp.clone()), 2_000, 1);
+///
+/// for state in [(0, 0), (0, 1), (0, 2), (1, 2), (2, 2)] {
+///     let utility = mdp.reward(&state) + mdp.gamma() * agent.utility(&state);
+///     assert!((utility - utilities[&state]).abs() < 0.03);
+/// }
+/// ```
+#[derive(Clone, Debug)]
+pub struct PassiveTDAgent<S, A> {
+    policy: HashMap<S, A>,
+    utilities: HashMap<S, f64>,
+    visits: HashMap<S, usize>,
+    alpha: fn(usize) -> f64,
+    gamma: f64,
+}
+
+impl<S: Clone + Eq + Hash, A: Clone + Eq + Hash> PassiveTDAgent<S, A> {
+    /// Creates a new agent given the policy to follow, the learning rate `alpha` as a function
+    /// of how often the state has been visited, and the discount factor `gamma`.
+    pub fn new(policy: HashMap<S, A>, alpha: fn(usize) -> f64, gamma: f64) -> Self {
+        PassiveTDAgent {
+            policy,
+            utilities: HashMap::new(),
+            visits: HashMap::new(),
+            alpha,
+            gamma,
+        }
+    }
+
+    /// Learns from one step from `state` to `next` with `reward`, where `done` says whether the
+    /// episode ended there. `train` calls this for every step it takes.
+    pub fn learn(&mut self, state: &S, reward: f64, next: &S, done: bool) {
+        let future = if done { 0.0 } else { self.utility(next) };
+        let n = self.visits.entry(state.clone()).or_insert(0);
+        *n += 1;
+        let alpha = (self.alpha)(*n);
+        let utility = self.utilities.entry(state.clone()).or_insert(0.0);
+        *utility += alpha * (reward + self.gamma * future - *utility);
+    }
+
+    /// Runs `episodes` episodes in `env` following the policy, learning from every step, and
+    /// returns the total reward collected in each. An episode ends when the environment says it
+    /// is done, when it reaches a state the policy has no action for, or after
+    /// `MAX_EPISODE_STEPS` steps.
+    pub fn train<E>(&mut self, env: &mut E, episodes: usize, seed: u64) -> Vec<f64>
+    where
+        E: RLEnvironment<State = S, Action = A>,
+    {
+        let policy = self.policy.clone();
+        follow_policy(env, &policy, episodes, seed, |state, reward, next, done| {
+            self.learn(state, reward, next, done)
+        })
+    }
+
+    /// The learned utility of `state`, which is zero until it has been visited.
+    pub fn utility(&self, state: &S) -> f64 {
+        self.utilities.get(state).cloned().unwrap_or(0.0)
+    }
+
+    /// The learned utility of every state visited so far.
+    pub fn utilities(&self) -> &HashMap<S, f64> {
+        &self.utilities
+    }
+}
+
+/// A passive TD agent with eligibility traces, TD($\lambda$). Where `PassiveTDAgent` only
+/// updates the state it has just left, this agent passes each TD error
+///
+/// $$ \delta = r + \gamma U(s') - U(s) $$
+///
+/// back to every state visited earlier in the episode, in proportion to its _eligibility_
+/// $e(x)$:
+///
+/// $$ U(x) \leftarrow U(x) + \alpha(N_x) \, \delta \, e(x) $$
+///
+/// On each step every eligibility decays by $\gamma\lambda$ and that of $s$ goes up by one, so
+/// recently and frequently visited states get the most credit. The traces are reset at the end
+/// of every episode. With $\lambda = 0$ only $s$ is eligible and the agent is TD(0), exactly as
+/// `PassiveTDAgent`; larger $\lambda$ lets a reward reach the states that led to it in fewer
+/// episodes.
+///
+/// # Examples
+///
+/// With $\lambda = 0$, the agent learns exactly what `PassiveTDAgent` does:
+///
+/// ```
+/// # use aima_rust::mdp::{best_policy, sequential_decision_environment, value_iteration, MDP};
+/// # use aima_rust::rl::{MdpEnvironment, PassiveTDAgent, PassiveTDLambdaAgent};
+/// let mdp = sequential_decision_environment();
+/// let policy = best_policy(&mdp, &value_iteration(&mdp, 0.001));
+/// let alpha = |n| 60.0 / (59.0 + n as f64);
+///
+/// let mut td = PassiveTDAgent::new(policy.clone(), alpha, mdp.gamma());
+/// td.train(&mut MdpEnvironment::new(mdp.clone()), 200, 1);
+/// let mut td_lambda = PassiveTDLambdaAgent::new(policy, alpha, mdp.gamma(), 0.0);
+/// td_lambda.train(&mut MdpEnvironment::new(mdp), 200, 1);
+///
+/// assert_eq!(td_lambda.utilities(), td.utilities());
+/// ```
+///
+/// With $\lambda > 0$, the utilities of the 4x3 world get close to the true ones in fewer
+/// episodes:
+///
+/// ```
+/// # use aima_rust::mdp::{best_policy, policy_evaluation, sequential_decision_environment, value_iteration, PolicyEvaluation, MDP};
+/// # use aima_rust::rl::{MdpEnvironment, PassiveTDLambdaAgent};
+/// let mdp = sequential_decision_environment();
+/// let policy = best_policy(&mdp, &value_iteration(&mdp, 0.001));
+/// let utilities = policy_evaluation(&policy, &mdp, PolicyEvaluation::Exact);
+///
+/// // The root-mean-square error of the utilities learned in `episodes` episodes.
+/// let error = |lambda: f64, episodes: usize| {
+///     let mut agent = PassiveTDLambdaAgent::new(policy.clone(), |_| 0.1, mdp.gamma(), lambda);
+///     agent.train(&mut MdpEnvironment::new(mdp.clone()), episodes, 1);
+///     let squares: f64 = policy
+///         .keys()
+///         .map(|s| (mdp.reward(s) + mdp.gamma() * agent.utility(s) - utilities[s]).powi(2))
+///         .sum();
+///     (squares / policy.len() as f64).sqrt()
+/// };
+///
+/// assert!(error(0.8, 20) < error(0.0, 20));
+/// assert!(error(0.8, 50) < error(0.0, 50));
+/// ```
+#[derive(Clone, Debug)]
+pub struct PassiveTDLambdaAgent<S, A> {
+    policy: HashMap<S, A>,
+    utilities: HashMap<S, f64>,
+    visits: HashMap<S, usize>,
+    traces: HashMap<S, f64>,
+    alpha: fn(usize) -> f64,
+    gamma: f64,
+    lambda: f64,
+}
+
+impl<S: Clone + Eq + Hash, A: Clone + Eq + Hash> PassiveTDLambdaAgent<S, A> {
+    /// Creates a new agent given the policy to follow, the learning rate `alpha` as a function
+    /// of how often the state has been visited, the discount factor `gamma`, and the trace decay
+    /// `lambda`.
+    ///
+    /// # Panics
+    ///
+    /// If `lambda` is not between `0.0` and `1.0`.
+    pub fn new(policy: HashMap<S, A>, alpha: fn(usize) -> f64, gamma: f64, lambda: f64) -> Self {
+        assert!(
+            (0.0..=1.0).contains(&lambda),
+            "lambda must be between 0 and 1"
+        );
+        PassiveTDLambdaAgent {
+            policy,
+            utilities: HashMap::new(),
+            visits: HashMap::new(),
+            traces: HashMap::new(),
+            alpha,
+            gamma,
+            lambda,
+        }
+    }
+
+    /// Learns from one step from `state` to `next` with `reward`, where `done` says whether the
+    /// episode ended there, and then resets the traces if it did. `train` calls this for every
+    /// step it takes.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use aima_rust::mdp::Direction;
+    /// # use aima_rust::rl::PassiveTDLambdaAgent;
+    /// # use std::collections::HashMap;
+    /// let policy: HashMap<_, _> = vec![((0, 0), Direction::Up), ((0, 1), Direction::Up)]
+    ///     .into_iter()
+    ///     .collect();
+    /// let mut agent = PassiveTDLambdaAgent::new(policy, |_| 0.5, 0.9, 0.5);
+    ///
+    /// agent.learn(&(0, 0), -0.04, &(0, 1), false);
+    /// agent.learn(&(0, 1), -0.04, &(0, 2), false);
+    /// assert_eq!(agent.traces()[&(0, 1)], 1.0);
+    /// assert!((agent.traces()[&(0, 0)] - 0.9 * 0.5).abs() < 1e-12);
+    ///
+    /// // Reaching a reward at the end of the episode credits both states that led to it.
+    /// agent.learn(&(0, 2), 1.0, &(1, 2), true);
+    /// assert!(agent.traces().is_empty());
+    /// assert!(agent.utility(&(0, 0)) > 0.0 && agent.utility(&(0, 1)) > 0.0);
+    /// ```
+    pub fn learn(&mut self, state: &S, reward: f64, next: &S, done: bool) {
+        let future = if done { 0.0 } else { self.utility(next) };
+        let delta = reward + self.gamma * future - self.utility(state);
+        let decay = self.gamma * self.lambda;
+        for trace in self.traces.values_mut() {
+            *trace *= decay;
+        }
+        self.traces.retain(|_, trace| *trace != 0.0);
+        *self.traces.entry(state.clone()).or_insert(0.0) += 1.0;
+        *self.visits.entry(state.clone()).or_insert(0) += 1;
+        for (x, trace) in &self.traces {
+            let alpha = (self.alpha)(self.visits[x]);
+            *self.utilities.entry(x.clone()).or_insert(0.0) += alpha * delta * trace;
+        }
+        if done {
+            self.traces.clear();
+        }
+    }
+
+    /// Runs `episodes` episodes in `env` following the policy, learning from every step, and
+    /// returns the total reward collected in each. An episode ends when the environment says it
+    /// is done, when it reaches a state the policy has no action for, or after
+    /// `MAX_EPISODE_STEPS` steps.
+    pub fn train<E>(&mut self, env: &mut E, episodes: usize, seed: u64) -> Vec<f64>
+    where
+        E: RLEnvironment<State = S, Action = A>,
+    {
+        let policy = self.policy.clone();
+        let returns = follow_policy(env, &policy, episodes, seed, |state, reward, next, done| {
+            self.learn(state, reward, next, done)
+        });
+        self.traces.clear();
+        returns
+    }
+
+    /// The learned utility of `state`, which is zero until it has been visited.
+    pub fn utility(&self, state: &S) -> f64 {
+        self.utilities.get(state).cloned().unwrap_or(0.0)
+    }
+
+    /// The learned utility of every state visited so far.
+    pub fn utilities(&self) -> &HashMap<S, f64> {
+        &self.utilities
+    }
+
+    /// The eligibility of each state visited in the current episode. It is empty between
+    /// episodes.
+    pub fn traces(&self) -> &HashMap<S, f64> {
+        &self.traces
+    }
+}
+
+/// The action-value function shared by the tabular agents, along with how often each pair has
+/// been tried, the actions seen in each state, and the states each pair has been seen to lead
+/// to.
+#[derive(Clone, Debug)]
+struct QTable<S, A> {
+    q: HashMap<(S, A), f64>,
+    visits: HashMap<(S, A), usize>,
+    actions: HashMap<S, Vec<A>>,
+    successors: HashMap<(S, A), HashSet<S>>,
+    alpha: fn(usize) -> f64,
+    gamma: f64,
+    exploration: f64,
+}
+
+impl<S: Clone + Eq + Hash, A: Clone + Eq + Hash> QTable<S, A> {
+    fn new(alpha: fn(usize) -> f64, gamma: f64, exploration: f64) -> Self {
+        QTable {
+            q: HashMap::new(),
+            visits: HashMap::new(),
+            actions: HashMap::new(),
+            successors: HashMap::new(),
+            alpha,
+            gamma,
+            exploration,
+        }
+    }
+
+    fn value(&self, state: &S, action: &A) -> f64 {
+        self.q
+            .get(&(state.clone(), action.clone()))
+            .cloned()
+            .unwrap_or(0.0)
+    }
+
+    fn greedy(&self, state: &S) -> Option<(A, f64)> {
+        let mut best: Option<(A, f64)> = None;
+        for action in self.actions.get(state)? {
+            let value = self.value(state, action);
+            if best.as_ref().is_none_or(|&(_, b)| value > b) {
+                best = Some((action.clone(), value));
+            }
+        }
+        best
+    }
+
+    fn observe(&mut self, state: &S, actions: Vec<A>) {
+        if !actions.is_empty() {
+            self.actions.entry(state.clone()).or_insert(actions);
+        }
+    }
+
+    fn record(&mut self, state: &S, action: &A, next: &S) {
+        self.successors
+            .entry((state.clone(), action.clone()))
+            .or_default()
+            .insert(next.clone());
+    }
+
+    /// Picks a random action with probability `exploration`, and the greedy one otherwise.
+    fn choose(&self, state: &S, rng: &mut Rng) -> Option<A> {
+        let actions = self.actions.get(state)?;
+        if rng.gen_bool(self.exploration) {
+            actions.choose(rng).cloned()
+        } else {
+            self.greedy(state).map(|(action, _)| action)
+        }
+    }
+
+    fn update(&mut self, state: &S, action: &A, target: f64) {
+        let key = (state.clone(), action.clone());
+        let n = self.visits.entry(key.clone()).or_insert(0);
+        *n += 1;
+        let alpha = (self.alpha)(*n);
+        let q = self.q.entry(key).or_insert(0.0);
+        *q += alpha * (target - *q);
+    }
+
+    fn policy(&self) -> HashMap<S, A> {
+        self.actions
+            .keys()
+            .filter_map(|state| Some((state.clone(), self.greedy(state)?.0)))
+            .collect()
+    }
+
+    fn to_table(&self, start: &S, horizon: usize) -> Table<S, A> {
+        trajectory_table(start, horizon, &self.policy(), |state, action| {
+            self.successors
+                .get(&(state.clone(), action.clone()))
+                .map_or_else(Vec::new, |next| next.iter().cloned().collect())
+        })
+    }
+}
+
+/// A step the agent took: the state, the action, the reward, the state reached, and whether the
+/// episode ended there.
+type Transition<S, A> = (S, A, f64, S, bool);
+
+/// The most recent transitions an agent has seen, replayed in random minibatches.
+#[derive(Clone, Debug)]
+struct ReplayBuffer<S, A> {
+    transitions: VecDeque<Transition<S, A>>,
+    capacity: usize,
+    batch_size: usize,
+}
+
+impl<S: Clone, A: Clone> ReplayBuffer<S, A> {
+    /// Stores `transition`, forgetting the oldest one if the buffer is full.
+    fn remember(&mut self, transition: Transition<S, A>) {
+        if self.transitions.len() == self.capacity {
+            self.transitions.pop_front();
+        }
+        self.transitions.push_back(transition);
+    }
+
+    /// Draws a minibatch of stored transitions, uniformly and with replacement.
+    fn sample(&self, rng: &mut Rng) -> Vec<Transition<S, A>> {
+        (0..self.batch_size)
+            .map(|_| self.transitions[rng.gen_range(0..self.transitions.len())].clone())
+            .collect()
+    }
+}
+
+/// Figure 21.8
+///
+/// An active temporal-difference agent that learns the value $Q(s, a)$ of each action in each
+/// state, without a model of the environment. After every step from $s$ to $s'$ with reward $r$:
+///
+/// $$ Q(s, a) \leftarrow Q(s, a) + \alpha(N_{sa}) \left( r + \gamma \max_{a'} Q(s', a') - Q(s, a) \right) $$
+///
+/// where $N_{sa}$ counts how often $a$ has been tried in $s$, and the maximum is zero once the
+/// episode is over. Q-learning is _off-policy_: it learns the values of acting greedily, whatever
+/// it actually does. While training it explores by picking a random action with probability
+/// `exploration`.
+///
+/// By default each step is learned from once, as it happens. `with_replay` makes the agent keep
+/// its recent steps and learn from a random sample of them after every step as well.
+///
+/// # Examples
+///
+/// Trained on the 4x3 world, the agent settles on the same policy as value iteration:
+///
+/// ```
+/// # use aima_rust::mdp::{best_policy, sequential_decision_environment, value_iteration, MDP};
+/// # use aima_rust::rl::{MdpEnvironment, QLearningAgent};
+/// let mdp = sequential_decision_environment();
+/// let optimal = best_policy(&mdp, &value_iteration(&mdp, 0.001));
+///
+/// let mut agent = QLearningAgent::new(|n| 60.0 / (59.0 + n as f64), mdp.gamma(), 0.2);
+/// let mut env = MdpEnvironment::new(mdp);
+/// agent.train(&mut env, 20_000, 1);
+///
+/// assert_eq!(agent.policy(), optimal);
+/// ```
+#[derive(Clone, Debug)]
+pub struct QLearningAgent<S, A> {
+    table: QTable<S, A>,
+    replay: Option<ReplayBuffer<S, A>>,
+}
+
+impl<S: Clone + Eq + Hash, A: Clone + Eq + Hash> QLearningAgent<S, A> {
+    /// Creates a new agent given the learning rate `alpha`, as a function of how often the
+    /// action has been tried in the state, the discount factor `gamma`, and the probability of
+    /// exploring.
+    pub fn new(alpha: fn(usize) -> f64, gamma: f64, exploration: f64) -> Self {
+        QLearningAgent {
+            table: QTable::new(alpha, gamma, exploration),
+            replay: None,
+        }
+    }
+
+    /// Turns on experience replay. The agent remembers its last `capacity` steps, and after
+    /// every step also learns from `batch_size` of them drawn at random, so each step it takes
+    /// is learned from many times over. The draws come from the generator seeded in `train`.
+    ///
+    /// # Panics
+    ///
+    /// If `capacity` is zero.
+    ///
+    /// # Examples
+    ///
+    /// Replaying past steps, the agent finds the optimal policy of the 4x3 world in fewer
+    /// episodes than learning from each step only once:
+    ///
+    /// ```
+    /// # use aima_rust::mdp::{best_policy, sequential_decision_environment, value_iteration, MDP};
+    /// # use aima_rust::rl::{MdpEnvironment, QLearningAgent};
+    /// let mdp = sequential_decision_environment();
+    /// let optimal = best_policy(&mdp, &value_iteration(&mdp, 0.001));
+    ///
+    /// // Trains in rounds of 50 episodes until the greedy policy is optimal.
+    /// let episodes_needed = |mut agent: QLearningAgent<_, _>| {
+    ///     let mut env = MdpEnvironment::new(mdp.clone());
+    ///     let mut episodes = 0;
+    ///     while agent.policy() != optimal {
+    ///         assert!(episodes < 50_000, "never found the optimal policy");
+    ///         agent.train(&mut env, 50, episodes as u64);
+    ///         episodes += 50;
+    ///     }
+    ///     episodes
+    /// };
+    ///
+    /// let alpha = |n| 60.0 / (59.0 + n as f64);
+    /// let online = episodes_needed(QLearningAgent::new(alpha, mdp.gamma(), 0.2));
+    /// let replayed =
+    ///     episodes_needed(QLearningAgent::new(alpha, mdp.gamma(), 0.2).with_replay(1_000, 8));
+    /// assert!(replayed < online);
+    /// ```
+    pub fn with_replay(mut self, capacity: usize, batch_size: usize) -> Self {
+        assert!(
+            capacity > 0,
+            "a replay buffer needs room for at least one transition"
+        );
+        self.replay = Some(ReplayBuffer {
+            transitions: VecDeque::with_capacity(capacity),
+            capacity,
+            batch_size,
+        });
+        self
+    }
+
+    /// Moves $Q(s, a)$ towards the reward plus the discounted value of the best action from the
+    /// state reached, which is zero once the episode is over.
+    fn learn(&mut self, (state, action, reward, next, done): &Transition<S, A>) {
+        let future = match self.table.greedy(next) {
+            Some((_, value)) if !done => value,
+            _ => 0.0,
+        };
+        let target = reward + self.table.gamma * future;
+        self.table.update(state, action, target);
+    }
+
+    /// Runs `episodes` episodes in `env`, learning from every step, and returns the total reward
+    /// collected in each. Each episode runs until the environment says it is done, or for at most
+    /// `MAX_EPISODE_STEPS` steps.
+    pub fn train<E>(&mut self, env: &mut E, episodes: usize, seed: u64) -> Vec<f64>
+    where
+        E: RLEnvironment<State = S, Action = A>,
+    {
+        let mut rng = Rng::seeded(seed);
+        let mut returns = Vec::with_capacity(episodes);
+        for episode in 0..episodes {
+            let mut state = env.reset(seed.wrapping_add(episode as u64));
+            self.table.observe(&state, env.actions(&state));
+            let mut total = 0.0;
+            for _ in 0..MAX_EPISODE_STEPS {
+                let action = match self.table.choose(&state, &mut rng) {
+                    Some(action) => action,
+                    None => break,
+                };
+                let (next, reward, done) = env.step(&action);
+                self.table.observe(&next, env.actions(&next));
+                self.table.record(&state, &action, &next);
+                let transition = (state, action, reward, next.clone(), done);
+                self.learn(&transition);
+                if let Some(replay) = &mut self.replay {
+                    replay.remember(transition);
+                    for past in replay.sample(&mut rng) {
+                        self.learn(&past);
+                    }
+                }
+                total += reward;
+                if done {
+                    break;
+                }
+                state = next;
+            }
+            returns.push(total);
+        }
+        returns
+    }
+
+    /// The learned value of taking `action` in `state`, which is zero until it has been tried.
+    pub fn q_value(&self, state: &S, action: &A) -> f64 {
+        self.table.value(state, action)
+    }
+
+    /// The greedy policy: the action with the highest Q-value in each non-terminal state the
+    /// agent has visited. Ties go to the action the environment listed first.
+    pub fn policy(&self) -> HashMap<S, A> {
+        self.table.policy()
+    }
+
+    /// Freezes the greedy policy into the table of a `TableDrivenAgent` that starts in `start`
+    /// and acts for up to `horizon` steps. A table-driven agent looks up its whole percept
+    /// sequence, so the table has an entry for every sequence of states the agent could see on
+    /// the way: starting from `start`, each sequence is extended by every state the greedy action
+    /// in its last state has been seen to lead to, and mapped to that action. Sequences stop at
+    /// terminal states and at states the agent never visited, and a move never seen in training
+    /// leaves the agent with no entry to look up.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use aima_rust::agents::table::TableDrivenAgent;
+    /// # use aima_rust::mdp::{sequential_decision_environment, MDP};
+    /// # use aima_rust::rl::{MdpEnvironment, QLearningAgent, RLEnvironment};
+    /// let mdp = sequential_decision_environment();
+    /// let mut env = MdpEnvironment::new(mdp.clone());
+    /// let mut agent = QLearningAgent::new(|n| 60.0 / (59.0 + n as f64), mdp.gamma(), 0.2);
+    /// agent.train(&mut env, 2_000, 1);
+    ///
+    /// let policy = agent.policy();
+    /// let table = agent.to_table(&(0, 0), 8);
+    /// for (sequence, action) in &table {
+    ///     assert_eq!(sequence[0], (0, 0));
+    ///     assert_eq!(action, &policy[sequence.last().unwrap()]);
+    /// }
+    ///
+    /// for seed in 0..20 {
+    ///     let mut frozen = TableDrivenAgent::new(table.clone());
+    ///     let mut state = env.reset(seed);
+    ///     for _ in 0..8 {
+    ///         let action = frozen.run(state).clone();
+    ///         assert_eq!(action, policy[&state]);
+    ///         let (next, _, done) = env.step(&action);
+    ///         if done {
+    ///             break;
+    ///         }
+    ///         state = next;
+    ///     }
+    /// }
+    /// ```
+    pub fn to_table(&self, start: &S, horizon: usize) -> Table<S, A> {
+        self.table.to_table(start, horizon)
+    }
+}
+
+/// An on-policy relative of `QLearningAgent`. SARSA backs up the value of the action it will
+/// actually take next, $a'$, rather than the best one:
+///
+/// $$ Q(s, a) \leftarrow Q(s, a) + \alpha(N_{sa}) \left( r + \gamma \, Q(s', a') - Q(s, a) \right) $$
+///
+/// so what it learns accounts for its own exploration. On the cliff walking grid that makes it
+/// keep away from the edge, where a random step would be costly.
+///
+/// # Examples
+///
+/// Trained side by side with a `QLearningAgent` from the same seed, and each following its greedy
+/// policy from the start, SARSA climbs away from the cliff and only comes back down beside the
+/// goal. Q-learning learns the values of the greedy policy it isn't following while it explores,
+/// and takes the shortest route, right along the edge:
+///
+/// ```
+/// # use aima_rust::mdp::{cliff_walking, Cell, Direction, MDP};
+/// # use aima_rust::rl::{MdpEnvironment, QLearningAgent, SarsaAgent};
+/// # use std::collections::HashMap;
+/// let mdp = cliff_walking();
+/// let greedy_path = |policy: HashMap<Cell, Direction>| {
+///     let mut path = vec![mdp.initial_state()];
+///     while let Some(action) = policy.get(path.last().unwrap()) {
+///         assert!(path.len() < 50, "the greedy policy goes round in circles");
+///         let (_, next) = mdp.transition(path.last().unwrap(), action)[0];
+///         path.push(next);
+///     }
+///     path
+/// };
+/// // The cells strictly between the start and goal columns.
+/// let middle = |path: &[Cell]| -> Vec<Cell> {
+///     path.iter().cloned().filter(|&(x, _)| 0 < x && x < 11).collect()
+/// };
+///
+/// let mut sarsa = SarsaAgent::new(|_| 0.1, 1.0, 0.1);
+/// sarsa.train(&mut MdpEnvironment::new(mdp.clone()), 500, 3);
+/// let mut q_learning = QLearningAgent::new(|_| 0.1, 1.0, 0.1);
+/// q_learning.train(&mut MdpEnvironment::new(mdp.clone()), 500, 3);
+///
+/// let safe = greedy_path(sarsa.policy());
+/// assert_eq!(*safe.last().unwrap(), (11, 0));
+/// assert!(middle(&safe).iter().all(|&(_, y)| y > 1));
+///
+/// let risky = greedy_path(q_learning.policy());
+/// assert_eq!(*risky.last().unwrap(), (11, 0));
+/// assert!(middle(&risky).iter().all(|&(_, y)| y == 1));
+/// assert!(risky.len() < safe.len());
+/// ```
+#[derive(Clone, Debug)]
+pub struct SarsaAgent<S, A> {
+    table: QTable<S, A>,
+}
+
+impl<S: Clone + Eq + Hash, A: Clone + Eq + Hash> SarsaAgent<S, A> {
+    /// Creates a new agent given the learning rate `alpha`, as a function of how often the
+    /// action has been tried in the state, the discount factor `gamma`, and the probability of
+    /// exploring.
+    pub fn new(alpha: fn(usize) -> f64, gamma: f64, exploration: f64) -> Self {
+        SarsaAgent {
+            table: QTable::new(alpha, gamma, exploration),
+        }
+    }
+
+    /// Runs `episodes` episodes in `env`, learning from every step, and returns the total reward
+    /// collected in each. Each episode runs until the environment says it is done, or for at most
+    /// `MAX_EPISODE_STEPS` steps.
+    pub fn train<E>(&mut self, env: &mut E, episodes: usize, seed: u64) -> Vec<f64>
+    where
+        E: RLEnvironment<State = S, Action = A>,
+    {
+        let mut rng = Rng::seeded(seed);
+        let mut returns = Vec::with_capacity(episodes);
+        for episode in 0..episodes {
+            let mut state = env.reset(seed.wrapping_add(episode as u64));
+            self.table.observe(&state, env.actions(&state));
+            let mut action = self.table.choose(&state, &mut rng);
+            let mut total = 0.0;
+            for _ in 0..MAX_EPISODE_STEPS {
+                let current = match action {
+                    Some(current) => current,
+                    None => break,
+                };
+                let (next, reward, done) = env.step(&current);
+                self.table.observe(&next, env.actions(&next));
+                self.table.record(&state, &current, &next);
+                action = if done {
+                    None
+                } else {
+                    self.table.choose(&next, &mut rng)
+                };
+                let future = action.as_ref().map_or(0.0, |a| self.table.value(&next, a));
+                let target = reward + self.table.gamma * future;
+                self.table.update(&state, &current, target);
+                total += reward;
+                state = next;
+            }
+            returns.push(total);
+        }
+        returns
+    }
+
+    /// The learned value of taking `action` in `state`, which is zero until it has been tried.
+    pub fn q_value(&self, state: &S, action: &A) -> f64 {
+        self.table.value(state, action)
+    }
+
+    /// The greedy policy: the action with the highest Q-value in each non-terminal state the
+    /// agent has visited. Ties go to the action the environment listed first.
+    pub fn policy(&self) -> HashMap<S, A> {
+        self.table.policy()
+    }
+
+    /// Freezes the greedy policy into the table of a `TableDrivenAgent` that starts in `start`
+    /// and acts for up to `horizon` steps; see `QLearningAgent::to_table`.
+    pub fn to_table(&self, start: &S, horizon: usize) -> Table<S, A> {
+        self.table.to_table(start, horizon)
+    }
+}
+
+/// A Q-learning agent that represents $Q$ as a linear function of features of the state and
+/// action, for state spaces too large to keep a table of:
+///
+/// $$ \hat{Q}_{\mathbf{w}}(s, a) = \sum_i w_i \, f_i(s, a) $$
+///
+/// After each step, every weight moves along the gradient of the temporal-difference error:
+///
+/// $$ w_i \leftarrow w_i + \alpha \left( r + \gamma \max_{a'} \hat{Q}_{\mathbf{w}}(s', a') - \hat{Q}_{\mathbf{w}}(s, a) \right) f_i(s, a) $$
+///
+/// # The Feature Function
+///
+/// `features(state, action)` must return a vector of the same length for every pair; the
+/// weights are sized from the first one seen and start at zero. The agent can only tell pairs
+/// apart through their features, so any information the policy needs has to be in there.
+/// Features on a scale of about one keep a fixed `alpha` stable; large ones can make the
+/// weights diverge.
+///
+/// # Examples
+///
+/// With one indicator feature for each state and action, the approximation can represent any
+/// table, and the agent recovers the optimal policy of the 4x3 world:
+///
+/// ```
+/// # use aima_rust::mdp::{best_policy, sequential_decision_environment, value_iteration, Cell, Direction, MDP};
+/// # use aima_rust::rl::{ApproxQLearningAgent, MdpEnvironment};
+/// let mdp = sequential_decision_environment();
+/// let optimal = best_policy(&mdp, &value_iteration(&mdp, 0.001));
+///
+/// let states = mdp.states();
+/// let actions = [Direction::Up, Direction::Down, Direction::Left, Direction::Right];
+/// let indicator = move |s: &Cell, a: &Direction| {
+///     let mut features = vec![0.0; states.len() * actions.len()];
+///     let i = states.iter().position(|x| x == s).unwrap();
+///     let j = actions.iter().position(|x| x == a).unwrap();
+///     features[i * actions.len() + j] = 1.0;
+///     features
+/// };
+///
+/// let mut agent = ApproxQLearningAgent::new(indicator, 0.02, mdp.gamma(), 0.2);
+/// agent.train(&mut MdpEnvironment::new(mdp.clone()), 20_000, 5);
+///
+/// assert_eq!(agent.weights().len(), 44);
+/// for (state, action) in &optimal {
+///     assert_eq!(agent.best_action(state, &mdp.actions(state)).as_ref(), Some(action));
+/// }
+/// ```
+#[derive(Clone, Debug)]
+pub struct ApproxQLearningAgent<S, A, F> {
+    features: F,
+    weights: Vec<f64>,
+    alpha: f64,
+    gamma: f64,
+    exploration: f64,
+    _phantom: PhantomData<fn(&S, &A)>,
+}
+
+impl<S, A, F> ApproxQLearningAgent<S, A, F>
+where
+    S: Clone + Eq + Hash,
+    A: Clone + Eq + Hash,
+    F: Fn(&S, &A) -> Vec<f64>,
+{
+    /// Creates a new agent given the feature function, a fixed learning rate `alpha`, the
+    /// discount factor `gamma`, and the probability of exploring.
+    pub fn new(features: F, alpha: f64, gamma: f64, exploration: f64) -> Self {
+        ApproxQLearningAgent {
+            features,
+            weights: Vec::new(),
+            alpha,
+            gamma,
+            exploration,
+            _phantom: PhantomData,
+        }
+    }
+
+    /// The learned weights, one for each feature.
+    pub fn weights(&self) -> &[f64] {
+        &self.weights
+    }
+
+    /// The approximate value of taking `action` in `state`.
+    pub fn q_value(&self, state: &S, action: &A) -> f64 {
+        (self.features)(state, action)
+            .iter()
+            .zip(&self.weights)
+            .map(|(f, w)| f * w)
+            .sum()
+    }
+
+    /// The one of `actions` with the highest approximate value in `state`. Ties go to the
+    /// action listed first.
+    pub fn best_action(&self, state: &S, actions: &[A]) -> Option<A> {
+        let mut best: Option<(&A, f64)> = None;
+        for action in actions {
+            let value = self.q_value(state, action);
+            if best.is_none_or(|(_, b)| value > b) {
+                best = Some((action, value));
+            }
+        }
+        best.map(|(action, _)| action.clone())
+    }
+
+    /// Runs `episodes` episodes in `env`, learning from every step, and returns the total reward
+    /// collected in each. Each episode runs until the environment says it is done, or for at most
+    /// `MAX_EPISODE_STEPS` steps.
+    pub fn train<E>(&mut self, env: &mut E, episodes: usize, seed: u64) -> Vec<f64>
+    where
+        E: RLEnvironment<State = S, Action = A>,
+    {
+        let mut rng = Rng::seeded(seed);
+        let mut returns = Vec::with_capacity(episodes);
+        for episode in 0..episodes {
+            let mut state = env.reset(seed.wrapping_add(episode as u64));
+            let mut total = 0.0;
+            for _ in 0..MAX_EPISODE_STEPS {
+                let actions = env.actions(&state);
+                let action = if rng.gen_bool(self.exploration) {
+                    actions.choose(&mut rng).cloned()
+                } else {
+                    self.best_action(&state, &actions)
+                };
+                let action = match action {
+                    Some(action) => action,
+                    None => break,
+                };
+                let (next, reward, done) = env.step(&action);
+                let future = if done {
+                    0.0
+                } else {
+                    let next_actions = env.actions(&next);
+                    self.best_action(&next, &next_actions)
+                        .map_or(0.0, |a| self.q_value(&next, &a))
+                };
+                let features = (self.features)(&state, &action);
+                if self.weights.is_empty() {
+                    self.weights = vec![0.0; features.len()];
+                }
+                let error = reward + self.gamma * future - self.q_value(&state, &action);
+                for (w, f) in self.weights.iter_mut().zip(&features) {
+                    *w += self.alpha * error * f;
+                }
+                total += reward;
+                if done {
+                    break;
+                }
+                state = next;
+            }
+            returns.push(total);
+        }
+        returns
+    }
+}
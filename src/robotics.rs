@@ -0,0 +1,412 @@
+//! # Robotics
+//!
+//! This module covers material in Chapter 25.
+//!
+//! ## Index of Algorithms
+//!
+//! | **Figure** | **Name**                          | **Module Link**
+//! |:-----------|:----------------------------------|:-------------------------------
+//! | 25         | Motion-Model                      | `MotionModel`                                            |
+//! | 25         | Sensor-Model                      | `SensorModel`                                            |
+//! | 25         | Gaussian-Odometry-Model           | `GaussianOdometry`                                       |
+//! | 25         | Beam-Range-Sensor-Model           | `BeamRangeSensor`                                        |
+//! | 25         | Occupancy-Grid                    | `OccupancyGrid`                                          |
+//! | 25         | Path-Planning                     | `plan_path`                                              |
+//!
+use crate::search::{astar_search, Problem};
+use rand::Rng;
+use std::f64::consts::PI;
+
+/// Where a robot is on the plane and which way it faces, with `heading` in radians
+/// counterclockwise from the x axis.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Pose {
+    pub x: f64,
+    pub y: f64,
+    pub heading: f64,
+}
+
+impl Pose {
+    /// Creates a new Pose.
+    pub fn new(x: f64, y: f64, heading: f64) -> Self {
+        Pose { x, y, heading }
+    }
+
+    /// The pose reached by carrying out `motion` exactly: turning in place, then driving
+    /// straight ahead.
+    pub fn moved(&self, motion: &Motion) -> Pose {
+        let heading = self.heading + motion.rotation;
+        Pose {
+            x: self.x + motion.distance * heading.cos(),
+            y: self.y + motion.distance * heading.sin(),
+            heading,
+        }
+    }
+}
+
+/// An odometry reading: the robot turned by `rotation` radians and then drove `distance`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Motion {
+    pub rotation: f64,
+    pub distance: f64,
+}
+
+/// The probabilistic motion model $P(X_{t+1} \mid X_t, A_t)$. Localization only ever needs to
+/// draw samples from it.
+pub trait MotionModel {
+    type Action;
+
+    /// Samples a pose the robot could end up in after taking `action` from `pose`.
+    fn sample<R: Rng + ?Sized>(&self, pose: &Pose, action: &Self::Action, rng: &mut R) -> Pose;
+}
+
+/// The sensor model $P(Z_t \mid X_t)$, evaluated against a map of the environment.
+pub trait SensorModel<Map> {
+    type Observation;
+
+    /// How likely the robot is to observe `observation` from `pose` in `map`.
+    fn likelihood(&self, pose: &Pose, observation: &Self::Observation, map: &Map) -> f64;
+}
+
+/// A map that can be queried for the distance to the nearest obstacle along a ray.
+pub trait RangeMap {
+    /// The distance from `pose` to the first obstacle in the direction `angle`, measured in
+    /// radians relative to the pose's heading.
+    fn ray_cast(&self, pose: &Pose, angle: f64) -> f64;
+}
+
+/// Draws a sample from a normal distribution using the Box-Muller transform.
+fn gaussian<R: Rng + ?Sized>(rng: &mut R, mean: f64, std_dev: f64) -> f64 {
+    let u1: f64 = 1.0 - rng.gen::<f64>();
+    let u2: f64 = rng.gen();
+    mean + std_dev * (-2.0 * u1.ln()).sqrt() * (2.0 * PI * u2).cos()
+}
+
+/// The density of a normal distribution at `x`.
+fn gaussian_pdf(x: f64, mean: f64, std_dev: f64) -> f64 {
+    let z = (x - mean) / std_dev;
+    (-0.5 * z * z).exp() / (std_dev * (2.0 * PI).sqrt())
+}
+
+/// A motion model for odometry readings that adds zero-mean Gaussian noise to both the rotation
+/// and the distance before moving.
+///
+/// # Examples
+///
+/// Samples scatter around the pose the reading predicts:
+///
+/// ```
+/// # use aima_rust::robotics::{GaussianOdometry, Motion, MotionModel, Pose};
+/// # use aima_rust::util::Rng;
+/// let model = GaussianOdometry::new(0.05, 0.1);
+/// let start = Pose::new(0.0, 0.0, 0.0);
+/// let motion = Motion { rotation: 0.0, distance: 10.0 };
+/// let mut rng = Rng::seeded(42);
+///
+/// let samples: Vec<Pose> = (0..1000).map(|_| model.sample(&start, &motion, &mut rng)).collect();
+/// let mean_x = samples.iter().map(|p| p.x).sum::<f64>() / 1000.0;
+/// let mean_y = samples.iter().map(|p| p.y).sum::<f64>() / 1000.0;
+///
+/// let expected = start.moved(&motion);
+/// assert!((mean_x - expected.x).abs() < 0.1);
+/// assert!((mean_y - expected.y).abs() < 0.1);
+/// assert!(samples.iter().any(|p| p != &expected));
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct GaussianOdometry {
+    pub rotation_noise: f64,
+    pub distance_noise: f64,
+}
+
+impl GaussianOdometry {
+    /// Creates a new GaussianOdometry given the standard deviations of the rotation and
+    /// distance noise.
+    pub fn new(rotation_noise: f64, distance_noise: f64) -> Self {
+        GaussianOdometry {
+            rotation_noise,
+            distance_noise,
+        }
+    }
+}
+
+impl MotionModel for GaussianOdometry {
+    type Action = Motion;
+
+    fn sample<R: Rng + ?Sized>(&self, pose: &Pose, action: &Motion, rng: &mut R) -> Pose {
+        pose.moved(&Motion {
+            rotation: gaussian(rng, action.rotation, self.rotation_noise),
+            distance: gaussian(rng, action.distance, self.distance_noise),
+        })
+    }
+}
+
+/// A range finder that casts beams at fixed angles relative to the robot's heading. Each beam's
+/// reading is modelled as the true distance to the nearest obstacle (capped at `max_range`) plus
+/// Gaussian noise, and the beams are assumed independent, so the likelihood of a scan is the
+/// product of the per-beam densities.
+///
+/// # Examples
+///
+/// ```
+/// # use aima_rust::robotics::{BeamRangeSensor, Pose, RangeMap, SensorModel};
+/// // A corridor with a wall across it at x = 10.
+/// struct Wall;
+/// impl RangeMap for Wall {
+///     fn ray_cast(&self, pose: &Pose, angle: f64) -> f64 {
+///         (10.0 - pose.x) / (pose.heading + angle).cos()
+///     }
+/// }
+///
+/// let sensor = BeamRangeSensor::new(vec![0.0], 0.5, 100.0);
+/// let reading = vec![4.0];
+/// let likelihood = |x| sensor.likelihood(&Pose::new(x, 0.0, 0.0), &reading, &Wall);
+///
+/// assert!(likelihood(6.0) > likelihood(5.5));
+/// assert!(likelihood(6.0) > likelihood(6.5));
+/// assert!(likelihood(5.5) > likelihood(3.0));
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct BeamRangeSensor {
+    pub angles: Vec<f64>,
+    pub noise: f64,
+    pub max_range: f64,
+}
+
+impl BeamRangeSensor {
+    /// Creates a new BeamRangeSensor with a beam at each of `angles`, the standard deviation of
+    /// a reading's noise, and the longest distance the sensor can report.
+    pub fn new(angles: Vec<f64>, noise: f64, max_range: f64) -> Self {
+        BeamRangeSensor {
+            angles,
+            noise,
+            max_range,
+        }
+    }
+}
+
+impl<Map: RangeMap> SensorModel<Map> for BeamRangeSensor {
+    /// One range reading per beam, in the same order as `angles`.
+    type Observation = Vec<f64>;
+
+    fn likelihood(&self, pose: &Pose, observation: &Vec<f64>, map: &Map) -> f64 {
+        self.angles
+            .iter()
+            .zip(observation)
+            .map(|(&angle, &range)| {
+                let expected = map.ray_cast(pose, angle).min(self.max_range);
+                gaussian_pdf(range, expected, self.noise)
+            })
+            .product()
+    }
+}
+
+/// A cell of an `OccupancyGrid`, as `(x, y)`. Cell `(x, y)` covers the unit square from
+/// `(x, y)` to `(x + 1, y + 1)`.
+pub type Cell = (i32, i32);
+
+/// # Occupancy Grid
+///
+/// An occupancy grid divides the environment into unit cells and stores, for each, the
+/// probability that it is occupied by an obstacle. A cell is considered free when that
+/// probability is below one half. Everything outside the grid is treated as occupied, so rays
+/// always end at the grid's edge.
+///
+/// # Examples
+///
+/// ```
+/// # use aima_rust::robotics::{OccupancyGrid, Pose, RangeMap};
+/// # use std::f64::consts::PI;
+/// // A 10x5 room with a wall across it at x = 8.
+/// let rows: Vec<Vec<bool>> = (0..5).map(|_| (0..10).map(|x| x == 8).collect()).collect();
+/// let grid = OccupancyGrid::from_occupied(&rows);
+///
+/// let robot = Pose::new(2.5, 2.5, 0.0);
+/// assert!((grid.ray_cast(&robot, 0.0) - 5.5).abs() < 1e-9);
+/// assert!((grid.ray_cast(&robot, PI) - 2.5).abs() < 1e-9);
+/// assert!((grid.ray_cast(&robot, PI / 2.0) - 2.5).abs() < 1e-9);
+///
+/// assert!(grid.is_free((0, 0)));
+/// assert!(grid.is_free((9, 4)));
+/// assert!(!grid.is_free((8, 2)));
+/// assert!(!grid.is_free((-1, 0)));
+/// assert!(!grid.is_free((10, 0)));
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct OccupancyGrid {
+    probabilities: Vec<Vec<f64>>,
+}
+
+impl OccupancyGrid {
+    /// Creates a new OccupancyGrid where `probabilities[y][x]` is the probability that cell
+    /// `(x, y)` is occupied. Every row must have the same length.
+    pub fn new(probabilities: Vec<Vec<f64>>) -> Self {
+        assert!(
+            probabilities.windows(2).all(|w| w[0].len() == w[1].len()),
+            "every row of the grid must have the same width"
+        );
+        OccupancyGrid { probabilities }
+    }
+
+    /// Creates an OccupancyGrid from known obstacles, where `occupied[y][x]` tells whether cell
+    /// `(x, y)` holds one.
+    pub fn from_occupied(occupied: &[Vec<bool>]) -> Self {
+        OccupancyGrid::new(
+            occupied
+                .iter()
+                .map(|row| row.iter().map(|&o| if o { 1.0 } else { 0.0 }).collect())
+                .collect(),
+        )
+    }
+
+    /// The number of cells along the x axis.
+    pub fn width(&self) -> i32 {
+        self.probabilities.first().map_or(0, |row| row.len() as i32)
+    }
+
+    /// The number of cells along the y axis.
+    pub fn height(&self) -> i32 {
+        self.probabilities.len() as i32
+    }
+
+    /// The probability that `cell` is occupied; cells outside the grid are certainly occupied.
+    pub fn occupancy(&self, cell: Cell) -> f64 {
+        let (x, y) = cell;
+        if x < 0 || y < 0 || x >= self.width() || y >= self.height() {
+            1.0
+        } else {
+            self.probabilities[y as usize][x as usize]
+        }
+    }
+
+    /// Whether `cell` lies inside the grid and is more likely free than occupied.
+    pub fn is_free(&self, cell: Cell) -> bool {
+        self.occupancy(cell) < 0.5
+    }
+}
+
+impl RangeMap for OccupancyGrid {
+    /// Walks the ray through the grid one cell boundary at a time (a digital differential
+    /// analyzer) and returns the distance at which it enters the first cell that isn't free.
+    fn ray_cast(&self, pose: &Pose, angle: f64) -> f64 {
+        let (dx, dy) = ((pose.heading + angle).cos(), (pose.heading + angle).sin());
+        let mut cell = (pose.x.floor() as i32, pose.y.floor() as i32);
+
+        // The distance along the ray to the next vertical and horizontal cell boundaries, and
+        // the distance between successive boundaries of each kind.
+        let boundary = |position: f64, direction: f64, index: i32| {
+            if direction > 0.0 {
+                ((index + 1) as f64 - position) / direction
+            } else if direction < 0.0 {
+                (index as f64 - position) / direction
+            } else {
+                f64::INFINITY
+            }
+        };
+        let mut next_x = boundary(pose.x, dx, cell.0);
+        let mut next_y = boundary(pose.y, dy, cell.1);
+        let (step_x, step_y) = ((1.0 / dx).abs(), (1.0 / dy).abs());
+
+        let mut distance = 0.0;
+        while self.is_free(cell) {
+            if next_x < next_y {
+                distance = next_x;
+                next_x += step_x;
+                cell.0 += dx.signum() as i32;
+            } else {
+                distance = next_y;
+                next_y += step_y;
+                cell.1 += dy.signum() as i32;
+            }
+        }
+        distance
+    }
+}
+
+/// Moving between free cells of an `OccupancyGrid` as a search `Problem`. The robot can move to
+/// any of its eight neighbouring cells that is free; straight moves cost `1` and diagonal ones
+/// $\sqrt{2}$. A diagonal move is only allowed when both cells it cuts past are free as well, so
+/// the robot never squeezes between two obstacles that touch at a corner.
+pub struct PathPlanningProblem<'a> {
+    pub grid: &'a OccupancyGrid,
+    pub start: Cell,
+    pub goal: Cell,
+}
+
+impl<'a> Problem for PathPlanningProblem<'a> {
+    type State = Cell;
+    type Action = (i32, i32);
+
+    fn initial_state(&self) -> Cell {
+        self.start
+    }
+
+    fn actions(&self, &(x, y): &Cell) -> Vec<(i32, i32)> {
+        let mut moves = Vec::new();
+        for dx in -1..=1 {
+            for dy in -1..=1 {
+                let free = |cell| self.grid.is_free(cell);
+                if (dx, dy) != (0, 0)
+                    && free((x + dx, y + dy))
+                    && free((x + dx, y))
+                    && free((x, y + dy))
+                {
+                    moves.push((dx, dy));
+                }
+            }
+        }
+        moves
+    }
+
+    fn result(&self, &(x, y): &Cell, &(dx, dy): &(i32, i32)) -> Cell {
+        (x + dx, y + dy)
+    }
+
+    fn goal_test(&self, cell: &Cell) -> bool {
+        *cell == self.goal
+    }
+
+    fn step_cost(&self, _cell: &Cell, &(dx, dy): &(i32, i32), _next: &Cell) -> f64 {
+        if dx != 0 && dy != 0 {
+            std::f64::consts::SQRT_2
+        } else {
+            1.0
+        }
+    }
+}
+
+/// Plans the shortest route through `grid` from `start` to `goal`, using A\* search over a
+/// `PathPlanningProblem` with the straight-line (Euclidean) distance to the goal as its
+/// heuristic. Returns the cells visited, from `start` to `goal`, together with the cost of the
+/// route, or `None` if the goal can't be reached.
+///
+/// # Examples
+///
+/// ```
+/// # use aima_rust::robotics::{plan_path, OccupancyGrid};
+/// // A 5x5 room with a wall rising from the floor at x = 2, leaving a gap at the top.
+/// let rows: Vec<Vec<bool>> = (0..5).map(|y| (0..5).map(|x| x == 2 && y < 4).collect()).collect();
+/// let grid = OccupancyGrid::from_occupied(&rows);
+///
+/// let (cells, cost) = plan_path(&grid, (0, 0), (4, 0)).unwrap();
+/// assert_eq!(cells.first(), Some(&(0, 0)));
+/// assert_eq!(cells.last(), Some(&(4, 0)));
+/// assert!(cells.contains(&(2, 4)));
+/// assert!(cells.iter().all(|&cell| grid.is_free(cell)));
+/// assert!(cost > 4.0);
+///
+/// // Walling off the goal leaves no route at all.
+/// let mut rows = rows;
+/// rows[1][4] = true;
+/// rows[1][3] = true;
+/// rows[0][3] = true;
+/// let grid = OccupancyGrid::from_occupied(&rows);
+/// assert_eq!(plan_path(&grid, (0, 0), (4, 0)), None);
+/// ```
+pub fn plan_path(grid: &OccupancyGrid, start: Cell, goal: Cell) -> Option<(Vec<Cell>, f64)> {
+    if !grid.is_free(start) || !grid.is_free(goal) {
+        return None;
+    }
+    let problem = PathPlanningProblem { grid, start, goal };
+    let distance = |&(x, y): &Cell| (((x - goal.0).pow(2) + (y - goal.1).pow(2)) as f64).sqrt();
+    astar_search(&problem, distance).map(|node| (node.path(), node.path_cost))
+}
@@ -0,0 +1,2329 @@
+//! # Search
+//!
+//! This module covers material in Chapters 3 & 4.
+//!
+//! ## Index of Algorithms
+//!
+//! | **Figure** | **Name**                          | **Module Link**
+//! |:-----------|:----------------------------------|:-------------------------------
+//! | 3.1        | Simple-Problem-Solving-Agent      | `SimpleProblemSolvingAgent`                              |
+//! | 3          | Problem                           | `Problem`                                                |
+//! | 3          | Node                              | `Node`                                                   |
+//! | 3.11       | Breadth-First-Search              | `breadth_first_search`                                   |
+//! | 3.17       | Depth-Limited-Search              | `depth_limited_search`                                   |
+//! | 3.18       | Iterative-Deepening-Search        | `iterative_deepening_search`                             |
+//! | 3          | Search Outcome                    | `SearchOutcome`                                          |
+//! | 3.22       | Best-First-Search                 | `best_first_graph_search`                                |
+//! | 3.24       | A\*-Search                        | `astar_search`                                           |
+//! | 3          | A\*-Search with Tie-Breaking      | `astar_search_with_tiebreak`                             |
+//! | 3          | Lazy Solution Enumeration         | `solutions`                                              |
+//! | 3          | K-Shortest-Paths                  | `k_shortest_paths`                                       |
+//! | 3          | Instrumented Problem              | `InstrumentedProblem`                                    |
+//! | 4.8        | Genetic-Algorithm                 | `genetic_algorithm`                                      |
+//! | 4.11       | And-Or-Graph-Search               | `and_or_graph_search`                                    |
+//! | 4          | Execute Conditional Plan          | `execute_conditional_plan`                               |
+//! | 4          | Sensorless Search                 | `sensorless_search`                                      |
+//! | 3          | Missionaries and Cannibals        | `missionaries_cannibals`                                 |
+//! | 3          | Towers of Hanoi                   | `hanoi`                                                  |
+//! | 3.3        | 8-Puzzle                          | `EightPuzzle`                                            |
+//! | 3          | Graph Problem                     | `GraphProblem`                                           |
+//! | 3          | Preprocessed Graph (ALT)          | `Preprocessed`                                           |
+//! | 3.2        | Romania                           | [`romania`](romania/index.html)                          |
+//!
+use crate::agents::{Agent, Environment};
+use crate::util::Rng;
+use rand::seq::SliceRandom;
+use rand::Rng as _;
+use std::cell::Cell;
+use std::cmp::Ordering;
+use std::collections::{BTreeSet, BinaryHeap, HashMap, HashSet, VecDeque};
+use std::fmt;
+use std::hash::Hash;
+use std::rc::Rc;
+
+/// A problem the search algorithms can solve: an initial state, the actions available in each
+/// state, a transition model, a goal test, and the cost of each step.
+pub trait Problem {
+    type State: Clone + Eq + Hash;
+    type Action: Clone;
+
+    /// The state the search starts from.
+    fn initial_state(&self) -> Self::State;
+
+    /// The actions that can be taken in `state`.
+    fn actions(&self, state: &Self::State) -> Vec<Self::Action>;
+
+    /// The state reached by taking `action` in `state`.
+    fn result(&self, state: &Self::State, action: &Self::Action) -> Self::State;
+
+    /// Whether `state` is a goal.
+    fn goal_test(&self, state: &Self::State) -> bool;
+
+    /// The cost of going from `state` to `next` by taking `action`. Every step costs `1` unless
+    /// a problem says otherwise.
+    fn step_cost(&self, _state: &Self::State, _action: &Self::Action, _next: &Self::State) -> f64 {
+        1.0
+    }
+}
+
+/// A node in a search tree: a state, together with the action and parent node that led to it and
+/// the total cost of the path from the initial state.
+pub struct Node<P: Problem> {
+    pub state: P::State,
+    pub parent: Option<Rc<Node<P>>>,
+    pub action: Option<P::Action>,
+    pub path_cost: f64,
+    pub depth: usize,
+}
+
+impl<P: Problem> Node<P> {
+    /// Creates the root node of a search tree.
+    pub fn root(state: P::State) -> Self {
+        Node {
+            state,
+            parent: None,
+            action: None,
+            path_cost: 0.0,
+            depth: 0,
+        }
+    }
+
+    /// Creates the child node reached by taking `action` from `parent`.
+    pub fn child(problem: &P, parent: &Rc<Node<P>>, action: P::Action) -> Self {
+        let state = problem.result(&parent.state, &action);
+        let path_cost = parent.path_cost + problem.step_cost(&parent.state, &action, &state);
+        Node {
+            state,
+            parent: Some(Rc::clone(parent)),
+            action: Some(action),
+            path_cost,
+            depth: parent.depth + 1,
+        }
+    }
+
+    /// The actions along the path from the root to this node.
+    pub fn solution(&self) -> Vec<P::Action> {
+        let mut actions = Vec::with_capacity(self.depth);
+        let mut node = self;
+        while let (Some(action), Some(parent)) = (&node.action, &node.parent) {
+            actions.push(action.clone());
+            node = parent;
+        }
+        actions.reverse();
+        actions
+    }
+
+    /// The states along the path from the root to this node, both included.
+    pub fn path(&self) -> Vec<P::State> {
+        let mut states = vec![self.state.clone()];
+        let mut node = self;
+        while let Some(parent) = &node.parent {
+            states.push(parent.state.clone());
+            node = parent;
+        }
+        states.reverse();
+        states
+    }
+}
+
+/// Figure 3.11
+///
+/// Expands the shallowest unexpanded node first, using a FIFO queue as the frontier. A state is
+/// added to the frontier only the first time it is reached, and the goal test is applied when a
+/// node is generated rather than when it is expanded. The solution found has the fewest steps.
+///
+/// # Examples
+///
+/// ```
+/// # use aima_rust::search::{breadth_first_search, Problem};
+/// struct Doubling;
+///
+/// impl Problem for Doubling {
+///     type State = u32;
+///     type Action = &'static str;
+///
+///     fn initial_state(&self) -> u32 { 1 }
+///     fn actions(&self, _state: &u32) -> Vec<&'static str> { vec!["+1", "*2"] }
+///     fn result(&self, state: &u32, action: &&'static str) -> u32 {
+///         if *action == "+1" { state + 1 } else { state * 2 }
+///     }
+///     fn goal_test(&self, state: &u32) -> bool { *state == 10 }
+/// }
+///
+/// let node = breadth_first_search(&Doubling).unwrap();
+///
+/// assert_eq!(node.path(), vec![1, 2, 4, 5, 10]);
+/// ```
+pub fn breadth_first_search<P: Problem>(problem: &P) -> Option<Node<P>> {
+    let root: Node<P> = Node::root(problem.initial_state());
+    if problem.goal_test(&root.state) {
+        return Some(root);
+    }
+    let mut reached = HashSet::new();
+    let mut frontier = VecDeque::new();
+    reached.insert(root.state.clone());
+    frontier.push_back(Rc::new(root));
+
+    while let Some(node) = frontier.pop_front() {
+        for action in problem.actions(&node.state) {
+            let child = Node::child(problem, &node, action);
+            if reached.insert(child.state.clone()) {
+                if problem.goal_test(&child.state) {
+                    return Some(child);
+                }
+                frontier.push_back(Rc::new(child));
+            }
+        }
+    }
+    None
+}
+
+/// What a search that can give up early found: a goal, or the reason it found none.
+pub enum SearchOutcome<P: Problem> {
+    Found(Node<P>),
+    /// No goal within the limit, but the limit stopped the search somewhere, so a goal may lie
+    /// deeper.
+    LimitReached,
+    /// The search space was exhausted: no goal is reachable at any depth.
+    Unsolvable,
+}
+
+impl<P: Problem> SearchOutcome<P> {
+    /// The goal node, if one was found.
+    pub fn found(self) -> Option<Node<P>> {
+        match self {
+            SearchOutcome::Found(node) => Some(node),
+            _ => None,
+        }
+    }
+}
+
+/// Whether the state of `node` already appears among its ancestors.
+fn is_cycle<P: Problem>(node: &Node<P>) -> bool {
+    let mut ancestor = &node.parent;
+    while let Some(parent) = ancestor {
+        if parent.state == node.state {
+            return true;
+        }
+        ancestor = &parent.parent;
+    }
+    false
+}
+
+fn recursive_dls<P: Problem>(node: Node<P>, problem: &P, limit: usize) -> SearchOutcome<P> {
+    if problem.goal_test(&node.state) {
+        return SearchOutcome::Found(node);
+    }
+    if limit == 0 {
+        return SearchOutcome::LimitReached;
+    }
+    let node = Rc::new(node);
+    let mut cutoff = false;
+    for action in problem.actions(&node.state) {
+        let child = Node::child(problem, &node, action);
+        if is_cycle(&child) {
+            continue;
+        }
+        match recursive_dls(child, problem, limit - 1) {
+            SearchOutcome::LimitReached => cutoff = true,
+            SearchOutcome::Unsolvable => {}
+            found => return found,
+        }
+    }
+    if cutoff {
+        SearchOutcome::LimitReached
+    } else {
+        SearchOutcome::Unsolvable
+    }
+}
+
+/// Figure 3.17
+///
+/// Depth-first tree search that treats nodes at depth `limit` as if they had no successors, and
+/// skips any path that returns to a state already on it. Returns `None` if no goal lies within
+/// the limit; `depth_limited_search_outcome` also says whether that was because of the limit.
+///
+/// Memory use is linear in the limit, but states reachable by several paths are searched once
+/// for each path.
+pub fn depth_limited_search<P: Problem>(problem: &P, limit: usize) -> Option<Node<P>> {
+    depth_limited_search_outcome(problem, limit).found()
+}
+
+/// Depth-limited search, as in `depth_limited_search`, that tells apart a search cut off by the
+/// limit, which may succeed with a higher one, from one that ran out of paths and proves no goal
+/// can be reached.
+///
+/// # Examples
+///
+/// ```
+/// # use aima_rust::search::{breadth_first_search, depth_limited_search_outcome, iterative_deepening_search_outcome, GraphProblem, SearchOutcome};
+/// let edges = vec![("a", "b", 1.0), ("b", "c", 1.0), ("c", "a", 1.0), ("x", "y", 1.0)];
+///
+/// let reachable = GraphProblem::from_edges(edges.clone(), "a", "c");
+/// assert!(matches!(depth_limited_search_outcome(&reachable, 0), SearchOutcome::LimitReached));
+/// assert!(matches!(depth_limited_search_outcome(&reachable, 1), SearchOutcome::Found(_)));
+///
+/// // "y" lies in another component of the graph.
+/// let unreachable = GraphProblem::from_edges(edges, "a", "y");
+/// assert!(breadth_first_search(&unreachable).is_none());
+/// assert!(matches!(depth_limited_search_outcome(&unreachable, 1), SearchOutcome::LimitReached));
+/// assert!(matches!(depth_limited_search_outcome(&unreachable, 5), SearchOutcome::Unsolvable));
+/// assert!(matches!(iterative_deepening_search_outcome(&unreachable, 100), SearchOutcome::Unsolvable));
+/// ```
+pub fn depth_limited_search_outcome<P: Problem>(problem: &P, limit: usize) -> SearchOutcome<P> {
+    recursive_dls(Node::root(problem.initial_state()), problem, limit)
+}
+
+/// Figure 3.18
+///
+/// Runs depth-limited search with limits zero, one, two and so on, until a goal is found. Like
+/// breadth-first search it finds the solution with the fewest steps, but it needs only as much
+/// memory as depth-first search.
+///
+/// Returns `None` once a limit is reached that cuts nothing off, meaning every path without a
+/// cycle was searched. A problem with infinitely many states always has deeper paths to explore,
+/// so the search never gives up on it; `iterative_deepening_search_outcome` takes a maximum depth.
+pub fn iterative_deepening_search<P: Problem>(problem: &P) -> Option<Node<P>> {
+    iterative_deepening_search_outcome(problem, usize::MAX).found()
+}
+
+/// Iterative deepening search, as in `iterative_deepening_search`, that stops after trying
+/// `max_depth`. The outcome is `LimitReached` if that limit still cut the search off, and
+/// `Unsolvable` if some limit searched every path without finding a goal.
+pub fn iterative_deepening_search_outcome<P: Problem>(
+    problem: &P,
+    max_depth: usize,
+) -> SearchOutcome<P> {
+    for limit in 0..=max_depth {
+        match depth_limited_search_outcome(problem, limit) {
+            SearchOutcome::LimitReached => {}
+            outcome => return outcome,
+        }
+    }
+    SearchOutcome::LimitReached
+}
+
+/// How best-first search chooses among frontier nodes with the same `f`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TieBreak {
+    /// The node added to the frontier first.
+    Fifo,
+    /// The node with the larger path cost `g`, that is, the one nearer the goal by the heuristic,
+    /// falling back to the node added first.
+    LargerG,
+}
+
+/// A frontier entry ordered so that `BinaryHeap`, a max-heap, pops the lowest `f` first, then the
+/// lowest `tie`, and then the entry that was pushed first.
+struct Prioritized<P: Problem> {
+    f: f64,
+    tie: f64,
+    order: usize,
+    node: Rc<Node<P>>,
+}
+
+impl<P: Problem> PartialEq for Prioritized<P> {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl<P: Problem> Eq for Prioritized<P> {}
+
+impl<P: Problem> PartialOrd for Prioritized<P> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<P: Problem> Ord for Prioritized<P> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other
+            .f
+            .total_cmp(&self.f)
+            .then_with(|| other.tie.total_cmp(&self.tie))
+            .then_with(|| other.order.cmp(&self.order))
+    }
+}
+
+/// Figure 3.22
+///
+/// Expands the frontier node with the lowest value of `f` first, keeping an explored set so that
+/// each state is expanded at most once. Returns the first goal node to be expanded, or `None` if
+/// the frontier runs dry.
+pub fn best_first_graph_search<P, F>(problem: &P, f: F) -> Option<Node<P>>
+where
+    P: Problem,
+    F: Fn(&Node<P>) -> f64,
+{
+    best_first_search_with_tiebreak(problem, f, TieBreak::Fifo)
+}
+
+fn best_first_search_with_tiebreak<P, F>(problem: &P, f: F, tiebreak: TieBreak) -> Option<Node<P>>
+where
+    P: Problem,
+    F: Fn(&Node<P>) -> f64,
+{
+    let tie = |node: &Node<P>| match tiebreak {
+        TieBreak::Fifo => 0.0,
+        TieBreak::LargerG => -node.path_cost,
+    };
+    let root: Node<P> = Node::root(problem.initial_state());
+    let mut explored = HashSet::new();
+    let mut best_cost: HashMap<P::State, f64> = HashMap::new();
+    let mut frontier = BinaryHeap::new();
+    let mut order = 0;
+    best_cost.insert(root.state.clone(), 0.0);
+    frontier.push(Prioritized {
+        f: f(&root),
+        tie: tie(&root),
+        order,
+        node: Rc::new(root),
+    });
+
+    while let Some(Prioritized { node, .. }) = frontier.pop() {
+        // A state may be on the frontier more than once; only its first copy is expanded.
+        if !explored.insert(node.state.clone()) {
+            continue;
+        }
+        if problem.goal_test(&node.state) {
+            // The goal node hasn't been expanded, so no child holds a reference to it.
+            return Rc::try_unwrap(node).ok();
+        }
+        for action in problem.actions(&node.state) {
+            let child = Node::child(problem, &node, action);
+            if !explored.contains(&child.state)
+                && best_cost
+                    .get(&child.state)
+                    .is_none_or(|&cost| child.path_cost < cost)
+            {
+                best_cost.insert(child.state.clone(), child.path_cost);
+                order += 1;
+                frontier.push(Prioritized {
+                    f: f(&child),
+                    tie: tie(&child),
+                    order,
+                    node: Rc::new(child),
+                });
+            }
+        }
+    }
+    None
+}
+
+/// Figure 3.24
+///
+/// A\* search expands nodes in order of $f(n) = g(n) + h(n)$, the cost of the path so far plus a
+/// heuristic estimate of the cost remaining. With an admissible heuristic, one that never
+/// overestimates, the solution found is optimal.
+///
+/// # Examples
+///
+/// Walking along a number line from `0` to `5`, one step at a time:
+///
+/// ```
+/// # use aima_rust::search::{astar_search, Problem};
+/// struct NumberLine;
+///
+/// impl Problem for NumberLine {
+///     type State = i32;
+///     type Action = i32;
+///
+///     fn initial_state(&self) -> i32 { 0 }
+///     fn actions(&self, _state: &i32) -> Vec<i32> { vec![-1, 1] }
+///     fn result(&self, state: &i32, action: &i32) -> i32 { state + action }
+///     fn goal_test(&self, state: &i32) -> bool { *state == 5 }
+/// }
+///
+/// let node = astar_search(&NumberLine, |state| (5 - state).abs() as f64).unwrap();
+///
+/// assert_eq!(node.solution(), vec![1; 5]);
+/// assert_eq!(node.path(), vec![0, 1, 2, 3, 4, 5]);
+/// assert_eq!(node.path_cost, 5.0);
+/// ```
+pub fn astar_search<P, H>(problem: &P, h: H) -> Option<Node<P>>
+where
+    P: Problem,
+    H: Fn(&P::State) -> f64,
+{
+    best_first_graph_search(problem, |node| node.path_cost + h(&node.state))
+}
+
+/// A\* search with a choice of how to break ties between frontier nodes with the same
+/// $f = g + h$; `astar_search` takes them first in, first out.
+///
+/// Ties are common when step costs and heuristic values are small integers, as in the 8-puzzle.
+/// Along an optimal path, $f$ tends to stay the same while $g$ grows, so preferring the larger
+/// $g$ pushes on toward the goal instead of fanning out across every node of the same $f$, and
+/// usually expands noticeably fewer nodes. Either way the solution found is optimal when the
+/// heuristic is consistent.
+///
+/// # Examples
+///
+/// ```
+/// # use aima_rust::search::{astar_search_with_tiebreak, EightPuzzle, InstrumentedProblem, TieBreak};
+/// let puzzle = EightPuzzle::new([7, 2, 4, 5, 0, 6, 8, 3, 1]);
+/// let solve = |tiebreak| {
+///     let problem = InstrumentedProblem::new(&puzzle);
+///     let node = astar_search_with_tiebreak(&problem, |s| puzzle.manhattan_distance(s), tiebreak);
+///     (node.unwrap().path_cost, problem.expansions())
+/// };
+///
+/// let (fifo_cost, fifo_expansions) = solve(TieBreak::Fifo);
+/// let (deep_cost, deep_expansions) = solve(TieBreak::LargerG);
+///
+/// assert_eq!(fifo_cost, 20.0);
+/// assert_eq!(deep_cost, fifo_cost);
+/// // 91 expansions against 282.
+/// assert!(deep_expansions < fifo_expansions);
+/// ```
+pub fn astar_search_with_tiebreak<P, H>(problem: &P, h: H, tiebreak: TieBreak) -> Option<Node<P>>
+where
+    P: Problem,
+    H: Fn(&P::State) -> f64,
+{
+    best_first_search_with_tiebreak(problem, |node| node.path_cost + h(&node.state), tiebreak)
+}
+
+/// Lazily enumerates the solutions of `problem` in order of increasing path cost, so the best
+/// few can be taken without searching again for each one. Like `astar_search`, it expands paths
+/// in order of $f(n) = g(n) + h(n)$; with an admissible heuristic no path can reach a goal more
+/// cheaply than its $f$, so each solution comes out only after every cheaper one. With $h = 0$
+/// it is uniform-cost search. Unlike graph search, this keeps every path on its frontier rather
+/// than only the cheapest path to each state, and goes on expanding past goals; it skips only
+/// paths that revisit a state, so each solution is a simple path.
+///
+/// The frontier is kept between calls to `next`, so taking the $k$-th solution costs no more
+/// than the search up to it. But it holds every partial path generated so far, which can grow
+/// exponentially with the cost of the solutions taken. On an infinite state space, or once every
+/// solution has been yielded from a large one, a call to `next` may never return; take only as
+/// many solutions as are needed.
+///
+/// # Examples
+///
+/// ```
+/// # use aima_rust::search::{solutions, GraphProblem, Problem};
+/// let edges = vec![
+///     ("S", "A", 1.0),
+///     ("A", "G", 1.0),
+///     ("S", "B", 1.0),
+///     ("B", "G", 1.5),
+///     ("S", "G", 3.0),
+/// ];
+/// let problem = GraphProblem::from_edges(edges, "S", "G");
+/// let cost = |path: &[&str]| {
+///     let mut state = problem.initial_state();
+///     let mut total = 0.0;
+///     for next in path {
+///         total += problem.step_cost(&state, next, next);
+///         state = next;
+///     }
+///     total
+/// };
+///
+/// let mut found = solutions(&problem, |_| 0.0);
+/// let best = found.next().unwrap();
+/// let second = found.next().unwrap();
+/// assert_eq!(best, vec!["A", "G"]);
+/// assert_eq!(second, vec!["B", "G"]);
+/// assert!(cost(&best) < cost(&second));
+///
+/// assert_eq!(found.next(), Some(vec!["G"]));
+/// assert_eq!(found.next(), None);
+/// ```
+///
+/// The straight-line distance to Bucharest never overestimates the road distance, so the routes
+/// still come out cheapest first:
+///
+/// ```
+/// # use aima_rust::search::{k_shortest_paths, romania, solutions, GraphProblem};
+/// let problem = GraphProblem::from_edges(romania::EDGES.iter().cloned(), "Arad", "Bucharest")
+///     .with_heuristic(romania::STRAIGHT_LINE_TO_BUCHAREST.iter().cloned());
+///
+/// let routes: Vec<Vec<&str>> = solutions(&problem, |city| problem.h(city)).take(3).collect();
+/// let cheapest: Vec<Vec<&str>> = k_shortest_paths(&problem, 3)
+///     .iter()
+///     .map(|node| node.solution())
+///     .collect();
+/// assert_eq!(routes, cheapest);
+/// assert_eq!(routes[0], vec!["Sibiu", "Rimnicu Vilcea", "Pitesti", "Bucharest"]);
+/// ```
+pub fn solutions<'a, P, H>(problem: &'a P, h: H) -> impl Iterator<Item = Vec<P::Action>> + 'a
+where
+    P: Problem,
+    H: Fn(&P::State) -> f64 + 'a,
+{
+    let mut frontier = BinaryHeap::new();
+    frontier.push(Prioritized {
+        f: h(&problem.initial_state()),
+        tie: 0.0,
+        order: 0,
+        node: Rc::new(Node::<P>::root(problem.initial_state())),
+    });
+    let mut order = 0;
+    std::iter::from_fn(move || {
+        while let Some(Prioritized { node, .. }) = frontier.pop() {
+            for action in problem.actions(&node.state) {
+                let child = Node::child(problem, &node, action);
+                let revisits = std::iter::successors(Some(&node), |a| a.parent.as_ref())
+                    .any(|a| a.state == child.state);
+                if !revisits {
+                    order += 1;
+                    frontier.push(Prioritized {
+                        f: child.path_cost + h(&child.state),
+                        tie: 0.0,
+                        order,
+                        node: Rc::new(child),
+                    });
+                }
+            }
+            if problem.goal_test(&node.state) {
+                return Some(node.solution());
+            }
+        }
+        None
+    })
+}
+
+/// A problem searched from `start` with some states and steps taken out, for the spur searches
+/// of `k_shortest_paths`.
+struct Detour<'a, P: Problem> {
+    problem: &'a P,
+    start: P::State,
+    blocked_states: HashSet<P::State>,
+    blocked_steps: HashSet<(P::State, P::State)>,
+}
+
+impl<'a, P: Problem> Problem for Detour<'a, P> {
+    type State = P::State;
+    type Action = P::Action;
+
+    fn initial_state(&self) -> P::State {
+        self.start.clone()
+    }
+
+    fn actions(&self, state: &P::State) -> Vec<P::Action> {
+        self.problem
+            .actions(state)
+            .into_iter()
+            .filter(|action| {
+                let next = self.problem.result(state, action);
+                !self.blocked_states.contains(&next)
+                    && !self.blocked_steps.contains(&(state.clone(), next))
+            })
+            .collect()
+    }
+
+    fn result(&self, state: &P::State, action: &P::Action) -> P::State {
+        self.problem.result(state, action)
+    }
+
+    fn goal_test(&self, state: &P::State) -> bool {
+        self.problem.goal_test(state)
+    }
+
+    fn step_cost(&self, state: &P::State, action: &P::Action, next: &P::State) -> f64 {
+        self.problem.step_cost(state, action, next)
+    }
+}
+
+/// The actions along a path and the states it passes through, starting with the initial state.
+type Path<P> = (Vec<<P as Problem>::Action>, Vec<<P as Problem>::State>);
+
+/// The node reached by taking `actions` in turn from the initial state.
+fn replay<P: Problem>(problem: &P, actions: &[P::Action]) -> Node<P> {
+    let mut node = Node::root(problem.initial_state());
+    for action in actions {
+        node = Node::child(problem, &Rc::new(node), action.clone());
+    }
+    node
+}
+
+/// # K-Shortest-Paths
+///
+/// The `k` cheapest distinct paths to a goal, cheapest first, found with Yen's algorithm. Paths
+/// are distinct when they pass through different sequences of states, and never revisit a
+/// state. If there are fewer than `k` such paths, all of them are returned.
+///
+/// Each new path is found by deviating from the last one accepted. For every state along that
+/// path, a _spur_ search looks for the cheapest way on to a goal from it, keeping the _root_ of
+/// the path up to that state but not the next step taken there by any accepted path with the
+/// same root, nor any state of the root itself. The cheapest of all the deviations found so far
+/// is the next path. Each path costs up to one uniform-cost search per state on the path before
+/// it, which suits route alternatives on maps; `solutions` can be cheaper when the paths are
+/// short.
+///
+/// # Examples
+///
+/// ```
+/// # use aima_rust::search::{k_shortest_paths, romania, GraphProblem};
+/// let problem = GraphProblem::from_edges(romania::EDGES.iter().cloned(), "Arad", "Bucharest");
+///
+/// let routes = k_shortest_paths(&problem, 3);
+/// let costs: Vec<f64> = routes.iter().map(|node| node.path_cost).collect();
+/// assert_eq!(costs, vec![418.0, 450.0, 575.0]);
+/// assert_eq!(routes[1].path(), vec!["Arad", "Sibiu", "Fagaras", "Bucharest"]);
+/// assert_eq!(
+///     routes[2].path(),
+///     vec!["Arad", "Zerind", "Oradea", "Sibiu", "Rimnicu Vilcea", "Pitesti", "Bucharest"]
+/// );
+///
+/// // There are only ten routes that never go back through a city.
+/// let all = k_shortest_paths(&problem, 20);
+/// assert_eq!(all.len(), 10);
+/// assert!(all.windows(2).all(|pair| pair[0].path_cost <= pair[1].path_cost));
+/// for (i, a) in all.iter().enumerate() {
+///     assert!(all[i + 1..].iter().all(|b| a.path() != b.path()));
+/// }
+/// ```
+///
+/// With fewer than `k` paths to the goal, it returns them all, in the same order as
+/// `solutions`:
+///
+/// ```
+/// # use aima_rust::search::{k_shortest_paths, solutions, GraphProblem};
+/// let edges = vec![("S", "A", 1.0), ("A", "G", 1.0), ("S", "B", 1.0), ("B", "G", 1.5)];
+/// let problem = GraphProblem::from_edges(edges, "S", "G");
+///
+/// let paths: Vec<Vec<&str>> = k_shortest_paths(&problem, 5)
+///     .iter()
+///     .map(|node| node.solution())
+///     .collect();
+/// assert_eq!(paths, solutions(&problem, |_| 0.0).collect::<Vec<_>>());
+/// assert_eq!(paths.len(), 2);
+///
+/// let unreachable = GraphProblem::from_edges(vec![("S", "A", 1.0)], "S", "G");
+/// assert!(k_shortest_paths(&unreachable, 3).is_empty());
+/// ```
+pub fn k_shortest_paths<P: Problem>(problem: &P, k: usize) -> Vec<Node<P>> {
+    let mut accepted: Vec<Path<P>> = Vec::new();
+    if k == 0 {
+        return Vec::new();
+    }
+    match best_first_graph_search(problem, |node| node.path_cost) {
+        Some(node) => accepted.push((node.solution(), node.path())),
+        None => return Vec::new(),
+    }
+    let mut candidates: Vec<(f64, Path<P>)> = Vec::new();
+    while accepted.len() < k {
+        let (last_actions, last_states) = accepted.last().unwrap().clone();
+        for i in 0..last_states.len() - 1 {
+            let root = &last_states[..=i];
+            let detour = Detour {
+                problem,
+                start: last_states[i].clone(),
+                blocked_states: root[..i].iter().cloned().collect(),
+                blocked_steps: accepted
+                    .iter()
+                    .filter(|(_, states)| states.len() > i + 1 && &states[..=i] == root)
+                    .map(|(_, states)| (states[i].clone(), states[i + 1].clone()))
+                    .collect(),
+            };
+            if let Some(spur) = best_first_graph_search(&detour, |node| node.path_cost) {
+                let mut actions = last_actions[..i].to_vec();
+                actions.extend(spur.solution());
+                let mut states = root[..i].to_vec();
+                states.extend(spur.path());
+                let known = candidates.iter().any(|(_, (_, s))| *s == states)
+                    || accepted.iter().any(|(_, s)| *s == states);
+                if !known {
+                    let cost = replay(problem, &actions).path_cost;
+                    candidates.push((cost, (actions, states)));
+                }
+            }
+        }
+        let best = candidates
+            .iter()
+            .enumerate()
+            .min_by(|a, b| a.1 .0.total_cmp(&b.1 .0))
+            .map(|(i, _)| i);
+        match best {
+            Some(i) => {
+                accepted.push(candidates.remove(i).1);
+            }
+            None => break,
+        }
+    }
+    accepted
+        .iter()
+        .map(|(actions, _)| replay(problem, actions))
+        .collect()
+}
+
+/// Wraps a problem to count the work a search does on it, for comparing algorithms: how many
+/// states it expands by asking for their actions, and how many it goal-tests.
+pub struct InstrumentedProblem<P: Problem> {
+    problem: P,
+    expansions: Cell<usize>,
+    goal_tests: Cell<usize>,
+}
+
+impl<P: Problem> InstrumentedProblem<P> {
+    /// Creates a new InstrumentedProblem given the problem to count searches of.
+    pub fn new(problem: P) -> Self {
+        InstrumentedProblem {
+            problem,
+            expansions: Cell::new(0),
+            goal_tests: Cell::new(0),
+        }
+    }
+
+    /// The number of states expanded so far.
+    pub fn expansions(&self) -> usize {
+        self.expansions.get()
+    }
+
+    /// The number of goal tests so far.
+    pub fn goal_tests(&self) -> usize {
+        self.goal_tests.get()
+    }
+}
+
+impl<P: Problem> Problem for InstrumentedProblem<P> {
+    type State = P::State;
+    type Action = P::Action;
+
+    fn initial_state(&self) -> P::State {
+        self.problem.initial_state()
+    }
+
+    fn actions(&self, state: &P::State) -> Vec<P::Action> {
+        self.expansions.set(self.expansions.get() + 1);
+        self.problem.actions(state)
+    }
+
+    fn result(&self, state: &P::State, action: &P::Action) -> P::State {
+        self.problem.result(state, action)
+    }
+
+    fn goal_test(&self, state: &P::State) -> bool {
+        self.goal_tests.set(self.goal_tests.get() + 1);
+        self.problem.goal_test(state)
+    }
+
+    fn step_cost(&self, state: &P::State, action: &P::Action, next: &P::State) -> f64 {
+        self.problem.step_cost(state, action, next)
+    }
+}
+
+/// A borrowed problem is the same problem, so wrappers such as `InstrumentedProblem` can borrow
+/// the problem they wrap.
+impl<P: Problem> Problem for &P {
+    type State = P::State;
+    type Action = P::Action;
+
+    fn initial_state(&self) -> P::State {
+        (**self).initial_state()
+    }
+
+    fn actions(&self, state: &P::State) -> Vec<P::Action> {
+        (**self).actions(state)
+    }
+
+    fn result(&self, state: &P::State, action: &P::Action) -> P::State {
+        (**self).result(state, action)
+    }
+
+    fn goal_test(&self, state: &P::State) -> bool {
+        (**self).goal_test(state)
+    }
+
+    fn step_cost(&self, state: &P::State, action: &P::Action, next: &P::State) -> f64 {
+        (**self).step_cost(state, action, next)
+    }
+}
+
+/// Figure 3.1
+///
+/// An agent that plans ahead. When it has no plan, it formulates a goal and a problem for
+/// reaching it from its current state, searches for a solution, and then carries out the
+/// solution one action per step without looking at its percepts, before planning afresh.
+///
+/// The agent is made of three functions: `update_state` turns a percept into a description of the
+/// current state, `formulate` chooses a goal for that state and poses the problem of reaching it
+/// (or returns `None` when there is nothing to achieve), and `search` solves the problem. The
+/// agent's action is `None`, the null action, when it has nothing to do or the search fails.
+///
+/// # Examples
+///
+/// Driving through Romania with a plan found by A\* search:
+///
+/// ```
+/// # use aima_rust::agents::Agent;
+/// # use aima_rust::search::{astar_search, romania, GraphProblem, SimpleProblemSolvingAgent};
+/// let update_state = |city: &'static str| city;
+/// let formulate = |&city: &&'static str| {
+///     let problem = GraphProblem::from_edges(romania::EDGES.iter().cloned(), city, "Bucharest");
+///     Some(problem.with_heuristic(romania::STRAIGHT_LINE_TO_BUCHAREST.iter().cloned()))
+/// };
+/// let search = |problem: &GraphProblem<&'static str>| {
+///     astar_search(problem, |city| problem.h(city)).map(|node| node.solution())
+/// };
+/// let mut agent = SimpleProblemSolvingAgent::new(update_state, formulate, search);
+///
+/// assert_eq!(agent.program("Arad"), Some("Sibiu"));
+/// assert_eq!(agent.plan(), &["Rimnicu Vilcea", "Pitesti", "Bucharest"]);
+/// assert_eq!(agent.program("Sibiu"), Some("Rimnicu Vilcea"));
+/// assert_eq!(agent.program("Rimnicu Vilcea"), Some("Pitesti"));
+/// assert_eq!(agent.program("Pitesti"), Some("Bucharest"));
+/// assert_eq!(agent.program("Bucharest"), None);
+/// ```
+pub struct SimpleProblemSolvingAgent<Percept, P, U, F, S>
+where
+    P: Problem,
+    U: FnMut(Percept) -> P::State,
+    F: FnMut(&P::State) -> Option<P>,
+    S: FnMut(&P) -> Option<Vec<P::Action>>,
+{
+    update_state: U,
+    formulate: F,
+    search: S,
+    state: Option<P::State>,
+    plan: VecDeque<P::Action>,
+    _percept: std::marker::PhantomData<fn(Percept)>,
+}
+
+impl<Percept, P, U, F, S> SimpleProblemSolvingAgent<Percept, P, U, F, S>
+where
+    P: Problem,
+    U: FnMut(Percept) -> P::State,
+    F: FnMut(&P::State) -> Option<P>,
+    S: FnMut(&P) -> Option<Vec<P::Action>>,
+{
+    /// Creates a new agent, with no plan yet, given its three functions.
+    pub fn new(update_state: U, formulate: F, search: S) -> Self {
+        SimpleProblemSolvingAgent {
+            update_state,
+            formulate,
+            search,
+            state: None,
+            plan: VecDeque::new(),
+            _percept: std::marker::PhantomData,
+        }
+    }
+
+    /// The agent's latest description of the current state.
+    pub fn state(&self) -> Option<&P::State> {
+        self.state.as_ref()
+    }
+
+    /// The actions of the current plan that remain to be taken.
+    pub fn plan(&self) -> &VecDeque<P::Action> {
+        &self.plan
+    }
+}
+
+impl<Percept, P, U, F, S> Agent for SimpleProblemSolvingAgent<Percept, P, U, F, S>
+where
+    P: Problem,
+    U: FnMut(Percept) -> P::State,
+    F: FnMut(&P::State) -> Option<P>,
+    S: FnMut(&P) -> Option<Vec<P::Action>>,
+{
+    type Percept = Percept;
+    type Action = Option<P::Action>;
+
+    fn program(&mut self, percept: Percept) -> Option<P::Action> {
+        let state = (self.update_state)(percept);
+        if self.plan.is_empty() {
+            if let Some(problem) = (self.formulate)(&state) {
+                self.plan = (self.search)(&problem).unwrap_or_default().into();
+            }
+        }
+        self.state = Some(state);
+        self.plan.pop_front()
+    }
+}
+
+/// A problem whose actions have nondeterministic outcomes: each action may lead to any of several
+/// states. The environment is fully observable, so the agent learns which outcome occurred.
+pub trait NondeterministicProblem {
+    type State: Clone + Eq + Hash;
+    type Action: Clone;
+
+    /// The state the search starts from.
+    fn initial_state(&self) -> Self::State;
+
+    /// The actions that can be taken in `state`.
+    fn actions(&self, state: &Self::State) -> Vec<Self::Action>;
+
+    /// Every state that taking `action` in `state` may lead to.
+    fn results(&self, state: &Self::State, action: &Self::Action) -> Vec<Self::State>;
+
+    /// Whether `state` is a goal.
+    fn goal_test(&self, state: &Self::State) -> bool;
+}
+
+/// The sub-plans of a conditional plan step, one for each state the step may lead to.
+pub type Branches<S, A> = Vec<(S, ConditionalPlan<S, A>)>;
+
+/// A conditional plan: an action to take, followed by a sub-plan for each state it may lead to.
+#[derive(Clone, Debug, PartialEq)]
+pub enum ConditionalPlan<S, A> {
+    /// The goal has been reached; nothing more to do.
+    Done,
+    /// Take `action`, observe the resulting state, and continue with the plan for it.
+    Step { action: A, branches: Branches<S, A> },
+}
+
+/// Figure 4.11
+///
+/// Searches the AND-OR tree of a nondeterministic problem for a conditional plan that reaches a
+/// goal whatever outcomes occur. OR nodes choose an action; AND nodes must handle every outcome of
+/// it. Returns `None` if no plan is guaranteed to succeed without looping.
+///
+/// # Examples
+///
+/// In the erratic vacuum world, sucking sometimes cleans the neighboring square too, so the plan
+/// must check whether moving is still needed:
+///
+/// ```
+/// # use aima_rust::search::{and_or_graph_search, ConditionalPlan};
+/// # use aima_rust::agents::envs::vacuum::{Action, ErraticVacuumWorld, Location, Status};
+/// let problem = ErraticVacuumWorld::new((Location::A, Status::Dirty, Status::Dirty));
+///
+/// match and_or_graph_search(&problem).unwrap() {
+///     ConditionalPlan::Step { action, branches } => {
+///         assert_eq!(action, Action::Suck);
+///         assert_eq!(branches.len(), 2);
+///     }
+///     ConditionalPlan::Done => unreachable!(),
+/// }
+/// ```
+pub fn and_or_graph_search<P: NondeterministicProblem>(
+    problem: &P,
+) -> Option<ConditionalPlan<P::State, P::Action>> {
+    or_search(problem, &problem.initial_state(), &mut Vec::new())
+}
+
+fn or_search<P: NondeterministicProblem>(
+    problem: &P,
+    state: &P::State,
+    path: &mut Vec<P::State>,
+) -> Option<ConditionalPlan<P::State, P::Action>> {
+    if problem.goal_test(state) {
+        return Some(ConditionalPlan::Done);
+    }
+    if path.contains(state) {
+        return None;
+    }
+    path.push(state.clone());
+    let plan = problem.actions(state).into_iter().find_map(|action| {
+        let outcomes = problem.results(state, &action);
+        and_search(problem, outcomes, path)
+            .map(|branches| ConditionalPlan::Step { action, branches })
+    });
+    path.pop();
+    plan
+}
+
+fn and_search<P: NondeterministicProblem>(
+    problem: &P,
+    states: Vec<P::State>,
+    path: &mut Vec<P::State>,
+) -> Option<Branches<P::State, P::Action>> {
+    states
+        .into_iter()
+        .map(|state| or_search(problem, &state, path).map(|plan| (state, plan)))
+        .collect()
+}
+
+/// Returned when executing a conditional plan leads to a state the plan has no branch for.
+#[derive(Clone, Debug, PartialEq)]
+pub struct UnexpectedState<S>(pub S);
+
+impl<S: fmt::Debug> fmt::Display for UnexpectedState<S> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "the plan has no branch for the observed state {:?}",
+            self.0
+        )
+    }
+}
+
+impl<S: fmt::Debug> std::error::Error for UnexpectedState<S> {}
+
+/// Executes a conditional plan in a live environment with a single agent. After each action the
+/// agent observes the state, as its percept, and follows the branch of the plan for it. Returns
+/// the actions taken, or an error carrying the observed state if the plan has no branch for it.
+///
+/// # Examples
+///
+/// Whatever the erratic vacuum does, the plan leaves both squares clean:
+///
+/// ```
+/// # use aima_rust::search::{and_or_graph_search, execute_conditional_plan};
+/// # use aima_rust::agents::Environment;
+/// # use aima_rust::agents::envs::vacuum::{ErraticVacuumEnvironment, ErraticVacuumWorld, Location, Status};
+/// let start = (Location::A, Status::Dirty, Status::Dirty);
+/// let plan = and_or_graph_search(&ErraticVacuumWorld::new(start)).unwrap();
+///
+/// for seed in 0..20 {
+///     let mut env = ErraticVacuumEnvironment::new(start, seed);
+///     execute_conditional_plan(&plan, &mut env).unwrap();
+///     let (_, a, b) = env.percept(0);
+///     assert_eq!((a, b), (Status::Clean, Status::Clean));
+/// }
+/// ```
+///
+/// A plan that doesn't anticipate what happens fails with an error:
+///
+/// ```
+/// # use aima_rust::search::{execute_conditional_plan, ConditionalPlan, UnexpectedState};
+/// # use aima_rust::agents::envs::vacuum::{Action, ErraticVacuumEnvironment, Location, Status};
+/// let start = (Location::A, Status::Dirty, Status::Dirty);
+/// let plan = ConditionalPlan::Step {
+///     action: Action::Right,
+///     branches: vec![((Location::A, Status::Dirty, Status::Dirty), ConditionalPlan::Done)],
+/// };
+///
+/// let mut env = ErraticVacuumEnvironment::new(start, 0);
+/// assert_eq!(
+///     execute_conditional_plan(&plan, &mut env),
+///     Err(UnexpectedState((Location::B, Status::Dirty, Status::Dirty)))
+/// );
+/// ```
+pub fn execute_conditional_plan<E>(
+    plan: &ConditionalPlan<E::Percept, E::Action>,
+    env: &mut E,
+) -> Result<Vec<E::Action>, UnexpectedState<E::Percept>>
+where
+    E: Environment,
+    E::Percept: PartialEq,
+    E::Action: Clone,
+{
+    let mut taken = Vec::new();
+    let mut plan = plan;
+    while let ConditionalPlan::Step { action, branches } = plan {
+        env.execute(vec![action.clone()]);
+        taken.push(action.clone());
+        let observed = env.percept(0);
+        plan = match branches.iter().find(|(state, _)| *state == observed) {
+            Some((_, next)) => next,
+            None => return Err(UnexpectedState(observed)),
+        };
+    }
+    Ok(taken)
+}
+
+/// The most belief states `sensorless_search` may reach before it gives up.
+pub const MAX_BELIEF_STATES: usize = 1 << 16;
+
+/// A problem over belief states: the sets of physical states the agent might be in.
+struct BeliefProblem<'a, P: Problem> {
+    problem: &'a P,
+    initial: BTreeSet<P::State>,
+}
+
+impl<'a, P> Problem for BeliefProblem<'a, P>
+where
+    P: Problem,
+    P::State: Ord,
+    P::Action: PartialEq,
+{
+    type State = BTreeSet<P::State>;
+    type Action = P::Action;
+
+    fn initial_state(&self) -> BTreeSet<P::State> {
+        self.initial.clone()
+    }
+
+    fn actions(&self, belief: &BTreeSet<P::State>) -> Vec<P::Action> {
+        let mut actions = Vec::new();
+        for state in belief {
+            for action in self.problem.actions(state) {
+                if !actions.contains(&action) {
+                    actions.push(action);
+                }
+            }
+        }
+        actions
+    }
+
+    fn result(&self, belief: &BTreeSet<P::State>, action: &P::Action) -> BTreeSet<P::State> {
+        belief
+            .iter()
+            .map(|state| {
+                if self.problem.actions(state).contains(action) {
+                    self.problem.result(state, action)
+                } else {
+                    state.clone()
+                }
+            })
+            .collect()
+    }
+
+    fn goal_test(&self, belief: &BTreeSet<P::State>) -> bool {
+        belief.iter().all(|state| self.problem.goal_test(state))
+    }
+}
+
+/// Searches for a conformant plan: a sequence of actions that reaches a goal from every one of
+/// `initial_states`, for an agent with no sensors that can't tell which one it is in.
+///
+/// The search runs breadth-first over belief states, the sets of states the agent might be in,
+/// kept sorted so that equal sets hash alike. Each action maps a belief state to the set of
+/// results of applying it to each member. An action that is illegal in some member is assumed
+/// to leave that member unchanged. A belief state is a goal when all its members are goals.
+///
+/// There can be exponentially many belief states in the number of physical states, so the search
+/// gives up once it has reached `MAX_BELIEF_STATES` of them, counting both those expanded and
+/// those still on the frontier. Returns `None` if there is no plan within that limit.
+///
+/// # Examples
+///
+/// Without sensors, the vacuum agent must clean both squares blindly:
+///
+/// ```
+/// # use aima_rust::search::{sensorless_search, Problem};
+/// # use aima_rust::agents::envs::vacuum::{Location, Status, VacuumWorld};
+/// let mut all_states = Vec::new();
+/// for &location in &[Location::A, Location::B] {
+///     for &a in &[Status::Clean, Status::Dirty] {
+///         for &b in &[Status::Clean, Status::Dirty] {
+///             all_states.push((location, a, b));
+///         }
+///     }
+/// }
+///
+/// let world = VacuumWorld::new(all_states[0]);
+/// let plan = sensorless_search(&world, all_states.clone()).unwrap();
+///
+/// assert_eq!(plan.len(), 4);
+/// for state in all_states {
+///     let end = plan.iter().fold(state, |state, action| world.result(&state, action));
+///     assert!(world.goal_test(&end));
+/// }
+/// ```
+///
+/// Walking blind on an endless number line, the agent can never be sure where it is, and there
+/// is no end to the belief states; the search stops at the limit:
+///
+/// ```
+/// # use aima_rust::search::{sensorless_search, Problem};
+/// struct NumberLine;
+///
+/// impl Problem for NumberLine {
+///     type State = i32;
+///     type Action = i32;
+///
+///     fn initial_state(&self) -> i32 { 0 }
+///     fn actions(&self, _state: &i32) -> Vec<i32> { vec![-1, 1] }
+///     fn result(&self, state: &i32, action: &i32) -> i32 { state + action }
+///     fn goal_test(&self, state: &i32) -> bool { *state == 5 }
+/// }
+///
+/// assert_eq!(sensorless_search(&NumberLine, vec![0]), Some(vec![1; 5]));
+/// assert_eq!(sensorless_search(&NumberLine, vec![0, 1]), None);
+/// ```
+pub fn sensorless_search<P, I>(problem: &P, initial_states: I) -> Option<Vec<P::Action>>
+where
+    P: Problem,
+    P::State: Ord,
+    P::Action: PartialEq,
+    I: IntoIterator<Item = P::State>,
+{
+    let belief = BeliefProblem {
+        problem,
+        initial: initial_states.into_iter().collect(),
+    };
+    let root: Node<BeliefProblem<P>> = Node::root(belief.initial_state());
+    if belief.goal_test(&root.state) {
+        return Some(root.solution());
+    }
+    let mut reached = HashSet::new();
+    let mut frontier = VecDeque::new();
+    reached.insert(root.state.clone());
+    frontier.push_back(Rc::new(root));
+
+    while let Some(node) = frontier.pop_front() {
+        for action in belief.actions(&node.state) {
+            let child = Node::child(&belief, &node, action);
+            if reached.insert(child.state.clone()) {
+                if belief.goal_test(&child.state) {
+                    return Some(child.solution());
+                }
+                if reached.len() >= MAX_BELIEF_STATES {
+                    return None;
+                }
+                frontier.push_back(Rc::new(child));
+            }
+        }
+    }
+    None
+}
+
+/// An individual in a genetic algorithm: a fixed-length string of genes.
+pub type Individual<G> = Vec<G>;
+
+/// # Genetic Algorithm
+///
+/// The settings of a run of the genetic algorithm of figure 4.8, created by `genetic_algorithm`
+/// and adjusted with the `with_` methods before calling `run`.
+pub struct GeneticAlgorithm<G, F> {
+    population: Vec<Individual<G>>,
+    fitness: F,
+    gene_pool: Vec<G>,
+    mutation_rate: f64,
+    sharing_radius: Option<f64>,
+    tournament_size: Option<usize>,
+    elitism: usize,
+}
+
+/// What a run of the genetic algorithm produced.
+#[derive(Clone, Debug, PartialEq)]
+pub struct GeneticReport<G> {
+    /// The fittest individual of the final population, by raw fitness.
+    pub best: Individual<G>,
+    /// The fitness of `best`.
+    pub best_fitness: f64,
+    /// The final population.
+    pub population: Vec<Individual<G>>,
+    /// The number of distinct individuals in each generation, starting with the initial
+    /// population.
+    pub diversity: Vec<usize>,
+    /// The fitness of the fittest individual in each generation, starting with the initial
+    /// population.
+    pub best_fitnesses: Vec<f64>,
+}
+
+/// Figure 4.8
+///
+/// Sets up a genetic algorithm that evolves `population` towards higher values of `fitness`.
+/// Children are bred from two parents, by default chosen with probability proportional to their
+/// fitness, which must therefore never be negative, by splicing a prefix of one onto the suffix
+/// of the other at a random point. Each child is mutated with probability 0.1, by replacing one
+/// of its genes with one drawn at random from `gene_pool`.
+///
+/// # Examples
+///
+/// Evolving bit strings towards all ones:
+///
+/// ```
+/// # use aima_rust::search::genetic_algorithm;
+/// let population = vec![vec![0u8; 12]; 20];
+/// let ones = |individual: &[u8]| individual.iter().filter(|&&bit| bit == 1).count() as f64;
+///
+/// let report = genetic_algorithm(population, ones, vec![0, 1])
+///     .with_mutation_rate(0.5)
+///     .run(200, 42);
+///
+/// assert!(report.best_fitness >= 11.0);
+/// assert_eq!(report.diversity.len(), 201);
+/// ```
+pub fn genetic_algorithm<G, F>(
+    population: Vec<Individual<G>>,
+    fitness: F,
+    gene_pool: Vec<G>,
+) -> GeneticAlgorithm<G, F>
+where
+    G: Clone + Eq + Hash,
+    F: Fn(&[G]) -> f64,
+{
+    GeneticAlgorithm {
+        population,
+        fitness,
+        gene_pool,
+        mutation_rate: 0.1,
+        sharing_radius: None,
+        tournament_size: None,
+        elitism: 0,
+    }
+}
+
+impl<G, F> GeneticAlgorithm<G, F>
+where
+    G: Clone + Eq + Hash,
+    F: Fn(&[G]) -> f64,
+{
+    /// Sets the probability that a child is mutated.
+    pub fn with_mutation_rate(mut self, mutation_rate: f64) -> Self {
+        self.mutation_rate = mutation_rate;
+        self
+    }
+
+    /// Turns on fitness sharing, a form of niching. Each individual's fitness is divided by its
+    /// niche count, the number of individuals within `sharing_radius` of it, counted with a
+    /// weight that falls linearly from one for identical individuals to zero at the radius:
+    ///
+    /// $$ f'(x) = \frac{f(x)}{\sum_y \max\left(0, 1 - \frac{d(x, y)}{\sigma}\right)} $$
+    ///
+    /// where $d$ is the Hamming distance, the number of positions at which two individuals
+    /// differ. Crowded individuals are less likely to be chosen as parents, so the population
+    /// spreads out over several optima instead of converging on one.
+    ///
+    /// # Examples
+    ///
+    /// A fitness with two peaks, at all zeros and all ones. The plain algorithm ends up on one of
+    /// them, while with niching the population keeps individuals near both:
+    ///
+    /// ```
+    /// # use aima_rust::search::genetic_algorithm;
+    /// let twin_peaks = |individual: &[u8]| {
+    ///     let ones = individual.iter().filter(|&&bit| bit == 1).count() as f64;
+    ///     let zeros = individual.len() as f64 - ones;
+    ///     ones.max(zeros).powi(4)
+    /// };
+    /// // Alternating bits, halfway between the peaks.
+    /// let population: Vec<Vec<u8>> = (0..40)
+    ///     .map(|i| (0..10).map(|j| ((i + j) % 2) as u8).collect())
+    ///     .collect();
+    /// let near_peaks = |population: &[Vec<u8>]| {
+    ///     let ones = |x: &&Vec<u8>| x.iter().filter(|&&bit| bit == 1).count();
+    ///     let near_zeros = population.iter().filter(|x| ones(x) <= 2).count();
+    ///     let near_ones = population.iter().filter(|x| ones(x) >= 8).count();
+    ///     (near_zeros, near_ones)
+    /// };
+    ///
+    /// let plain = genetic_algorithm(population.clone(), twin_peaks, vec![0, 1]).run(100, 1);
+    /// let niched = genetic_algorithm(population, twin_peaks, vec![0, 1])
+    ///     .with_niching(5.0)
+    ///     .run(100, 1);
+    ///
+    /// let (zeros, ones) = near_peaks(&plain.population);
+    /// assert!(zeros == 0 || ones == 0);
+    /// let (zeros, ones) = near_peaks(&niched.population);
+    /// assert!(zeros > 0 && ones > 0);
+    /// assert!(niched.diversity[100] > plain.diversity[100]);
+    /// ```
+    pub fn with_niching(mut self, sharing_radius: f64) -> Self {
+        self.sharing_radius = Some(sharing_radius);
+        self
+    }
+
+    /// Chooses parents by tournament instead of in proportion to their fitness: each parent is
+    /// the fittest of `size` individuals drawn at random. Only the order of the fitnesses
+    /// matters, so a few individuals far fitter than the rest can't take over the population in
+    /// a generation or two, as they can when selection is proportional.
+    ///
+    /// The size sets the selection pressure. Larger tournaments favour the fittest individuals
+    /// more strongly, so the population converges faster but loses diversity sooner and is more
+    /// likely to settle on a local optimum; a size of one is random selection, and a size of two
+    /// is often a good balance.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `size` is zero.
+    ///
+    /// # Examples
+    ///
+    /// Two individuals are far fitter than the rest. Proportional selection copies them
+    /// everywhere at once, while tournaments keep the population varied and still find the
+    /// optimum:
+    ///
+    /// ```
+    /// # use aima_rust::search::genetic_algorithm;
+    /// let fitness = |x: &[u8]| 8f64.powi(x.iter().filter(|&&bit| bit == 1).count() as i32);
+    /// let mut population: Vec<Vec<u8>> = (0..30)
+    ///     .map(|i: usize| (0..12).map(|j| ((i * 7 + j * 5) % 11 < 2) as u8).collect())
+    ///     .collect();
+    /// population[0] = vec![1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 0, 0];
+    /// population[1] = vec![0, 0, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1];
+    ///
+    /// let roulette = genetic_algorithm(population.clone(), fitness, vec![0, 1]).run(100, 3);
+    /// let tournament = genetic_algorithm(population, fitness, vec![0, 1])
+    ///     .with_tournament(2)
+    ///     .run(100, 3);
+    ///
+    /// let early = |diversity: &[usize]| diversity[1..10].iter().sum::<usize>();
+    /// assert!(early(&tournament.diversity) > 3 * early(&roulette.diversity));
+    /// assert_eq!(tournament.best, vec![1; 12]);
+    /// ```
+    pub fn with_tournament(mut self, size: usize) -> Self {
+        assert!(size > 0, "a tournament needs at least one contestant");
+        self.tournament_size = Some(size);
+        self
+    }
+
+    /// The index of a parent, chosen by tournament or roulette according to `weights`.
+    fn select(&self, weights: &[f64], rng: &mut Rng) -> usize {
+        match self.tournament_size {
+            None => roulette_select(weights, rng),
+            Some(size) => (0..size)
+                .map(|_| rng.gen_range(0..weights.len()))
+                .max_by(|&i, &j| weights[i].total_cmp(&weights[j]))
+                .unwrap(),
+        }
+    }
+
+    /// Carries the `count` fittest individuals of each generation into the next unchanged, so
+    /// that the best fitness in the population never decreases. The rest of the generation is
+    /// bred as usual. The default is no elitism.
+    ///
+    /// Elites take up places that would otherwise go to new children, so the count should be a
+    /// small fraction of the population size: one or two, or a few percent of a large
+    /// population. The more elites there are, the sooner their descendants crowd out everyone
+    /// else; with as many elites as individuals, nothing new is ever bred.
+    ///
+    /// # Examples
+    ///
+    /// With every child mutated, the best individual is often lost without elitism:
+    ///
+    /// ```
+    /// # use aima_rust::search::genetic_algorithm;
+    /// let ones = |x: &[u8]| x.iter().filter(|&&bit| bit == 1).count() as f64;
+    /// let population = vec![vec![0u8; 16]; 10];
+    ///
+    /// let plain = genetic_algorithm(population.clone(), ones, vec![0, 1])
+    ///     .with_mutation_rate(1.0)
+    ///     .run(60, 5);
+    /// let elitist = genetic_algorithm(population, ones, vec![0, 1])
+    ///     .with_mutation_rate(1.0)
+    ///     .with_elitism(1)
+    ///     .run(60, 5);
+    ///
+    /// let never_drops = |curve: &[f64]| curve.windows(2).all(|pair| pair[0] <= pair[1]);
+    /// assert!(!never_drops(&plain.best_fitnesses));
+    /// assert!(never_drops(&elitist.best_fitnesses));
+    /// assert_eq!(elitist.best_fitness, 16.0);
+    /// ```
+    pub fn with_elitism(mut self, count: usize) -> Self {
+        self.elitism = count;
+        self
+    }
+
+    /// Runs the algorithm for `generations` generations, making its random choices with a
+    /// generator seeded with `seed`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the population is empty.
+    pub fn run(&self, generations: usize, seed: u64) -> GeneticReport<G> {
+        assert!(!self.population.is_empty(), "the population is empty");
+        let mut rng = Rng::seeded(seed);
+        let mut population = self.population.clone();
+        let mut diversity = vec![distinct(&population)];
+        let mut best_fitnesses = vec![self.fittest(&population).1];
+        for _ in 0..generations {
+            let weights = self.selection_weights(&population);
+            let mut next = self.elites(&population);
+            while next.len() < population.len() {
+                let x = &population[self.select(&weights, &mut rng)];
+                let y = &population[self.select(&weights, &mut rng)];
+                let mut child = reproduce(x, y, &mut rng);
+                if !child.is_empty() && rng.gen_bool(self.mutation_rate) {
+                    let i = rng.gen_range(0..child.len());
+                    child[i] = self.gene_pool.choose(&mut rng).unwrap().clone();
+                }
+                next.push(child);
+            }
+            population = next;
+            diversity.push(distinct(&population));
+            best_fitnesses.push(self.fittest(&population).1);
+        }
+        let (best, best_fitness) = self.fittest(&population);
+        GeneticReport {
+            best: best.clone(),
+            best_fitness,
+            population,
+            diversity,
+            best_fitnesses,
+        }
+    }
+
+    /// The fittest individual of `population`, by raw fitness, and its fitness.
+    fn fittest<'a>(&self, population: &'a [Individual<G>]) -> (&'a Individual<G>, f64) {
+        population
+            .iter()
+            .map(|x| (x, (self.fitness)(x)))
+            .max_by(|(_, a), (_, b)| a.total_cmp(b))
+            .unwrap()
+    }
+
+    /// Copies of the fittest individuals of `population`, by raw fitness, as many as the
+    /// elitism count allows.
+    fn elites(&self, population: &[Individual<G>]) -> Vec<Individual<G>> {
+        let mut ranked: Vec<(&Individual<G>, f64)> =
+            population.iter().map(|x| (x, (self.fitness)(x))).collect();
+        ranked.sort_by(|(_, a), (_, b)| b.total_cmp(a));
+        ranked
+            .into_iter()
+            .take(self.elitism)
+            .map(|(x, _)| x.clone())
+            .collect()
+    }
+
+    /// The weight of each individual in parent selection: its fitness, shared if niching is on.
+    fn selection_weights(&self, population: &[Individual<G>]) -> Vec<f64> {
+        population
+            .iter()
+            .map(|x| {
+                let fitness = (self.fitness)(x);
+                match self.sharing_radius {
+                    None => fitness,
+                    Some(radius) => {
+                        let niche_count: f64 = population
+                            .iter()
+                            .map(|y| {
+                                let distance = x.iter().zip(y).filter(|(a, b)| a != b).count();
+                                (1.0 - distance as f64 / radius).max(0.0)
+                            })
+                            .sum();
+                        fitness / niche_count
+                    }
+                }
+            })
+            .collect()
+    }
+}
+
+/// An index chosen with probability proportional to its weight, or uniformly if every weight is
+/// zero.
+fn roulette_select(weights: &[f64], rng: &mut Rng) -> usize {
+    let total: f64 = weights.iter().sum();
+    if total <= 0.0 {
+        return rng.gen_range(0..weights.len());
+    }
+    let mut r = rng.gen::<f64>() * total;
+    for (i, &weight) in weights.iter().enumerate() {
+        if r < weight {
+            return i;
+        }
+        r -= weight;
+    }
+    weights.len() - 1
+}
+
+/// A child with a prefix of `x` and the rest from `y`, split at a random point.
+fn reproduce<G: Clone>(x: &[G], y: &[G], rng: &mut Rng) -> Individual<G> {
+    let c = rng.gen_range(0..=x.len());
+    x[..c].iter().chain(&y[c..]).cloned().collect()
+}
+
+/// The number of distinct individuals in `population`.
+fn distinct<G: Eq + Hash>(population: &[Individual<G>]) -> usize {
+    population.iter().collect::<HashSet<_>>().len()
+}
+
+/// A river bank.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Bank {
+    Left,
+    Right,
+}
+
+/// A boatload crossing the river.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Crossing {
+    pub missionaries: u8,
+    pub cannibals: u8,
+}
+
+impl fmt::Display for Crossing {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let people = |count: u8, one: &str, many: &str| match count {
+            0 => None,
+            1 => Some(format!("1 {}", one)),
+            _ => Some(format!("{} {}", count, many)),
+        };
+        let parts: Vec<String> = vec![
+            people(self.missionaries, "missionary", "missionaries"),
+            people(self.cannibals, "cannibal", "cannibals"),
+        ]
+        .into_iter()
+        .flatten()
+        .collect();
+        write!(f, "{} cross", parts.join(" and "))
+    }
+}
+
+/// The missionaries-and-cannibals puzzle, as set up by `missionaries_cannibals`.
+pub struct MissionariesCannibals {
+    missionaries: u8,
+    cannibals: u8,
+    capacity: u8,
+}
+
+impl MissionariesCannibals {
+    /// Whether no group of missionaries in `state` is outnumbered by cannibals, on either bank.
+    pub fn is_safe(&self, &(m, c, _): &(u8, u8, Bank)) -> bool {
+        let (other_m, other_c) = (self.missionaries - m, self.cannibals - c);
+        (m == 0 || m >= c) && (other_m == 0 || other_m >= other_c)
+    }
+}
+
+impl Problem for MissionariesCannibals {
+    /// The missionaries and cannibals on the left bank, and the bank the boat is on.
+    type State = (u8, u8, Bank);
+    type Action = Crossing;
+
+    fn initial_state(&self) -> (u8, u8, Bank) {
+        (self.missionaries, self.cannibals, Bank::Left)
+    }
+
+    /// The boatloads that fit in the boat, are available on the boat's bank, and leave everyone
+    /// safe. Unsafe states are never generated.
+    fn actions(&self, state: &(u8, u8, Bank)) -> Vec<Crossing> {
+        let &(m, c, boat) = state;
+        let (here_m, here_c) = match boat {
+            Bank::Left => (m, c),
+            Bank::Right => (self.missionaries - m, self.cannibals - c),
+        };
+        let mut crossings = Vec::new();
+        for missionaries in 0..=here_m.min(self.capacity) {
+            for cannibals in 0..=here_c.min(self.capacity - missionaries) {
+                let crossing = Crossing {
+                    missionaries,
+                    cannibals,
+                };
+                if missionaries + cannibals > 0 && self.is_safe(&self.result(state, &crossing)) {
+                    crossings.push(crossing);
+                }
+            }
+        }
+        crossings
+    }
+
+    fn result(&self, &(m, c, boat): &(u8, u8, Bank), crossing: &Crossing) -> (u8, u8, Bank) {
+        match boat {
+            Bank::Left => (
+                m - crossing.missionaries,
+                c - crossing.cannibals,
+                Bank::Right,
+            ),
+            Bank::Right => (
+                m + crossing.missionaries,
+                c + crossing.cannibals,
+                Bank::Left,
+            ),
+        }
+    }
+
+    fn goal_test(&self, state: &(u8, u8, Bank)) -> bool {
+        *state == (0, 0, Bank::Right)
+    }
+}
+
+/// Three missionaries and three cannibals must cross a river in a boat that holds one or two
+/// people. The cannibals must never outnumber the missionaries on either bank, or the missionaries
+/// there will be eaten.
+///
+/// States are `(left_m, left_c, boat_side)`: the missionaries and cannibals still on the left,
+/// starting bank, and where the boat is.
+///
+/// # Examples
+///
+/// ```
+/// # use aima_rust::search::{breadth_first_search, missionaries_cannibals, Problem};
+/// let problem = missionaries_cannibals();
+/// let node = breadth_first_search(&problem).unwrap();
+///
+/// assert_eq!(node.solution().len(), 11);
+/// assert!(node.path().iter().all(|state| problem.is_safe(state)));
+/// assert_eq!(node.solution()[0].to_string(), "2 cannibals cross");
+///
+/// let plan: Vec<String> = node.solution().iter().map(|c| c.to_string()).collect();
+/// assert_eq!(
+///     plan[3..8],
+///     [
+///         "1 cannibal cross",
+///         "2 missionaries cross",
+///         "1 missionary and 1 cannibal cross",
+///         "2 missionaries cross",
+///         "1 cannibal cross",
+///     ]
+/// );
+/// ```
+pub fn missionaries_cannibals() -> MissionariesCannibals {
+    MissionariesCannibals {
+        missionaries: 3,
+        cannibals: 3,
+        capacity: 2,
+    }
+}
+
+/// The direction the blank moves in the 8-puzzle, swapping places with the tile it moves onto.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Slide {
+    Up,
+    Down,
+    Left,
+    Right,
+}
+
+/// # 8-Puzzle
+///
+/// Eight numbered tiles and a blank on a three-by-three board. A tile next to the blank can slide
+/// into it, and the goal is to order the tiles
+///
+/// ```text
+/// 1 2 3
+/// 4 5 6
+/// 7 8 _
+/// ```
+///
+/// States list the tiles row by row from the top left, with `0` for the blank. Only half of all
+/// arrangements can reach the goal; see `is_solvable`.
+///
+/// # Examples
+///
+/// ```
+/// # use aima_rust::search::{astar_search, EightPuzzle, Problem, Slide};
+/// let puzzle = EightPuzzle::new([1, 2, 3, 4, 5, 6, 0, 7, 8]);
+/// assert!(puzzle.is_solvable());
+///
+/// let node = astar_search(&puzzle, |s| puzzle.manhattan_distance(s)).unwrap();
+/// assert_eq!(node.solution(), vec![Slide::Right, Slide::Right]);
+///
+/// assert!(!EightPuzzle::new([2, 1, 3, 4, 5, 6, 7, 8, 0]).is_solvable());
+/// ```
+pub struct EightPuzzle {
+    initial: [u8; 9],
+}
+
+impl EightPuzzle {
+    /// The goal arrangement.
+    pub const GOAL: [u8; 9] = [1, 2, 3, 4, 5, 6, 7, 8, 0];
+
+    /// Creates a new puzzle given its initial arrangement.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `initial` isn't an arrangement of the numbers zero to eight.
+    pub fn new(initial: [u8; 9]) -> Self {
+        let mut sorted = initial;
+        sorted.sort_unstable();
+        assert_eq!(
+            sorted,
+            [0, 1, 2, 3, 4, 5, 6, 7, 8],
+            "not an 8-puzzle arrangement"
+        );
+        EightPuzzle { initial }
+    }
+
+    /// Whether the goal can be reached: it can exactly when the tiles, read in order and ignoring
+    /// the blank, contain an even number of inversions, pairs out of order.
+    pub fn is_solvable(&self) -> bool {
+        let tiles: Vec<u8> = self.initial.iter().cloned().filter(|&t| t != 0).collect();
+        let inversions = (0..tiles.len())
+            .flat_map(|i| (i + 1..tiles.len()).map(move |j| (i, j)))
+            .filter(|&(i, j)| tiles[i] > tiles[j])
+            .count();
+        inversions % 2 == 0
+    }
+
+    /// The sum over the tiles of their horizontal and vertical distances from their goal
+    /// squares: an admissible and consistent heuristic, since every move shifts one tile by one
+    /// square.
+    pub fn manhattan_distance(&self, state: &[u8; 9]) -> f64 {
+        state
+            .iter()
+            .enumerate()
+            .filter(|&(_, &tile)| tile != 0)
+            .map(|(square, &tile)| {
+                let goal = tile as usize - 1;
+                ((square / 3) as i32 - (goal / 3) as i32).abs()
+                    + ((square % 3) as i32 - (goal % 3) as i32).abs()
+            })
+            .sum::<i32>() as f64
+    }
+}
+
+impl Problem for EightPuzzle {
+    type State = [u8; 9];
+    type Action = Slide;
+
+    fn initial_state(&self) -> [u8; 9] {
+        self.initial
+    }
+
+    fn actions(&self, state: &[u8; 9]) -> Vec<Slide> {
+        let blank = state.iter().position(|&t| t == 0).unwrap();
+        let mut actions = Vec::with_capacity(4);
+        if blank >= 3 {
+            actions.push(Slide::Up);
+        }
+        if blank < 6 {
+            actions.push(Slide::Down);
+        }
+        if blank % 3 > 0 {
+            actions.push(Slide::Left);
+        }
+        if blank % 3 < 2 {
+            actions.push(Slide::Right);
+        }
+        actions
+    }
+
+    fn result(&self, state: &[u8; 9], action: &Slide) -> [u8; 9] {
+        let blank = state.iter().position(|&t| t == 0).unwrap();
+        let target = match action {
+            Slide::Up => blank - 3,
+            Slide::Down => blank + 3,
+            Slide::Left => blank - 1,
+            Slide::Right => blank + 1,
+        };
+        let mut next = *state;
+        next.swap(blank, target);
+        next
+    }
+
+    fn goal_test(&self, state: &[u8; 9]) -> bool {
+        *state == EightPuzzle::GOAL
+    }
+}
+
+/// The towers-of-Hanoi puzzle, as set up by `hanoi`.
+pub struct Hanoi {
+    disks: usize,
+}
+
+impl Hanoi {
+    /// The number of disks not yet on the last peg: a lower bound on the moves still needed,
+    /// since each of them must move at least once.
+    pub fn disks_off_target(&self, pegs: &[Vec<usize>; 3]) -> f64 {
+        (self.disks - pegs[2].len()) as f64
+    }
+}
+
+impl Problem for Hanoi {
+    /// The three pegs, each listing its disks from the bottom up. Disks are numbered by size.
+    type State = [Vec<usize>; 3];
+    /// A move of the top disk from one peg to another.
+    type Action = (usize, usize);
+
+    fn initial_state(&self) -> [Vec<usize>; 3] {
+        [(1..=self.disks).rev().collect(), Vec::new(), Vec::new()]
+    }
+
+    fn actions(&self, pegs: &[Vec<usize>; 3]) -> Vec<(usize, usize)> {
+        let mut moves = Vec::new();
+        for from in 0..3 {
+            for to in 0..3 {
+                if let Some(disk) = pegs[from].last() {
+                    if from != to && pegs[to].last().is_none_or(|top| top > disk) {
+                        moves.push((from, to));
+                    }
+                }
+            }
+        }
+        moves
+    }
+
+    fn result(&self, pegs: &[Vec<usize>; 3], &(from, to): &(usize, usize)) -> [Vec<usize>; 3] {
+        let mut pegs = pegs.clone();
+        let disk = pegs[from].pop().unwrap();
+        pegs[to].push(disk);
+        pegs
+    }
+
+    fn goal_test(&self, pegs: &[Vec<usize>; 3]) -> bool {
+        pegs[2].len() == self.disks
+    }
+}
+
+/// The towers of Hanoi with `n` disks: move the stack from the first peg to the last, one disk at
+/// a time, never placing a disk on a smaller one. The shortest solution takes $2^n - 1$ moves, so
+/// the puzzle makes a benchmark whose difficulty grows exponentially with `n`.
+///
+/// # Examples
+///
+/// ```
+/// # use aima_rust::search::{astar_search, breadth_first_search, hanoi, iterative_deepening_search};
+/// let problem = hanoi(3);
+///
+/// let bfs = breadth_first_search(&problem).unwrap();
+/// let ids = iterative_deepening_search(&problem).unwrap();
+/// let astar = astar_search(&problem, |pegs| problem.disks_off_target(pegs)).unwrap();
+///
+/// assert_eq!(bfs.solution().len(), 7);
+/// assert_eq!(ids.solution().len(), 7);
+/// assert_eq!(astar.solution().len(), 7);
+/// assert_eq!(astar.state, [vec![], vec![], vec![3, 2, 1]]);
+/// ```
+pub fn hanoi(n: usize) -> Hanoi {
+    Hanoi { disks: n }
+}
+
+/// # Graph Problem
+///
+/// A route-finding problem on an explicit weighted graph: find a path from `start` to `goal`,
+/// where each step follows an edge and costs its weight. Building one from a list of edges saves
+/// implementing `Problem` by hand.
+///
+/// Edges are undirected. Actions are the neighbors to move to, in the order their edges were
+/// given. Heuristic values for A\* can be attached with `with_heuristic`; nodes without one are
+/// estimated at zero.
+///
+/// # Examples
+///
+/// ```
+/// # use aima_rust::search::{astar_search, breadth_first_search, romania, GraphProblem};
+/// let problem = GraphProblem::from_edges(romania::EDGES.iter().cloned(), "Arad", "Bucharest")
+///     .with_heuristic(romania::STRAIGHT_LINE_TO_BUCHAREST.iter().cloned());
+///
+/// let node = astar_search(&problem, |city| problem.h(city)).unwrap();
+/// assert_eq!(node.path(), vec!["Arad", "Sibiu", "Rimnicu Vilcea", "Pitesti", "Bucharest"]);
+/// assert_eq!(node.path_cost, 418.0);
+///
+/// let node = breadth_first_search(&problem).unwrap();
+/// assert_eq!(node.path(), vec!["Arad", "Sibiu", "Fagaras", "Bucharest"]);
+/// assert_eq!(node.path_cost, 450.0);
+///
+/// // Without heuristic values, every estimate is zero.
+/// let problem = GraphProblem::from_edges(romania::EDGES.iter().cloned(), "Arad", "Bucharest");
+/// assert_eq!(problem.h(&"Arad"), 0.0);
+/// ```
+#[derive(Clone, Debug)]
+pub struct GraphProblem<N: Eq + Hash> {
+    adjacency: HashMap<N, Vec<(N, f64)>>,
+    heuristic: HashMap<N, f64>,
+    start: N,
+    goal: N,
+}
+
+impl<N: Clone + Eq + Hash> GraphProblem<N> {
+    /// Creates a new problem given weighted edges `(a, b, cost)` and the start and goal nodes.
+    pub fn from_edges<I: IntoIterator<Item = (N, N, f64)>>(edges: I, start: N, goal: N) -> Self {
+        let mut adjacency: HashMap<N, Vec<(N, f64)>> = HashMap::new();
+        for (a, b, cost) in edges {
+            adjacency
+                .entry(a.clone())
+                .or_default()
+                .push((b.clone(), cost));
+            adjacency.entry(b).or_default().push((a, cost));
+        }
+        GraphProblem {
+            adjacency,
+            heuristic: HashMap::new(),
+            start,
+            goal,
+        }
+    }
+
+    /// Attaches heuristic estimates of the cost from each node to the goal.
+    pub fn with_heuristic<I: IntoIterator<Item = (N, f64)>>(mut self, values: I) -> Self {
+        self.heuristic.extend(values);
+        self
+    }
+
+    /// The heuristic estimate for `node`, or zero if it has none.
+    pub fn h(&self, node: &N) -> f64 {
+        self.heuristic.get(node).cloned().unwrap_or(0.0)
+    }
+
+    /// The neighbors of `node` and the cost of the edge to each.
+    pub fn neighbors(&self, node: &N) -> &[(N, f64)] {
+        self.adjacency.get(node).map_or(&[], Vec::as_slice)
+    }
+}
+
+impl<N: Clone + Eq + Hash> Problem for GraphProblem<N> {
+    type State = N;
+    type Action = N;
+
+    fn initial_state(&self) -> N {
+        self.start.clone()
+    }
+
+    fn actions(&self, node: &N) -> Vec<N> {
+        self.neighbors(node)
+            .iter()
+            .map(|(next, _)| next.clone())
+            .collect()
+    }
+
+    fn result(&self, _node: &N, next: &N) -> N {
+        next.clone()
+    }
+
+    fn goal_test(&self, node: &N) -> bool {
+        *node == self.goal
+    }
+
+    fn step_cost(&self, node: &N, _action: &N, next: &N) -> f64 {
+        self.neighbors(node)
+            .iter()
+            .filter(|(neighbor, _)| neighbor == next)
+            .map(|&(_, cost)| cost)
+            .fold(f64::INFINITY, f64::min)
+    }
+}
+
+/// The shortest distance from `source` to every node reachable from it, by uniform-cost search
+/// run until the frontier is empty.
+fn distances_from<N: Clone + Eq + Hash>(graph: &GraphProblem<N>, source: &N) -> HashMap<N, f64> {
+    let mut distances = HashMap::new();
+    let mut frontier = BinaryHeap::new();
+    let mut order = 0;
+    frontier.push(Prioritized {
+        f: 0.0,
+        tie: 0.0,
+        order,
+        node: Rc::new(Node::<GraphProblem<N>>::root(source.clone())),
+    });
+    while let Some(Prioritized { node, .. }) = frontier.pop() {
+        if distances.contains_key(&node.state) {
+            continue;
+        }
+        distances.insert(node.state.clone(), node.path_cost);
+        for action in graph.actions(&node.state) {
+            let child = Node::child(graph, &node, action);
+            if !distances.contains_key(&child.state) {
+                order += 1;
+                frontier.push(Prioritized {
+                    f: child.path_cost,
+                    tie: 0.0,
+                    order,
+                    node: Rc::new(child),
+                });
+            }
+        }
+    }
+    distances
+}
+
+/// A `GraphProblem`'s graph with another start and goal.
+struct Route<'a, N: Eq + Hash> {
+    graph: &'a GraphProblem<N>,
+    start: N,
+    goal: N,
+}
+
+impl<'a, N: Clone + Eq + Hash> Problem for Route<'a, N> {
+    type State = N;
+    type Action = N;
+
+    fn initial_state(&self) -> N {
+        self.start.clone()
+    }
+
+    fn actions(&self, node: &N) -> Vec<N> {
+        self.graph.actions(node)
+    }
+
+    fn result(&self, _node: &N, next: &N) -> N {
+        next.clone()
+    }
+
+    fn goal_test(&self, node: &N) -> bool {
+        *node == self.goal
+    }
+
+    fn step_cost(&self, node: &N, action: &N, next: &N) -> f64 {
+        self.graph.step_cost(node, action, next)
+    }
+}
+
+/// # Preprocessed Graph
+///
+/// A graph prepared for many shortest-path queries, using A\* with landmarks and the triangle
+/// inequality (ALT). Preprocessing picks a few _landmark_ nodes and finds the distance from each
+/// of them to every node. Since the graph is undirected, for any landmark $L$,
+///
+/// $$ h(n) = \max_L \lvert d(L, goal) - d(L, n) \rvert \le d(n, goal) $$
+///
+/// so the heuristic is admissible and consistent for any goal, and each query is an optimal A\*
+/// search that is usually much better informed than a hand-made heuristic for one fixed goal.
+/// Landmarks are chosen far apart, each as far as possible from those already chosen, since a
+/// landmark "behind" the start or the goal gives the tightest bounds.
+///
+/// The preprocessing costs one uniform-cost search of the whole graph per landmark, and keeps
+/// one distance per landmark and node. That is far less than the all-pairs table, which would
+/// make queries mere lookups but grows with the square of the number of nodes. More landmarks
+/// tighten the heuristic but add to every query as well, so their number is capped at
+/// `MAX_LANDMARKS`.
+///
+/// # Examples
+///
+/// ```
+/// # use aima_rust::search::{romania, GraphProblem, Preprocessed};
+/// let map = GraphProblem::from_edges(romania::EDGES.iter().cloned(), "Arad", "Bucharest");
+/// let preprocessed = Preprocessed::build(&map);
+/// assert_eq!(preprocessed.landmarks().len(), 4);
+///
+/// let (path, cost) = preprocessed.query(&"Arad", &"Bucharest").unwrap();
+/// assert_eq!(path, vec!["Arad", "Sibiu", "Rimnicu Vilcea", "Pitesti", "Bucharest"]);
+/// assert_eq!(cost, 418.0);
+///
+/// // Every query gives the same cost as a fresh search, without preprocessing again.
+/// for &(from, _) in romania::STRAIGHT_LINE_TO_BUCHAREST.iter() {
+///     for &(to, _) in romania::STRAIGHT_LINE_TO_BUCHAREST.iter() {
+///         let (_, cost) = preprocessed.query(&from, &to).unwrap();
+///         assert_eq!(Some(cost), romania::optimal_cost(from, to));
+///     }
+/// }
+/// ```
+///
+/// The landmark heuristic needs fewer expansions than the straight-line distance:
+///
+/// ```
+/// # use aima_rust::search::{astar_search, romania, GraphProblem, InstrumentedProblem, Preprocessed};
+/// let map = GraphProblem::from_edges(romania::EDGES.iter().cloned(), "Arad", "Bucharest")
+///     .with_heuristic(romania::STRAIGHT_LINE_TO_BUCHAREST.iter().cloned());
+/// let preprocessed = Preprocessed::build(&map);
+///
+/// let straight_line = InstrumentedProblem::new(&map);
+/// let a = astar_search(&straight_line, |city| map.h(city)).unwrap();
+/// let landmarks = InstrumentedProblem::new(&map);
+/// let b = astar_search(&landmarks, |city| preprocessed.heuristic(city, &"Bucharest")).unwrap();
+///
+/// assert_eq!(a.path_cost, b.path_cost);
+/// assert!(landmarks.expansions() < straight_line.expansions());
+/// ```
+#[derive(Clone, Debug)]
+pub struct Preprocessed<N: Eq + Hash> {
+    graph: GraphProblem<N>,
+    landmarks: Vec<N>,
+    distances: Vec<HashMap<N, f64>>,
+}
+
+/// The most landmarks a `Preprocessed` graph keeps.
+pub const MAX_LANDMARKS: usize = 16;
+
+impl<N: Clone + Eq + Hash> Preprocessed<N> {
+    /// Preprocesses the graph of `problem` with four landmarks.
+    pub fn build(problem: &GraphProblem<N>) -> Self {
+        Preprocessed::build_with_landmarks(problem, 4)
+    }
+
+    /// Preprocesses the graph of `problem` with up to `count` landmarks, and never more than
+    /// `MAX_LANDMARKS`. The first landmark is the node farthest from the problem's start, and
+    /// each of the others the node farthest from the landmarks before it. There are fewer if the
+    /// nodes reachable from the start run out.
+    pub fn build_with_landmarks(problem: &GraphProblem<N>, count: usize) -> Self {
+        let mut landmarks: Vec<N> = Vec::new();
+        let mut distances: Vec<HashMap<N, f64>> = Vec::new();
+        let mut nearest = distances_from(problem, &problem.start);
+        while landmarks.len() < count.min(MAX_LANDMARKS) {
+            let farthest = nearest
+                .iter()
+                .filter(|(node, _)| !landmarks.contains(node))
+                .max_by(|a, b| a.1.total_cmp(b.1))
+                .map(|(node, _)| node.clone());
+            let landmark = match farthest {
+                Some(landmark) => landmark,
+                None => break,
+            };
+            let from_landmark = distances_from(problem, &landmark);
+            for (node, d) in nearest.iter_mut() {
+                let to_landmark = from_landmark[node];
+                if landmarks.is_empty() || to_landmark < *d {
+                    *d = to_landmark;
+                }
+            }
+            landmarks.push(landmark);
+            distances.push(from_landmark);
+        }
+        Preprocessed {
+            graph: problem.clone(),
+            landmarks,
+            distances,
+        }
+    }
+
+    /// The landmarks, in the order they were chosen.
+    pub fn landmarks(&self) -> &[N] {
+        &self.landmarks
+    }
+
+    /// The landmark lower bound on the distance from `node` to `goal`. Landmarks that can't
+    /// reach both contribute nothing.
+    pub fn heuristic(&self, node: &N, goal: &N) -> f64 {
+        self.distances
+            .iter()
+            .filter_map(|d| Some((d.get(node)? - d.get(goal)?).abs()))
+            .fold(0.0, f64::max)
+    }
+
+    /// The shortest path from `start` to `goal`, with its cost, or `None` if there is none.
+    pub fn query(&self, start: &N, goal: &N) -> Option<(Vec<N>, f64)> {
+        let route = Route {
+            graph: &self.graph,
+            start: start.clone(),
+            goal: goal.clone(),
+        };
+        astar_search(&route, |node| self.heuristic(node, goal))
+            .map(|node| (node.path(), node.path_cost))
+    }
+}
+
+/// # Romania
+///
+/// The simplified road map of part of Romania from figure 3.2, the book's running example of
+/// route finding, with road lengths in kilometers.
+pub mod romania {
+    /// The roads between cities and their lengths.
+    pub const EDGES: [(&str, &str, f64); 23] = [
+        ("Arad", "Zerind", 75.0),
+        ("Arad", "Sibiu", 140.0),
+        ("Arad", "Timisoara", 118.0),
+        ("Bucharest", "Urziceni", 85.0),
+        ("Bucharest", "Pitesti", 101.0),
+        ("Bucharest", "Giurgiu", 90.0),
+        ("Bucharest", "Fagaras", 211.0),
+        ("Craiova", "Drobeta", 120.0),
+        ("Craiova", "Rimnicu Vilcea", 146.0),
+        ("Craiova", "Pitesti", 138.0),
+        ("Drobeta", "Mehadia", 75.0),
+        ("Eforie", "Hirsova", 86.0),
+        ("Fagaras", "Sibiu", 99.0),
+        ("Hirsova", "Urziceni", 98.0),
+        ("Iasi", "Vaslui", 92.0),
+        ("Iasi", "Neamt", 87.0),
+        ("Lugoj", "Timisoara", 111.0),
+        ("Lugoj", "Mehadia", 70.0),
+        ("Oradea", "Zerind", 71.0),
+        ("Oradea", "Sibiu", 151.0),
+        ("Pitesti", "Rimnicu Vilcea", 97.0),
+        ("Rimnicu Vilcea", "Sibiu", 80.0),
+        ("Urziceni", "Vaslui", 142.0),
+    ];
+
+    /// Figure 3.22
+    ///
+    /// The straight-line distance from each city to Bucharest.
+    pub const STRAIGHT_LINE_TO_BUCHAREST: [(&str, f64); 20] = [
+        ("Arad", 366.0),
+        ("Bucharest", 0.0),
+        ("Craiova", 160.0),
+        ("Drobeta", 242.0),
+        ("Eforie", 161.0),
+        ("Fagaras", 176.0),
+        ("Giurgiu", 77.0),
+        ("Hirsova", 151.0),
+        ("Iasi", 226.0),
+        ("Lugoj", 244.0),
+        ("Mehadia", 241.0),
+        ("Neamt", 234.0),
+        ("Oradea", 380.0),
+        ("Pitesti", 100.0),
+        ("Rimnicu Vilcea", 193.0),
+        ("Sibiu", 253.0),
+        ("Timisoara", 329.0),
+        ("Urziceni", 80.0),
+        ("Vaslui", 199.0),
+        ("Zerind", 374.0),
+    ];
+
+    /// The length of the shortest route by road from `from` to `to`, found by uniform-cost
+    /// search, or `None` if there is no such route. This is the ground truth a heuristic
+    /// for the map can be checked against: it is admissible only if it never estimates more.
+    ///
+    /// # Examples
+    ///
+    /// The straight-line distance is admissible from every city, and is tightest at Pitesti, just
+    /// one kilometer short of the road distance:
+    ///
+    /// ```
+    /// # use aima_rust::search::romania::{optimal_cost, STRAIGHT_LINE_TO_BUCHAREST};
+    /// let margins: Vec<(&str, f64)> = STRAIGHT_LINE_TO_BUCHAREST
+    ///     .iter()
+    ///     .filter(|&&(city, _)| city != "Bucharest")
+    ///     .map(|&(city, h)| (city, optimal_cost(city, "Bucharest").unwrap() - h))
+    ///     .collect();
+    ///
+    /// assert!(margins.iter().all(|&(_, margin)| margin >= 0.0));
+    ///
+    /// let tightest = margins.iter().min_by(|a, b| a.1.total_cmp(&b.1)).unwrap();
+    /// assert_eq!(*tightest, ("Pitesti", 1.0));
+    ///
+    /// assert_eq!(optimal_cost("Arad", "Bucharest"), Some(418.0));
+    /// assert_eq!(optimal_cost("Arad", "Budapest"), None);
+    /// ```
+    pub fn optimal_cost(from: &str, to: &str) -> Option<f64> {
+        let problem = super::GraphProblem::from_edges(EDGES.iter().cloned(), from, to);
+        super::best_first_graph_search(&problem, |node| node.path_cost).map(|node| node.path_cost)
+    }
+}